@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
@@ -65,6 +66,56 @@ pub struct ScannerConfig {
     pub save_results: bool,
     /// 扫描结果保存路径
     pub results_path: String,
+    /// 主动扫描测试的插入点类型
+    #[serde(default = "default_insertion_points")]
+    pub insertion_points: Vec<InsertionPointType>,
+}
+
+impl ScannerConfig {
+    /// 是否测试指定类型的插入点
+    pub fn allows(&self, kind: InsertionPointType) -> bool {
+        self.insertion_points.contains(&kind)
+    }
+}
+
+/// 主动扫描的插入点类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InsertionPointType {
+    /// URL查询参数
+    Query,
+    /// 表单字段
+    Form,
+    /// JSON字段
+    Json,
+    /// Cookie
+    Cookie,
+    /// 请求头
+    Header,
+    /// URL路径中的数字段
+    Path,
+    /// 整个请求体，例如XML或序列化数据
+    Body,
+}
+
+impl InsertionPointType {
+    /// 全部插入点类型
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Query,
+            Self::Form,
+            Self::Json,
+            Self::Cookie,
+            Self::Header,
+            Self::Path,
+            Self::Body,
+        ]
+    }
+}
+
+/// 默认测试全部插入点
+fn default_insertion_points() -> Vec<InsertionPointType> {
+    InsertionPointType::all()
 }
 
 /// XSS漏洞配置
@@ -78,6 +129,9 @@ pub struct XssConfig {
     pub max_params: usize,
     /// 插桩测试的最大深度
     pub max_depth: usize,
+    /// 是否使用无头浏览器确认DOM型XSS
+    #[serde(default = "default_true")]
+    pub dom_confirm: bool,
 }
 
 /// SQL注入漏洞配置
@@ -87,6 +141,9 @@ pub struct SqlInjectionConfig {
     pub enabled: bool,
     /// 检测级别：low, medium, high
     pub level: String,
+    /// 按类别自定义payload，非空时替换同名类别的内置payload
+    #[serde(default)]
+    pub payloads: HashMap<String, Vec<String>>,
 }
 
 /// RCE漏洞配置
@@ -96,6 +153,9 @@ pub struct RceConfig {
     pub enabled: bool,
     /// 检测级别：low, medium, high
     pub level: String,
+    /// 按类别自定义payload，非空时替换同名类别的内置payload
+    #[serde(default)]
+    pub payloads: HashMap<String, Vec<String>>,
 }
 
 /// 路径遍历漏洞配置
@@ -112,6 +172,174 @@ pub struct OpenRedirectConfig {
     pub enabled: bool,
 }
 
+/// SSRF漏洞配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SsrfConfig {
+    /// 是否启用
+    pub enabled: bool,
+}
+
+impl Default for SsrfConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// XXE漏洞配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XxeConfig {
+    /// 是否启用
+    pub enabled: bool,
+}
+
+impl Default for XxeConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// SSTI漏洞配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SstiConfig {
+    /// 是否启用
+    pub enabled: bool,
+}
+
+impl Default for SstiConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// 被动检查漏洞配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassiveCheckConfig {
+    /// 是否启用
+    pub enabled: bool,
+}
+
+impl Default for PassiveCheckConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// CORS漏洞配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorsConfig {
+    /// 是否启用
+    pub enabled: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// JWT漏洞配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtConfig {
+    /// 是否启用
+    pub enabled: bool,
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// NoSQL注入漏洞配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoSqlInjectionConfig {
+    /// 是否启用
+    pub enabled: bool,
+}
+
+impl Default for NoSqlInjectionConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// 越权测试使用的低权限身份
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthzRole {
+    /// 身份名称，例如 "普通用户"
+    pub name: String,
+    /// 替换到请求中的请求头，例如 Authorization
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 替换到请求中的Cookie
+    #[serde(default)]
+    pub cookie: Option<String>,
+}
+
+/// 越权访问漏洞配置
+///
+/// 代理流量视为高权限会话，依次使用低权限身份和未认证状态重放并比较响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthzConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 低权限身份列表
+    #[serde(default)]
+    pub roles: Vec<AuthzRole>,
+    /// 是否测试去掉认证信息后的未授权访问
+    #[serde(default = "default_true")]
+    pub test_unauthenticated: bool,
+    /// 是否重放POST、PUT、DELETE等可能修改数据的请求
+    #[serde(default)]
+    pub replay_unsafe_methods: bool,
+    /// 公开接口白名单，支持*通配符，匹配完整URL或路径
+    #[serde(default)]
+    pub allow_list: Vec<String>,
+    /// 响应内容相似度阈值（0-1）
+    #[serde(default = "default_authz_similarity")]
+    pub similarity_threshold: f64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_authz_similarity() -> f64 {
+    0.9
+}
+
+impl Default for AuthzConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            roles: Vec::new(),
+            test_unauthenticated: true,
+            replay_unsafe_methods: false,
+            allow_list: Vec::new(),
+            similarity_threshold: default_authz_similarity(),
+        }
+    }
+}
+
+/// Java反序列化漏洞配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JavaDeserializationConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 确认反序列化后是否发送执行命令（ping回连）的gadget载荷识别可利用链，会在目标上执行命令，默认关闭
+    #[serde(default)]
+    pub exec_gadgets: bool,
+}
+
+impl Default for JavaDeserializationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            exec_gadgets: false,
+        }
+    }
+}
+
 /// 漏洞配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VulnerabilitiesConfig {
@@ -125,6 +353,33 @@ pub struct VulnerabilitiesConfig {
     pub path_traversal: PathTraversalConfig,
     /// 开放重定向漏洞配置
     pub open_redirect: OpenRedirectConfig,
+    /// SSRF漏洞配置
+    #[serde(default)]
+    pub ssrf: SsrfConfig,
+    /// XXE漏洞配置
+    #[serde(default)]
+    pub xxe: XxeConfig,
+    /// SSTI漏洞配置
+    #[serde(default)]
+    pub ssti: SstiConfig,
+    /// 被动检查漏洞配置
+    #[serde(default)]
+    pub passive_checks: PassiveCheckConfig,
+    /// CORS漏洞配置
+    #[serde(default)]
+    pub cors: CorsConfig,
+    /// JWT漏洞配置
+    #[serde(default)]
+    pub jwt: JwtConfig,
+    /// NoSQL注入漏洞配置
+    #[serde(default)]
+    pub nosql_injection: NoSqlInjectionConfig,
+    /// 越权访问漏洞配置
+    #[serde(default)]
+    pub authz: AuthzConfig,
+    /// Java反序列化漏洞配置
+    #[serde(default)]
+    pub java_deserialization: JavaDeserializationConfig,
 }
 
 /// 规则配置
@@ -153,6 +408,193 @@ pub struct LoggingConfig {
     pub colored_output: bool,
 }
 
+/// 带外（OOB）回连服务配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OobConfig {
+    /// 是否启用
+    #[serde(default = "default_oob_enabled")]
+    pub enabled: bool,
+    /// 监听地址
+    #[serde(default = "default_oob_bind_host")]
+    pub bind_host: String,
+    /// 写入载荷中的回连地址，为空时使用监听地址；该地址为回环或任意地址时扫描器不使用OOB载荷
+    #[serde(default)]
+    pub public_host: Option<String>,
+    /// HTTP监听端口
+    #[serde(default = "default_oob_http_port")]
+    pub http_port: u16,
+    /// HTTPS监听端口，0表示不启用
+    #[serde(default = "default_oob_https_port")]
+    pub https_port: u16,
+    /// DNS监听端口，0表示不启用
+    #[serde(default = "default_oob_dns_port")]
+    pub dns_port: u16,
+    /// SMTP监听端口，0表示不启用
+    #[serde(default = "default_oob_smtp_port")]
+    pub smtp_port: u16,
+    /// LDAP监听端口，0表示不启用
+    #[serde(default = "default_oob_ldap_port")]
+    pub ldap_port: u16,
+    /// DNS回连使用的域名（需将该域名的NS指向回连地址）
+    #[serde(default = "default_oob_domain")]
+    pub domain: String,
+    /// 注入后等待回连的时间（毫秒）
+    #[serde(default = "default_oob_wait_ms")]
+    pub wait_ms: u64,
+}
+
+/// 默认启用OOB监听
+fn default_oob_enabled() -> bool {
+    true
+}
+
+/// 默认OOB监听地址
+fn default_oob_bind_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+/// 默认OOB HTTP端口
+fn default_oob_http_port() -> u16 {
+    8899
+}
+
+/// 默认OOB HTTPS端口
+fn default_oob_https_port() -> u16 {
+    8443
+}
+
+/// 默认OOB DNS端口
+fn default_oob_dns_port() -> u16 {
+    5353
+}
+
+/// 默认OOB SMTP端口
+fn default_oob_smtp_port() -> u16 {
+    2525
+}
+
+/// 默认OOB LDAP端口
+fn default_oob_ldap_port() -> u16 {
+    1389
+}
+
+/// 默认OOB域名
+fn default_oob_domain() -> String {
+    "oob.rshield.local".to_string()
+}
+
+/// 默认回连等待时间（毫秒）
+fn default_oob_wait_ms() -> u64 {
+    3000
+}
+
+impl Default for OobConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_oob_enabled(),
+            bind_host: default_oob_bind_host(),
+            public_host: None,
+            http_port: default_oob_http_port(),
+            https_port: default_oob_https_port(),
+            dns_port: default_oob_dns_port(),
+            smtp_port: default_oob_smtp_port(),
+            ldap_port: default_oob_ldap_port(),
+            domain: default_oob_domain(),
+            wait_ms: default_oob_wait_ms(),
+        }
+    }
+}
+
+/// Rhai扫描插件配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginConfig {
+    /// 是否在扫描流程中执行插件
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 单个插件处理一个请求的超时时间（毫秒）
+    #[serde(default = "default_plugin_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+/// 默认插件超时时间（毫秒）
+fn default_plugin_timeout_ms() -> u64 {
+    10000
+}
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_ms: default_plugin_timeout_ms(),
+        }
+    }
+}
+
+/// 出站请求限速配置，按目标主机（host:port）分别生效
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 每个主机每秒最多发送的请求数，0表示不限制
+    #[serde(default = "default_requests_per_second")]
+    pub requests_per_second: f64,
+    /// 每个主机同时进行的最大请求数，0表示不限制
+    #[serde(default = "default_max_in_flight")]
+    pub max_in_flight: usize,
+    /// 收到429/503且没有Retry-After时的初始退避时间（毫秒），连续触发时翻倍
+    #[serde(default = "default_backoff_base_ms")]
+    pub backoff_base_ms: u64,
+    /// 最长退避时间（毫秒）
+    #[serde(default = "default_backoff_max_ms")]
+    pub backoff_max_ms: u64,
+    /// 是否在超时和限流时自动降低发送速率
+    #[serde(default = "default_true")]
+    pub adaptive: bool,
+    /// 自动降速的最大倍数
+    #[serde(default = "default_max_slowdown")]
+    pub max_slowdown: f64,
+}
+
+/// 默认每主机每秒请求数
+fn default_requests_per_second() -> f64 {
+    10.0
+}
+
+/// 默认每主机并发请求数
+fn default_max_in_flight() -> usize {
+    5
+}
+
+/// 默认初始退避时间（毫秒）
+fn default_backoff_base_ms() -> u64 {
+    1000
+}
+
+/// 默认最长退避时间（毫秒）
+fn default_backoff_max_ms() -> u64 {
+    60000
+}
+
+/// 默认最大降速倍数
+fn default_max_slowdown() -> f64 {
+    8.0
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            requests_per_second: default_requests_per_second(),
+            max_in_flight: default_max_in_flight(),
+            backoff_base_ms: default_backoff_base_ms(),
+            backoff_max_ms: default_backoff_max_ms(),
+            adaptive: true,
+            max_slowdown: default_max_slowdown(),
+        }
+    }
+}
+
 /// 应用配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -164,6 +606,15 @@ pub struct AppConfig {
     pub rules: RulesConfig,
     /// 日志配置
     pub logging: LoggingConfig,
+    /// OOB回连监听配置
+    #[serde(default)]
+    pub oob: OobConfig,
+    /// 扫描插件配置
+    #[serde(default)]
+    pub plugins: PluginConfig,
+    /// 出站请求限速配置
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
 }
 
 impl AppConfig {
@@ -202,6 +653,7 @@ impl AppConfig {
                 timeout_ms: 5000,
                 save_results: true,
                 results_path: "results".to_string(),
+                insertion_points: default_insertion_points(),
             },
             rules: RulesConfig {
                 enable_builtin: true,
@@ -213,14 +665,17 @@ impl AppConfig {
                         use_ast: true,
                         max_params: 20,
                         max_depth: 3,
+                        dom_confirm: true,
                     },
                     sql_injection: SqlInjectionConfig {
                         enabled: true,
                         level: "high".to_string(),
+                        payloads: HashMap::new(),
                     },
                     rce: RceConfig {
                         enabled: true,
                         level: "high".to_string(),
+                        payloads: HashMap::new(),
                     },
                     path_traversal: PathTraversalConfig {
                         enabled: true,
@@ -228,6 +683,15 @@ impl AppConfig {
                     open_redirect: OpenRedirectConfig {
                         enabled: true,
                     },
+                    ssrf: SsrfConfig::default(),
+                    xxe: XxeConfig::default(),
+                    ssti: SstiConfig::default(),
+                    passive_checks: PassiveCheckConfig::default(),
+                    cors: CorsConfig::default(),
+                    jwt: JwtConfig::default(),
+                    nosql_injection: NoSqlInjectionConfig::default(),
+                    authz: AuthzConfig::default(),
+                    java_deserialization: JavaDeserializationConfig::default(),
                 },
             },
             logging: LoggingConfig {
@@ -236,6 +700,9 @@ impl AppConfig {
                 file_path: "logs/passvia_scan.log".to_string(),
                 colored_output: true,
             },
+            oob: OobConfig::default(),
+            plugins: PluginConfig::default(),
+            rate_limit: RateLimitConfig::default(),
         }
     }
 } 
\ No newline at end of file
@@ -0,0 +1,412 @@
+//! 出站请求调度器
+//!
+//! 扫描器、ASM、Rhai插件和Repeater主动发出的请求都经过这里，按目标主机（host:port）
+//! 限制每秒请求数和同时进行的请求数。收到429/503时按Retry-After或指数退避暂停该主机，
+//! 请求超时时自动降低该主机的发送速率，正常响应后逐步恢复。
+//! 并发名额随响应返回，读取完响应体后才归还。
+
+use crate::core::config::{AppConfig, RateLimitConfig};
+use bytes::Bytes;
+use once_cell::sync::Lazy;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use url::Url;
+
+/// 未限制速率的主机被降速时使用的基础间隔
+const ADAPTIVE_BASE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 同步等待并发名额时的轮询间隔
+const BLOCKING_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 请求结果，用于调整主机的发送速率
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// 收到响应，retry_after取自Retry-After响应头
+    Response { status: u16, retry_after: Option<Duration> },
+    /// 请求超时
+    Timeout,
+    /// 连接失败等其他错误
+    Failed,
+}
+
+impl Outcome {
+    pub fn from_response(status: u16, headers: &HeaderMap) -> Self {
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        Outcome::Response { status, retry_after }
+    }
+
+    pub fn from_error(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Outcome::Timeout
+        } else {
+            Outcome::Failed
+        }
+    }
+}
+
+/// 单个主机的发送计划
+#[derive(Debug)]
+struct HostSchedule {
+    /// 下一个请求最早的发送时间
+    next_slot: Instant,
+    /// 限流退避结束时间
+    backoff_until: Option<Instant>,
+    /// 连续收到429/503的次数
+    consecutive_backoffs: u32,
+    /// 当前降速倍数，1表示按配置速率发送
+    slowdown: f64,
+}
+
+impl HostSchedule {
+    fn new(now: Instant) -> Self {
+        Self {
+            next_slot: now,
+            backoff_until: None,
+            consecutive_backoffs: 0,
+            slowdown: 1.0,
+        }
+    }
+
+    /// 当前的请求间隔
+    fn interval(&self, config: &RateLimitConfig) -> Duration {
+        let base = if config.requests_per_second > 0.0 {
+            Duration::from_secs_f64(1.0 / config.requests_per_second)
+        } else if self.slowdown > 1.0 {
+            ADAPTIVE_BASE_INTERVAL
+        } else {
+            Duration::ZERO
+        };
+        base.mul_f64(self.slowdown)
+    }
+
+    /// 预约下一个发送时间
+    fn reserve(&mut self, config: &RateLimitConfig, now: Instant) -> Instant {
+        let mut slot = self.next_slot.max(now);
+        if let Some(until) = self.backoff_until {
+            slot = slot.max(until);
+        }
+        self.next_slot = slot + self.interval(config);
+        slot
+    }
+
+    /// 根据请求结果调整发送速率
+    fn record(&mut self, config: &RateLimitConfig, outcome: Outcome, now: Instant) {
+        let max_slowdown = config.max_slowdown.max(1.0);
+        match outcome {
+            Outcome::Response { status: 429 | 503, retry_after } => {
+                self.consecutive_backoffs += 1;
+                let exponent = (self.consecutive_backoffs - 1).min(16);
+                let backoff = Duration::from_millis(config.backoff_base_ms.saturating_mul(1 << exponent));
+                let delay = retry_after.unwrap_or(backoff).min(Duration::from_millis(config.backoff_max_ms));
+                self.backoff_until = Some(now + delay);
+                if config.adaptive {
+                    self.slowdown = (self.slowdown * 2.0).min(max_slowdown);
+                }
+            }
+            Outcome::Response { .. } => {
+                self.consecutive_backoffs = 0;
+                self.slowdown = (self.slowdown * 0.9).max(1.0);
+            }
+            Outcome::Timeout => {
+                if config.adaptive {
+                    self.slowdown = (self.slowdown * 1.5).min(max_slowdown);
+                }
+            }
+            Outcome::Failed => {}
+        }
+    }
+}
+
+/// 单个主机的限速器
+struct HostLimiter {
+    in_flight: Option<Arc<Semaphore>>,
+    schedule: Mutex<HostSchedule>,
+}
+
+/// 发送许可，释放时归还主机的并发名额
+pub struct GovernorPermit {
+    limiter: Option<Arc<HostLimiter>>,
+    config: RateLimitConfig,
+    _in_flight: Option<OwnedSemaphorePermit>,
+}
+
+impl GovernorPermit {
+    /// 记录请求结果
+    pub fn record(&self, outcome: Outcome) {
+        if let Some(limiter) = &self.limiter {
+            limiter.schedule.lock().unwrap().record(&self.config, outcome, Instant::now());
+        }
+    }
+
+    /// 根据请求结果记录
+    pub fn record_result<T>(&self, result: &reqwest::Result<T>, response: impl Fn(&T) -> Outcome) {
+        match result {
+            Ok(value) => self.record(response(value)),
+            Err(e) => self.record(Outcome::from_error(e)),
+        }
+    }
+}
+
+/// 经调度器收到的响应，持有发送许可直到响应体读取完成
+pub struct GovernedResponse<R> {
+    response: R,
+    permit: GovernorPermit,
+}
+
+impl<R> Deref for GovernedResponse<R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.response
+    }
+}
+
+impl GovernedResponse<reqwest::Response> {
+    /// 读取响应体，读取超时同样计入主机的发送速率
+    pub async fn bytes(self) -> reqwest::Result<Bytes> {
+        let result = self.response.bytes().await;
+        if let Err(e) = &result {
+            self.permit.record(Outcome::from_error(e));
+        }
+        result
+    }
+
+    pub async fn text(self) -> reqwest::Result<String> {
+        let result = self.response.text().await;
+        if let Err(e) = &result {
+            self.permit.record(Outcome::from_error(e));
+        }
+        result
+    }
+}
+
+impl GovernedResponse<reqwest::blocking::Response> {
+    pub fn bytes(self) -> reqwest::Result<Bytes> {
+        let result = self.response.bytes();
+        if let Err(e) = &result {
+            self.permit.record(Outcome::from_error(e));
+        }
+        result
+    }
+
+    pub fn text(self) -> reqwest::Result<String> {
+        let result = self.response.text();
+        if let Err(e) = &result {
+            self.permit.record(Outcome::from_error(e));
+        }
+        result
+    }
+}
+
+/// 出站请求调度器
+pub struct Governor {
+    config: RwLock<RateLimitConfig>,
+    hosts: Mutex<HashMap<String, Arc<HostLimiter>>>,
+}
+
+static GOVERNOR: Lazy<Governor> = Lazy::new(|| Governor::new(AppConfig::default().rate_limit));
+
+impl Governor {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 全局调度器，使用配置文件中的限速配置
+    pub fn global() -> &'static Governor {
+        &GOVERNOR
+    }
+
+    pub fn config(&self) -> RateLimitConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    /// 更新限速配置，各主机的发送状态重新计算
+    pub fn update_config(&self, config: RateLimitConfig) {
+        *self.config.write().unwrap() = config;
+        self.hosts.lock().unwrap().clear();
+    }
+
+    fn limiter(&self, host: &str, config: &RateLimitConfig) -> Arc<HostLimiter> {
+        self.hosts
+            .lock()
+            .unwrap()
+            .entry(host.to_string())
+            .or_insert_with(|| {
+                Arc::new(HostLimiter {
+                    in_flight: (config.max_in_flight > 0).then(|| Arc::new(Semaphore::new(config.max_in_flight))),
+                    schedule: Mutex::new(HostSchedule::new(Instant::now())),
+                })
+            })
+            .clone()
+    }
+
+    /// 等待向主机发送请求的许可
+    pub async fn acquire(&self, host: &str) -> GovernorPermit {
+        let config = self.config();
+        if !config.enabled {
+            return GovernorPermit { limiter: None, config, _in_flight: None };
+        }
+
+        let limiter = self.limiter(host, &config);
+        let in_flight = match &limiter.in_flight {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        let slot = limiter.schedule.lock().unwrap().reserve(&config, Instant::now());
+        tokio::time::sleep_until(slot.into()).await;
+
+        GovernorPermit { limiter: Some(limiter), config, _in_flight: in_flight }
+    }
+
+    /// 同步等待发送许可，用于阻塞式HTTP客户端，到截止时间仍未获得许可时返回None
+    pub fn acquire_blocking(&self, host: &str, deadline: Option<Instant>) -> Option<GovernorPermit> {
+        let config = self.config();
+        if !config.enabled {
+            return Some(GovernorPermit { limiter: None, config, _in_flight: None });
+        }
+
+        let limiter = self.limiter(host, &config);
+        let in_flight = match &limiter.in_flight {
+            Some(semaphore) => Some(loop {
+                if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+                    break permit;
+                }
+                if deadline.is_some_and(|d| Instant::now() >= d) {
+                    return None;
+                }
+                std::thread::sleep(BLOCKING_POLL_INTERVAL);
+            }),
+            None => None,
+        };
+        let slot = limiter.schedule.lock().unwrap().reserve(&config, Instant::now());
+        if deadline.is_some_and(|d| slot >= d) {
+            return None;
+        }
+        std::thread::sleep(slot.saturating_duration_since(Instant::now()));
+
+        Some(GovernorPermit { limiter: Some(limiter), config, _in_flight: in_flight })
+    }
+}
+
+/// 解析秒数形式的Retry-After响应头
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+/// 限速使用的主机标识（host:port）
+pub fn host_key(url: &Url) -> String {
+    format!(
+        "{}:{}",
+        url.host_str().unwrap_or_default(),
+        url.port_or_known_default().unwrap_or_default()
+    )
+}
+
+/// 经调度器发送请求
+pub async fn send(builder: reqwest::RequestBuilder) -> reqwest::Result<GovernedResponse<reqwest::Response>> {
+    send_timed(builder).await.map(|(response, _)| response)
+}
+
+/// 经调度器发送请求，同时返回实际开始发送的时间，用于计算不含排队等待的耗时
+pub async fn send_timed(
+    builder: reqwest::RequestBuilder,
+) -> reqwest::Result<(GovernedResponse<reqwest::Response>, Instant)> {
+    let (client, request) = builder.build_split();
+    let request = request?;
+    let permit = Governor::global().acquire(&host_key(request.url())).await;
+
+    let started = Instant::now();
+    let result = client.execute(request).await;
+    permit.record_result(&result, |r| Outcome::from_response(r.status().as_u16(), r.headers()));
+    result.map(|response| (GovernedResponse { response, permit }, started))
+}
+
+/// 经调度器发送阻塞式请求
+///
+/// 设置deadline时，排队等待和请求本身都不会超过截止时间，请求超时缩短为剩余时间。
+pub fn send_blocking(
+    builder: reqwest::blocking::RequestBuilder,
+    deadline: Option<Instant>,
+) -> anyhow::Result<GovernedResponse<reqwest::blocking::Response>> {
+    let (client, request) = builder.build_split();
+    let mut request = request?;
+    let permit = Governor::global()
+        .acquire_blocking(&host_key(request.url()), deadline)
+        .ok_or_else(|| anyhow::anyhow!("等待发送许可超过截止时间"))?;
+    if let Some(deadline) = deadline {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let timeout = request.timeout_mut();
+        *timeout = Some(timeout.map_or(remaining, |t| t.min(remaining)));
+    }
+
+    let result = client.execute(request);
+    permit.record_result(&result, |r| Outcome::from_response(r.status().as_u16(), r.headers()));
+    Ok(GovernedResponse { response: result?, permit })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_spacing_and_backoff() {
+        let config = RateLimitConfig {
+            requests_per_second: 10.0,
+            ..Default::default()
+        };
+        let now = Instant::now();
+        let mut schedule = HostSchedule::new(now);
+        assert_eq!(schedule.reserve(&config, now), now);
+        assert_eq!(schedule.reserve(&config, now), now + Duration::from_millis(100));
+
+        // 429按Retry-After退避并降速
+        let outcome = Outcome::Response { status: 429, retry_after: Some(Duration::from_secs(2)) };
+        schedule.record(&config, outcome, now);
+        assert_eq!(schedule.reserve(&config, now), now + Duration::from_secs(2));
+        assert_eq!(schedule.slowdown, 2.0);
+
+        // 没有Retry-After时指数退避
+        schedule.record(&config, Outcome::Response { status: 503, retry_after: None }, now);
+        assert_eq!(schedule.backoff_until, Some(now + Duration::from_millis(2000)));
+
+        // 正常响应后逐步恢复
+        schedule.record(&config, Outcome::Response { status: 200, retry_after: None }, now);
+        assert_eq!(schedule.consecutive_backoffs, 0);
+        assert!(schedule.slowdown < 4.0);
+    }
+
+    #[test]
+    fn test_unlimited_host_slows_down_on_timeout() {
+        let config = RateLimitConfig {
+            requests_per_second: 0.0,
+            ..Default::default()
+        };
+        let now = Instant::now();
+        let mut schedule = HostSchedule::new(now);
+        assert_eq!(schedule.interval(&config), Duration::ZERO);
+
+        for _ in 0..10 {
+            schedule.record(&config, Outcome::Timeout, now);
+        }
+        assert_eq!(schedule.slowdown, config.max_slowdown);
+        assert_eq!(schedule.interval(&config), ADAPTIVE_BASE_INTERVAL.mul_f64(config.max_slowdown));
+    }
+
+    #[test]
+    fn test_host_key() {
+        let url = Url::parse("https://example.com/a?b=1").unwrap();
+        assert_eq!(host_key(&url), "example.com:443");
+        let url = Url::parse("http://127.0.0.1:8080/").unwrap();
+        assert_eq!(host_key(&url), "127.0.0.1:8080");
+    }
+}
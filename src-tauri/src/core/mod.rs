@@ -1,5 +1,6 @@
 pub mod config;
 pub mod utils;
 pub mod proxy;
+pub mod governor;
 
 pub use config::AppConfig;
@@ -0,0 +1,290 @@
+//! 每次启动时执行的数据库迁移
+//!
+//! init_db只在首次运行时执行，后续版本新增的表、列和索引放在这里，
+//! 所有语句都可以重复执行，已有数据库升级后也能使用新功能。
+
+use crate::internal::file::get_db_path;
+use log::error;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+
+/// 新增的表，(名称, 建表语句)
+const TABLES: &[(&str, &str)] = &[
+    (
+        "oob",
+        r#"
+        CREATE TABLE IF NOT EXISTS oob_token (
+            token     TEXT PRIMARY KEY,
+            owner     TEXT,
+            create_at INTEGER
+        );
+        CREATE TABLE IF NOT EXISTS oob_interaction (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            token       TEXT NOT NULL,
+            protocol    TEXT,
+            remote_addr TEXT,
+            summary     TEXT,
+            raw         TEXT,
+            create_at   INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS oob_interaction_token_IDX ON oob_interaction (token);
+        "#,
+    ),
+    (
+        "graphql_schema",
+        r#"
+        CREATE TABLE IF NOT EXISTS graphql_schema (
+            id        INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id   INTEGER,
+            endpoint  TEXT NOT NULL,
+            source    TEXT,
+            schema    TEXT,
+            operations TEXT,
+            update_at INTEGER
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS graphql_schema_endpoint_IDX ON graphql_schema (task_id, endpoint);
+        "#,
+    ),
+    (
+        "scan_vulnerability",
+        r#"
+        CREATE TABLE IF NOT EXISTS scan_vulnerability (
+            id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+            vulnerability_type TEXT NOT NULL,
+            name               TEXT NOT NULL DEFAULT '',
+            url                TEXT,
+            host               TEXT NOT NULL,
+            path_template      TEXT NOT NULL,
+            parameter          TEXT NOT NULL DEFAULT '',
+            value              TEXT,
+            risk_level         TEXT,
+            description        TEXT,
+            solution           TEXT,
+            evidence           TEXT,
+            details            TEXT,
+            confidence         TEXT NOT NULL DEFAULT 'firm',
+            status             TEXT NOT NULL DEFAULT 'new',
+            occurrences        INTEGER NOT NULL DEFAULT 1,
+            first_seen         INTEGER,
+            last_seen          INTEGER
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS scan_vulnerability_key_IDX ON scan_vulnerability (vulnerability_type, host, path_template, parameter, name);
+        "#,
+    ),
+    (
+        "active_scan_queue",
+        r#"
+        CREATE TABLE IF NOT EXISTS active_scan_queue (
+            id               TEXT PRIMARY KEY,
+            scan_id          TEXT NOT NULL,
+            kind             TEXT NOT NULL,
+            target           TEXT NOT NULL,
+            phase            TEXT,
+            insertion_point  TEXT,
+            payload_index    INTEGER NOT NULL DEFAULT 0,
+            completed_points TEXT,
+            status           TEXT NOT NULL DEFAULT 'pending',
+            data             TEXT NOT NULL,
+            created_at       INTEGER,
+            updated_at       INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS active_scan_queue_scan_IDX ON active_scan_queue (scan_id);
+        "#,
+    ),
+    (
+        "scan_profile",
+        r#"
+        CREATE TABLE IF NOT EXISTS scan_profile (
+            name        TEXT PRIMARY KEY,
+            description TEXT,
+            content     TEXT NOT NULL,
+            created_at  INTEGER,
+            updated_at  INTEGER
+        );
+        "#,
+    ),
+    (
+        "vulndb",
+        r#"
+        CREATE TABLE IF NOT EXISTS cve_feed (
+            path        TEXT PRIMARY KEY,
+            format      TEXT,
+            modified    INTEGER,
+            entries     INTEGER,
+            imported_at INTEGER
+        );
+        CREATE TABLE IF NOT EXISTS cve_entry (
+            id         TEXT NOT NULL,
+            feed       TEXT NOT NULL,
+            summary    TEXT,
+            cvss_score REAL,
+            severity   TEXT,
+            published  TEXT,
+            UNIQUE (id, feed)
+        );
+        CREATE TABLE IF NOT EXISTS cve_affected (
+            cve_id                  TEXT NOT NULL,
+            feed                    TEXT NOT NULL,
+            vendor                  TEXT,
+            product                 TEXT NOT NULL,
+            version                 TEXT,
+            version_start_including TEXT,
+            version_start_excluding TEXT,
+            version_end_including   TEXT,
+            version_end_excluding   TEXT
+        );
+        CREATE INDEX IF NOT EXISTS cve_affected_product_IDX ON cve_affected (product);
+        "#,
+    ),
+];
+
+/// 列不存在时添加
+async fn add_column(pool: &SqlitePool, table: &str, column: &str, definition: &str) -> Result<(), sqlx::Error> {
+    let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+        .bind(table)
+        .bind(column)
+        .fetch_one(pool)
+        .await?;
+    if exists == 0 {
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// 已有表新增的列，(表, 列, 类型)
+const COLUMNS: &[(&str, &str, &str)] = &[
+    ("api", "headers", "TEXT"),
+    ("api", "body", "TEXT"),
+    ("webcomp", "comp_version", "TEXT"),
+];
+
+/// 定义变化的索引，(索引, 新定义中的特征片段, 建索引语句)
+///
+/// - 导入的接口按请求方法区分，旧索引只包含uri和ufrom
+/// - 漏洞去重键加入名称，同一位置的不同检查项（被动检查、规则、JWT）分别记录
+const INDEXES: &[(&str, &str, &str)] = &[
+    (
+        "api_uri_IDX",
+        "IFNULL",
+        "CREATE UNIQUE INDEX api_uri_IDX ON api (uri, ufrom, IFNULL(method, ''))",
+    ),
+    (
+        "scan_vulnerability_key_IDX",
+        "parameter, name)",
+        "CREATE UNIQUE INDEX scan_vulnerability_key_IDX ON scan_vulnerability (vulnerability_type, host, path_template, parameter, name)",
+    ),
+];
+
+/// 索引定义不含特征片段时重建
+async fn migrate_index(pool: &SqlitePool, index: &str, marker: &str, create: &str) -> Result<(), sqlx::Error> {
+    let sql: Option<String> = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'index' AND name = ?")
+        .bind(index)
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+    if sql.is_some_and(|sql| sql.contains(marker)) {
+        return Ok(());
+    }
+    sqlx::query(&format!("DROP INDEX IF EXISTS {}; {};", index, create)).execute(pool).await?;
+    Ok(())
+}
+
+/// 执行全部迁移，单项失败只记录日志
+async fn migrate(pool: &SqlitePool) {
+    for (name, sql) in TABLES {
+        if let Err(e) = sqlx::query(sql).execute(pool).await {
+            error!("数据库迁移 {} 失败: {}", name, e);
+        }
+    }
+    for (table, column, definition) in COLUMNS {
+        if let Err(e) = add_column(pool, table, column, definition).await {
+            error!("数据库迁移 {}.{} 失败: {}", table, column, e);
+        }
+    }
+    for (index, marker, create) in INDEXES {
+        if let Err(e) = migrate_index(pool, index, marker, create).await {
+            error!("数据库迁移 {} 失败: {}", index, e);
+        }
+    }
+}
+
+pub async fn migrate_db() {
+    let options = SqliteConnectOptions::new()
+        .filename(get_db_path())
+        .create_if_missing(true);
+    let pool = match SqlitePool::connect_with(options).await {
+        Ok(pool) => pool,
+        Err(e) => {
+            error!("数据库迁移连接失败: {}", e);
+            return;
+        }
+    };
+    migrate(&pool).await;
+    pool.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_migrate_is_idempotent() {
+        // 内存数据库每个连接相互独立，只使用一个连接
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrate(&pool).await;
+        migrate(&pool).await;
+
+        let tables: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('oob_token', 'oob_interaction')",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(tables, 2);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_existing_api_table() {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE api (id INTEGER PRIMARY KEY AUTOINCREMENT, method TEXT, uri TEXT NOT NULL, ufrom TEXT); \
+             CREATE UNIQUE INDEX api_uri_IDX ON api (uri,ufrom);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        migrate(&pool).await;
+        migrate(&pool).await;
+
+        sqlx::query("INSERT INTO api (method, uri, ufrom, headers, body) VALUES ('GET', '/a', 'x', '{}', ''), ('POST', '/a', 'x', '{}', '')")
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_migrate_vulnerability_key() {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE scan_vulnerability (id INTEGER PRIMARY KEY AUTOINCREMENT, vulnerability_type TEXT NOT NULL, name TEXT, \
+             host TEXT NOT NULL, path_template TEXT NOT NULL, parameter TEXT NOT NULL DEFAULT ''); \
+             CREATE UNIQUE INDEX scan_vulnerability_key_IDX ON scan_vulnerability (vulnerability_type, host, path_template, parameter);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        migrate(&pool).await;
+        migrate(&pool).await;
+
+        // 同一位置的不同检查项分别记录
+        sqlx::query(
+            "INSERT INTO scan_vulnerability (vulnerability_type, name, host, path_template, parameter) \
+             VALUES ('Passive', 'a', 'h', '/', ''), ('Passive', 'b', 'h', '/', '')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+}
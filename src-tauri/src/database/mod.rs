@@ -9,6 +9,9 @@ use dirs;
 
 use sqlx::query;
 
+mod migrate;
+pub use migrate::migrate_db;
+
 pub async fn init_db() {
     println!("Initializing database");
 
@@ -153,10 +156,12 @@ pub async fn init_db() {
             url TEXT,
             get_response TEXT,
             post_response TEXT,
+            headers TEXT,
+            body TEXT,
             UNIQUE (id)
         );
         CREATE INDEX idx_api_update_at ON api(update_at DESC);
-        CREATE UNIQUE INDEX api_uri_IDX ON api (uri,ufrom);
+        CREATE UNIQUE INDEX api_uri_IDX ON api (uri,ufrom,IFNULL(method, ''));
         COMMIT;
         "#
     )
@@ -342,6 +347,7 @@ pub async fn init_db() {
             task_id  INTEGER NOT NULL,
             website  TEXT,
             comp_name TEXT,
+            comp_version TEXT,
             ctype TEXT,
             create_at INTEGER,
             update_at INTEGER,
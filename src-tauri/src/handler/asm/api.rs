@@ -31,7 +31,9 @@ use url::Url;
 
 // use crate::{global::config::AppConfig, internal::html::extract_js_from_html};
 
+use crate::core::governor;
 use crate::{global::config::CoreConfig, internal::rsubdomain::handle};
+use crate::handler::scan::proxy::HttpRequest;
 
 use super::asm_task::INNERASK_MODULE;
 
@@ -57,6 +59,14 @@ pub struct ApiInfo {
     pub handle_status: i64,
     pub get_body_length: i64,
     pub post_body_length: i64,
+    /// 导入的API定义中的请求头（JSON）
+    #[sqlx(default)]
+    #[serde(default)]
+    pub headers: Option<String>,
+    /// 导入的API定义中的请求体
+    #[sqlx(default)]
+    #[serde(default)]
+    pub body: Option<String>,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
@@ -200,6 +210,42 @@ pub async fn collection_api(task_id: &i32, apis: &Vec<ApiInfo>) -> Result<(), Bo
     Ok(())
 }
 
+/// 保存从API定义导入的请求，返回新增的数量
+pub async fn save_imported_apis(task_id: &i32, requests: &[HttpRequest], source: &str) -> Result<usize, Box<dyn Error>> {
+    let task_module = match INNERASK_MODULE.get() {
+        Some(tm) => tm,
+        None => return Err("Global variable not initialized".into()),
+    };
+    let write_conn: Arc<SqlitePool> = Arc::clone(&task_module.write_conn);
+    let now = chrono::Local::now().timestamp();
+    let mut tx: sqlx::Transaction<'_, sqlx::Sqlite> = write_conn.begin().await?;
+
+    let mut saved = 0;
+    for request in requests {
+        let uri = match Url::parse(&request.url) {
+            Ok(url) => url.path().to_string(),
+            Err(_) => continue,
+        };
+        let result = sqlx::query(
+            "INSERT INTO api (task_id,method,uri,ufrom,url,headers,body,update_at) VALUES (?,?,?,?,?,?,?,?) ON CONFLICT DO NOTHING",
+        )
+        .bind(task_id)
+        .bind(&request.method)
+        .bind(&uri)
+        .bind(source)
+        .bind(&request.url)
+        .bind(serde_json::to_string(&request.headers)?)
+        .bind(String::from_utf8_lossy(&request.body).to_string())
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        saved += result.rows_affected() as usize;
+    }
+
+    tx.commit().await?;
+    Ok(saved)
+}
+
 pub async fn scan_api(task_id: &i32) -> Result<(), Box<dyn Error>> {
     //获取当前扫描任务的所有API信息
     //提取ufrom的网站前缀
@@ -257,11 +303,10 @@ pub async fn scan_api(task_id: &i32) -> Result<(), Box<dyn Error>> {
             let _permit = semaphore_clone.acquire().await;
             // GET请求
             // println!("scan api: {}", api_clone.url);
-            match client_clone.get(&api_clone.url)
+            match governor::send(client_clone.get(&api_clone.url)
                 .header("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)...")
                 .header("Referer", "https://www.mgtv.com/")
-                .header("Accept", "application/json")
-                .send().await {
+                .header("Accept", "application/json")).await {
                 Ok(response) => {
                     if response.status().is_success() {
                         api_clone.http_status = response.status().as_u16() as i64;
@@ -299,11 +344,10 @@ pub async fn scan_api(task_id: &i32) -> Result<(), Box<dyn Error>> {
                 }
             }
 
-            match client_clone.post(&api_clone.url)
+            match governor::send(client_clone.post(&api_clone.url)
             .header("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)...")
             .header("Referer", "https://www.mgtv.com/")
-            .header("Accept", "application/json")
-            .send().await {
+            .header("Accept", "application/json")).await {
                 Ok(response) => {
                     if response.status().is_success() {
                         api_clone.http_status = response.status().as_u16() as i64;
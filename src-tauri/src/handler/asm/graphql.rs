@@ -0,0 +1,209 @@
+//GraphQL资产
+//1.按常见路径和响应结构识别站点上的GraphQL端点
+//2.内省或字段建议获取模式并保存
+//3.生成的操作交给SQL注入、XSS、越权扫描器测试
+//4.内省、批量查询、深度限制等配置问题写入风险
+
+use log::{error, info};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sqlx::{query_as, query_scalar, FromRow};
+use std::collections::HashMap;
+use std::{error::Error, sync::Arc};
+
+use super::asm_task::INNERASK_MODULE;
+use crate::core::config::AppConfig;
+use crate::global::config::CoreConfig;
+use crate::handler::scan::engine::ScanResult;
+use crate::handler::scan::graphql::{self, GraphqlReport};
+use crate::handler::scan::scanners::{create_scanner, ScannerType, ScannerTypeEnum};
+use crate::handler::scan::utils::http;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct GraphqlSchemaInfo {
+    pub id: Option<i32>,
+    pub task_id: Option<i32>,
+    pub endpoint: String,
+    pub source: Option<String>,
+    pub schema: Option<String>,
+    pub operations: Option<String>,
+    pub update_at: i64,
+}
+
+/// 获取全局http client
+fn http_client() -> Client {
+    CoreConfig::global()
+        .ok()
+        .and_then(|c| c.http_client.clone())
+        .unwrap_or_default()
+}
+
+/// 使用注入类和越权扫描器测试生成的操作
+async fn scan_operations(client: &Client, report: &GraphqlReport) -> Vec<ScanResult> {
+    let config = Arc::new(AppConfig::default());
+    let scanners: Vec<_> = [ScannerTypeEnum::SqlInjection, ScannerTypeEnum::Xss, ScannerTypeEnum::Authz]
+        .into_iter()
+        .map(|scanner_type| create_scanner(ScannerType { scanner_type }, config.clone()))
+        .collect();
+
+    let mut results = Vec::new();
+    for operation in &report.operations {
+        let request = operation.to_request(&report.endpoint, &HashMap::new());
+        let response = match http::send_request(client, &request).await {
+            Ok(response) => response,
+            Err(_) => continue,
+        };
+        for scanner in &scanners {
+            for mut result in scanner.scan(&request, &response).await {
+                result.details = Some(format!(
+                    "{}\nGraphQL操作: {}\n变量: {}",
+                    result.details.unwrap_or_default(),
+                    operation.query,
+                    operation.variables
+                ));
+                results.push(result);
+            }
+        }
+    }
+    results
+}
+
+/// 保存模式和风险
+async fn save_report(task_id: &i32, report: &GraphqlReport, results: &[ScanResult]) -> Result<(), Box<dyn Error>> {
+    let task_module = match INNERASK_MODULE.get() {
+        Some(tm) => tm,
+        None => return Err("Global variable not initialized".into()),
+    };
+    let write_conn = Arc::clone(&task_module.write_conn);
+    let now = chrono::Local::now().timestamp();
+    let mut tx = write_conn.begin().await?;
+
+    if let Some(schema) = &report.schema {
+        sqlx::query(
+            "INSERT INTO graphql_schema (task_id, endpoint, source, schema, operations, update_at) VALUES (?, ?, ?, ?, ?, ?) \
+             ON CONFLICT (task_id, endpoint) DO UPDATE SET source = excluded.source, schema = excluded.schema, operations = excluded.operations, update_at = excluded.update_at",
+        )
+        .bind(task_id)
+        .bind(&report.endpoint)
+        .bind(&schema.source)
+        .bind(serde_json::to_string(schema)?)
+        .bind(serde_json::to_string(&report.operations)?)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for result in report.findings.iter().chain(results.iter()) {
+        sqlx::query(
+            "INSERT INTO risk (task_id,risk_name,risk_type, risk_desc,risk_level,risk_detail,risk_status,response,ufrom,update_at) VALUES (?, ?,?,?,?,?,?,?,?,?) ON CONFLICT DO NOTHING",
+        )
+        .bind(task_id)
+        .bind(&result.name)
+        .bind("graphql")
+        .bind(&result.description)
+        .bind(&result.risk_level)
+        .bind(format!(
+            "{} {} {}",
+            result.url,
+            result.name,
+            result.parameter.clone().unwrap_or_default()
+        ))
+        .bind(0)
+        .bind(format!(
+            "{}\n{}",
+            result.evidence.clone().unwrap_or_default(),
+            result.details.clone().unwrap_or_default()
+        ))
+        .bind(&report.endpoint)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 扫描任务下所有站点的GraphQL端点
+pub async fn scan_graphql(task_id: &i32) -> Result<(), Box<dyn Error>> {
+    let task_module = match INNERASK_MODULE.get() {
+        Some(tm) => tm,
+        None => return Err("Global variable not initialized".into()),
+    };
+    let pool_clone = Arc::clone(&task_module.tauri_conn);
+
+    let mut targets: Vec<String> = query_scalar("SELECT url FROM website WHERE task_id = ?")
+        .bind(task_id)
+        .fetch_all(&*pool_clone)
+        .await?;
+    // API收集中路径像GraphQL的地址
+    let api_urls: Vec<String> = query_scalar(
+        "SELECT url FROM api WHERE task_id = ? AND url IS NOT NULL AND (uri LIKE '%graphql%' OR uri LIKE '%gql%')",
+    )
+    .bind(task_id)
+    .fetch_all(&*pool_clone)
+    .await?;
+    targets.extend(api_urls);
+
+    let client = http_client();
+    let mut endpoints: Vec<String> = Vec::new();
+    for target in &targets {
+        for endpoint in graphql::detect_endpoints(&client, target, &HashMap::new()).await {
+            if !endpoints.contains(&endpoint) {
+                endpoints.push(endpoint);
+            }
+        }
+    }
+    info!("发现 {} 个GraphQL端点", endpoints.len());
+
+    for endpoint in endpoints {
+        // 变更操作可能修改数据，自动扫描只测试查询
+        let report = graphql::analyze_endpoint(&client, &endpoint, HashMap::new(), false).await;
+        let results = scan_operations(&client, &report).await;
+        if let Err(e) = save_report(task_id, &report, &results).await {
+            error!("保存GraphQL扫描结果失败: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// 分析指定的GraphQL端点，不写入数据库
+#[tauri::command(rename_all = "snake_case")]
+pub async fn graphql_analyze(
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    include_mutations: Option<bool>,
+) -> Result<GraphqlReport, String> {
+    let client = http_client();
+    let headers = headers.unwrap_or_default();
+    let endpoint = match graphql::detect_endpoints(&client, &url, &headers).await.into_iter().next() {
+        Some(endpoint) => endpoint,
+        None => return Err("未发现GraphQL端点".to_string()),
+    };
+    Ok(graphql::analyze_endpoint(&client, &endpoint, headers, include_mutations.unwrap_or(false)).await)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_graphql_schemas(task_id: Option<i32>) -> Result<Vec<GraphqlSchemaInfo>, String> {
+    let task_module = match INNERASK_MODULE.get() {
+        Some(tm) => tm,
+        None => return Err("Global variable not initialized".into()),
+    };
+    let pool_clone = Arc::clone(&task_module.tauri_conn);
+
+    let schemas = match task_id {
+        Some(task_id) => query_as("SELECT * FROM graphql_schema WHERE task_id = ? ORDER BY update_at DESC")
+            .bind(task_id)
+            .fetch_all(&*pool_clone)
+            .await,
+        None => query_as("SELECT * FROM graphql_schema ORDER BY update_at DESC")
+            .fetch_all(&*pool_clone)
+            .await,
+    };
+
+    schemas.map_err(|e| {
+        error!("Failed to fetch GraphQL schemas: {}", e);
+        e.to_string()
+    })
+}
@@ -20,7 +20,7 @@ use super::internal::{fetch_domain_by_plugin, fetch_website, resolver_dns, resol
 use super::port::{self, scan_ports_by_plugin};
 use super::risk::{self, scan_risk_by_plugin};
 use super::web_comp;
-use super::{api, fetch_finger, port_scan_by_nmap};
+use super::{api, fetch_finger, graphql, port_scan_by_nmap, vulndb};
 
 // 任务结构体
 #[derive(Clone)]
@@ -291,6 +291,10 @@ impl InnerTaskModule {
             update_task_status(&task_id, "scan risk", None).await;
             // 原始风险扫描
             let _ = risk::risk_scan(&task_id).await;
+            // 组件和服务版本关联CVE
+            if let Err(e) = vulndb::match_task(&task_id).await {
+                error!("匹配CVE失败: {}", e);
+            }
             //插件风险扫描
             if config.risk_scan_plugin_status {
                 scan_risk_by_plugin(&task_id, &website).await;
@@ -301,6 +305,10 @@ impl InnerTaskModule {
             if let Err(e) = api::scan_api(&task_id).await {
                 error!("Failed to scan api: {}", e);
             }
+            // 扫描GraphQL端点
+            if let Err(e) = graphql::scan_graphql(&task_id).await {
+                error!("Failed to scan graphql: {}", e);
+            }
             // 任务完成，更新任务状态
             update_task_status(&task_id, "wait", Some(now)).await;
         }
@@ -481,6 +489,9 @@ impl InnerTaskModule {
                 // API扫描
                 update_task_status(&task_id, "scan api", None).await;
                 let _ = api::scan_api(&task_id).await;
+                if let Err(e) = graphql::scan_graphql(&task_id).await {
+                    error!("Failed to scan graphql: {}", e);
+                }
             }
             "webcomp" => {
                 // Web组件扫描
@@ -509,6 +520,10 @@ impl InnerTaskModule {
                 update_task_status(&task_id, "scan risk", None).await;
                 // 原始风险扫描
                 let _ =risk::risk_scan(&task_id).await;
+                // 组件和服务版本关联CVE
+                if let Err(e) = vulndb::match_task(&task_id).await {
+                    error!("匹配CVE失败: {}", e);
+                }
 
                 // 插件风险扫描
                 // if config.risk_scan_plugin_status {
@@ -26,6 +26,7 @@ use super::asm_task::INNERASK_MODULE;
 use super::domain::Domain;
 use super::ips::IPs;
 use super::website::WebSite;
+use crate::core::governor;
 use crate::global::config::CoreConfig;
 use crate::internal::finger::get_teamplate;
 use log::*;
@@ -575,17 +576,12 @@ async fn check_website(
     let http_url = format!("http://{}/", domain);
     let https_url = format!("https://{}/", domain);
 
-    let http_response = match client
-        .get(http_url.clone())
-        .headers(headers.clone())
-        .send()
-        .await
-    {
+    let http_response = match governor::send(client.get(http_url.clone()).headers(headers.clone())).await {
         Ok(res) => Some(res),
         Err(_) => None,
     };
 
-    let https_response = match client.get(https_url.clone()).headers(headers).send().await {
+    let https_response = match governor::send(client.get(https_url.clone()).headers(headers)).await {
         Ok(res) => Some(res),
         Err(_) => None,
     };
@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 pub mod api;
 pub mod domain;
+pub mod graphql;
 pub mod innertask;
 pub mod internal;
 pub mod ips;
@@ -17,6 +18,7 @@ pub mod plugin;
 pub mod plugin_commands;
 pub mod visualization;
 pub mod command;
+pub mod vulndb;
 
 
 pub use api::*;
@@ -162,6 +164,8 @@ pub mod asm_task {
 
         // initialize_conn().await.unwrap();
         task_module.start().await;
+        // 漏洞数据源文件更新后重新导入
+        tokio::spawn(vulndb::watch_feeds());
         // INNERASK_MODULE.start().await;
         // INNERASK_MODULE.query_task_status(1);
 
@@ -34,6 +34,7 @@ use oxc_span::SourceType;
 use tauri::Manager;
 
 use super::{asm_task::INNERASK_MODULE, WebSite};
+use crate::core::governor;
 use crate::{asm::api::ApiInfo, scan::scanners::XssScanner};
 use crate::{
     global::config::CoreConfig,
@@ -291,7 +292,7 @@ impl RiskScanner {
                         Err(_) => return,
                     };
 
-                    match client_clone.get(url_clone.clone()).send().await {
+                    match governor::send(client_clone.get(url_clone.clone())).await {
                         Ok(res) => {
                             let status = res.status();
                             let html = res.text().await.unwrap_or("".to_string());
@@ -626,7 +627,7 @@ impl RiskScanner {
             //     }
             // });
 
-            if let Ok(resp) = client.get(current_url.clone()).send().await {
+            if let Ok(resp) = governor::send(client.get(current_url.clone())).await {
                 let source_code: String;
                 if current_url.ends_with(".js") {
                     source_code = resp.text().await.unwrap_or("".to_string());
@@ -730,6 +731,8 @@ impl<'a> Visit<'a> for MethodVisitor<'a> {
                         handle_status: 0,
                         get_body_length: 0,
                         post_body_length: 0,
+                        headers: None,
+                        body: None,
                         update_at: chrono::Local::now().timestamp(),
                     });
                 }
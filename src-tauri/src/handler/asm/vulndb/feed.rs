@@ -0,0 +1,307 @@
+//! NVD/OSV数据源解析
+//!
+//! 支持NVD 1.1 JSON数据源（`CVE_Items`）、NVD 2.0 API导出（`vulnerabilities`）
+//! 以及OSV格式（单条记录、记录数组或`{"vulns": [...]}`）。
+
+use super::version::normalize;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 数据源格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    Nvd,
+    Osv,
+}
+
+impl FeedFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedFormat::Nvd => "nvd",
+            FeedFormat::Osv => "osv",
+        }
+    }
+}
+
+/// 受影响的产品和版本范围，version为精确版本，范围边界都为空时表示全部版本
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Affected {
+    pub vendor: String,
+    pub product: String,
+    pub version: Option<String>,
+    pub start_including: Option<String>,
+    pub start_excluding: Option<String>,
+    pub end_including: Option<String>,
+    pub end_excluding: Option<String>,
+}
+
+/// 漏洞条目
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CveEntry {
+    pub id: String,
+    pub summary: String,
+    pub cvss_score: Option<f64>,
+    /// 数据源给出的严重程度，没有CVSS分数时使用
+    pub severity: String,
+    pub published: String,
+    pub affected: Vec<Affected>,
+}
+
+fn text(value: &Value) -> Option<String> {
+    value.as_str().map(str::to_string).filter(|s| !s.is_empty())
+}
+
+/// 解析CPE 2.3名称，cpe:2.3:a:vendor:product:version:...
+fn parse_cpe(cpe: &str) -> Option<Affected> {
+    let fields: Vec<&str> = cpe.split(':').collect();
+    if fields.len() < 6 || fields[0] != "cpe" {
+        return None;
+    }
+    let version = match fields[5] {
+        "-" => return None,
+        "*" | "" => None,
+        v => Some(v.replace('\\', "")),
+    };
+    Some(Affected {
+        vendor: normalize(&fields[3].replace('\\', "")),
+        product: normalize(&fields[4].replace('\\', "")),
+        version,
+        ..Default::default()
+    })
+}
+
+/// 解析NVD配置中的cpeMatch/cpe_match条目
+fn parse_cpe_match(item: &Value, out: &mut Vec<Affected>) {
+    if item["vulnerable"].as_bool() == Some(false) {
+        return;
+    }
+    let cpe = item["criteria"].as_str().or_else(|| item["cpe23Uri"].as_str()).unwrap_or_default();
+    let Some(mut affected) = parse_cpe(cpe) else {
+        return;
+    };
+    affected.start_including = text(&item["versionStartIncluding"]);
+    affected.start_excluding = text(&item["versionStartExcluding"]);
+    affected.end_including = text(&item["versionEndIncluding"]);
+    affected.end_excluding = text(&item["versionEndExcluding"]);
+    if !out.contains(&affected) {
+        out.push(affected);
+    }
+}
+
+/// 递归解析NVD配置节点
+fn parse_nodes(nodes: &Value, out: &mut Vec<Affected>) {
+    for node in nodes.as_array().into_iter().flatten() {
+        for key in ["cpeMatch", "cpe_match"] {
+            for item in node[key].as_array().into_iter().flatten() {
+                parse_cpe_match(item, out);
+            }
+        }
+        parse_nodes(&node["children"], out);
+    }
+}
+
+fn english_description(list: &Value) -> String {
+    let items = list.as_array().cloned().unwrap_or_default();
+    items
+        .iter()
+        .find(|d| d["lang"] == "en")
+        .or_else(|| items.first())
+        .and_then(|d| text(&d["value"]))
+        .unwrap_or_default()
+}
+
+/// NVD 2.0 API导出
+fn parse_nvd2(item: &Value) -> Option<CveEntry> {
+    let cve = &item["cve"];
+    let id = text(&cve["id"])?;
+    let metrics = &cve["metrics"];
+    let metric = ["cvssMetricV40", "cvssMetricV31", "cvssMetricV30", "cvssMetricV2"]
+        .iter()
+        .find_map(|key| metrics[*key].as_array().and_then(|m| m.first()));
+    let mut affected = Vec::new();
+    for config in cve["configurations"].as_array().into_iter().flatten() {
+        parse_nodes(&config["nodes"], &mut affected);
+    }
+    Some(CveEntry {
+        id,
+        summary: english_description(&cve["descriptions"]),
+        cvss_score: metric.and_then(|m| m["cvssData"]["baseScore"].as_f64()),
+        severity: metric
+            .and_then(|m| text(&m["cvssData"]["baseSeverity"]).or_else(|| text(&m["baseSeverity"])))
+            .unwrap_or_default()
+            .to_lowercase(),
+        published: text(&cve["published"]).unwrap_or_default(),
+        affected,
+    })
+}
+
+/// NVD 1.1 JSON数据源
+fn parse_nvd1(item: &Value) -> Option<CveEntry> {
+    let cve = &item["cve"];
+    let id = text(&cve["CVE_data_meta"]["ID"])?;
+    let impact = &item["impact"];
+    let (score, severity) = if impact["baseMetricV3"].is_object() {
+        (
+            impact["baseMetricV3"]["cvssV3"]["baseScore"].as_f64(),
+            text(&impact["baseMetricV3"]["cvssV3"]["baseSeverity"]),
+        )
+    } else {
+        (
+            impact["baseMetricV2"]["cvssV2"]["baseScore"].as_f64(),
+            text(&impact["baseMetricV2"]["severity"]),
+        )
+    };
+    let mut affected = Vec::new();
+    parse_nodes(&item["configurations"]["nodes"], &mut affected);
+    Some(CveEntry {
+        id,
+        summary: english_description(&cve["description"]["description_data"]),
+        cvss_score: score,
+        severity: severity.unwrap_or_default().to_lowercase(),
+        published: text(&item["publishedDate"]).unwrap_or_default(),
+        affected,
+    })
+}
+
+/// OSV记录，优先使用CVE别名作为编号
+fn parse_osv(item: &Value) -> Option<CveEntry> {
+    let osv_id = text(&item["id"])?;
+    let id = item["aliases"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .find(|a| a.starts_with("CVE-"))
+        .map(str::to_string)
+        .unwrap_or(osv_id);
+
+    let mut affected = Vec::new();
+    for entry in item["affected"].as_array().into_iter().flatten() {
+        let package = &entry["package"];
+        let name = package["name"].as_str().unwrap_or_default();
+        // maven等生态的包名带组名，取最后一段作为产品名
+        let product = normalize(name.rsplit([':', '/']).next().unwrap_or(name));
+        if product.is_empty() {
+            continue;
+        }
+        // OSV的生态名不是CPE厂商，匹配时只比较产品名
+        let base = Affected { product, ..Default::default() };
+
+        for range in entry["ranges"].as_array().into_iter().flatten() {
+            if range["type"] == "GIT" {
+                continue;
+            }
+            // Some(None)表示从最早版本开始受影响
+            let mut start: Option<Option<String>> = None;
+            for event in range["events"].as_array().into_iter().flatten() {
+                if let Some(introduced) = text(&event["introduced"]) {
+                    start = Some(Some(introduced).filter(|v| v != "0"));
+                } else if let Some(fixed) = text(&event["fixed"]) {
+                    affected.push(Affected { start_including: start.take().flatten(), end_excluding: Some(fixed), ..base.clone() });
+                } else if let Some(last) = text(&event["last_affected"]) {
+                    affected.push(Affected { start_including: start.take().flatten(), end_including: Some(last), ..base.clone() });
+                }
+            }
+            // 只有introduced时表示之后的全部版本
+            if let Some(start) = start {
+                affected.push(Affected { start_including: start, ..base.clone() });
+            }
+        }
+        for version in entry["versions"].as_array().into_iter().flatten().filter_map(text) {
+            affected.push(Affected { version: Some(version), ..base.clone() });
+        }
+    }
+
+    let severity = text(&item["database_specific"]["severity"])
+        .or_else(|| item["affected"][0]["ecosystem_specific"]["severity"].as_str().map(str::to_string))
+        .unwrap_or_default()
+        .to_lowercase();
+    Some(CveEntry {
+        id,
+        summary: text(&item["summary"]).or_else(|| text(&item["details"])).unwrap_or_default(),
+        cvss_score: item["severity"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find_map(|s| s["score"].as_f64().or_else(|| s["score"].as_str().and_then(|v| v.parse().ok()))),
+        severity: if severity == "moderate" { "medium".to_string() } else { severity },
+        published: text(&item["published"]).unwrap_or_default(),
+        affected,
+    })
+}
+
+/// 解析数据源文件内容
+pub fn parse(content: &str) -> Result<(FeedFormat, Vec<CveEntry>)> {
+    let doc: Value = serde_json::from_str(content)?;
+    if let Some(items) = doc["vulnerabilities"].as_array() {
+        return Ok((FeedFormat::Nvd, items.iter().filter_map(parse_nvd2).collect()));
+    }
+    if let Some(items) = doc["CVE_Items"].as_array() {
+        return Ok((FeedFormat::Nvd, items.iter().filter_map(parse_nvd1).collect()));
+    }
+    let records: Vec<&Value> = match &doc {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) if doc["vulns"].is_array() => doc["vulns"].as_array().into_iter().flatten().collect(),
+        Value::Object(_) if doc["affected"].is_array() => vec![&doc],
+        _ => bail!("无法识别的漏洞数据源格式"),
+    };
+    Ok((FeedFormat::Osv, records.into_iter().filter_map(parse_osv).collect()))
+}
+
+/// 按CVSS分数换算风险等级，没有分数时使用数据源给出的严重程度
+pub fn risk_level(score: Option<f64>, severity: &str) -> &'static str {
+    match score {
+        Some(s) if s >= 9.0 => "critical",
+        Some(s) if s >= 7.0 => "high",
+        Some(s) if s >= 4.0 => "medium",
+        Some(s) if s > 0.0 => "low",
+        Some(_) => "info",
+        None => match severity {
+            "critical" => "critical",
+            "high" => "high",
+            "medium" | "moderate" => "medium",
+            "low" => "low",
+            _ => "info",
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nvd_and_osv() {
+        let nvd = r#"{"vulnerabilities": [{"cve": {
+            "id": "CVE-2021-41773",
+            "published": "2021-10-05T09:15:07.593",
+            "descriptions": [{"lang": "en", "value": "Path traversal in Apache HTTP Server 2.4.49"}],
+            "metrics": {"cvssMetricV31": [{"cvssData": {"baseScore": 7.5, "baseSeverity": "HIGH"}}]},
+            "configurations": [{"nodes": [{"cpeMatch": [
+                {"vulnerable": true, "criteria": "cpe:2.3:a:apache:http_server:2.4.49:*:*:*:*:*:*:*"},
+                {"vulnerable": false, "criteria": "cpe:2.3:o:fedoraproject:fedora:34:*:*:*:*:*:*:*"}
+            ]}]}]
+        }}]}"#;
+        let (format, entries) = parse(nvd).unwrap();
+        assert_eq!(format, FeedFormat::Nvd);
+        assert_eq!(entries[0].id, "CVE-2021-41773");
+        assert_eq!(entries[0].cvss_score, Some(7.5));
+        assert_eq!(entries[0].affected.len(), 1);
+        assert_eq!(entries[0].affected[0].product, "http_server");
+        assert_eq!(entries[0].affected[0].version.as_deref(), Some("2.4.49"));
+
+        let osv = r#"{"id": "GHSA-jfh8-c2jp-5v3q", "aliases": ["CVE-2021-44228"], "summary": "Log4Shell",
+            "database_specific": {"severity": "CRITICAL"},
+            "affected": [{"package": {"ecosystem": "Maven", "name": "org.apache.logging.log4j:log4j-core"},
+                "ranges": [{"type": "ECOSYSTEM", "events": [{"introduced": "2.0-beta9"}, {"fixed": "2.15.0"}]}]}]}"#;
+        let (format, entries) = parse(osv).unwrap();
+        assert_eq!(format, FeedFormat::Osv);
+        assert_eq!(entries[0].id, "CVE-2021-44228");
+        assert_eq!(entries[0].affected[0].product, "log4j_core");
+        assert_eq!(entries[0].affected[0].start_including.as_deref(), Some("2.0-beta9"));
+        assert_eq!(entries[0].affected[0].end_excluding.as_deref(), Some("2.15.0"));
+        assert_eq!(risk_level(entries[0].cvss_score, &entries[0].severity), "critical");
+    }
+}
@@ -0,0 +1,303 @@
+//! 离线漏洞知识库
+//!
+//! 导入NVD/OSV的JSON数据源，按CPE产品名和版本范围把指纹识别出的组件、
+//! 端口服务版本关联到CVE，匹配结果写入risk表，风险等级由CVSS分数换算。
+//! 数据源文件更新后由后台任务重新导入并重新匹配。
+
+mod feed;
+mod store;
+mod version;
+
+pub use feed::{risk_level, Affected, CveEntry, FeedFormat};
+pub use store::FeedInfo;
+
+use super::asm_task::INNERASK_MODULE;
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use version::{candidates, compare, split_product_version};
+
+/// 检查数据源文件更新的间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// 产品版本匹配到的漏洞
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CveMatch {
+    pub cve_id: String,
+    pub product: String,
+    pub version: String,
+    pub summary: String,
+    pub cvss_score: Option<f64>,
+    pub risk_level: String,
+}
+
+/// 版本是否在受影响范围内
+fn affects(range: &Affected, version: &str) -> bool {
+    if let Some(exact) = &range.version {
+        return compare(version, exact) == Ordering::Equal;
+    }
+    let bounds = [
+        (&range.start_including, [Ordering::Greater, Ordering::Equal]),
+        (&range.start_excluding, [Ordering::Greater, Ordering::Greater]),
+        (&range.end_including, [Ordering::Less, Ordering::Equal]),
+        (&range.end_excluding, [Ordering::Less, Ordering::Less]),
+    ];
+    bounds
+        .iter()
+        .all(|(bound, allowed)| bound.as_ref().is_none_or(|b| allowed.contains(&compare(version, b))))
+}
+
+/// 从识别结果中取出产品名和版本，version可以是单独的版本号或"Apache httpd 2.4.41"形式
+fn detected(name: &str, version: Option<&str>) -> Option<(String, String)> {
+    if let Some(value) = version.map(str::trim).filter(|v| !v.is_empty()) {
+        if let (product, Some(version)) = split_product_version(value) {
+            return Some((product, version));
+        }
+        if value.starts_with(|c: char| c.is_ascii_digit()) {
+            let version = value.split_whitespace().next().unwrap_or(value);
+            return Some((name.trim().to_string(), version.to_string())).filter(|(n, _)| !n.is_empty());
+        }
+    }
+    match split_product_version(name) {
+        (product, Some(version)) => Some((product, version)),
+        _ => None,
+    }
+}
+
+/// 查询产品版本对应的漏洞
+pub async fn lookup(product: &str, version: &str) -> Result<Vec<CveMatch>> {
+    let keys = candidates(product);
+    let products: Vec<String> = keys.iter().map(|k| k.product.clone()).collect();
+    let rows = store::find_affected(&products).await?;
+
+    let mut seen = HashSet::new();
+    let mut matches = Vec::new();
+    for row in rows {
+        let vendor = row.vendor.clone().unwrap_or_default();
+        let key_matched = keys.iter().any(|k| {
+            k.product == row.product && (vendor.is_empty() || k.vendor.as_ref().is_none_or(|v| *v == vendor))
+        });
+        if !key_matched || !affects(&row.range(), version) || !seen.insert(row.cve_id.clone()) {
+            continue;
+        }
+        matches.push(CveMatch {
+            risk_level: risk_level(row.cvss_score, row.severity.as_deref().unwrap_or_default()).to_string(),
+            cve_id: row.cve_id,
+            product: product.to_string(),
+            version: version.to_string(),
+            summary: row.summary.unwrap_or_default(),
+            cvss_score: row.cvss_score,
+        });
+    }
+    Ok(matches)
+}
+
+/// 将任务的Web组件和端口服务与知识库匹配，结果写入risk表，返回新增的风险数量
+///
+/// 已有的匹配结果更新风险等级、描述和详情，不再匹配的CVE风险会被删除
+pub async fn match_task(task_id: &i32) -> Result<usize> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let read_conn = Arc::clone(&task_module.read_conn);
+    let write_conn = Arc::clone(&task_module.write_conn);
+
+    // (资产, 产品名, 版本)
+    let mut assets: Vec<(String, String, String)> = Vec::new();
+    // 读取失败时直接返回，避免把已有的CVE风险当作不再匹配而删除
+    let comps: Vec<(Option<String>, Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT website, comp_name, comp_version FROM webcomp WHERE task_id = ?")
+            .bind(task_id)
+            .fetch_all(&*read_conn)
+            .await
+            .map_err(|e| anyhow!("读取Web组件失败: {}", e))?;
+    for (website, name, version) in comps {
+        if let Some((product, version)) = detected(&name.unwrap_or_default(), version.as_deref()) {
+            assets.push((website.unwrap_or_default(), product, version));
+        }
+    }
+    let ports: Vec<(Option<String>, Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT ip_addr, CAST(port AS TEXT), service, version FROM port WHERE task_id = ?",
+    )
+    .bind(task_id)
+    .fetch_all(&*read_conn)
+    .await
+    .map_err(|e| anyhow!("读取端口服务失败: {}", e))?;
+    for (ip, port, service, version) in ports {
+        if let Some((product, version)) = detected(&service.unwrap_or_default(), version.as_deref()) {
+            let asset = format!("{}:{}", ip.unwrap_or_default(), port.unwrap_or_default());
+            assets.push((asset, product, version));
+        }
+    }
+
+    let existing: Vec<(String,)> = sqlx::query_as("SELECT risk_detail FROM risk WHERE task_id = ? AND risk_type = 'cve'")
+        .bind(task_id)
+        .fetch_all(&*read_conn)
+        .await?;
+    let existing: HashSet<String> = existing.into_iter().map(|(detail,)| detail).collect();
+
+    let now = chrono::Local::now().timestamp();
+    let mut matched = HashSet::new();
+    let mut count = 0;
+    for (asset, product, version) in assets {
+        for m in lookup(&product, &version).await? {
+            let detail = format!("{} {}", asset, m.cve_id);
+            // 知识库更新后风险等级和描述可能变化，保留研判状态
+            sqlx::query(
+                "INSERT INTO risk (task_id,risk_name,risk_type, risk_desc,risk_level,risk_detail,risk_status,response,ufrom,update_at) VALUES (?, ?,?,?,?,?,?,?,?,?) \
+                 ON CONFLICT (task_id, risk_detail) DO UPDATE SET risk_name = excluded.risk_name, risk_desc = excluded.risk_desc, risk_level = excluded.risk_level, response = excluded.response, update_at = excluded.update_at",
+            )
+            .bind(task_id)
+            .bind(format!("{} {} {}", m.cve_id, m.product, m.version))
+            .bind("cve")
+            .bind(&m.summary)
+            .bind(&m.risk_level)
+            .bind(&detail)
+            .bind(0)
+            .bind(serde_json::to_string(&m)?)
+            .bind(&asset)
+            .bind(now)
+            .execute(&*write_conn)
+            .await?;
+            if !existing.contains(&detail) {
+                count += 1;
+            }
+            matched.insert(detail);
+        }
+    }
+
+    for detail in existing.difference(&matched) {
+        sqlx::query("DELETE FROM risk WHERE task_id = ? AND risk_type = 'cve' AND risk_detail = ?")
+            .bind(task_id)
+            .bind(detail)
+            .execute(&*write_conn)
+            .await?;
+    }
+    Ok(count)
+}
+
+fn modified_time(path: &str) -> Result<i64> {
+    let modified = std::fs::metadata(path)?.modified()?;
+    Ok(modified.duration_since(UNIX_EPOCH)?.as_secs() as i64)
+}
+
+/// 导入数据源文件，同一路径再次导入时替换原有条目
+pub async fn import_feed(path: &str) -> Result<FeedInfo> {
+    let modified = modified_time(path)?;
+    let content = tokio::fs::read_to_string(path).await?;
+    let (format, entries) = tokio::task::spawn_blocking(move || feed::parse(&content)).await??;
+    store::replace_feed(path, format, modified, &entries).await?;
+    info!("导入漏洞数据源 {}: {} 条", path, entries.len());
+    Ok(FeedInfo {
+        path: path.to_string(),
+        format: format.as_str().to_string(),
+        modified,
+        entries: entries.len() as i64,
+        imported_at: chrono::Local::now().timestamp(),
+    })
+}
+
+/// 对所有已有资产的任务重新匹配
+async fn match_all_tasks() -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let read_conn = Arc::clone(&task_module.read_conn);
+    let task_ids: Vec<i32> =
+        sqlx::query_scalar("SELECT DISTINCT task_id FROM webcomp UNION SELECT DISTINCT task_id FROM port")
+            .fetch_all(&*read_conn)
+            .await?;
+    for task_id in task_ids {
+        match match_task(&task_id).await {
+            Ok(count) => info!("任务 {} 匹配到 {} 个CVE", task_id, count),
+            Err(e) => error!("任务 {} 匹配CVE失败: {}", task_id, e),
+        }
+    }
+    Ok(())
+}
+
+/// 后台检查已导入的数据源文件，修改时间变化时重新导入并重新匹配
+pub async fn watch_feeds() {
+    loop {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+        let feeds = match store::list_feeds().await {
+            Ok(feeds) => feeds,
+            Err(e) => {
+                warn!("读取漏洞数据源失败: {}", e);
+                continue;
+            }
+        };
+        let mut updated = false;
+        for feed in feeds {
+            match modified_time(&feed.path) {
+                Ok(modified) if modified != feed.modified => match import_feed(&feed.path).await {
+                    Ok(_) => updated = true,
+                    Err(e) => error!("重新导入漏洞数据源 {} 失败: {}", feed.path, e),
+                },
+                Ok(_) => {}
+                Err(e) => warn!("漏洞数据源 {} 不可用: {}", feed.path, e),
+            }
+        }
+        if updated {
+            if let Err(e) = match_all_tasks().await {
+                error!("重新匹配CVE失败: {}", e);
+            }
+        }
+    }
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn import_vuln_feed(path: String) -> Result<FeedInfo, String> {
+    let info = import_feed(&path).await.map_err(|e| e.to_string())?;
+    if let Err(e) = match_all_tasks().await {
+        error!("重新匹配CVE失败: {}", e);
+    }
+    Ok(info)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_vuln_feeds() -> Result<Vec<FeedInfo>, String> {
+    store::list_feeds().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn remove_vuln_feed(path: String) -> Result<(), String> {
+    store::remove_feed(&path).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn lookup_cves(product: String, version: String) -> Result<Vec<CveMatch>, String> {
+    lookup(&product, &version).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn match_task_cves(task_id: i32) -> Result<usize, String> {
+    match_task(&task_id).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_affects_and_detected() {
+        let range = Affected {
+            product: "http_server".to_string(),
+            start_including: Some("2.4.0".to_string()),
+            end_excluding: Some("2.4.51".to_string()),
+            ..Default::default()
+        };
+        assert!(affects(&range, "2.4.49"));
+        assert!(!affects(&range, "2.4.51"));
+        assert!(!affects(&range, "2.2.34"));
+        assert!(affects(&Affected { product: "nginx".to_string(), ..Default::default() }, "1.18.0"));
+
+        assert_eq!(
+            detected("http", Some("Apache httpd 2.4.41 ((Ubuntu))")),
+            Some(("Apache httpd".to_string(), "2.4.41".to_string()))
+        );
+        assert_eq!(detected("jQuery", Some("1.12.4")), Some(("jQuery".to_string(), "1.12.4".to_string())));
+        assert_eq!(detected("nginx/1.18.0", None), Some(("nginx".to_string(), "1.18.0".to_string())));
+        assert_eq!(detected("http", None), None);
+    }
+}
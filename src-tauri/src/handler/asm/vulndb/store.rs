@@ -0,0 +1,149 @@
+//! 漏洞知识库的存储
+//!
+//! 每个数据源文件的条目按文件路径整体替换，重新导入同一文件不会产生重复记录。
+
+use super::feed::{Affected, CveEntry, FeedFormat};
+use crate::handler::asm::asm_task::INNERASK_MODULE;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, Sqlite};
+use std::sync::Arc;
+
+/// 已导入的数据源
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct FeedInfo {
+    pub path: String,
+    pub format: String,
+    /// 导入时文件的修改时间
+    pub modified: i64,
+    pub entries: i64,
+    pub imported_at: i64,
+}
+
+/// 受影响产品及所属漏洞
+#[derive(Debug, Clone, FromRow)]
+pub struct AffectedRow {
+    pub cve_id: String,
+    pub vendor: Option<String>,
+    pub product: String,
+    pub version: Option<String>,
+    pub version_start_including: Option<String>,
+    pub version_start_excluding: Option<String>,
+    pub version_end_including: Option<String>,
+    pub version_end_excluding: Option<String>,
+    pub summary: Option<String>,
+    pub cvss_score: Option<f64>,
+    pub severity: Option<String>,
+}
+
+impl AffectedRow {
+    pub fn range(&self) -> Affected {
+        Affected {
+            vendor: self.vendor.clone().unwrap_or_default(),
+            product: self.product.clone(),
+            version: self.version.clone(),
+            start_including: self.version_start_including.clone(),
+            start_excluding: self.version_start_excluding.clone(),
+            end_including: self.version_end_including.clone(),
+            end_excluding: self.version_end_excluding.clone(),
+        }
+    }
+}
+
+/// 用数据源的最新内容替换已有条目
+pub async fn replace_feed(path: &str, format: FeedFormat, modified: i64, entries: &[CveEntry]) -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let write_conn = Arc::clone(&task_module.write_conn);
+    let mut tx = write_conn.begin().await?;
+
+    sqlx::query("DELETE FROM cve_affected WHERE feed = ?").bind(path).execute(&mut *tx).await?;
+    sqlx::query("DELETE FROM cve_entry WHERE feed = ?").bind(path).execute(&mut *tx).await?;
+
+    for entry in entries {
+        sqlx::query(
+            "INSERT INTO cve_entry (id, feed, summary, cvss_score, severity, published) VALUES (?, ?, ?, ?, ?, ?) ON CONFLICT DO NOTHING",
+        )
+        .bind(&entry.id)
+        .bind(path)
+        .bind(&entry.summary)
+        .bind(entry.cvss_score)
+        .bind(&entry.severity)
+        .bind(&entry.published)
+        .execute(&mut *tx)
+        .await?;
+
+        for affected in &entry.affected {
+            sqlx::query(
+                "INSERT INTO cve_affected (cve_id, feed, vendor, product, version, version_start_including, version_start_excluding, version_end_including, version_end_excluding) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&entry.id)
+            .bind(path)
+            .bind(&affected.vendor)
+            .bind(&affected.product)
+            .bind(&affected.version)
+            .bind(&affected.start_including)
+            .bind(&affected.start_excluding)
+            .bind(&affected.end_including)
+            .bind(&affected.end_excluding)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    sqlx::query(
+        "INSERT INTO cve_feed (path, format, modified, entries, imported_at) VALUES (?, ?, ?, ?, ?) \
+         ON CONFLICT (path) DO UPDATE SET format = excluded.format, modified = excluded.modified, entries = excluded.entries, imported_at = excluded.imported_at",
+    )
+    .bind(path)
+    .bind(format.as_str())
+    .bind(modified)
+    .bind(entries.len() as i64)
+    .bind(chrono::Local::now().timestamp())
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 已导入的数据源列表
+pub async fn list_feeds() -> Result<Vec<FeedInfo>> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let read_conn = Arc::clone(&task_module.read_conn);
+    Ok(sqlx::query_as("SELECT * FROM cve_feed ORDER BY imported_at DESC").fetch_all(&*read_conn).await?)
+}
+
+/// 移除数据源及其条目
+pub async fn remove_feed(path: &str) -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let write_conn = Arc::clone(&task_module.write_conn);
+    let mut tx = write_conn.begin().await?;
+    for table in ["cve_affected", "cve_entry"] {
+        sqlx::query(&format!("DELETE FROM {} WHERE feed = ?", table)).bind(path).execute(&mut *tx).await?;
+    }
+    sqlx::query("DELETE FROM cve_feed WHERE path = ?").bind(path).execute(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 查询产品名对应的受影响条目
+pub async fn find_affected(products: &[String]) -> Result<Vec<AffectedRow>> {
+    if products.is_empty() {
+        return Ok(Vec::new());
+    }
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let read_conn = Arc::clone(&task_module.read_conn);
+
+    let mut builder = QueryBuilder::<Sqlite>::new(
+        "SELECT a.cve_id, a.vendor, a.product, a.version, a.version_start_including, a.version_start_excluding, \
+         a.version_end_including, a.version_end_excluding, e.summary, e.cvss_score, e.severity \
+         FROM cve_affected a JOIN cve_entry e ON e.id = a.cve_id AND e.feed = a.feed WHERE a.product IN (",
+    );
+    let mut separated = builder.separated(", ");
+    for product in products {
+        separated.push_bind(product);
+    }
+    separated.push_unseparated(")");
+    Ok(builder.build_query_as::<AffectedRow>().fetch_all(&*read_conn).await?)
+}
@@ -0,0 +1,185 @@
+//! 版本比较和产品名归一化
+
+use std::cmp::Ordering;
+
+/// 表示补丁或更新的后缀，排在同号版本之后（7.4p1 > 7.4）
+const POST_RELEASE: &[&str] = &["p", "patch", "sp", "u", "update", "r", "build"];
+
+/// 常见服务识别名称到CPE (vendor, product) 的映射
+const ALIASES: &[(&str, &str, &str)] = &[
+    ("apache_httpd", "apache", "http_server"),
+    ("apache_http_server", "apache", "http_server"),
+    ("apache", "apache", "http_server"),
+    ("httpd", "apache", "http_server"),
+    ("apache_tomcat", "apache", "tomcat"),
+    ("apache_tomcat_coyote_jsp_engine", "apache", "tomcat"),
+    ("microsoft_iis_httpd", "microsoft", "internet_information_services"),
+    ("microsoft_iis", "microsoft", "internet_information_services"),
+    ("iis", "microsoft", "internet_information_services"),
+    ("openssh", "openbsd", "openssh"),
+    ("nginx", "f5", "nginx"),
+    ("mysql", "oracle", "mysql"),
+    ("exim_smtpd", "exim", "exim"),
+    ("postfix_smtpd", "postfix", "postfix"),
+    ("jetty", "eclipse", "jetty"),
+    ("eclipse_jetty", "eclipse", "jetty"),
+    ("lighttpd", "lighttpd", "lighttpd"),
+    ("weblogic", "oracle", "weblogic_server"),
+    ("oracle_weblogic", "oracle", "weblogic_server"),
+];
+
+/// 匹配时使用的产品名，vendor为Some时要求厂商一致
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductKey {
+    pub vendor: Option<String>,
+    pub product: String,
+}
+
+/// 产品名归一化：小写，非字母数字字符替换为下划线
+pub fn normalize(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.trim().to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            out.push(c);
+        } else if !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    out.trim_matches('_').to_string()
+}
+
+/// 识别名称可能对应的CPE产品名
+pub fn candidates(name: &str) -> Vec<ProductKey> {
+    let norm = normalize(name);
+    if norm.is_empty() {
+        return Vec::new();
+    }
+    let mut keys = Vec::new();
+    let mut push = |vendor: Option<&str>, product: &str| {
+        let key = ProductKey { vendor: vendor.map(str::to_string), product: product.to_string() };
+        if !product.is_empty() && !keys.contains(&key) {
+            keys.push(key);
+        }
+    };
+
+    if let Some((_, vendor, product)) = ALIASES.iter().find(|(alias, _, _)| *alias == norm) {
+        push(Some(vendor), product);
+    }
+    push(None, &norm);
+    for suffix in ["_httpd", "_smtpd", "_ftpd", "_sshd", "_server", "_daemon"] {
+        if let Some(stripped) = norm.strip_suffix(suffix) {
+            push(None, stripped);
+        }
+    }
+    // 带厂商前缀的名称，例如 apache_struts -> struts
+    if let Some((vendor, product)) = norm.split_once('_') {
+        push(Some(vendor), product);
+    }
+    keys
+}
+
+/// 从识别结果中拆分产品名和版本，例如 "Apache httpd 2.4.41 ((Ubuntu))"、"nginx/1.18.0"
+pub fn split_product_version(value: &str) -> (String, Option<String>) {
+    let tokens: Vec<&str> = value.split(|c: char| c.is_whitespace() || c == '/').filter(|t| !t.is_empty()).collect();
+    for (i, token) in tokens.iter().enumerate() {
+        let token = token.trim_matches(|c: char| !c.is_alphanumeric());
+        let digits = token.strip_prefix(['v', 'V']).unwrap_or(token);
+        if i > 0 && digits.starts_with(|c: char| c.is_ascii_digit()) {
+            return (tokens[..i].join(" "), Some(digits.to_string()));
+        }
+    }
+    (value.trim().to_string(), None)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Part {
+    Num(u64),
+    Str(String),
+}
+
+fn parts(version: &str) -> Vec<Part> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let flush = |current: &mut String, out: &mut Vec<Part>| {
+        if current.is_empty() {
+            return;
+        }
+        match current.parse::<u64>() {
+            Ok(n) => out.push(Part::Num(n)),
+            Err(_) => out.push(Part::Str(current.to_lowercase())),
+        }
+        current.clear();
+    };
+    for c in version.chars() {
+        if !c.is_alphanumeric() {
+            flush(&mut current, &mut out);
+            continue;
+        }
+        if !current.is_empty() && current.chars().last().is_some_and(|l| l.is_ascii_digit()) != c.is_ascii_digit() {
+            flush(&mut current, &mut out);
+        }
+        current.push(c);
+    }
+    flush(&mut current, &mut out);
+    out
+}
+
+/// 比较版本号，数字段按数值比较，1.0rc1 < 1.0 < 1.0p1 < 1.0.1
+pub fn compare(a: &str, b: &str) -> Ordering {
+    let (a, b) = (parts(a), parts(b));
+    for i in 0..a.len().max(b.len()) {
+        let ordering = match (a.get(i), b.get(i)) {
+            (Some(Part::Num(x)), Some(Part::Num(y))) => x.cmp(y),
+            (Some(Part::Str(x)), Some(Part::Str(y))) => x.cmp(y),
+            (Some(Part::Num(_)), Some(Part::Str(_))) => Ordering::Greater,
+            (Some(Part::Str(_)), Some(Part::Num(_))) => Ordering::Less,
+            (Some(part), None) => extra_ordering(part),
+            (None, Some(part)) => extra_ordering(part).reverse(),
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// 较长的版本多出的部分：数字或补丁后缀表示更新，其他字母表示预发布
+fn extra_ordering(part: &Part) -> Ordering {
+    match part {
+        Part::Num(_) => Ordering::Greater,
+        Part::Str(s) if POST_RELEASE.contains(&s.as_str()) => Ordering::Greater,
+        Part::Str(_) => Ordering::Less,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(compare("2.4.49", "2.4.50"), Ordering::Less);
+        assert_eq!(compare("2.4.10", "2.4.9"), Ordering::Greater);
+        assert_eq!(compare("1.0", "1.0.0"), Ordering::Less);
+        assert_eq!(compare("1.0rc1", "1.0"), Ordering::Less);
+        assert_eq!(compare("7.4p1", "7.4"), Ordering::Greater);
+        assert_eq!(compare("7.4p1", "7.5"), Ordering::Less);
+        assert_eq!(compare("3.4.1", "3.4.1"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_split_and_candidates() {
+        assert_eq!(
+            split_product_version("Apache httpd 2.4.41 ((Ubuntu))"),
+            ("Apache httpd".to_string(), Some("2.4.41".to_string()))
+        );
+        assert_eq!(split_product_version("nginx/1.18.0"), ("nginx".to_string(), Some("1.18.0".to_string())));
+        assert_eq!(split_product_version("OpenSSH 7.4p1 Debian"), ("OpenSSH".to_string(), Some("7.4p1".to_string())));
+        assert_eq!(split_product_version("jQuery"), ("jQuery".to_string(), None));
+
+        let keys = candidates("Apache httpd");
+        assert_eq!(keys[0], ProductKey { vendor: Some("apache".to_string()), product: "http_server".to_string() });
+        assert!(candidates("Apache Struts").contains(&ProductKey { vendor: Some("apache".to_string()), product: "struts".to_string() }));
+    }
+}
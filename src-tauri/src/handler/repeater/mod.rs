@@ -26,6 +26,7 @@ use bytes::{Bytes, BytesMut};
 use std::sync::Arc;
 use futures::prelude::*;
 use flate2::read::GzDecoder;
+use crate::core::governor::{self, Governor, Outcome};
 
 // 保存历史记录的结构体
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -991,12 +992,24 @@ pub async fn repeater_send_request(
     let start = Instant::now();
     println!("开始处理请求...");
     
+    let https = use_https.unwrap_or(false);
+    let port = target_port.unwrap_or(if https { 443 } else { 80 });
+
+    // 经出站请求调度器按目标主机限速，Socket模式与HTTP库方式使用相同的主机标识
+    let host = if use_socket == Some(true) {
+        let scheme = if https { "https" } else { "http" };
+        url::Url::parse(&format!("{}://{}:{}", scheme, target_host.as_deref().unwrap_or_default(), port))
+            .map(|u| governor::host_key(&u))
+            .unwrap_or_default()
+    } else {
+        url::Url::parse(&url).map(|u| governor::host_key(&u)).unwrap_or_default()
+    };
+    let permit = Governor::global().acquire(&host).await;
+    
     let result = if use_socket == Some(true) {
         // 使用Socket方式发送
         let host = target_host.ok_or("使用Socket模式时必须提供目标主机")?;
-        let port = target_port.unwrap_or(80);
         let raw = raw_request.ok_or("使用Socket模式时必须提供原始请求")?;
-        let https = use_https.unwrap_or(false);
         
         // 自动检测HTTP版本
         let detected_version = detect_http_version(&raw);
@@ -1013,7 +1026,20 @@ pub async fn repeater_send_request(
     } else {
         // 使用HTTP库方式发送
         send_http_request(&method, &url, &headers, &body).await
-    }?;
+    };
+    match &result {
+        Ok(response) => {
+            let retry_after = response
+                .headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))
+                .and_then(|(_, value)| governor::parse_retry_after(value));
+            permit.record(Outcome::Response { status: response.status, retry_after });
+        }
+        Err(_) => permit.record(Outcome::Failed),
+    }
+    drop(permit);
+    let result = result?;
     
     let elapsed = start.elapsed().as_millis() as u64;
     println!("请求总耗时: {}ms，响应状态码: {}", elapsed, result.status);
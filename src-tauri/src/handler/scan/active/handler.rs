@@ -1,8 +1,22 @@
 // src-tauri/src/handler/scan/active/handler.rs
-use tauri::State;
-use crate::handler::scan::common::types::{ActiveScanConfig, SuccessResponse, TargetType};
+use tauri::{Emitter, State, WebviewWindow};
+use crate::core::config::AppConfig;
+use crate::global::config::CoreConfig;
+use crate::handler::asm::api::save_imported_apis;
+use crate::handler::scan::api_import;
+use crate::handler::scan::crawler::{CrawlConfig, Crawler};
+use crate::handler::scan::common::types::{ActiveScanConfig, ApiImportConfig, ApiImportResult, ScannerStatus, SuccessResponse, TargetType};
+use crate::handler::scan::engine::queue::{self, QueuedTask};
+use crate::handler::scan::engine::{ScanManager, ScanResult};
+use crate::handler::scan::profile;
+use crate::handler::scan::results::store;
+use crate::handler::scan::proxy::HttpRequest;
 use crate::state::ScannerState;
 use log::{info, error, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use super::orchestrator;
 
 pub async fn handle_start_active_scan(
@@ -48,6 +62,16 @@ pub async fn handle_start_active_scan(
         warn!("超时时间调整为最大值300秒");
     }
 
+    // 应用扫描模板，用户未指定详细选项时使用模板中的任务选项
+    let (_, scan_profile) = profile::load_config(config.profile.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(scan_profile) = scan_profile {
+        if config.detailed_scan_options.is_none() {
+            config.detailed_scan_options = scan_profile.scan_options;
+        }
+    }
+
     // 验证扫描类型
     if !["full", "quick", "custom", "nuclei"].contains(&config.scan_type.as_str()) {
         return Err(format!("无效的扫描类型: {}", config.scan_type));
@@ -60,4 +84,309 @@ pub async fn handle_start_active_scan(
 
     // 调用扫描协调器
     orchestrator::run_scan(config, state).await
-} 
\ No newline at end of file
+}
+
+/// 暂停主动扫描队列，执行中的任务在断点处停止
+pub async fn handle_pause_active_scan() -> Result<SuccessResponse, String> {
+    queue::pause().await.map_err(|e| format!("暂停扫描失败: {}", e))?;
+    info!("主动扫描队列已暂停");
+    Ok(SuccessResponse {
+        success: true,
+        message: "主动扫描已暂停".to_string(),
+    })
+}
+
+/// 继续执行暂停的主动扫描队列
+pub async fn handle_resume_active_scan() -> Result<SuccessResponse, String> {
+    queue::resume().await.map_err(|e| format!("继续扫描失败: {}", e))?;
+    info!("主动扫描队列已继续");
+    Ok(SuccessResponse {
+        success: true,
+        message: "主动扫描已继续".to_string(),
+    })
+}
+
+/// 取消指定的队列任务，未指定时取消全部任务
+pub async fn handle_cancel_active_scan(task_id: Option<String>) -> Result<SuccessResponse, String> {
+    queue::cancel(task_id.as_deref()).await.map_err(|e| format!("取消扫描失败: {}", e))?;
+    info!("已取消主动扫描任务: {}", task_id.as_deref().unwrap_or("全部"));
+    Ok(SuccessResponse {
+        success: true,
+        message: "主动扫描任务已取消".to_string(),
+    })
+}
+
+/// 获取持久化队列中未完成的主动扫描任务
+pub async fn handle_get_active_scan_queue() -> Result<Vec<QueuedTask>, String> {
+    queue::list(None).await.map_err(|e| format!("获取扫描队列失败: {}", e))
+}
+
+/// 导入OpenAPI/Postman定义，可选写入ASM api表并加入主动扫描
+pub async fn handle_import_api_spec(
+    config: ApiImportConfig,
+    state: State<'_, ScannerState>,
+) -> Result<ApiImportResult, String> {
+    let content = match (&config.content, &config.path) {
+        (Some(content), _) if !content.trim().is_empty() => content.clone(),
+        (_, Some(path)) if !path.is_empty() => {
+            std::fs::read_to_string(path).map_err(|e| format!("读取API定义失败: {}", e))?
+        }
+        _ => return Err("未指定API定义内容或文件".to_string()),
+    };
+
+    // 加入主动扫描时先加载扫描模板，模板不存在时不导入
+    let scan_config = if config.scan {
+        let (app_config, _) = profile::load_config(config.profile.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+        Some(app_config)
+    } else {
+        None
+    };
+
+    let mut spec = api_import::parse_spec(&content, config.base_url.as_deref())?;
+    if let Some(extra) = &config.headers {
+        for request in &mut spec.requests {
+            for (k, v) in extra {
+                request.headers.insert(k.clone(), v.clone());
+            }
+        }
+    }
+    info!("导入API定义 {:?} \"{}\"，共 {} 个请求", spec.format, spec.title, spec.requests.len());
+
+    let mut saved_count = 0;
+    if let Some(task_id) = config.task_id {
+        let source = config
+            .path
+            .clone()
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| format!("import:{}", spec.title));
+        saved_count = save_imported_apis(&task_id, &spec.requests, &source)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(app_config) = scan_config {
+        queue_active_scan(spec.requests.clone(), app_config, config.profile.clone(), &state);
+    }
+
+    Ok(ApiImportResult {
+        format: format!("{:?}", spec.format),
+        title: spec.title,
+        request_count: spec.requests.len(),
+        saved_count,
+        queued: config.scan,
+    })
+}
+
+/// 爬取目标站点，发现的请求作为扫描项加入主动扫描
+pub async fn handle_start_crawl(
+    config: CrawlConfig,
+    state: State<'_, ScannerState>,
+) -> Result<SuccessResponse, String> {
+    let client = CoreConfig::global()
+        .ok()
+        .and_then(|c| c.http_client.clone())
+        .unwrap_or_default();
+    let crawler = Crawler::new(config.clone(), client).map_err(|e| e.to_string())?;
+
+    // 加入主动扫描时先加载扫描模板，模板不存在时不爬取
+    let scan_config = if config.scan {
+        let (app_config, _) = profile::load_config(config.profile.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+        Some(app_config)
+    } else {
+        None
+    };
+    info!("开始爬取: {:?}，最大深度 {}，最多 {} 个页面", config.targets, config.max_depth, config.max_pages);
+
+    let status = state.status.clone();
+    let window = state.window.clone();
+    tokio::spawn(async move {
+        let (crawl_tx, mut crawl_rx) = mpsc::channel(100);
+        let crawl = tokio::spawn(async move { crawler.run(crawl_tx).await });
+
+        let mut discovered = Vec::new();
+        match scan_config {
+            Some(app_config) => {
+                let (result_tx, result_rx) = mpsc::channel(100);
+                let (request_tx, request_rx) = mpsc::channel(100);
+                let manager = ScanManager::new_active(Arc::new(app_config), config.profile.clone(), result_tx).await;
+                let manager_handle = {
+                    let manager = manager.clone();
+                    tokio::spawn(async move { manager.start(request_rx).await })
+                };
+                let collector = spawn_result_collector(result_rx, status, window.clone());
+
+                while let Some((request, response)) = crawl_rx.recv().await {
+                    discovered.push(format!("{} {}", request.method, request.url));
+                    if request_tx.send((request, response)).await.is_err() {
+                        break;
+                    }
+                }
+                drop(request_tx);
+                wait_for_manager(manager, manager_handle, collector, discovered.len()).await;
+            }
+            None => {
+                while let Some((request, _)) = crawl_rx.recv().await {
+                    discovered.push(format!("{} {}", request.method, request.url));
+                }
+            }
+        }
+
+        let summary = crawl.await.unwrap_or_default();
+        if let Err(e) = window.emit("crawl_completed", serde_json::json!({
+            "summary": summary,
+            "requests": discovered,
+            "scanned": config.scan,
+        })) {
+            error!("Failed to emit crawl_completed event: {}", e);
+        }
+    });
+
+    Ok(SuccessResponse {
+        success: true,
+        message: "爬虫已启动".to_string(),
+    })
+}
+
+/// 在后台获取每个请求的基准响应并交给扫描管理器，结果写入漏洞列表
+fn queue_active_scan(requests: Vec<HttpRequest>, app_config: AppConfig, profile: Option<String>, state: &ScannerState) {
+    let status = state.status.clone();
+    let window = state.window.clone();
+
+    tokio::spawn(async move {
+        let (result_tx, result_rx) = mpsc::channel(100);
+        let (request_tx, request_rx) = mpsc::channel(100);
+        let manager = ScanManager::new_active(Arc::new(app_config), profile, result_tx).await;
+        let manager_handle = {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.start(request_rx).await })
+        };
+        let collector = spawn_result_collector(result_rx, status, window.clone());
+
+        let client = CoreConfig::global()
+            .ok()
+            .and_then(|c| c.http_client.clone())
+            .unwrap_or_default();
+        let mut queued = 0;
+        for request in requests {
+            let response = match api_import::fetch_response(&client, &request).await {
+                Some(response) => response,
+                None => {
+                    warn!("获取基准响应失败: {} {}", request.method, request.url);
+                    continue;
+                }
+            };
+            if request_tx.send((request, response)).await.is_err() {
+                break;
+            }
+            queued += 1;
+        }
+        drop(request_tx);
+
+        // 等待队列处理完成后停止扫描管理器
+        wait_for_manager(manager, manager_handle, collector, queued).await;
+
+        info!("API定义主动扫描完成，共扫描 {} 个请求", queued);
+        if let Err(e) = window.emit("api_import_scan_completed", serde_json::json!({ "count": queued })) {
+            error!("Failed to emit api_import_scan_completed event: {}", e);
+        }
+    });
+}
+
+/// 应用启动后恢复上次未完成的主动扫描队列
+pub async fn resume_active_scan_queue(state: &ScannerState) {
+    // 数据库在后台初始化，等待其可用
+    for _ in 0..60 {
+        if queue::is_available() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    if !queue::is_available() {
+        warn!("数据库未初始化，跳过恢复扫描队列");
+        return;
+    }
+
+    let targets = orchestrator::resume_pending(state).await;
+
+    // 每次扫描按入队时的配置恢复
+    let (result_tx, result_rx) = mpsc::channel(100);
+    let managers = ScanManager::restore(result_tx).await;
+    let restored: usize = managers.iter().map(|(_, count)| count).sum();
+    if targets + restored == 0 {
+        return;
+    }
+    info!("恢复扫描队列，目标任务 {} 个，请求任务 {} 个", targets, restored);
+    if restored == 0 {
+        return;
+    }
+
+    let collector = spawn_result_collector(result_rx, state.status.clone(), state.window.clone());
+    for (manager, count) in managers {
+        let (request_tx, request_rx) = mpsc::channel(1);
+        drop(request_tx);
+        let manager_handle = {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.start(request_rx).await })
+        };
+        stop_when_done(manager, manager_handle, count).await;
+    }
+    let _ = collector.await;
+    info!("恢复的扫描队列执行完成");
+}
+
+/// 保存扫描结果并通知前端
+fn spawn_result_collector(
+    mut result_rx: mpsc::Receiver<ScanResult>,
+    status: Arc<tokio::sync::Mutex<ScannerStatus>>,
+    window: Arc<WebviewWindow>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Some(result) = result_rx.recv().await {
+            let vulnerability = match store::save_vulnerability(result).await {
+                Ok(saved) if saved.should_notify() => saved.vulnerability,
+                Ok(_) => continue,
+                Err(e) => {
+                    error!("保存漏洞失败: {}", e);
+                    continue;
+                }
+            };
+            let count = store::count_vulnerabilities().await.unwrap_or_default();
+
+            {
+                let mut status_lock = status.lock().await;
+                status_lock.vulnerability_count = count;
+                status_lock.last_update = Some(chrono::Utc::now().to_rfc3339());
+            }
+            if let Err(e) = window.emit("vulnerability_found", serde_json::json!({
+                "count": count,
+                "latest": vulnerability
+            })) {
+                error!("Failed to emit vulnerability_found event: {}", e);
+            }
+        }
+    })
+}
+
+/// 等待扫描管理器完成指定数量的任务后停止，并等待结果保存完毕
+async fn wait_for_manager(
+    manager: ScanManager,
+    manager_handle: JoinHandle<()>,
+    collector: JoinHandle<()>,
+    task_count: usize,
+) {
+    stop_when_done(manager, manager_handle, task_count).await;
+    let _ = collector.await;
+}
+
+/// 等待扫描管理器完成指定数量的任务后停止
+async fn stop_when_done(manager: ScanManager, manager_handle: JoinHandle<()>, task_count: usize) {
+    while manager.get_status().await.completed_count < task_count {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    manager.stop().await;
+    let _ = manager_handle.await;
+}
@@ -1,8 +1,11 @@
 use tauri::State;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use crate::handler::scan::common::types::{ActiveScanConfig, SuccessResponse, DetailedScanOptions, Target, TargetType};
+use crate::handler::scan::common::types::{ActiveScanConfig, SuccessResponse, DetailedScanOptions, ScannerStatus, Target, TargetType};
+use crate::handler::scan::engine::queue::{self, QueueKind, QueueStatus, QueuedTask};
 use crate::state::ScannerState;
+use serde::{Deserialize, Serialize};
+use tauri::WebviewWindow;
 use tokio::sync::mpsc;
 use log::{info, error, warn, debug};
 use chrono::Local;
@@ -27,6 +30,17 @@ struct ScanTask {
     target: Target,
     status: ScanTaskStatus,
     result: Option<String>,
+    /// 持久化队列中的任务，数据库不可用时为None
+    queued: Option<QueuedTask>,
+}
+
+/// 队列中保存的目标扫描任务数据
+#[derive(Serialize, Deserialize)]
+struct QueuedScanData {
+    task_id: String,
+    task_type: String,
+    target: Target,
+    config: ActiveScanConfig,
 }
 
 pub async fn run_scan(
@@ -82,22 +96,140 @@ pub async fn run_scan(
 
     // 创建任务队列
     let tasks = Arc::new(Mutex::new(Vec::new()));
-    let (tx, mut rx) = mpsc::channel(100);
+    let (tx, rx) = mpsc::channel(100);
     
     // 根据不同扫描选项创建任务
     create_scan_tasks(processed_targets, &scan_options, tasks.clone(), tx.clone()).await?;
     
-    // 克隆需要在异步闭包中使用的变量
-    let tasks_clone = tasks.clone();
-    let config_clone = config.clone();
-    let window = state.window.clone();
-    let running = state.running.clone();
-    let status = state.status.clone();
-    
+    // 写入持久化队列，应用重启后继续未完成的任务
+    let scan_id = uuid::Uuid::new_v4().to_string();
+    persist_tasks(&scan_id, &config, &tasks).await;
+
     // 启动扫描任务调度器
+    spawn_scheduler(
+        tasks,
+        config,
+        (tx, rx),
+        state.window.clone(),
+        state.running.clone(),
+        state.status.clone(),
+    );
+
+    Ok(SuccessResponse {
+        success: true,
+        message: "主动扫描任务已成功启动".to_string(),
+    })
+}
+
+/// 任务写入持久化队列
+async fn persist_tasks(scan_id: &str, config: &ActiveScanConfig, tasks: &Arc<Mutex<Vec<ScanTask>>>) {
+    if !queue::is_available() {
+        return;
+    }
+
+    let mut queued_tasks = Vec::new();
+    {
+        let mut guard = tasks.lock().unwrap();
+        for task in guard.iter_mut() {
+            let data = QueuedScanData {
+                task_id: task.id.clone(),
+                task_type: task.task_type.clone(),
+                target: task.target.clone(),
+                config: config.clone(),
+            };
+            let mut queued = QueuedTask::new(
+                scan_id,
+                QueueKind::Target,
+                &task.target.value,
+                serde_json::to_string(&data).unwrap_or_default(),
+            );
+            queued.checkpoint.enter_phase(&task.task_type);
+            task.queued = Some(queued.clone());
+            queued_tasks.push(queued);
+        }
+    }
+
+    for queued in &queued_tasks {
+        if let Err(e) = queue::enqueue(queued).await {
+            warn!("扫描任务写入队列失败: {}", e);
+        }
+    }
+}
+
+/// 恢复上次退出时未完成的目标扫描任务，返回恢复的任务数
+pub async fn resume_pending(state: &ScannerState) -> usize {
+    let queued_tasks = match queue::restore(QueueKind::Target).await {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            error!("加载扫描队列失败: {}", e);
+            return 0;
+        }
+    };
+
+    // 按扫描分组，每次扫描使用各自的配置
+    let mut scans: Vec<(String, ActiveScanConfig, Vec<ScanTask>)> = Vec::new();
+    for queued in queued_tasks {
+        let data = match serde_json::from_str::<QueuedScanData>(&queued.data) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("解析队列任务 {} 失败，已丢弃: {}", queued.id, e);
+                let _ = queue::complete(&queued.id).await;
+                continue;
+            }
+        };
+        let scan_id = queued.scan_id.clone();
+        let task = ScanTask {
+            id: data.task_id,
+            task_type: data.task_type,
+            target: data.target,
+            status: ScanTaskStatus::Pending,
+            result: None,
+            queued: Some(queued),
+        };
+        match scans.iter_mut().find(|(id, _, _)| *id == scan_id) {
+            Some((_, _, tasks)) => tasks.push(task),
+            None => scans.push((scan_id, data.config, vec![task])),
+        }
+    }
+
+    let mut restored = 0;
+    for (scan_id, config, tasks) in scans {
+        info!("恢复扫描 {}，未完成任务数: {}", scan_id, tasks.len());
+        restored += tasks.len();
+
+        {
+            let mut running = state.running.lock().await;
+            *running = true;
+        }
+        state.update_status(|status| {
+            status.running = true;
+            status.message = Some(format!("继续执行{}扫描", config.scan_type));
+        }).await;
+
+        spawn_scheduler(
+            Arc::new(Mutex::new(tasks)),
+            config,
+            mpsc::channel(100),
+            state.window.clone(),
+            state.running.clone(),
+            state.status.clone(),
+        );
+    }
+    restored
+}
+
+/// 启动扫描任务调度器
+fn spawn_scheduler(
+    tasks: Arc<Mutex<Vec<ScanTask>>>,
+    config: ActiveScanConfig,
+    (tx, mut rx): (mpsc::Sender<String>, mpsc::Receiver<String>),
+    window: Arc<WebviewWindow>,
+    running: Arc<tokio::sync::Mutex<bool>>,
+    status: Arc<tokio::sync::Mutex<ScannerStatus>>,
+) {
     tokio::spawn(async move {
         let tasks_count = {
-            let guard = tasks_clone.lock().unwrap();
+            let guard = tasks.lock().unwrap();
             guard.len()
         };
         info!("启动扫描调度器，共计任务数: {}", tasks_count);
@@ -121,13 +253,13 @@ pub async fn run_scan(
         
         // 创建线程池
         let mut handles = vec![];
-        let max_threads = config_clone.threads as usize;
+        let max_threads = config.threads as usize;
         let semaphore = Arc::new(tokio::sync::Semaphore::new(max_threads));
         
         // 执行任务
         for i in 0..tasks_count {
             let task = {
-                let mut guard = tasks_clone.lock().unwrap();
+                let mut guard = tasks.lock().unwrap();
                 if i < guard.len() {
                     guard[i].clone()
                 } else {
@@ -135,10 +267,15 @@ pub async fn run_scan(
                 }
             };
             
+            // 暂停期间不分发新任务
+            if let Some(queued) = &task.queued {
+                queue::wait_while_paused(queued).await;
+            }
+            
             let task_tx = tx.clone();
             let permit = semaphore.clone().acquire_owned().await.unwrap();
-            let task_timeout = config_clone.timeout;
-            let task_tasks_clone = tasks_clone.clone();
+            let task_timeout = config.timeout;
+            let task_tasks_clone = tasks.clone();
             
             // 启动单个任务
             let handle = tokio::spawn(async move {
@@ -155,18 +292,30 @@ pub async fn run_scan(
                     }
                 }
                 
+                // 已取消的任务不再执行
+                let cancelled = task.queued.as_ref().is_some_and(|q| q.is_cancelled());
+                if let Some(queued) = task.queued.as_ref().filter(|_| !cancelled) {
+                    if let Err(e) = queue::set_status(&queued.id, QueueStatus::Running).await {
+                        warn!("更新队列任务状态失败: {}", e);
+                    }
+                }
+                
                 // 根据任务类型执行不同的扫描逻辑
-                let result = match task.task_type.as_str() {
-                    "host_survival" => execute_host_survival(&task, task_timeout).await,
-                    "port_scan" => execute_port_scan(&task, task_timeout).await,
-                    "fingerprint" => execute_fingerprint_scan(&task, task_timeout).await,
-                    "web_sensitive" => execute_web_sensitive_scan(&task, task_timeout).await,
-                    "nuclei" => execute_nuclei_scan(&task, task_timeout).await,
-                    "vulnerability" => execute_vulnerability_scan(&task, task_timeout).await,
-                    "service_bruteforce" => execute_service_bruteforce(&task, task_timeout).await,
-                    _ => {
-                        error!("未知的任务类型: {}", task.task_type);
-                        Err("未知的任务类型".to_string())
+                let result = if cancelled {
+                    Err("任务已取消".to_string())
+                } else {
+                    match task.task_type.as_str() {
+                        "host_survival" => execute_host_survival(&task, task_timeout).await,
+                        "port_scan" => execute_port_scan(&task, task_timeout).await,
+                        "fingerprint" => execute_fingerprint_scan(&task, task_timeout).await,
+                        "web_sensitive" => execute_web_sensitive_scan(&task, task_timeout).await,
+                        "nuclei" => execute_nuclei_scan(&task, task_timeout).await,
+                        "vulnerability" => execute_vulnerability_scan(&task, task_timeout).await,
+                        "service_bruteforce" => execute_service_bruteforce(&task, task_timeout).await,
+                        _ => {
+                            error!("未知的任务类型: {}", task.task_type);
+                            Err("未知的任务类型".to_string())
+                        }
                     }
                 };
                 
@@ -190,6 +339,13 @@ pub async fn run_scan(
                     }
                 }
                 
+                // 任务结束后从持久化队列删除
+                if let Some(queued) = &task.queued {
+                    if let Err(e) = queue::complete(&queued.id).await {
+                        warn!("删除队列任务失败: {}", e);
+                    }
+                }
+                
                 // 发送任务完成通知
                 if let Err(e) = task_tx.send(task.id.clone()).await {
                     error!("无法发送任务完成通知: {}", e);
@@ -228,7 +384,7 @@ pub async fn run_scan(
             
             // 提取完成任务的结果
             let task_result = {
-                let guard = tasks_clone.lock().unwrap();
+                let guard = tasks.lock().unwrap();
                 guard.iter()
                     .find(|t| t.id == task_id)
                     .map(|t| t.result.clone())
@@ -259,9 +415,9 @@ pub async fn run_scan(
                 info!("所有扫描任务已完成");
                 
                 // 保存结果
-                if config_clone.save_results {
-                    if let Some(path) = &config_clone.results_path {
-                        match save_scan_results(path, tasks_clone.clone()).await {
+                if config.save_results {
+                    if let Some(path) = &config.results_path {
+                        match save_scan_results(path, tasks.clone()).await {
                             Ok(_) => info!("扫描结果已保存到: {}", path),
                             Err(e) => error!("保存扫描结果失败: {}", e),
                         }
@@ -296,11 +452,6 @@ pub async fn run_scan(
             }
         }
     });
-
-    Ok(SuccessResponse {
-        success: true,
-        message: "主动扫描任务已成功启动".to_string(),
-    })
 }
 
 // 获取有效的扫描选项
@@ -400,6 +551,7 @@ async fn create_scan_tasks(
                 target: target.clone(),
                 status: ScanTaskStatus::Pending,
                 result: None,
+                queued: None,
             };
             
             {
@@ -418,6 +570,7 @@ async fn create_scan_tasks(
                 target: target.clone(),
                 status: ScanTaskStatus::Pending,
                 result: None,
+                queued: None,
             };
             
             {
@@ -436,6 +589,7 @@ async fn create_scan_tasks(
                 target: target.clone(),
                 status: ScanTaskStatus::Pending,
                 result: None,
+                queued: None,
             };
             
             {
@@ -456,6 +610,7 @@ async fn create_scan_tasks(
                     target: target.clone(),
                     status: ScanTaskStatus::Pending,
                     result: None,
+                    queued: None,
                 };
                 
                 {
@@ -475,6 +630,7 @@ async fn create_scan_tasks(
                 target: target.clone(),
                 status: ScanTaskStatus::Pending,
                 result: None,
+                queued: None,
             };
             
             {
@@ -493,6 +649,7 @@ async fn create_scan_tasks(
                 target: target.clone(),
                 status: ScanTaskStatus::Pending,
                 result: None,
+                queued: None,
             };
             
             {
@@ -515,6 +672,7 @@ async fn create_scan_tasks(
                     target: target.clone(),
                     status: ScanTaskStatus::Pending,
                     result: None,
+                    queued: None,
                 };
                 
                 {
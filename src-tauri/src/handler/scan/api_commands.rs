@@ -4,15 +4,26 @@ use tauri::{State, command};
 // However, get_scan_status now returns crate::state::ScannerStatus (which itself imports from common::types)
 // So this direct import might not be strictly needed here if types are correctly inferred from function signatures.
 // Let's assume for now it's needed for clarity or direct use elsewhere in this file.
-use super::common::types::{ActiveScanConfig, PassiveScanConfig, SuccessResponse, Vulnerability, ScannerStatus};
+use super::common::types::{ActiveScanConfig, ApiImportConfig, ApiImportResult, PassiveScanConfig, SuccessResponse, TriageStatus, Vulnerability, ScannerStatus};
 use crate::state::ScannerState; // For State<'_, ScannerState>
 
 // Direct imports for handlers to potentially resolve linter issues
 use crate::handler::scan::status::handler::handle_get_scan_status as get_status_handler;
 use crate::handler::scan::cert_utils::handler::handle_open_cert_file as open_cert_handler;
-use crate::handler::scan::active::handler::handle_start_active_scan;
+use crate::handler::scan::active::handler::{
+    handle_cancel_active_scan, handle_get_active_scan_queue, handle_import_api_spec, handle_pause_active_scan,
+    handle_resume_active_scan, handle_start_active_scan, handle_start_crawl,
+};
+use crate::handler::scan::crawler::CrawlConfig;
+use crate::handler::scan::engine::queue::QueuedTask;
 use crate::handler::scan::passive::handler::{handle_start_passive_scan, handle_stop_passive_scan};
-use crate::handler::scan::results::handler::{handle_get_scan_vulnerabilities, handle_clear_scan_vulnerabilities, handle_export_scan_vulnerabilities};
+use crate::handler::scan::profile::handler::{
+    handle_delete_scan_profile, handle_export_scan_profile, handle_get_default_scan_profile, handle_import_scan_profile,
+    handle_list_scan_profiles, handle_save_scan_profile,
+};
+use crate::handler::scan::profile::ScanProfile;
+use crate::handler::scan::results::handler::{handle_get_scan_vulnerabilities, handle_clear_scan_vulnerabilities, handle_export_scan_report, handle_export_scan_vulnerabilities, handle_update_scan_vulnerability_status};
+use crate::handler::scan::results::report::ReportOptions;
 
 // Placeholder for actual logic handlers that will be in other modules
 // For example, active::handler::start_active_scan_logic, etc.
@@ -25,6 +36,72 @@ pub async fn start_active_scan(
     handle_start_active_scan(config, state).await
 }
 
+#[command]
+pub async fn import_api_spec(
+    config: ApiImportConfig,
+    state: State<'_, ScannerState>,
+) -> Result<ApiImportResult, String> {
+    handle_import_api_spec(config, state).await
+}
+
+#[command]
+pub async fn start_crawl(
+    config: CrawlConfig,
+    state: State<'_, ScannerState>,
+) -> Result<SuccessResponse, String> {
+    handle_start_crawl(config, state).await
+}
+
+#[command]
+pub async fn pause_active_scan() -> Result<SuccessResponse, String> {
+    handle_pause_active_scan().await
+}
+
+#[command]
+pub async fn resume_active_scan() -> Result<SuccessResponse, String> {
+    handle_resume_active_scan().await
+}
+
+#[command]
+pub async fn cancel_active_scan(task_id: Option<String>) -> Result<SuccessResponse, String> {
+    handle_cancel_active_scan(task_id).await
+}
+
+#[command]
+pub async fn get_active_scan_queue() -> Result<Vec<QueuedTask>, String> {
+    handle_get_active_scan_queue().await
+}
+
+#[command]
+pub async fn list_scan_profiles() -> Result<Vec<ScanProfile>, String> {
+    handle_list_scan_profiles().await
+}
+
+#[command]
+pub async fn get_default_scan_profile() -> Result<ScanProfile, String> {
+    handle_get_default_scan_profile().await
+}
+
+#[command]
+pub async fn save_scan_profile(profile: ScanProfile) -> Result<SuccessResponse, String> {
+    handle_save_scan_profile(profile).await
+}
+
+#[command]
+pub async fn delete_scan_profile(name: String) -> Result<SuccessResponse, String> {
+    handle_delete_scan_profile(name).await
+}
+
+#[command]
+pub async fn export_scan_profile(name: String, path: Option<String>) -> Result<String, String> {
+    handle_export_scan_profile(name, path).await
+}
+
+#[command]
+pub async fn import_scan_profile(content: Option<String>, path: Option<String>) -> Result<ScanProfile, String> {
+    handle_import_scan_profile(content, path).await
+}
+
 #[command]
 pub async fn start_passive_scan(
     config: PassiveScanConfig,
@@ -54,11 +131,21 @@ pub async fn clear_scan_vulnerabilities(state: State<'_, ScannerState>) -> Resul
 }
 
 #[command]
-pub async fn export_scan_vulnerabilities(
-    state: State<'_, ScannerState>,
-    path: String,
+pub async fn export_scan_vulnerabilities(path: String) -> Result<SuccessResponse, String> {
+    handle_export_scan_vulnerabilities(path).await
+}
+
+#[command]
+pub async fn export_scan_report(options: ReportOptions) -> Result<SuccessResponse, String> {
+    handle_export_scan_report(options).await
+}
+
+#[command]
+pub async fn update_scan_vulnerability_status(
+    ids: Vec<u32>,
+    status: TriageStatus,
 ) -> Result<SuccessResponse, String> {
-    handle_export_scan_vulnerabilities(state, path).await
+    handle_update_scan_vulnerability_status(ids, status).await
 }
 
 #[command]
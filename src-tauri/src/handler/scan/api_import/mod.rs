@@ -0,0 +1,306 @@
+//! API定义导入
+//!
+//! 解析OpenAPI 2/3（JSON/YAML）和Postman集合，把每个操作展开为带示例参数的`HttpRequest`，
+//! 用于主动扫描和写入ASM的api表。
+
+mod openapi;
+mod postman;
+
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::utils::http;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// 定义文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SpecFormat {
+    Swagger2,
+    OpenApi3,
+    Postman,
+}
+
+/// 导入结果
+#[derive(Debug, Clone)]
+pub struct ImportedSpec {
+    pub format: SpecFormat,
+    pub title: String,
+    pub requests: Vec<HttpRequest>,
+}
+
+/// YAML值转换为JSON值，非字符串的键（如响应码200）转换为字符串
+fn yaml_to_json(value: serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                n.as_f64().map(Value::from).unwrap_or(Value::Null)
+            }
+        }
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(items) => Value::Array(items.into_iter().map(yaml_to_json).collect()),
+        serde_yaml::Value::Mapping(map) => {
+            let mut out = Map::new();
+            for (k, v) in map {
+                let key = match k {
+                    serde_yaml::Value::String(s) => s,
+                    serde_yaml::Value::Number(n) => n.to_string(),
+                    serde_yaml::Value::Bool(b) => b.to_string(),
+                    _ => continue,
+                };
+                out.insert(key, yaml_to_json(v));
+            }
+            Value::Object(out)
+        }
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+/// 加载JSON或YAML文档
+pub fn load_document(content: &str) -> Result<Value, String> {
+    if let Ok(json) = serde_json::from_str::<Value>(content) {
+        return Ok(json);
+    }
+    serde_yaml::from_str::<serde_yaml::Value>(content)
+        .map(yaml_to_json)
+        .map_err(|e| format!("无法解析JSON/YAML文档: {}", e))
+}
+
+/// 识别文档格式
+pub fn detect_format(doc: &Value) -> Option<SpecFormat> {
+    if doc.get("swagger").and_then(Value::as_str).is_some_and(|v| v.starts_with('2')) {
+        return Some(SpecFormat::Swagger2);
+    }
+    if doc.get("openapi").and_then(Value::as_str).is_some_and(|v| v.starts_with('3')) {
+        return Some(SpecFormat::OpenApi3);
+    }
+    let postman_schema = doc
+        .pointer("/info/schema")
+        .and_then(Value::as_str)
+        .is_some_and(|s| s.contains("postman"));
+    if postman_schema || (doc.get("info").is_some() && doc.get("item").is_some_and(Value::is_array)) {
+        return Some(SpecFormat::Postman);
+    }
+    None
+}
+
+/// 解析API定义，base_url用于覆盖或补全文档中的服务器地址
+pub fn parse_spec(content: &str, base_url: Option<&str>) -> Result<ImportedSpec, String> {
+    let doc = load_document(content)?;
+    let format = detect_format(&doc).ok_or("无法识别的API定义格式，仅支持OpenAPI 2/3和Postman集合")?;
+    let base_url = base_url.map(str::trim).filter(|u| !u.is_empty());
+
+    let (title, requests) = match format {
+        SpecFormat::Swagger2 | SpecFormat::OpenApi3 => (
+            doc.pointer("/info/title").and_then(Value::as_str).unwrap_or_default().to_string(),
+            openapi::parse(&doc, base_url)?,
+        ),
+        SpecFormat::Postman => (
+            doc.pointer("/info/name").and_then(Value::as_str).unwrap_or_default().to_string(),
+            postman::parse(&doc, base_url)?,
+        ),
+    };
+
+    Ok(ImportedSpec { format, title, requests })
+}
+
+/// 根据完整URL构造请求，查询参数同时写入params
+pub(crate) fn build_request(method: &str, url: &str, headers: HashMap<String, String>, body: Vec<u8>) -> HttpRequest {
+    let params = url::Url::parse(url)
+        .map(|u| u.query_pairs().map(|(k, v)| (k.to_string(), v.to_string())).collect())
+        .unwrap_or_default();
+    HttpRequest::new(url, &method.to_uppercase(), headers, body, params)
+}
+
+/// 发送导入的请求获取基准响应
+pub async fn fetch_response(client: &reqwest::Client, request: &HttpRequest) -> Option<HttpResponse> {
+    http::send_request(client, request).await.ok()
+}
+
+/// 编码multipart/form-data请求体，返回(Content-Type, 请求体)
+pub(crate) fn multipart_body(fields: &[(String, String)]) -> (String, Vec<u8>) {
+    let boundary = format!("----RShieldBoundary{}", crate::handler::scan::utils::generate_random_string(16));
+    let mut body = String::new();
+    for (name, value) in fields {
+        body.push_str(&format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+            boundary, name, value
+        ));
+    }
+    body.push_str(&format!("--{}--\r\n", boundary));
+    (format!("multipart/form-data; boundary={}", boundary), body.into_bytes())
+}
+
+/// 编码application/x-www-form-urlencoded请求体
+pub(crate) fn form_body(fields: &[(String, String)]) -> Vec<u8> {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    for (k, v) in fields {
+        serializer.append_pair(k, v);
+    }
+    serializer.finish().into_bytes()
+}
+
+/// JSON值转换为参数字符串
+pub(crate) fn value_to_param(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SWAGGER2: &str = r##"
+swagger: "2.0"
+info:
+  title: Pets
+host: petstore.example.com
+basePath: /v1
+schemes: [https]
+consumes: [application/json]
+paths:
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        type: integer
+    get:
+      parameters:
+        - name: fields
+          in: query
+          type: string
+          enum: [name, tag]
+      responses:
+        200:
+          description: ok
+  /pets:
+    post:
+      parameters:
+        - name: body
+          in: body
+          schema:
+            $ref: "#/definitions/Pet"
+      responses:
+        201:
+          description: created
+definitions:
+  Pet:
+    type: object
+    properties:
+      name:
+        type: string
+        example: doggie
+      tags:
+        type: array
+        items:
+          type: string
+"##;
+
+    const OPENAPI3: &str = r#"{
+      "openapi": "3.0.1",
+      "info": {"title": "Users"},
+      "servers": [{"url": "/api"}],
+      "paths": {
+        "/users": {
+          "post": {
+            "requestBody": {
+              "content": {
+                "application/x-www-form-urlencoded": {
+                  "schema": {"type": "object", "properties": {"email": {"type": "string", "format": "email"}, "age": {"type": "integer", "minimum": 18}}}
+                }
+              }
+            }
+          }
+        },
+        "/users/{id}": {
+          "delete": {
+            "parameters": [
+              {"name": "id", "in": "path", "schema": {"type": "string", "format": "uuid"}},
+              {"name": "X-Trace", "in": "header", "example": "abc"}
+            ]
+          }
+        }
+      }
+    }"#;
+
+    const POSTMAN: &str = r#"{
+      "info": {"name": "Demo", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+      "variable": [{"key": "host", "value": "https://api.example.com"}],
+      "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "t0k"}]},
+      "item": [
+        {"name": "folder", "item": [
+          {"name": "login", "request": {
+            "method": "POST",
+            "header": [{"key": "X-Req", "value": "1"}, {"key": "X-Off", "value": "1", "disabled": true}],
+            "url": {"raw": "{{host}}/login?next={{next}}"},
+            "body": {"mode": "raw", "raw": "{\"user\":\"admin\"}", "options": {"raw": {"language": "json"}}}
+          }}
+        ]},
+        {"name": "search", "request": "{{host}}/search?q=1"}
+      ]
+    }"#;
+
+    #[test]
+    fn test_swagger2_yaml() {
+        let spec = parse_spec(SWAGGER2, None).unwrap();
+        assert_eq!(spec.format, SpecFormat::Swagger2);
+        assert_eq!(spec.title, "Pets");
+
+        let get = spec.requests.iter().find(|r| r.method == "GET").unwrap();
+        assert_eq!(get.url, "https://petstore.example.com/v1/pets/1?fields=name");
+        assert_eq!(get.params, vec![("fields".to_string(), "name".to_string())]);
+
+        let post = spec.requests.iter().find(|r| r.method == "POST").unwrap();
+        let body: Value = serde_json::from_slice(&post.body).unwrap();
+        assert_eq!(body["name"], "doggie");
+        assert!(body["tags"].is_array());
+        assert_eq!(post.headers.get("Content-Type").unwrap(), "application/json");
+    }
+
+    #[test]
+    fn test_openapi3_base_url() {
+        assert!(parse_spec(OPENAPI3, None).is_err());
+
+        let spec = parse_spec(OPENAPI3, Some("http://10.0.0.1:8080/")).unwrap();
+        assert_eq!(spec.format, SpecFormat::OpenApi3);
+
+        let post = spec.requests.iter().find(|r| r.method == "POST").unwrap();
+        assert_eq!(post.url, "http://10.0.0.1:8080/api/users");
+        let body = String::from_utf8(post.body.clone()).unwrap();
+        assert!(body.contains("email=test%40example.com"));
+        assert!(body.contains("age=18"));
+
+        let delete = spec.requests.iter().find(|r| r.method == "DELETE").unwrap();
+        assert!(delete.url.starts_with("http://10.0.0.1:8080/api/users/"));
+        assert_eq!(delete.headers.get("X-Trace").unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_postman_collection() {
+        let spec = parse_spec(POSTMAN, None).unwrap();
+        assert_eq!(spec.format, SpecFormat::Postman);
+        assert_eq!(spec.requests.len(), 2);
+
+        let login = &spec.requests[0];
+        assert_eq!(login.method, "POST");
+        assert_eq!(login.url, "https://api.example.com/login?next=1");
+        assert_eq!(login.headers.get("Authorization").unwrap(), "Bearer t0k");
+        assert_eq!(login.headers.get("X-Req").unwrap(), "1");
+        assert!(!login.headers.contains_key("X-Off"));
+        assert_eq!(login.headers.get("Content-Type").unwrap(), "application/json");
+        assert_eq!(login.body, br#"{"user":"admin"}"#.to_vec());
+
+        let search = &spec.requests[1];
+        assert_eq!(search.method, "GET");
+        assert_eq!(search.params, vec![("q".to_string(), "1".to_string())]);
+    }
+}
@@ -0,0 +1,337 @@
+//! OpenAPI 2（Swagger）/ OpenAPI 3 解析
+
+use super::{build_request, form_body, multipart_body, value_to_param};
+use crate::handler::scan::proxy::HttpRequest;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use url::Url;
+
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+/// $ref解析和示例生成的最大深度，避免循环引用
+const MAX_DEPTH: usize = 8;
+
+/// 解析文档内部的$ref引用
+fn resolve<'a>(root: &'a Value, value: &'a Value) -> &'a Value {
+    let mut current = value;
+    for _ in 0..MAX_DEPTH {
+        match current.get("$ref").and_then(Value::as_str) {
+            Some(r) if r.starts_with("#/") => match root.pointer(&r[1..]) {
+                Some(v) => current = v,
+                None => return current,
+            },
+            _ => return current,
+        }
+    }
+    current
+}
+
+/// 按format和参数名生成字符串示例
+fn sample_string(format: &str, name: &str) -> &'static str {
+    match format {
+        "date-time" => "2024-01-01T00:00:00Z",
+        "date" => "2024-01-01",
+        "time" => "12:00:00",
+        "email" => "test@example.com",
+        "uuid" => "00000000-0000-4000-8000-000000000001",
+        "uri" | "url" => "http://example.com",
+        "hostname" => "example.com",
+        "ipv4" => "127.0.0.1",
+        "ipv6" => "::1",
+        "password" => "Passw0rd!",
+        "byte" => "dGVzdA==",
+        _ => {
+            let name = name.to_lowercase();
+            if name == "id" || name.ends_with("id") {
+                "1"
+            } else if name.contains("mail") {
+                "test@example.com"
+            } else if name.contains("url") || name.contains("link") {
+                "http://example.com"
+            } else if name.contains("phone") || name.contains("mobile") {
+                "13800000000"
+            } else {
+                "test"
+            }
+        }
+    }
+}
+
+/// 根据schema生成示例值，优先使用文档中的example/default/enum
+fn sample_value(root: &Value, schema: &Value, name: &str, depth: usize) -> Value {
+    if depth > MAX_DEPTH {
+        return Value::Null;
+    }
+    let schema = resolve(root, schema);
+
+    for key in ["example", "x-example", "default", "const"] {
+        if let Some(v) = schema.get(key) {
+            return v.clone();
+        }
+    }
+    // OpenAPI 3.1 的examples为数组
+    if let Some(first) = schema.get("examples").and_then(Value::as_array).and_then(|a| a.first()) {
+        return first.clone();
+    }
+    if let Some(first) = schema.get("enum").and_then(Value::as_array).and_then(|e| e.first()) {
+        return first.clone();
+    }
+    if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for s in all {
+            if let Value::Object(m) = sample_value(root, s, name, depth + 1) {
+                merged.extend(m);
+            }
+        }
+        return Value::Object(merged);
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(first) = schema.get(key).and_then(Value::as_array).and_then(|a| a.first()) {
+            return sample_value(root, first, name, depth + 1);
+        }
+    }
+
+    let schema_type = match schema.get("type") {
+        Some(Value::String(t)) => t.as_str(),
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).find(|t| *t != "null").unwrap_or("string"),
+        _ if schema.get("properties").is_some() => "object",
+        _ if schema.get("items").is_some() => "array",
+        _ => "string",
+    };
+
+    match schema_type {
+        "integer" => json!(schema.get("minimum").and_then(Value::as_i64).unwrap_or(1)),
+        "number" => json!(schema.get("minimum").and_then(Value::as_f64).unwrap_or(1.5)),
+        "boolean" => json!(true),
+        "array" => {
+            let items = schema.get("items").cloned().unwrap_or_else(|| json!({}));
+            json!([sample_value(root, &items, name, depth + 1)])
+        }
+        "object" => {
+            let mut obj = Map::new();
+            if let Some(props) = schema.get("properties").and_then(Value::as_object) {
+                for (k, s) in props {
+                    obj.insert(k.clone(), sample_value(root, s, k, depth + 1));
+                }
+            } else if let Some(extra) = schema.get("additionalProperties").filter(|v| v.is_object()) {
+                obj.insert("key".to_string(), sample_value(root, extra, "key", depth + 1));
+            }
+            Value::Object(obj)
+        }
+        "file" => json!("test.txt"),
+        _ => json!(sample_string(schema.get("format").and_then(Value::as_str).unwrap_or(""), name)),
+    }
+}
+
+/// 参数示例值，OpenAPI 2的非body参数直接在参数上声明类型
+fn parameter_value(root: &Value, param: &Value) -> Value {
+    if let Some(v) = param.get("example").or_else(|| param.get("x-example")) {
+        return v.clone();
+    }
+    if let Some(example) = param.get("examples").and_then(Value::as_object).and_then(|m| m.values().next()) {
+        if let Some(v) = resolve(root, example).get("value") {
+            return v.clone();
+        }
+    }
+    let name = param.get("name").and_then(Value::as_str).unwrap_or_default();
+    sample_value(root, param.get("schema").unwrap_or(param), name, 0)
+}
+
+/// 参数值转为字符串，数组按逗号拼接
+fn param_string(value: &Value) -> String {
+    match value {
+        Value::Array(items) => items.iter().map(value_to_param).collect::<Vec<_>>().join(","),
+        other => value_to_param(other),
+    }
+}
+
+fn object_fields(value: &Value) -> Vec<(String, String)> {
+    value
+        .as_object()
+        .map(|m| m.iter().map(|(k, v)| (k.clone(), param_string(v))).collect())
+        .unwrap_or_default()
+}
+
+/// 按媒体类型编码请求体，返回(Content-Type, 请求体)
+fn encode_body(media_type: &str, value: &Value) -> (String, Vec<u8>) {
+    if media_type.contains("json") {
+        (media_type.to_string(), serde_json::to_vec(value).unwrap_or_default())
+    } else if media_type.contains("x-www-form-urlencoded") {
+        (media_type.to_string(), form_body(&object_fields(value)))
+    } else if media_type.contains("multipart/form-data") {
+        multipart_body(&object_fields(value))
+    } else {
+        (media_type.to_string(), value_to_param(value).into_bytes())
+    }
+}
+
+/// 服务器地址，指定base_url时只保留文档中的路径部分
+fn server_url(doc: &Value, base_url: Option<&str>) -> Result<String, String> {
+    let server = if doc.get("swagger").is_some() {
+        let base_path = doc.get("basePath").and_then(Value::as_str).unwrap_or("");
+        match doc.get("host").and_then(Value::as_str) {
+            Some(host) => {
+                let scheme = doc.pointer("/schemes/0").and_then(Value::as_str).unwrap_or("https");
+                format!("{}://{}{}", scheme, host, base_path)
+            }
+            None => base_path.to_string(),
+        }
+    } else {
+        match doc.pointer("/servers/0") {
+            Some(server) => {
+                let mut url = server.get("url").and_then(Value::as_str).unwrap_or("").to_string();
+                if let Some(vars) = server.get("variables").and_then(Value::as_object) {
+                    for (k, v) in vars {
+                        let default = v.get("default").map(value_to_param).unwrap_or_default();
+                        url = url.replace(&format!("{{{}}}", k), &default);
+                    }
+                }
+                url
+            }
+            None => String::new(),
+        }
+    };
+
+    match base_url {
+        Some(base) => {
+            let mut path = match Url::parse(&server) {
+                Ok(u) => u.path().to_string(),
+                Err(_) => server,
+            };
+            if !path.is_empty() && !path.starts_with('/') {
+                path.insert(0, '/');
+            }
+            Ok(format!("{}{}", base.trim_end_matches('/'), path.trim_end_matches('/')))
+        }
+        None if Url::parse(&server).is_ok() => Ok(server.trim_end_matches('/').to_string()),
+        None => Err("文档未包含完整的服务器地址，请指定目标地址".to_string()),
+    }
+}
+
+/// 展开单个操作
+fn build_operation(
+    doc: &Value,
+    server: &str,
+    path: &str,
+    method: &str,
+    operation: &Value,
+    params: &[&Value],
+) -> HttpRequest {
+    let mut path = path.to_string();
+    let mut query = Vec::new();
+    let mut headers = HashMap::new();
+    let mut cookies = Vec::new();
+    let mut form = Vec::new();
+    let mut body_value = None;
+
+    for param in params {
+        let name = param.get("name").and_then(Value::as_str).unwrap_or_default();
+        let location = param.get("in").and_then(Value::as_str).unwrap_or_default();
+        if location == "body" {
+            body_value = Some(param.get("schema").map(|s| sample_value(doc, s, name, 0)).unwrap_or(Value::Null));
+            continue;
+        }
+        let value = param_string(&parameter_value(doc, param));
+        match location {
+            "path" => path = path.replace(&format!("{{{}}}", name), &urlencoding::encode(&value)),
+            "query" => query.push((name.to_string(), value)),
+            "header" => {
+                headers.insert(name.to_string(), value);
+            }
+            "cookie" => cookies.push(format!("{}={}", name, value)),
+            "formData" => form.push((name.to_string(), value)),
+            _ => {}
+        }
+    }
+    if !cookies.is_empty() {
+        headers.insert("Cookie".to_string(), cookies.join("; "));
+    }
+
+    let mut url = format!("{}{}", server, path);
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(&String::from_utf8_lossy(&form_body(&query)));
+    }
+
+    let mut body = None;
+    if doc.get("swagger").is_some() {
+        let consumes: Vec<&str> = operation
+            .get("consumes")
+            .or_else(|| doc.get("consumes"))
+            .and_then(Value::as_array)
+            .map(|c| c.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        if !form.is_empty() {
+            body = Some(if consumes.iter().any(|c| c.contains("multipart/form-data")) {
+                multipart_body(&form)
+            } else {
+                ("application/x-www-form-urlencoded".to_string(), form_body(&form))
+            });
+        } else if let Some(value) = body_value {
+            let media_type = consumes.iter().find(|c| c.contains("json")).or(consumes.first()).copied().unwrap_or("application/json");
+            body = Some(encode_body(media_type, &value));
+        }
+    } else if let Some(content) = operation
+        .get("requestBody")
+        .map(|b| resolve(doc, b))
+        .and_then(|b| b.get("content"))
+        .and_then(Value::as_object)
+    {
+        let preferred = ["json", "x-www-form-urlencoded", "multipart/form-data"]
+            .iter()
+            .find_map(|p| content.iter().find(|(k, _)| k.contains(p)))
+            .or_else(|| content.iter().next());
+        if let Some((media_type, media)) = preferred {
+            let value = media
+                .get("example")
+                .cloned()
+                .or_else(|| {
+                    media
+                        .get("examples")
+                        .and_then(Value::as_object)
+                        .and_then(|m| m.values().next())
+                        .and_then(|e| resolve(doc, e).get("value").cloned())
+                })
+                .unwrap_or_else(|| sample_value(doc, media.get("schema").unwrap_or(&Value::Null), "", 0));
+            body = Some(encode_body(media_type, &value));
+        }
+    }
+
+    let body = match body {
+        Some((content_type, body)) => {
+            headers.insert("Content-Type".to_string(), content_type);
+            body
+        }
+        None => Vec::new(),
+    };
+
+    build_request(method, &url, headers, body)
+}
+
+/// 展开文档中的所有操作
+pub(super) fn parse(doc: &Value, base_url: Option<&str>) -> Result<Vec<HttpRequest>, String> {
+    let server = server_url(doc, base_url)?;
+    let paths = doc.get("paths").and_then(Value::as_object).ok_or("文档中没有paths")?;
+
+    let mut requests = Vec::new();
+    for (path, item) in paths {
+        let item = resolve(doc, item);
+        let common: &[Value] = item.get("parameters").and_then(Value::as_array).map(Vec::as_slice).unwrap_or(&[]);
+        for method in METHODS {
+            let operation = match item.get(method) {
+                Some(op) => op,
+                None => continue,
+            };
+            // 操作级参数覆盖路径级同名参数
+            let mut params: Vec<&Value> = Vec::new();
+            let own = operation.get("parameters").and_then(Value::as_array).into_iter().flatten();
+            for param in common.iter().chain(own) {
+                let param = resolve(doc, param);
+                let key = (param.get("name"), param.get("in"));
+                params.retain(|p| (p.get("name"), p.get("in")) != key);
+                params.push(param);
+            }
+            requests.push(build_operation(doc, &server, path, method, operation, &params));
+        }
+    }
+    Ok(requests)
+}
@@ -0,0 +1,215 @@
+//! Postman集合（v2.0/v2.1）解析
+
+use super::{build_request, form_body, multipart_body, value_to_param};
+use crate::handler::scan::proxy::HttpRequest;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// 替换{{变量}}，未定义的变量使用占位值
+fn substitute(text: &str, vars: &HashMap<String, String>) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start + 2..].find("}}") {
+            Some(end) => start + 2 + end,
+            None => break,
+        };
+        out.push_str(&rest[..start]);
+        let name = rest[start + 2..end].trim();
+        out.push_str(vars.get(name).map(String::as_str).unwrap_or("1"));
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 读取key/value列表，跳过已禁用的项
+fn key_values(list: Option<&Value>, vars: &HashMap<String, String>) -> Vec<(String, String)> {
+    list.and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter(|i| !i.get("disabled").and_then(Value::as_bool).unwrap_or(false))
+                .filter_map(|i| {
+                    let key = i.get("key").and_then(Value::as_str)?;
+                    let value = i.get("value").map(value_to_param).unwrap_or_default();
+                    Some((substitute(key, vars), substitute(&value, vars)))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 读取认证参数，兼容v2.1的数组形式和v2.0的对象形式
+fn auth_param(auth: &Value, auth_type: &str, key: &str) -> Option<String> {
+    match auth.get(auth_type)? {
+        Value::Array(items) => items
+            .iter()
+            .find(|i| i.get("key").and_then(Value::as_str) == Some(key))
+            .and_then(|i| i.get("value"))
+            .map(value_to_param),
+        Value::Object(map) => map.get(key).map(value_to_param),
+        _ => None,
+    }
+}
+
+/// 请求URL，base_url会替换开头的变量（如{{baseUrl}}）
+fn request_url(url: Option<&Value>, vars: &HashMap<String, String>, base_url: Option<&str>) -> String {
+    let raw = match url {
+        Some(Value::String(s)) => s.clone(),
+        Some(obj @ Value::Object(_)) => match obj.get("raw").and_then(Value::as_str) {
+            Some(raw) => raw.to_string(),
+            None => {
+                let join = |key: &str, sep: &str| {
+                    obj.get(key)
+                        .and_then(Value::as_array)
+                        .map(|parts| parts.iter().map(value_to_param).collect::<Vec<_>>().join(sep))
+                        .unwrap_or_default()
+                };
+                let mut raw = match obj.get("protocol").and_then(Value::as_str) {
+                    Some(protocol) => format!("{}://{}", protocol, join("host", ".")),
+                    None => join("host", "."),
+                };
+                if let Some(port) = obj.get("port").and_then(Value::as_str) {
+                    raw = format!("{}:{}", raw, port);
+                }
+                let path = join("path", "/");
+                if !path.is_empty() {
+                    raw = format!("{}/{}", raw, path);
+                }
+                let query = key_values(obj.get("query"), vars);
+                if !query.is_empty() {
+                    raw = format!("{}?{}", raw, String::from_utf8_lossy(&form_body(&query)));
+                }
+                raw
+            }
+        },
+        _ => String::new(),
+    };
+
+    let mut raw = raw.trim().to_string();
+    if let Some(base) = base_url {
+        if raw.starts_with("{{") {
+            if let Some(end) = raw.find("}}") {
+                raw = format!("{}{}", base.trim_end_matches('/'), &raw[end + 2..]);
+            }
+        }
+    }
+
+    let url = substitute(&raw, vars);
+    if url.starts_with("http://") || url.starts_with("https://") {
+        url
+    } else {
+        match base_url {
+            Some(base) => format!("{}/{}", base.trim_end_matches('/'), url.trim_start_matches('/')),
+            None => format!("http://{}", url),
+        }
+    }
+}
+
+/// 展开单个请求
+fn build_item(request: &Value, auth: Option<&Value>, vars: &HashMap<String, String>, base_url: Option<&str>) -> HttpRequest {
+    // 请求可以直接写成URL字符串
+    if request.is_string() {
+        return build_request("GET", &request_url(Some(request), vars, base_url), HashMap::new(), Vec::new());
+    }
+
+    let method = request.get("method").and_then(Value::as_str).unwrap_or("GET");
+    let mut url = request_url(request.get("url"), vars, base_url);
+    let mut headers: HashMap<String, String> = key_values(request.get("header"), vars).into_iter().collect();
+
+    if let Some(auth) = request.get("auth").or(auth) {
+        match auth.get("type").and_then(Value::as_str).unwrap_or_default() {
+            "bearer" => {
+                if let Some(token) = auth_param(auth, "bearer", "token") {
+                    headers.insert("Authorization".to_string(), format!("Bearer {}", substitute(&token, vars)));
+                }
+            }
+            "basic" => {
+                let username = auth_param(auth, "basic", "username").unwrap_or_default();
+                let password = auth_param(auth, "basic", "password").unwrap_or_default();
+                let credential = format!("{}:{}", substitute(&username, vars), substitute(&password, vars));
+                headers.insert("Authorization".to_string(), format!("Basic {}", STANDARD.encode(credential)));
+            }
+            "apikey" => {
+                let key = substitute(&auth_param(auth, "apikey", "key").unwrap_or_default(), vars);
+                let value = substitute(&auth_param(auth, "apikey", "value").unwrap_or_default(), vars);
+                if auth_param(auth, "apikey", "in").as_deref() == Some("query") {
+                    let sep = if url.contains('?') { '&' } else { '?' };
+                    url = format!("{}{}{}", url, sep, String::from_utf8_lossy(&form_body(&[(key, value)])));
+                } else if !key.is_empty() {
+                    headers.insert(key, value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut body = Vec::new();
+    let mut content_type = None;
+    if let Some(b) = request.get("body") {
+        match b.get("mode").and_then(Value::as_str).unwrap_or_default() {
+            "raw" => {
+                body = substitute(b.get("raw").and_then(Value::as_str).unwrap_or_default(), vars).into_bytes();
+                content_type = match b.pointer("/options/raw/language").and_then(Value::as_str) {
+                    Some("json") => Some("application/json".to_string()),
+                    Some("xml") => Some("application/xml".to_string()),
+                    Some("html") => Some("text/html".to_string()),
+                    Some("text") => Some("text/plain".to_string()),
+                    _ => None,
+                };
+            }
+            "urlencoded" => {
+                body = form_body(&key_values(b.get("urlencoded"), vars));
+                content_type = Some("application/x-www-form-urlencoded".to_string());
+            }
+            "formdata" => {
+                let (ct, data) = multipart_body(&key_values(b.get("formdata"), vars));
+                body = data;
+                content_type = Some(ct);
+            }
+            "graphql" => {
+                let query = b.pointer("/graphql/query").and_then(Value::as_str).unwrap_or_default();
+                let variables = b
+                    .pointer("/graphql/variables")
+                    .and_then(Value::as_str)
+                    .and_then(|v| serde_json::from_str::<Value>(&substitute(v, vars)).ok())
+                    .unwrap_or_else(|| json!({}));
+                body = serde_json::to_vec(&json!({ "query": query, "variables": variables })).unwrap_or_default();
+                content_type = Some("application/json".to_string());
+            }
+            _ => {}
+        }
+    }
+    if let Some(ct) = content_type {
+        if !headers.keys().any(|k| k.eq_ignore_ascii_case("content-type")) {
+            headers.insert("Content-Type".to_string(), ct);
+        }
+    }
+
+    build_request(method, &url, headers, body)
+}
+
+/// 递归遍历目录，目录上的认证设置由子请求继承
+fn walk(items: &Value, auth: Option<&Value>, vars: &HashMap<String, String>, base_url: Option<&str>, out: &mut Vec<HttpRequest>) {
+    for item in items.as_array().into_iter().flatten() {
+        let auth = item.get("auth").or(auth);
+        if let Some(children) = item.get("item") {
+            walk(children, auth, vars, base_url, out);
+        } else if let Some(request) = item.get("request") {
+            out.push(build_item(request, auth, vars, base_url));
+        }
+    }
+}
+
+/// 展开集合中的所有请求
+pub(super) fn parse(doc: &Value, base_url: Option<&str>) -> Result<Vec<HttpRequest>, String> {
+    let vars: HashMap<String, String> = key_values(doc.get("variable"), &HashMap::new()).into_iter().collect();
+    let mut requests = Vec::new();
+    walk(doc.get("item").unwrap_or(&Value::Null), doc.get("auth"), &vars, base_url, &mut requests);
+    if requests.is_empty() {
+        return Err("集合中没有请求".to_string());
+    }
+    Ok(requests)
+}
@@ -94,6 +94,54 @@ impl HtmlAstAnalyzer {
         watched.iter().cloned().collect()
     }
     
+    /// 提取meta refresh中的跳转地址
+    pub fn extract_meta_refresh_urls(&self, content: &str) -> Vec<String> {
+        let document = kuchiki::parse_html().one(content);
+        let mut urls = Vec::new();
+
+        if let Ok(metas) = document.select("meta") {
+            for meta in metas {
+                let attrs = meta.attributes.borrow();
+                let is_refresh = attrs
+                    .get("http-equiv")
+                    .map_or(false, |v| v.trim().eq_ignore_ascii_case("refresh"));
+                if !is_refresh {
+                    continue;
+                }
+
+                // content格式: "5; url=https://example.com"
+                if let Some(value) = attrs.get("content") {
+                    if let Some(url) = parse_refresh_url(value) {
+                        urls.push(url);
+                    }
+                }
+            }
+        }
+
+        urls
+    }
+
+    /// 提取内联脚本内容
+    pub fn extract_inline_scripts(&self, content: &str) -> Vec<String> {
+        let document = kuchiki::parse_html().one(content);
+        let mut scripts = Vec::new();
+
+        if let Ok(nodes) = document.select("script") {
+            for node in nodes {
+                // 跳过外部脚本
+                if node.attributes.borrow().get("src").is_some() {
+                    continue;
+                }
+                let text = node.text_contents();
+                if !text.trim().is_empty() {
+                    scripts.push(text);
+                }
+            }
+        }
+
+        scripts
+    }
+
     /// 检查标签是否在监控列表中
     fn is_tag_watched(&self, tag_name: &str) -> bool {
         let watched = self.watched_tags.read().unwrap();
@@ -408,4 +456,17 @@ impl AstAnalyzer for HtmlAstAnalyzer {
             details,
         })
     }
-} 
\ No newline at end of file
+} 
+/// 解析refresh指令（meta标签或Refresh响应头）中的跳转地址
+pub fn parse_refresh_url(value: &str) -> Option<String> {
+    let lower = value.to_lowercase();
+    let idx = lower.find("url")?;
+    let rest = value[idx + 3..].trim_start();
+    let rest = rest.strip_prefix('=')?.trim();
+    let url = rest.trim_matches(|c| c == '\'' || c == '"').trim();
+    if url.is_empty() {
+        None
+    } else {
+        Some(url.to_string())
+    }
+}
@@ -1,6 +1,13 @@
-use crate::scan::ast::{AstAnalysisResult, AstAnalyzer, InjectionResult, DangerousNode, RiskLevel};
+use crate::scan::ast::{AstAnalysisResult, AstAnalyzer, InjectionResult, DangerousNode, NodeLocation, RiskLevel};
 use anyhow::Result;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{AssignmentExpression, CallExpression, Expression, NewExpression, Program, VariableDeclarator};
+use oxc_ast::AstKind;
+use oxc_ast_visit::{walk, Visit};
+use oxc_parser::Parser;
+use oxc_span::{GetSpan, SourceType, Span};
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 
 /// JavaScript AST分析器
 #[allow(dead_code)]
@@ -47,73 +54,718 @@ impl JsAstAnalyzer {
             dangerous_globals,
         }
     }
+
+    /// 提取页面跳转类sink（location赋值、location.assign/replace、window.open）
+    ///
+    /// 返回的 `DangerousNode.content` 为写入sink的表达式源码，字符串字面量会去掉引号
+    pub fn extract_location_sinks(&self, content: &str) -> Vec<DangerousNode> {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, content, SourceType::default()).parse();
+        if ret.panicked {
+            return Vec::new();
+        }
+
+        let mut visitor = LocationSinkVisitor {
+            source: content,
+            sinks: Vec::new(),
+        };
+        visitor.visit_program(&ret.program);
+        visitor.sinks
+    }
 }
 
-impl AstAnalyzer for JsAstAnalyzer {
-    fn analyze(&self, content: &str) -> Result<AstAnalysisResult> {
-        // 使用字段进行简单分析
-        let mut dangerous_nodes = Vec::new();
-        
-        // 简单检查是否存在危险函数
-        for func in &self.dangerous_functions {
-            if content.contains(func) {
-                dangerous_nodes.push(DangerousNode {
-                    node_type: "Function".to_string(),
-                    content: func.clone(),
-                    location: None,
-                    risk_level: RiskLevel::Medium,
-                    reason: format!("Dangerous function: {}", func),
-                });
+/// 根据偏移量计算节点位置
+fn span_to_location(source: &str, span: Span) -> NodeLocation {
+    let (start_line, start_column) = offset_to_line_col(source, span.start as usize);
+    let (end_line, end_column) = offset_to_line_col(source, span.end as usize);
+    NodeLocation {
+        start_line,
+        start_column,
+        end_line,
+        end_column,
+    }
+}
+
+/// 将字节偏移量转换为行列号（从1开始）
+fn offset_to_line_col(source: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(source.len());
+    let before = &source.as_bytes()[..offset];
+    let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+    let column = match before.iter().rposition(|&b| b == b'\n') {
+        Some(pos) => offset - pos,
+        None => offset + 1,
+    };
+    (line, column)
+}
+
+/// 跳转sink访问器
+struct LocationSinkVisitor<'s> {
+    source: &'s str,
+    sinks: Vec<DangerousNode>,
+}
+
+impl<'s> LocationSinkVisitor<'s> {
+    /// 获取节点对应的源码
+    fn text(&self, span: Span) -> &'s str {
+        self.source
+            .get(span.start as usize..span.end as usize)
+            .unwrap_or("")
+    }
+
+    /// 判断赋值目标是否为location
+    fn is_location_target(target: &str) -> bool {
+        let target: String = target.chars().filter(|c| !c.is_whitespace()).collect();
+        matches!(
+            target.as_str(),
+            "location"
+                | "window.location"
+                | "document.location"
+                | "self.location"
+                | "top.location"
+                | "parent.location"
+        ) || target.ends_with("location.href")
+    }
+
+    /// 去掉字符串字面量两侧的引号
+    fn unquote(value: &str) -> String {
+        let value = value.trim();
+        let bytes = value.as_bytes();
+        if bytes.len() >= 2
+            && (bytes[0] == b'"' || bytes[0] == b'\'' || bytes[0] == b'`')
+            && bytes[bytes.len() - 1] == bytes[0]
+        {
+            value[1..value.len() - 1].to_string()
+        } else {
+            value.to_string()
+        }
+    }
+
+    fn push_sink(&mut self, sink: &str, span: Span) {
+        self.sinks.push(DangerousNode {
+            node_type: format!("sink:{}", sink),
+            content: Self::unquote(self.text(span)),
+            location: Some(span_to_location(self.source, span)),
+            risk_level: RiskLevel::Medium,
+            reason: format!("数据写入页面跳转sink: {}", sink),
+        });
+    }
+}
+
+impl<'a, 's> Visit<'a> for LocationSinkVisitor<'s> {
+    fn visit_assignment_expression(&mut self, it: &AssignmentExpression<'a>) {
+        let target = self.text(it.left.span());
+        if Self::is_location_target(target) {
+            let target = target.to_string();
+            self.push_sink(&target, it.right.span());
+        }
+        walk::walk_assignment_expression(self, it);
+    }
+
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        let callee: String = self
+            .text(it.callee.span())
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let is_sink = callee.ends_with("location.assign")
+            || callee.ends_with("location.replace")
+            || callee == "window.open"
+            || callee == "open";
+        if is_sink {
+            if let Some(arg) = it.arguments.first() {
+                self.push_sink(&callee, arg.span());
             }
         }
-        
-        // 检查危险属性
-        for prop in &self.dangerous_properties {
-            if content.contains(prop) {
-                dangerous_nodes.push(DangerousNode {
-                    node_type: "Property".to_string(),
-                    content: prop.clone(),
-                    location: None,
-                    risk_level: RiskLevel::Medium,
-                    reason: format!("Dangerous property: {}", prop),
-                });
+        walk::walk_call_expression(self, it);
+    }
+}
+
+
+/// 污点源，`window.`前缀会在匹配前去掉
+const TAINT_SOURCES: [&str; 16] = [
+    "location",
+    "location.href",
+    "location.search",
+    "location.hash",
+    "location.pathname",
+    "document.location",
+    "document.location.href",
+    "document.location.search",
+    "document.location.hash",
+    "document.location.pathname",
+    "document.URL",
+    "document.documentURI",
+    "document.baseURI",
+    "document.referrer",
+    "document.cookie",
+    "name",
+];
+
+/// 数据原样传递的全局函数
+const PASSTHROUGH_FUNCTIONS: [&str; 6] = ["decodeURIComponent", "decodeURI", "unescape", "atob", "JSON.parse", "String"];
+
+/// 净化函数，经过这些函数的数据不再视为污点
+const SANITIZERS: [&str; 8] = [
+    "encodeURIComponent",
+    "encodeURI",
+    "escape",
+    "parseInt",
+    "parseFloat",
+    "Number",
+    "DOMPurify.sanitize",
+    "btoa",
+];
+
+/// 不污染返回值的方法，例如`location.search.indexOf(...)`返回的是数字
+const NUMERIC_METHODS: [&str; 5] = ["indexOf", "lastIndexOf", "search", "charCodeAt", "localeCompare"];
+
+/// 污点传播迭代的最大轮数
+const MAX_TAINT_PASSES: usize = 5;
+
+/// 去掉源码中的空白字符
+fn compact(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// 取成员表达式文本的最后一个属性名，兼容`a.b`和`a['b']`
+fn last_property(target: &str) -> &str {
+    if let Some(inner) = target.strip_suffix(']') {
+        if let Some(pos) = inner.rfind('[') {
+            return inner[pos + 1..].trim_matches(|c| c == '"' || c == '\'' || c == '`');
+        }
+    }
+    target.rsplit('.').next().unwrap_or(target)
+}
+
+/// 判断是否为简单标识符
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty()
+        && !text.starts_with(|c: char| c.is_ascii_digit())
+        && text.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// 源到sink的数据流访问器
+///
+/// 按变量名做流不敏感的传播：先多轮遍历直到污点变量集合稳定，再遍历一次收集sink
+struct TaintVisitor<'s, 'f> {
+    source: &'s str,
+    dangerous_functions: &'f HashSet<String>,
+    /// 被污染的变量名 -> 污点源
+    tainted: HashMap<String, String>,
+    /// message事件处理函数的参数名
+    message_params: HashSet<String>,
+    /// 本轮是否发现了新的污点变量
+    changed: bool,
+    /// 是否收集sink
+    collect: bool,
+    findings: Vec<DangerousNode>,
+    seen: HashSet<(u32, u32)>,
+}
+
+impl<'s, 'f> TaintVisitor<'s, 'f> {
+    fn new(source: &'s str, dangerous_functions: &'f HashSet<String>) -> Self {
+        Self {
+            source,
+            dangerous_functions,
+            tainted: HashMap::new(),
+            message_params: HashSet::new(),
+            changed: false,
+            collect: false,
+            findings: Vec::new(),
+            seen: HashSet::new(),
+        }
+    }
+
+    fn text(&self, span: Span) -> &'s str {
+        self.source
+            .get(span.start as usize..span.end as usize)
+            .unwrap_or("")
+    }
+
+    /// 运行传播和收集
+    fn run(mut self, program: &Program<'_>) -> Vec<DangerousNode> {
+        for _ in 0..MAX_TAINT_PASSES {
+            self.changed = false;
+            self.visit_program(program);
+            if !self.changed {
+                break;
             }
         }
-        
-        // 检查危险全局对象
-        for global in &self.dangerous_globals {
-            if content.contains(global) {
-                dangerous_nodes.push(DangerousNode {
-                    node_type: "Global".to_string(),
-                    content: global.clone(),
-                    location: None,
-                    risk_level: RiskLevel::Medium,
-                    reason: format!("Dangerous global: {}", global),
-                });
+        self.collect = true;
+        self.visit_program(program);
+        self.findings
+    }
+
+    fn mark(&mut self, name: &str, source: String) {
+        if !self.tainted.contains_key(name) {
+            self.tainted.insert(name.to_string(), source);
+            self.changed = true;
+        }
+    }
+
+    /// 记录事件处理函数的第一个参数
+    fn register_message_handler(&mut self, handler: &Expression<'_>) {
+        let param = match handler {
+            Expression::FunctionExpression(f) => f.params.items.first().map(|p| p.pattern.span()),
+            Expression::ArrowFunctionExpression(f) => f.params.items.first().map(|p| p.pattern.span()),
+            _ => None,
+        };
+        if let Some(span) = param {
+            let name = self.text(span).trim();
+            if is_identifier(name) && self.message_params.insert(name.to_string()) {
+                self.changed = true;
             }
         }
-        
+    }
+
+    /// 表达式文本是否为污点源
+    fn source_name(&self, text: &str) -> Option<String> {
+        let text = compact(text);
+        let stripped = text
+            .strip_prefix("window.")
+            .or_else(|| text.strip_prefix("self."))
+            .unwrap_or(&text);
+        // 单独的name只有带window.前缀时才视为window.name
+        if stripped == "name" && stripped == text {
+            return None;
+        }
+        if TAINT_SOURCES.contains(&stripped) {
+            return Some(text.clone());
+        }
+        None
+    }
+
+    /// 计算表达式携带的污点源
+    fn taint_of(&self, expr: &Expression<'_>) -> Option<String> {
+        match expr {
+            Expression::Identifier(id) => self
+                .tainted
+                .get(id.name.as_str())
+                .cloned()
+                .or_else(|| self.source_name(id.name.as_str())),
+            Expression::StaticMemberExpression(_)
+            | Expression::ComputedMemberExpression(_)
+            | Expression::PrivateFieldExpression(_) => {
+                if let Some(source) = self.source_name(self.text(expr.span())) {
+                    return Some(source);
+                }
+                let member = expr.as_member_expression()?;
+                if let Expression::Identifier(object) = member.object() {
+                    if self.message_params.contains(object.name.as_str()) {
+                        return match member.static_property_name() {
+                            Some("data") => Some("postMessage".to_string()),
+                            _ => None,
+                        };
+                    }
+                }
+                self.taint_of(member.object())
+            }
+            Expression::CallExpression(call) => {
+                let callee = compact(self.text(call.callee.span()));
+                if SANITIZERS.contains(&callee.as_str()) {
+                    return None;
+                }
+                let first_arg = call.arguments.first().and_then(|a| a.as_expression());
+                if PASSTHROUGH_FUNCTIONS.contains(&callee.as_str()) {
+                    return first_arg.and_then(|a| self.taint_of(a));
+                }
+                let member = call.callee.as_member_expression()?;
+                if member
+                    .static_property_name()
+                    .is_some_and(|m| NUMERIC_METHODS.contains(&m))
+                {
+                    return None;
+                }
+                // 字符串方法（slice、split、replace、get等）沿接收者传播，concat还会传播参数
+                self.taint_of(member.object()).or_else(|| {
+                    if member.static_property_name() == Some("concat") {
+                        call.arguments
+                            .iter()
+                            .filter_map(|a| a.as_expression())
+                            .find_map(|a| self.taint_of(a))
+                    } else {
+                        None
+                    }
+                })
+            }
+            Expression::NewExpression(new) => {
+                let callee = compact(self.text(new.callee.span()));
+                if callee == "URLSearchParams" || callee == "URL" {
+                    new.arguments
+                        .first()
+                        .and_then(|a| a.as_expression())
+                        .and_then(|a| self.taint_of(a))
+                } else {
+                    None
+                }
+            }
+            Expression::BinaryExpression(bin) if bin.operator.as_str() == "+" => {
+                self.taint_of(&bin.left).or_else(|| self.taint_of(&bin.right))
+            }
+            Expression::LogicalExpression(logical) => {
+                self.taint_of(&logical.left).or_else(|| self.taint_of(&logical.right))
+            }
+            Expression::ConditionalExpression(cond) => self
+                .taint_of(&cond.consequent)
+                .or_else(|| self.taint_of(&cond.alternate)),
+            Expression::TemplateLiteral(template) => {
+                template.expressions.iter().find_map(|e| self.taint_of(e))
+            }
+            Expression::ParenthesizedExpression(paren) => self.taint_of(&paren.expression),
+            Expression::SequenceExpression(seq) => seq.expressions.last().and_then(|e| self.taint_of(e)),
+            Expression::AwaitExpression(await_expr) => self.taint_of(&await_expr.argument),
+            Expression::AssignmentExpression(assign) => self.taint_of(&assign.right),
+            _ => None,
+        }
+    }
+
+    fn push_finding(&mut self, sink: &str, source: String, span: Span, risk_level: RiskLevel) {
+        if !self.collect || !self.seen.insert((span.start, span.end)) {
+            return;
+        }
+        self.findings.push(DangerousNode {
+            node_type: format!("taint:{}", sink),
+            content: self.text(span).to_string(),
+            location: Some(span_to_location(self.source, span)),
+            risk_level,
+            reason: format!("来自 {} 的数据流入 {}", source, sink),
+        });
+    }
+
+    /// 第n个参数的污点
+    fn argument_taint(&self, call: &CallExpression<'_>, index: usize) -> Option<String> {
+        call.arguments
+            .get(index)
+            .and_then(|a| a.as_expression())
+            .and_then(|a| self.taint_of(a))
+    }
+}
+
+impl<'a, 's, 'f> Visit<'a> for TaintVisitor<'s, 'f> {
+    fn visit_variable_declarator(&mut self, it: &VariableDeclarator<'a>) {
+        if let Some(init) = &it.init {
+            let name = self.text(it.id.span()).trim();
+            if is_identifier(name) {
+                if let Some(source) = self.taint_of(init) {
+                    self.mark(name, source);
+                }
+            }
+        }
+        walk::walk_variable_declarator(self, it);
+    }
+
+    fn visit_assignment_expression(&mut self, it: &AssignmentExpression<'a>) {
+        let target = compact(self.text(it.left.span()));
+        let property = last_property(&target).to_string();
+
+        if property == "onmessage" {
+            self.register_message_handler(&it.right);
+        }
+
+        if let Some(source) = self.taint_of(&it.right) {
+            if is_identifier(&target) {
+                self.mark(&target, source.clone());
+            }
+            let sink = match property.as_str() {
+                "innerHTML" | "outerHTML" | "srcdoc" => Some(RiskLevel::High),
+                "src" => Some(RiskLevel::High),
+                "href" | "action" | "formAction" => Some(RiskLevel::Medium),
+                _ if LocationSinkVisitor::is_location_target(&target) => Some(RiskLevel::Medium),
+                _ => None,
+            };
+            if let Some(risk_level) = sink {
+                self.push_finding(&target, source, it.span, risk_level);
+            }
+        }
+        walk::walk_assignment_expression(self, it);
+    }
+
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        let callee = compact(self.text(it.callee.span()));
+        let plain = callee.strip_prefix("window.").unwrap_or(&callee);
+        let method = last_property(&callee).to_string();
+
+        if method == "addEventListener" {
+            let event = it.arguments.first().map(|a| self.text(a.span())).unwrap_or("");
+            if LocationSinkVisitor::unquote(event) == "message" {
+                if let Some(handler) = it.arguments.get(1).and_then(|a| a.as_expression()) {
+                    self.register_message_handler(handler);
+                }
+            }
+        }
+
+        let mut sink = None;
+        if self.dangerous_functions.contains(plain) {
+            let is_timer = plain == "setTimeout" || plain == "setInterval";
+            let callback_is_function = matches!(
+                it.arguments.first().and_then(|a| a.as_expression()),
+                Some(Expression::FunctionExpression(_)) | Some(Expression::ArrowFunctionExpression(_))
+            );
+            if !(is_timer && callback_is_function) {
+                let taint = if is_timer || plain.starts_with("document.") {
+                    self.argument_taint(it, 0)
+                } else {
+                    (0..it.arguments.len()).find_map(|i| self.argument_taint(it, i))
+                };
+                let risk = if plain.starts_with("document.") { RiskLevel::High } else { RiskLevel::Critical };
+                sink = taint.map(|s| (s, risk));
+            }
+        } else {
+            match method.as_str() {
+                "insertAdjacentHTML" => {
+                    sink = self.argument_taint(it, 1).map(|s| (s, RiskLevel::High));
+                }
+                "createContextualFragment" => {
+                    sink = self.argument_taint(it, 0).map(|s| (s, RiskLevel::High));
+                }
+                "html" | "append" | "prepend" | "after" | "before" | "replaceWith"
+                    if callee.starts_with('$') || callee.starts_with("jQuery") =>
+                {
+                    sink = self.argument_taint(it, 0).map(|s| (s, RiskLevel::High));
+                }
+                "setAttribute" => {
+                    let name = it.arguments.first().map(|a| self.text(a.span())).unwrap_or("");
+                    let name = LocationSinkVisitor::unquote(name).to_lowercase();
+                    if matches!(name.as_str(), "src" | "href" | "action" | "srcdoc") || name.starts_with("on") {
+                        sink = self.argument_taint(it, 1).map(|s| (s, RiskLevel::High));
+                    }
+                }
+                "assign" | "replace" if callee.ends_with("location.assign") || callee.ends_with("location.replace") => {
+                    sink = self.argument_taint(it, 0).map(|s| (s, RiskLevel::Medium));
+                }
+                "open" if plain == "open" => {
+                    sink = self.argument_taint(it, 0).map(|s| (s, RiskLevel::Medium));
+                }
+                _ if callee == "$" || callee == "jQuery" => {
+                    // 选择器注入，$(location.hash)
+                    sink = self.argument_taint(it, 0).map(|s| (s, RiskLevel::Medium));
+                }
+                _ => {}
+            }
+        }
+
+        if let Some((source, risk_level)) = sink {
+            self.push_finding(&callee, source, it.span, risk_level);
+        }
+        walk::walk_call_expression(self, it);
+    }
+
+    fn visit_new_expression(&mut self, it: &NewExpression<'a>) {
+        let callee = compact(self.text(it.callee.span()));
+        if callee == "Function" || callee == "window.Function" {
+            let taint = it
+                .arguments
+                .iter()
+                .filter_map(|a| a.as_expression())
+                .find_map(|a| self.taint_of(a));
+            if let Some(source) = taint {
+                self.push_finding("new Function", source, it.span, RiskLevel::Critical);
+            }
+        }
+        walk::walk_new_expression(self, it);
+    }
+}
+
+/// 结构统计访问器，统计节点数量、深度和节点类型
+#[derive(Default)]
+struct StructureVisitor {
+    node_count: usize,
+    depth: usize,
+    max_depth: usize,
+    node_types: HashMap<String, usize>,
+    /// 按遍历顺序记录的节点类型，用于计算结构哈希
+    sequence: Vec<String>,
+}
+
+impl<'a> Visit<'a> for StructureVisitor {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        self.node_count += 1;
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+        // debug_name 可能附带标识符名称，例如 IdentifierReference(foo)，只保留类型
+        let name = kind.debug_name();
+        let name = name.split('(').next().unwrap_or_default().to_string();
+        *self.node_types.entry(name.clone()).or_insert(0) += 1;
+        self.sequence.push(name);
+    }
+
+    fn leave_node(&mut self, _kind: AstKind<'a>) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+}
+
+impl AstAnalyzer for JsAstAnalyzer {
+    fn analyze(&self, content: &str) -> Result<AstAnalysisResult> {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, content, SourceType::default()).parse();
+
+        let mut structure = StructureVisitor::default();
+        structure.visit_program(&ret.program);
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        structure.sequence.hash(&mut hasher);
+        let structure_hash = format!("{:x}", hasher.finish());
+
+        let syntax_error = ret.errors.first().map(|e| e.to_string());
+        let has_syntax_error = ret.panicked || syntax_error.is_some();
+
+        // 解析失败时AST不完整，不做数据流分析
+        let dangerous_nodes = if ret.panicked {
+            Vec::new()
+        } else {
+            TaintVisitor::new(content, &self.dangerous_functions).run(&ret.program)
+        };
+
         Ok(AstAnalysisResult {
-            node_count: 0,
-            depth: 0,
-            structure_hash: "".to_string(),
-            node_types: HashMap::new(),
-            has_syntax_error: false,
-            syntax_error: None,
+            node_count: structure.node_count,
+            depth: structure.max_depth,
+            structure_hash,
+            node_types: structure.node_types,
+            has_syntax_error,
+            syntax_error,
             dangerous_nodes,
         })
     }
-    
-    fn detect_injection(&self, _original: &AstAnalysisResult, _modified: &AstAnalysisResult) -> Result<InjectionResult> {
-        // Simplified implementation
+
+    fn detect_injection(&self, original: &AstAnalysisResult, modified: &AstAnalysisResult) -> Result<InjectionResult> {
+        // 新出现的数据流
+        let new_dangerous_nodes: Vec<&DangerousNode> = modified
+            .dangerous_nodes
+            .iter()
+            .filter(|node| {
+                !original
+                    .dangerous_nodes
+                    .iter()
+                    .any(|o| o.node_type == node.node_type && o.content == node.content)
+            })
+            .collect();
+
+        // 载荷破坏了原有语法
+        let syntax_broken = modified.has_syntax_error && !original.has_syntax_error;
+
+        // 载荷逃逸出字符串后会新增节点，例如CallExpression
+        let mut added_types: Vec<(&String, usize, usize)> = modified
+            .node_types
+            .iter()
+            .filter_map(|(t, &count)| {
+                let before = original.node_types.get(t).copied().unwrap_or(0);
+                (count > before).then_some((t, before, count))
+            })
+            .collect();
+        added_types.sort();
+        let structure_changed = original.structure_hash != modified.structure_hash && !added_types.is_empty();
+
+        let detected = !new_dangerous_nodes.is_empty() || syntax_broken || structure_changed;
+        if !detected {
+            return Ok(InjectionResult {
+                detected: false,
+                injection_type: None,
+                risk_level: None,
+                injection_point: None,
+                injection_content: None,
+                location: None,
+                details: None,
+            });
+        }
+
+        let risk_level = new_dangerous_nodes
+            .iter()
+            .map(|n| n.risk_level.clone())
+            .max()
+            .unwrap_or(if structure_changed { RiskLevel::Medium } else { RiskLevel::Low });
+
+        let mut details = String::new();
+        if syntax_broken {
+            details.push_str(&format!(
+                "JavaScript语法被破坏: {}\n",
+                modified.syntax_error.clone().unwrap_or_default()
+            ));
+        }
+        if structure_changed {
+            details.push_str("JavaScript结构被修改:\n");
+            for (node_type, before, after) in &added_types {
+                details.push_str(&format!("- {}: {} -> {}\n", node_type, before, after));
+            }
+        }
+        if !new_dangerous_nodes.is_empty() {
+            details.push_str("检测到新的数据流:\n");
+            for node in &new_dangerous_nodes {
+                details.push_str(&format!("- {}\n  内容: {}\n", node.reason, node.content));
+            }
+        }
+
+        let first = new_dangerous_nodes.first();
         Ok(InjectionResult {
-            detected: false,
-            injection_type: None,
-            risk_level: None,
-            injection_point: None,
-            injection_content: None,
-            location: None,
-            details: None,
+            detected: true,
+            injection_type: Some(if first.is_some() { "DOM XSS" } else { "JavaScript注入" }.to_string()),
+            risk_level: Some(risk_level),
+            injection_point: first
+                .map(|n| n.node_type.trim_start_matches("taint:").to_string())
+                .or_else(|| added_types.first().map(|(t, _, _)| t.to_string())),
+            injection_content: first.map(|n| n.content.clone()),
+            location: first.and_then(|n| n.location.clone()),
+            details: Some(details),
         })
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flows(code: &str) -> Vec<DangerousNode> {
+        JsAstAnalyzer::new().analyze(code).unwrap().dangerous_nodes
+    }
+
+    #[test]
+    fn test_taint_flow_through_variables() {
+        let code = "var q = new URLSearchParams(location.search).get('q');\nvar msg = decodeURIComponent(q);\ndocument.getElementById('out').innerHTML = '<b>' + msg + '</b>';";
+        let nodes = flows(code);
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].node_type, "taint:document.getElementById('out').innerHTML");
+        assert_eq!(nodes[0].risk_level, RiskLevel::High);
+        assert!(nodes[0].reason.contains("location.search"));
+        let location = nodes[0].location.as_ref().unwrap();
+        assert_eq!((location.start_line, location.start_column), (3, 1));
+    }
+
+    #[test]
+    fn test_sanitized_and_safe_sinks() {
+        assert!(flows("el.innerHTML = encodeURIComponent(location.hash);").is_empty());
+        assert!(flows("el.textContent = location.hash;").is_empty());
+        assert!(flows("setTimeout(function () { go(location.hash); }, 10);").is_empty());
+        assert!(flows("var i = location.href.indexOf('#'); el.innerHTML = i;").is_empty());
+    }
+
+    #[test]
+    fn test_sinks() {
+        let code = r#"
+window.addEventListener('message', function (e) {
+  eval(e.data.code);
+});
+setTimeout("run(" + document.referrer + ")", 0);
+var s = document.createElement('script');
+s.src = location.hash.slice(1);
+document.write(`<img src=${window.name}>`);
+"#;
+        let nodes = flows(code);
+        let types: Vec<&str> = nodes.iter().map(|n| n.node_type.as_str()).collect();
+        assert_eq!(types, vec!["taint:eval", "taint:setTimeout", "taint:s.src", "taint:document.write"]);
+        assert!(nodes[0].reason.contains("postMessage"));
+        assert_eq!(nodes[0].risk_level, RiskLevel::Critical);
+        assert_eq!(nodes[1].location.as_ref().unwrap().start_line, 5);
+    }
+
+    #[test]
+    fn test_detect_injection() {
+        let analyzer = JsAstAnalyzer::new();
+        let original = analyzer.analyze("var a = 'test';").unwrap();
+        let reflected = analyzer.analyze("var a = 'foo bar';").unwrap();
+        let escaped = analyzer.analyze("var a = '';alert(1);//';").unwrap();
+
+        assert!(!analyzer.detect_injection(&original, &reflected).unwrap().detected);
+        let result = analyzer.detect_injection(&original, &escaped).unwrap();
+        assert!(result.detected);
+        assert!(result.details.unwrap().contains("CallExpression"));
+    }
+}
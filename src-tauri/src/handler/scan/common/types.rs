@@ -1,5 +1,7 @@
 // src-tauri/src/handler/scan/common/types.rs
+use crate::handler::scan::engine::{ByteRange, Confidence, ScanResult};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr};
 use std::str::FromStr;
 
@@ -58,6 +60,9 @@ pub struct ActiveScanConfig {
     pub save_results: bool,
     pub results_path: Option<String>,
     pub detailed_scan_options: Option<DetailedScanOptions>,
+    /// 扫描模板名称
+    #[serde(default)]
+    pub profile: Option<String>,
 }
 
 impl ActiveScanConfig {
@@ -97,6 +102,35 @@ impl ActiveScanConfig {
     }
 }
 
+/// API定义导入配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiImportConfig {
+    /// 定义文件路径，与content二选一
+    pub path: Option<String>,
+    /// 定义文件内容（OpenAPI 2/3的JSON/YAML或Postman集合）
+    pub content: Option<String>,
+    /// 目标地址，覆盖文档中的服务器地址
+    pub base_url: Option<String>,
+    /// 附加到每个请求的请求头，例如认证信息
+    pub headers: Option<HashMap<String, String>>,
+    /// 写入ASM api表的任务ID
+    pub task_id: Option<i32>,
+    /// 是否加入主动扫描
+    pub scan: bool,
+    /// 主动扫描使用的扫描模板名称
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiImportResult {
+    pub format: String,
+    pub title: String,
+    pub request_count: usize,
+    pub saved_count: usize,
+    pub queued: bool,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PassiveScanConfig { // Renamed from ScanConfig to avoid conflict if ScanConfig is used elsewhere
     pub port: u16,
@@ -104,6 +138,9 @@ pub struct PassiveScanConfig { // Renamed from ScanConfig to avoid conflict if S
     pub results_path: Option<String>,
     pub intercept_tls: bool,
     pub use_plugins: bool, // Kept for passive scan context if needed
+    /// 扫描模板名称
+    #[serde(default)]
+    pub profile: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,6 +148,47 @@ pub struct VulnerabilityDetail {
     pub note: String,
     pub request: String,
     pub response: String,
+    /// payload在request中的字节区间
+    #[serde(default)]
+    pub payload_ranges: Vec<ByteRange>,
+    /// 证据在response中的字节区间
+    #[serde(default)]
+    pub evidence_ranges: Vec<ByteRange>,
+}
+
+/// 漏洞的人工研判状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriageStatus {
+    #[default]
+    New,
+    Confirmed,
+    FalsePositive,
+    Fixed,
+    AcceptedRisk,
+}
+
+impl TriageStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TriageStatus::New => "new",
+            TriageStatus::Confirmed => "confirmed",
+            TriageStatus::FalsePositive => "false_positive",
+            TriageStatus::Fixed => "fixed",
+            TriageStatus::AcceptedRisk => "accepted_risk",
+        }
+    }
+
+    /// 解析数据库中的状态，未知值按新发现处理
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "confirmed" => TriageStatus::Confirmed,
+            "false_positive" => TriageStatus::FalsePositive,
+            "fixed" => TriageStatus::Fixed,
+            "accepted_risk" => TriageStatus::AcceptedRisk,
+            _ => TriageStatus::New,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -127,6 +205,49 @@ pub struct Vulnerability {
     pub value: Option<String>,
     pub evidence: Option<String>,
     pub details: Option<VulnerabilityDetail>,
+    /// 置信度
+    #[serde(default)]
+    pub confidence: Confidence,
+    /// 研判状态
+    #[serde(default)]
+    pub status: TriageStatus,
+    /// 相同漏洞（类型、主机、路径模板、参数、名称）出现的次数
+    #[serde(default)]
+    pub occurrences: u32,
+    /// 最后一次发现的时间，timestamp为首次发现时间
+    #[serde(default)]
+    pub last_seen: String,
+}
+
+impl Vulnerability {
+    /// 由扫描器结果生成漏洞记录
+    pub fn from_scan_result(id: u32, result: ScanResult) -> Self {
+        let has_details = result.details.is_some() || result.request_details.is_some() || result.response_details.is_some();
+        Self {
+            id,
+            vulnerability_type: result.vulnerability_type,
+            name: result.name,
+            url: result.url,
+            parameter: result.parameter,
+            value: result.value,
+            evidence: result.evidence,
+            risk_level: result.risk_level,
+            timestamp: result.timestamp.to_utc().to_string(),
+            description: result.description,
+            solution: result.remediation.unwrap_or_else(|| "No solution provided".to_string()),
+            details: has_details.then(|| VulnerabilityDetail {
+                note: result.details.unwrap_or_default(),
+                request: result.request_details.unwrap_or_else(|| "No request captured".to_string()),
+                response: result.response_details.unwrap_or_else(|| "No response captured".to_string()),
+                payload_ranges: result.payload_ranges,
+                evidence_ranges: result.evidence_ranges,
+            }),
+            confidence: result.confidence,
+            status: TriageStatus::New,
+            occurrences: 1,
+            last_seen: result.timestamp.to_utc().to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
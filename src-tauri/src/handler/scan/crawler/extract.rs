@@ -0,0 +1,269 @@
+//! 页面内容解析：链接、表单、robots.txt和sitemap
+
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashSet;
+use url::Url;
+
+/// 页面中发现的表单
+#[derive(Debug, Clone, PartialEq)]
+pub struct Form {
+    /// 提交地址
+    pub action: Url,
+    /// 请求方法，GET或POST
+    pub method: String,
+    /// 编码类型
+    pub enctype: String,
+    /// 字段名和填充的示例值
+    pub fields: Vec<(String, String)>,
+}
+
+/// 页面的基准地址，存在`<base href>`时以它为准
+pub fn base_url(page: &Url, html: &str) -> Url {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("base[href]").unwrap();
+    document
+        .select(&selector)
+        .next()
+        .and_then(|e| e.value().attr("href"))
+        .and_then(|href| page.join(href.trim()).ok())
+        .unwrap_or_else(|| page.clone())
+}
+
+/// 解析相对地址，忽略javascript:、mailto:等非HTTP地址，去掉片段
+fn resolve(base: &Url, href: &str) -> Option<Url> {
+    let href = href.trim();
+    if href.is_empty() || href.starts_with('#') {
+        return None;
+    }
+    let mut url = base.join(href).ok()?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return None;
+    }
+    url.set_fragment(None);
+    Some(url)
+}
+
+/// 提取页面中的链接
+pub fn links(base: &Url, html: &str) -> Vec<Url> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("a[href], area[href], iframe[src], frame[src]").unwrap();
+    let mut seen = HashSet::new();
+    document
+        .select(&selector)
+        .filter_map(|e| e.value().attr("href").or_else(|| e.value().attr("src")))
+        .filter_map(|href| resolve(base, href))
+        .filter(|url| seen.insert(url.to_string()))
+        .collect()
+}
+
+/// 按输入类型和字段名生成示例值
+fn dummy_value(kind: &str, name: &str) -> String {
+    let name = name.to_lowercase();
+    let value = match kind {
+        "email" => "test@example.com",
+        "number" | "range" => "1",
+        "url" => "http://example.com/",
+        "tel" => "13800138000",
+        "date" => "2024-01-01",
+        "datetime-local" => "2024-01-01T00:00",
+        "time" => "12:00",
+        "month" => "2024-01",
+        "week" => "2024-W01",
+        "color" => "#000000",
+        "password" => "RShield@123",
+        _ if name.contains("mail") => "test@example.com",
+        _ if name.contains("phone") || name.contains("mobile") || name.contains("tel") => "13800138000",
+        _ if name.contains("url") || name.contains("site") || name.contains("link") => "http://example.com/",
+        _ if name.contains("age") || name.contains("num") || name.contains("count") || name.ends_with("id") => "1",
+        _ => "rshield",
+    };
+    value.to_string()
+}
+
+/// 收集表单字段并填充示例值，隐藏字段保留原值，跳过文件上传
+fn form_fields(form: &ElementRef) -> Vec<(String, String)> {
+    let selector = Selector::parse("input[name], textarea[name], select[name], button[name]").unwrap();
+    let option_selector = Selector::parse("option").unwrap();
+    let mut fields: Vec<(String, String)> = Vec::new();
+    let mut submitted = false;
+
+    for element in form.select(&selector) {
+        let el = element.value();
+        let name = el.attr("name").unwrap_or_default().to_string();
+        if name.is_empty() || el.attr("disabled").is_some() {
+            continue;
+        }
+        let value = el.attr("value").unwrap_or_default().to_string();
+
+        let field = match el.name() {
+            "textarea" => {
+                let text = element.text().collect::<String>();
+                if text.trim().is_empty() { dummy_value("text", &name) } else { text }
+            }
+            "select" => element
+                .select(&option_selector)
+                .find(|o| o.value().attr("selected").is_some())
+                .or_else(|| element.select(&option_selector).next())
+                .map(|o| o.value().attr("value").map(str::to_string).unwrap_or_else(|| o.text().collect::<String>().trim().to_string()))
+                .unwrap_or_default(),
+            "button" => {
+                // 只提交第一个有名称的提交按钮
+                if submitted || el.attr("type").is_some_and(|t| !t.eq_ignore_ascii_case("submit")) {
+                    continue;
+                }
+                submitted = true;
+                value
+            }
+            _ => {
+                let kind = el.attr("type").unwrap_or("text").to_lowercase();
+                match kind.as_str() {
+                    "file" | "image" | "reset" | "button" => continue,
+                    "submit" => {
+                        if submitted {
+                            continue;
+                        }
+                        submitted = true;
+                        value
+                    }
+                    "hidden" => value,
+                    "checkbox" => if value.is_empty() { "on".to_string() } else { value },
+                    "radio" => {
+                        if fields.iter().any(|(n, _)| n == &name) {
+                            continue;
+                        }
+                        if value.is_empty() { "on".to_string() } else { value }
+                    }
+                    _ if !value.is_empty() => value,
+                    _ => dummy_value(&kind, &name),
+                }
+            }
+        };
+        fields.push((name, field));
+    }
+    fields
+}
+
+/// 提取页面中的表单
+pub fn forms(base: &Url, html: &str) -> Vec<Form> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("form").unwrap();
+    document
+        .select(&selector)
+        .filter_map(|form| {
+            let el = form.value();
+            let action = match el.attr("action").map(str::trim).filter(|a| !a.is_empty()) {
+                Some(action) => resolve(base, action)?,
+                None => {
+                    let mut page = base.clone();
+                    page.set_fragment(None);
+                    page
+                }
+            };
+            let method = match el.attr("method").map(str::to_uppercase) {
+                Some(m) if m == "POST" => "POST".to_string(),
+                _ => "GET".to_string(),
+            };
+            Some(Form {
+                action,
+                method,
+                enctype: el.attr("enctype").unwrap_or("application/x-www-form-urlencoded").to_lowercase(),
+                fields: form_fields(&form),
+            })
+        })
+        .collect()
+}
+
+/// 解析robots.txt，返回(Allow/Disallow路径, Sitemap地址)
+///
+/// 路径中的通配符之后的部分被截断。
+pub fn parse_robots(content: &str) -> (Vec<String>, Vec<String>) {
+    let mut paths = Vec::new();
+    let mut sitemaps = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim().to_lowercase().as_str() {
+            "allow" | "disallow" => {
+                let path = value.split(['*', '$']).next().unwrap_or_default();
+                if path.starts_with('/') && path != "/" && !paths.iter().any(|p| p == path) {
+                    paths.push(path.to_string());
+                }
+            }
+            "sitemap" if !value.is_empty() => sitemaps.push(value.to_string()),
+            _ => {}
+        }
+    }
+    (paths, sitemaps)
+}
+
+/// 提取sitemap中的`<loc>`地址，sitemap索引中的子sitemap也在其中
+pub fn parse_sitemap(content: &str) -> Vec<String> {
+    let re = regex::Regex::new(r"(?is)<loc>\s*(.*?)\s*</loc>").unwrap();
+    re.captures_iter(content)
+        .map(|c| c[1].replace("&amp;", "&"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r##"<html><head><base href="/app/"></head><body>
+        <a href="list?page=2#top">list</a>
+        <a href="javascript:void(0)">js</a>
+        <a href="mailto:a@example.com">mail</a>
+        <iframe src="https://other.example.org/frame"></iframe>
+        <form action="search" method="get">
+          <input name="q">
+          <input type="hidden" name="token" value="abc">
+          <select name="sort"><option value="asc">A</option><option value="desc" selected>D</option></select>
+          <input type="radio" name="scope" value="all"><input type="radio" name="scope" value="mine">
+          <input type="file" name="upload">
+          <input type="submit" name="go" value="Go">
+        </form>
+        <form method="POST"><input type="email" name="contact"><textarea name="comment"></textarea></form>
+    </body></html>"##;
+
+    #[test]
+    fn test_links_and_forms() {
+        let page = Url::parse("http://example.com/index.html").unwrap();
+        let base = base_url(&page, PAGE);
+        assert_eq!(base.as_str(), "http://example.com/app/");
+
+        let found: Vec<String> = links(&base, PAGE).iter().map(|u| u.to_string()).collect();
+        assert_eq!(found, vec!["http://example.com/app/list?page=2", "https://other.example.org/frame"]);
+
+        let forms = forms(&base, PAGE);
+        assert_eq!(forms.len(), 2);
+        assert_eq!(forms[0].action.as_str(), "http://example.com/app/search");
+        assert_eq!(forms[0].method, "GET");
+        assert_eq!(
+            forms[0].fields,
+            vec![
+                ("q".to_string(), "rshield".to_string()),
+                ("token".to_string(), "abc".to_string()),
+                ("sort".to_string(), "desc".to_string()),
+                ("scope".to_string(), "all".to_string()),
+                ("go".to_string(), "Go".to_string()),
+            ]
+        );
+        assert_eq!(forms[1].method, "POST");
+        assert_eq!(forms[1].action.as_str(), "http://example.com/app/");
+        assert_eq!(forms[1].fields[0], ("contact".to_string(), "test@example.com".to_string()));
+        assert_eq!(forms[1].fields[1], ("comment".to_string(), "rshield".to_string()));
+    }
+
+    #[test]
+    fn test_robots_and_sitemap() {
+        let robots = "User-agent: *\nDisallow: /admin/\nDisallow: /search*q=\nAllow: /\nSitemap: http://example.com/sitemap.xml # main\n";
+        let (paths, sitemaps) = parse_robots(robots);
+        assert_eq!(paths, vec!["/admin/", "/search"]);
+        assert_eq!(sitemaps, vec!["http://example.com/sitemap.xml"]);
+
+        let sitemap = "<urlset><url><loc> http://example.com/a?x=1&amp;y=2 </loc></url><url><loc>http://example.com/b</loc></url></urlset>";
+        assert_eq!(parse_sitemap(sitemap), vec!["http://example.com/a?x=1&y=2", "http://example.com/b"]);
+    }
+}
@@ -0,0 +1,435 @@
+//! 主动爬虫
+//!
+//! 从起始地址出发按广度优先爬取范围内的页面：跟随链接，用示例数据提交表单，
+//! 可选使用无头浏览器渲染页面以发现脚本生成的链接和XHR请求，并从robots.txt和sitemap.xml获取入口。
+//! 发现的请求连同响应一起发送给调用方，作为主动扫描的扫描项。
+
+mod extract;
+mod render;
+
+use crate::handler::scan::api_import;
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::utils::path_template;
+use anyhow::{bail, Result};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use tokio::sync::mpsc;
+use url::Url;
+
+/// 不爬取的静态资源扩展名
+const STATIC_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "ico", "svg", "webp", "css", "js", "map", "woff", "woff2", "ttf",
+    "eot", "otf", "mp3", "mp4", "avi", "mov", "webm", "pdf", "zip", "rar", "7z", "gz", "tar", "exe", "dmg",
+    "apk", "doc", "docx", "xls", "xlsx", "ppt", "pptx",
+];
+/// 最多读取的sitemap文件数
+const MAX_SITEMAPS: usize = 10;
+
+/// 爬虫配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlConfig {
+    /// 起始地址
+    pub targets: Vec<String>,
+    /// 最大链接深度，起始地址为0
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+    /// 最多请求的页面数
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+    /// 是否使用示例数据提交表单
+    #[serde(default = "default_true")]
+    pub submit_forms: bool,
+    /// 是否使用无头浏览器渲染页面
+    #[serde(default)]
+    pub render_js: bool,
+    /// 是否从robots.txt和sitemap.xml获取入口
+    #[serde(default = "default_true")]
+    pub use_sitemap: bool,
+    /// 是否包含起始地址的子域名
+    #[serde(default)]
+    pub include_subdomains: bool,
+    /// 排除的URL关键字，不区分大小写
+    #[serde(default = "default_exclude")]
+    pub exclude: Vec<String>,
+    /// 附加到每个请求的请求头，例如认证信息
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 是否把发现的请求加入主动扫描
+    #[serde(default = "default_true")]
+    pub scan: bool,
+    /// 主动扫描使用的扫描模板名称
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+fn default_max_depth() -> usize {
+    3
+}
+
+fn default_max_pages() -> usize {
+    200
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_exclude() -> Vec<String> {
+    ["logout", "signout", "sign-out", "logoff", "exit"].iter().map(|s| s.to_string()).collect()
+}
+
+impl Default for CrawlConfig {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            max_depth: default_max_depth(),
+            max_pages: default_max_pages(),
+            submit_forms: true,
+            render_js: false,
+            use_sitemap: true,
+            include_subdomains: false,
+            exclude: default_exclude(),
+            headers: HashMap::new(),
+            scan: true,
+            profile: None,
+        }
+    }
+}
+
+/// 爬取统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrawlSummary {
+    /// 请求的页面数
+    pub pages: usize,
+    /// 发现的不重复请求数
+    pub requests: usize,
+    /// 提交的表单数
+    pub forms: usize,
+}
+
+/// 爬取范围
+struct Scope {
+    hosts: Vec<String>,
+    include_subdomains: bool,
+    exclude: Vec<String>,
+}
+
+impl Scope {
+    fn contains(&self, url: &Url) -> bool {
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return false;
+        }
+        let host = match url.host_str() {
+            Some(h) => h.to_lowercase(),
+            None => return false,
+        };
+        let in_scope = self
+            .hosts
+            .iter()
+            .any(|h| host == *h || (self.include_subdomains && host.ends_with(&format!(".{}", h))));
+        let lower = url.as_str().to_lowercase();
+        in_scope && !self.exclude.iter().any(|e| lower.contains(e.as_str()))
+    }
+}
+
+fn is_static(url: &Url) -> bool {
+    url.path()
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .is_some_and(|(_, ext)| STATIC_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+fn is_html(response: &HttpResponse) -> bool {
+    match response.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case("content-type")) {
+        Some((_, v)) => v.contains("html"),
+        None => response.body.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'<'),
+    }
+}
+
+/// 请求去重键：方法、主机、路径模板和排序后的参数名，参数值不同的请求只扫描一次
+fn request_key(request: &HttpRequest) -> String {
+    let url = match Url::parse(&request.url) {
+        Ok(u) => u,
+        Err(_) => return format!("{} {}", request.method, request.url),
+    };
+    let mut names: Vec<String> = request.params.iter().map(|(k, _)| k.clone()).collect();
+    let content_type = request
+        .headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.to_lowercase())
+        .unwrap_or_default();
+    if content_type.contains("json") {
+        if let Ok(serde_json::Value::Object(map)) = serde_json::from_slice(&request.body) {
+            names.extend(map.keys().cloned());
+        }
+    } else if !request.body.is_empty() && !content_type.contains("multipart") {
+        names.extend(url::form_urlencoded::parse(&request.body).map(|(k, _)| k.to_string()));
+    }
+    names.sort();
+    names.dedup();
+    format!(
+        "{} {}{}|{}",
+        request.method,
+        url.host_str().unwrap_or_default(),
+        path_template(url.path()),
+        names.join(",")
+    )
+}
+
+/// 爬虫
+pub struct Crawler {
+    config: CrawlConfig,
+    seeds: Vec<Url>,
+    scope: Scope,
+    client: reqwest::Client,
+}
+
+impl Crawler {
+    pub fn new(config: CrawlConfig, client: reqwest::Client) -> Result<Self> {
+        let mut seeds = Vec::new();
+        for target in &config.targets {
+            let target = target.trim();
+            if target.is_empty() {
+                continue;
+            }
+            let target = if target.starts_with("http://") || target.starts_with("https://") {
+                target.to_string()
+            } else {
+                format!("http://{}", target)
+            };
+            match Url::parse(&target) {
+                Ok(url) if url.host_str().is_some() => seeds.push(url),
+                _ => bail!("无效的爬取地址: {}", target),
+            }
+        }
+        if seeds.is_empty() {
+            bail!("未指定爬取目标");
+        }
+
+        let scope = Scope {
+            hosts: seeds.iter().filter_map(|u| u.host_str().map(str::to_lowercase)).collect(),
+            include_subdomains: config.include_subdomains,
+            exclude: config.exclude.iter().map(|e| e.to_lowercase()).filter(|e| !e.is_empty()).collect(),
+        };
+        Ok(Self { config, seeds, scope, client })
+    }
+
+    fn build_request(&self, method: &str, url: &str, content_type: Option<&str>, body: Vec<u8>) -> HttpRequest {
+        let mut headers = self.config.headers.clone();
+        if let Some(content_type) = content_type.filter(|t| !t.is_empty()) {
+            headers.insert("Content-Type".to_string(), content_type.to_string());
+        }
+        api_import::build_request(method, url, headers, body)
+    }
+
+    /// 表单转换为请求，GET表单的字段替换地址中的查询参数
+    fn form_request(&self, form: &extract::Form) -> HttpRequest {
+        if form.method == "GET" {
+            let mut url = form.action.clone();
+            url.query_pairs_mut().clear().extend_pairs(&form.fields);
+            return self.build_request("GET", url.as_str(), None, Vec::new());
+        }
+        if form.enctype.contains("multipart") {
+            let (content_type, body) = api_import::multipart_body(&form.fields);
+            return self.build_request("POST", form.action.as_str(), Some(&content_type), body);
+        }
+        self.build_request(
+            "POST",
+            form.action.as_str(),
+            Some("application/x-www-form-urlencoded"),
+            api_import::form_body(&form.fields),
+        )
+    }
+
+    /// 从robots.txt和sitemap获取入口地址
+    async fn seed_from_sitemaps(&self, origin: &Url) -> Vec<Url> {
+        let mut urls = Vec::new();
+        let mut sitemaps: VecDeque<String> = VecDeque::new();
+
+        if let Ok(robots_url) = origin.join("/robots.txt") {
+            let request = self.build_request("GET", robots_url.as_str(), None, Vec::new());
+            if let Some(response) = api_import::fetch_response(&self.client, &request).await {
+                if response.status == 200 {
+                    let (paths, found) = extract::parse_robots(&String::from_utf8_lossy(&response.body));
+                    urls.extend(paths.iter().filter_map(|p| origin.join(p).ok()));
+                    sitemaps.extend(found);
+                }
+            }
+        }
+        if let Ok(default) = origin.join("/sitemap.xml") {
+            if !sitemaps.iter().any(|s| *s == default.as_str()) {
+                sitemaps.push_back(default.to_string());
+            }
+        }
+
+        let mut read = 0;
+        while let Some(sitemap) = sitemaps.pop_front() {
+            if read >= MAX_SITEMAPS {
+                break;
+            }
+            // robots.txt和sitemap索引中的地址可能指向范围外的主机
+            match Url::parse(&sitemap) {
+                Ok(url) if self.scope.contains(&url) => {}
+                _ => {
+                    debug!("跳过范围外的sitemap: {}", sitemap);
+                    continue;
+                }
+            }
+            read += 1;
+            let request = self.build_request("GET", &sitemap, None, Vec::new());
+            let response = match api_import::fetch_response(&self.client, &request).await {
+                Some(r) if r.status == 200 => r,
+                _ => continue,
+            };
+            for loc in extract::parse_sitemap(&String::from_utf8_lossy(&response.body)) {
+                if loc.to_lowercase().ends_with(".xml") {
+                    sitemaps.push_back(loc);
+                } else if let Ok(url) = Url::parse(&loc) {
+                    urls.push(url);
+                }
+            }
+        }
+        urls
+    }
+
+    /// 开始爬取，发现的请求和响应通过tx发送，接收端关闭时停止
+    pub async fn run(&self, tx: mpsc::Sender<(HttpRequest, HttpResponse)>) -> CrawlSummary {
+        let mut summary = CrawlSummary::default();
+        let mut queue: VecDeque<(Url, usize)> = VecDeque::new();
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut seen_requests: HashSet<String> = HashSet::new();
+
+        for seed in &self.seeds {
+            if visited.insert(seed.to_string()) {
+                queue.push_back((seed.clone(), 0));
+            }
+        }
+        if self.config.use_sitemap {
+            let mut origins: Vec<Url> = Vec::new();
+            for seed in &self.seeds {
+                if let Ok(origin) = seed.join("/") {
+                    if !origins.contains(&origin) {
+                        origins.push(origin);
+                    }
+                }
+            }
+            for origin in origins {
+                for url in self.seed_from_sitemaps(&origin).await {
+                    if self.scope.contains(&url) && !is_static(&url) && visited.insert(url.to_string()) {
+                        queue.push_back((url, 1));
+                    }
+                }
+            }
+        }
+
+        while let Some((url, depth)) = queue.pop_front() {
+            if summary.pages >= self.config.max_pages || tx.is_closed() {
+                break;
+            }
+            let request = self.build_request("GET", url.as_str(), None, Vec::new());
+            let response = match api_import::fetch_response(&self.client, &request).await {
+                Some(response) => response,
+                None => {
+                    debug!("爬取页面失败: {}", url);
+                    continue;
+                }
+            };
+            summary.pages += 1;
+            let html = is_html(&response).then(|| String::from_utf8_lossy(&response.body).to_string());
+            if seen_requests.insert(request_key(&request)) {
+                summary.requests += 1;
+                if tx.send((request, response)).await.is_err() {
+                    break;
+                }
+            }
+
+            let Some(mut html) = html else {
+                continue;
+            };
+            let mut discovered: Vec<HttpRequest> = Vec::new();
+            if self.config.render_js {
+                if let Some(page) = render::render(url.as_str(), &self.config.headers).await {
+                    html = page.html;
+                    for r in page.requests {
+                        let body = r.body.into_bytes();
+                        discovered.push(self.build_request(&r.method, &r.url, Some(&r.content_type), body));
+                    }
+                }
+            }
+
+            let base = extract::base_url(&url, &html);
+            if depth < self.config.max_depth {
+                for link in extract::links(&base, &html) {
+                    if self.scope.contains(&link) && !is_static(&link) && visited.insert(link.to_string()) {
+                        queue.push_back((link, depth + 1));
+                    }
+                }
+            }
+            if self.config.submit_forms {
+                for form in extract::forms(&base, &html) {
+                    discovered.push(self.form_request(&form));
+                    summary.forms += 1;
+                }
+            }
+
+            for request in discovered {
+                if summary.pages >= self.config.max_pages {
+                    break;
+                }
+                let in_scope = Url::parse(&request.url).is_ok_and(|u| self.scope.contains(&u));
+                if !in_scope || !seen_requests.insert(request_key(&request)) {
+                    continue;
+                }
+                let Some(response) = api_import::fetch_response(&self.client, &request).await else {
+                    continue;
+                };
+                summary.pages += 1;
+                summary.requests += 1;
+                if tx.send((request, response)).await.is_err() {
+                    break;
+                }
+            }
+        }
+
+        info!(
+            "爬取完成，请求页面 {} 个，发现请求 {} 个，提交表单 {} 个",
+            summary.pages, summary.requests, summary.forms
+        );
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_and_request_key() {
+        let config = CrawlConfig {
+            targets: vec!["example.com/app".to_string()],
+            ..Default::default()
+        };
+        let crawler = Crawler::new(config, reqwest::Client::new()).unwrap();
+        assert!(crawler.scope.contains(&Url::parse("https://example.com/other").unwrap()));
+        assert!(!crawler.scope.contains(&Url::parse("http://api.example.com/").unwrap()));
+        assert!(!crawler.scope.contains(&Url::parse("http://example.com/user/Logout").unwrap()));
+        assert!(is_static(&Url::parse("http://example.com/static/app.JS?v=1").unwrap()));
+
+        let a = crawler.build_request("GET", "http://example.com/item/12?b=1&a=2", None, Vec::new());
+        let b = crawler.build_request("GET", "http://example.com/item/99?a=x&b=y", None, Vec::new());
+        assert_eq!(request_key(&a), request_key(&b));
+
+        let form = extract::Form {
+            action: Url::parse("http://example.com/login").unwrap(),
+            method: "POST".to_string(),
+            enctype: "application/x-www-form-urlencoded".to_string(),
+            fields: vec![("user".to_string(), "rshield".to_string())],
+        };
+        let post = crawler.form_request(&form);
+        assert_eq!(post.body, b"user=rshield".to_vec());
+        assert!(request_key(&post).ends_with("|user"));
+    }
+}
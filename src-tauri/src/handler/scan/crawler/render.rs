@@ -0,0 +1,140 @@
+//! 使用无头浏览器渲染页面
+//!
+//! 页面脚本执行前注入钩子记录fetch/XMLHttpRequest发出的请求，渲染完成后返回DOM内容，
+//! 用于发现由脚本生成的链接、表单和接口请求。
+
+use crate::core::governor::{self, Governor, Outcome};
+use crate::handler::scan::scanners::dom_xss;
+use headless_chrome::protocol::cdp::Page;
+use headless_chrome::Browser;
+use log::debug;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 页面加载后等待异步脚本执行的时间
+const SETTLE_TIME: Duration = Duration::from_millis(2000);
+
+/// 记录fetch和XMLHttpRequest请求的钩子脚本
+const HOOK_SCRIPT: &str = r#"(() => {
+  if (window.__rshieldRequests) return;
+  const requests = window.__rshieldRequests = [];
+  const record = (method, url, body, type) => {
+    try {
+      requests.push({
+        method: String(method || 'GET').toUpperCase(),
+        url: new URL(String(url), location.href).href,
+        body: typeof body === 'string' ? body : '',
+        content_type: type || ''
+      });
+    } catch (e) {}
+  };
+  const origFetch = window.fetch;
+  if (origFetch) {
+    window.fetch = function(input, init) {
+      init = init || {};
+      const url = typeof input === 'string' ? input : (input && input.url);
+      let type = '';
+      if (init.headers) {
+        const headers = new Headers(init.headers);
+        type = headers.get('content-type') || '';
+      }
+      record(init.method || (input && input.method), url, init.body, type);
+      return origFetch.apply(this, arguments);
+    };
+  }
+  const origOpen = XMLHttpRequest.prototype.open;
+  const origSetHeader = XMLHttpRequest.prototype.setRequestHeader;
+  const origSend = XMLHttpRequest.prototype.send;
+  XMLHttpRequest.prototype.open = function(method, url) {
+    this.__rshield = { method: method, url: url, type: '' };
+    return origOpen.apply(this, arguments);
+  };
+  XMLHttpRequest.prototype.setRequestHeader = function(name, value) {
+    if (this.__rshield && String(name).toLowerCase() === 'content-type') this.__rshield.type = value;
+    return origSetHeader.apply(this, arguments);
+  };
+  XMLHttpRequest.prototype.send = function(body) {
+    if (this.__rshield) record(this.__rshield.method, this.__rshield.url, body, this.__rshield.type);
+    return origSend.apply(this, arguments);
+  };
+})();"#;
+
+/// 脚本发出的请求
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScriptRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub content_type: String,
+}
+
+/// 渲染结果
+#[derive(Debug, Clone, Default)]
+pub struct RenderedPage {
+    /// 渲染后的DOM
+    pub html: String,
+    /// 页面加载期间脚本发出的请求
+    pub requests: Vec<ScriptRequest>,
+}
+
+fn render_blocking(browser: &Browser, url: &str, headers: &HashMap<String, String>) -> anyhow::Result<RenderedPage> {
+    // 页面导航和普通请求一样计入目标主机的速率限制
+    let host = url::Url::parse(url).map(|u| governor::host_key(&u)).unwrap_or_default();
+    let permit = Governor::global()
+        .acquire_blocking(&host, None)
+        .ok_or_else(|| anyhow::anyhow!("等待发送许可失败"))?;
+    let tab = browser.new_tab()?;
+    tab.set_default_timeout(Duration::from_secs(15));
+    if !headers.is_empty() {
+        tab.set_extra_http_headers(headers.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect())?;
+    }
+    tab.call_method(Page::AddScriptToEvaluateOnNewDocument {
+        source: HOOK_SCRIPT.to_string(),
+        world_name: None,
+        include_command_line_api: None,
+        run_immediately: None,
+    })?;
+
+    let outcome = (|| -> anyhow::Result<RenderedPage> {
+        if let Err(e) = tab.navigate_to(url).and_then(|tab| tab.wait_until_navigated()) {
+            permit.record(Outcome::Failed);
+            return Err(e);
+        }
+        permit.record(Outcome::Response { status: 200, retry_after: None });
+        std::thread::sleep(SETTLE_TIME);
+        let html = tab.get_content()?;
+        let requests = tab
+            .evaluate("JSON.stringify(window.__rshieldRequests || [])", false)?
+            .value
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_else(|| "[]".to_string());
+        Ok(RenderedPage {
+            html,
+            requests: serde_json::from_str(&requests)?,
+        })
+    })();
+    let _ = tab.close(true);
+    outcome
+}
+
+/// 使用爬虫配置的请求头渲染页面，浏览器不可用或加载失败时返回None
+pub async fn render(url: &str, headers: &HashMap<String, String>) -> Option<RenderedPage> {
+    let url = url.to_string();
+    let headers = headers.clone();
+    tokio::task::spawn_blocking(move || {
+        let browser = dom_xss::browser()?;
+        match render_blocking(&browser, &url, &headers) {
+            Ok(page) => Some(page),
+            Err(e) => {
+                debug!("渲染页面失败 {}: {}", url, e);
+                None
+            }
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
@@ -1,10 +1,13 @@
 use crate::core::config::AppConfig;
+use crate::handler::scan::engine::queue::{self, QueueKind, QueueStatus, QueuedTask, TaskProgress};
 use crate::handler::scan::engine::result::ScanResult;
+use crate::handler::scan::profile;
 use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
 use crate::handler::scan::scanners::{
-    PluginManager, Scanner, ScannerType, ScannerTypeEnum
+    create_scanner, PluginManager, Scanner, ScannerType, ScannerTypeEnum
 };
-use log::{error, info};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex, Semaphore};
 use std::collections::VecDeque;
@@ -12,13 +15,28 @@ use chrono::Utc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
+/// 插件扫描阶段名称
+const PLUGIN_PHASE: &str = "plugins";
+
 /// 扫描任务
 #[derive(Clone)]
 struct ScanTask {
-    id: String,
     request: HttpRequest,
     response: HttpResponse,
-    _timestamp: i64,
+    /// 持久化队列中的任务及断点
+    queued: QueuedTask,
+}
+
+/// 队列中保存的任务数据
+#[derive(Serialize, Deserialize)]
+struct TaskData {
+    request: HttpRequest,
+    response: HttpResponse,
+    /// 入队时使用的扫描模板，恢复时重新加载模板创建扫描器
+    ///
+    /// 配置中包含越权测试身份等凭据，不写入队列；未使用模板或旧版本的任务为None，按配置文件恢复。
+    #[serde(default)]
+    profile: Option<String>,
 }
 
 /// 扫描状态
@@ -36,9 +54,9 @@ pub struct ScanManager {
     /// 配置
     config: Arc<AppConfig>,
     /// 扫描器列表
-    scanners: Vec<ScannerType>,
+    scanners: Vec<Arc<dyn Scanner + Send + Sync>>,
     /// 插件管理器
-    plugin_manager: Arc<Mutex<PluginManager>>,
+    plugin_manager: Arc<PluginManager>,
     /// 任务队列
     task_queue: Arc<Mutex<VecDeque<ScanTask>>>,
     /// 并发控制
@@ -49,32 +67,41 @@ pub struct ScanManager {
     result_tx: mpsc::Sender<ScanResult>,
     /// 停止标志
     stop_flag: Arc<AtomicBool>,
+    /// 本次扫描在持久化队列中的标识
+    scan_id: String,
+    /// 任务是否写入持久化队列，只有主动扫描需要断点续扫，也只有主动扫描受队列暂停控制
+    persistent: bool,
+    /// 主动扫描使用的扫描模板名称
+    profile: Option<String>,
 }
 
 impl ScanManager {
-    /// 创建新的扫描管理器
+    /// 创建新的扫描管理器，任务只保存在内存中（被动扫描）
     pub async fn new(config: Arc<AppConfig>, result_tx: mpsc::Sender<ScanResult>) -> Self {
-        let mut scanners = Vec::new();
-        
-        // 初始化内置扫描器
-        if config.rules.vulnerabilities.xss.enabled {
-            scanners.push(ScannerType { scanner_type: ScannerTypeEnum::Xss });
-        }
-        
-        if config.rules.vulnerabilities.sql_injection.enabled {
-            scanners.push(ScannerType { scanner_type: ScannerTypeEnum::SqlInjection });
-        }
-        
-        if config.rules.vulnerabilities.rce.enabled {
-            scanners.push(ScannerType { scanner_type: ScannerTypeEnum::Rce });
-        }
-        
-        // 初始化插件管理器
-        let plugin_manager = PluginManager::new();
-        // 从数据库加载插件，而不是文件系统
-        if let Err(e) = plugin_manager.load_plugins_from_db().await {
-            error!("Failed to load plugins from database: {}", e);
-        }
+        // 按配置创建内置扫描器，扫描器持有本次扫描的配置（插入点、检测级别、自定义payload等）
+        let checks = &config.rules.vulnerabilities;
+        let scanners: Vec<Arc<dyn Scanner + Send + Sync>> = [
+            (checks.xss.enabled, ScannerTypeEnum::Xss),
+            (checks.sql_injection.enabled, ScannerTypeEnum::SqlInjection),
+            (checks.rce.enabled, ScannerTypeEnum::Rce),
+            (checks.open_redirect.enabled, ScannerTypeEnum::OpenRedirect),
+            (checks.ssrf.enabled, ScannerTypeEnum::Ssrf),
+            (checks.xxe.enabled, ScannerTypeEnum::Xxe),
+            (checks.ssti.enabled, ScannerTypeEnum::Ssti),
+            (checks.passive_checks.enabled, ScannerTypeEnum::PassiveCheck),
+            (checks.cors.enabled, ScannerTypeEnum::Cors),
+            (checks.jwt.enabled, ScannerTypeEnum::Jwt),
+            (checks.nosql_injection.enabled, ScannerTypeEnum::NoSqlInjection),
+            (checks.authz.enabled, ScannerTypeEnum::Authz),
+            (checks.java_deserialization.enabled, ScannerTypeEnum::JavaDeserialization),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, scanner_type)| Arc::from(create_scanner(ScannerType { scanner_type }, config.clone())))
+        .collect();
+
+        // 初始化插件管理器，插件由全局扫描插件管理器在启动时加载
+        let plugin_manager = PluginManager::new(&config.plugins);
         
         // 初始化扫描状态
         let status = ScanStatus {
@@ -88,22 +115,52 @@ impl ScanManager {
         Self {
             config: config.clone(),
             scanners,
-            plugin_manager: Arc::new(Mutex::new(plugin_manager)),
+            plugin_manager: Arc::new(plugin_manager),
             task_queue: Arc::new(Mutex::new(VecDeque::new())),
             concurrency_limiter: Arc::new(Semaphore::new(config.scanner.concurrency)),
             status: Arc::new(Mutex::new(status)),
             result_tx,
             stop_flag: Arc::new(AtomicBool::new(false)),
+            scan_id: uuid::Uuid::new_v4().to_string(),
+            persistent: false,
+            profile: None,
         }
     }
+
+    /// 创建主动扫描管理器，数据库可用时任务写入持久化队列，退出后可以恢复
+    ///
+    /// config由profile指定的扫描模板加载，恢复时按模板名称重新加载。
+    pub async fn new_active(config: Arc<AppConfig>, profile: Option<String>, result_tx: mpsc::Sender<ScanResult>) -> Self {
+        let mut manager = Self::new(config, result_tx).await;
+        manager.persistent = true;
+        manager.profile = profile;
+        manager
+    }
     
-    /// 添加扫描任务
+    /// 添加扫描任务，主动扫描在数据库可用时同时写入持久化队列
     pub async fn add_task(&self, request: HttpRequest, response: HttpResponse) {
-        let task = ScanTask {
-            id: uuid::Uuid::new_v4().to_string(),
+        let persisted = self.persistent && queue::is_available();
+        let data = TaskData {
             request,
             response,
-            _timestamp: Utc::now().timestamp(),
+            profile: self.profile.clone(),
+        };
+        let queued = QueuedTask::new(
+            &self.scan_id,
+            QueueKind::Request,
+            &data.request.url,
+            if persisted { serde_json::to_string(&data).unwrap_or_default() } else { String::new() },
+        );
+        if persisted {
+            if let Err(e) = queue::enqueue(&queued).await {
+                warn!("扫描任务写入队列失败: {}", e);
+            }
+        }
+
+        let task = ScanTask {
+            request: data.request,
+            response: data.response,
+            queued,
         };
         
         let mut queue = self.task_queue.lock().await;
@@ -112,6 +169,59 @@ impl ScanManager {
         let mut status = self.status.lock().await;
         status.task_count += 1;
     }
+
+    /// 恢复上次退出时未完成的任务，从各自的断点继续执行
+    ///
+    /// 每次扫描按入队时使用的扫描模板创建一个主动扫描管理器，返回管理器及其恢复的任务数。
+    pub async fn restore(result_tx: mpsc::Sender<ScanResult>) -> Vec<(ScanManager, usize)> {
+        let tasks = match queue::restore(QueueKind::Request).await {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                error!("加载扫描队列失败: {}", e);
+                return Vec::new();
+            }
+        };
+
+        // 按scan_id分组，保持入队顺序
+        let mut groups: Vec<(String, Option<String>, Vec<ScanTask>)> = Vec::new();
+        for queued in tasks {
+            let data = match serde_json::from_str::<TaskData>(&queued.data) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("解析队列任务 {} 失败，已丢弃: {}", queued.id, e);
+                    let _ = queue::complete(&queued.id).await;
+                    continue;
+                }
+            };
+            let task = ScanTask {
+                request: data.request,
+                response: data.response,
+                queued,
+            };
+            match groups.iter_mut().find(|(scan_id, _, _)| *scan_id == task.queued.scan_id) {
+                Some((_, _, group)) => group.push(task),
+                None => groups.push((task.queued.scan_id.clone(), data.profile, vec![task])),
+            }
+        }
+
+        let mut managers = Vec::new();
+        for (scan_id, profile, tasks) in groups {
+            let config = match profile::load_config(profile.as_deref()).await {
+                Ok((config, _)) => config,
+                Err(e) => {
+                    warn!("扫描 {} 的扫描模板加载失败，使用默认配置恢复: {}", scan_id, e);
+                    AppConfig::default()
+                }
+            };
+            let mut manager = Self::new_active(Arc::new(config), profile, result_tx.clone()).await;
+            manager.scan_id = scan_id;
+            let count = tasks.len();
+            manager.task_queue.lock().await.extend(tasks);
+            manager.status.lock().await.task_count += count;
+            managers.push((manager, count));
+        }
+        managers
+    }
     
     /// 获取扫描状态
     pub async fn get_status(&self) -> ScanStatus {
@@ -205,8 +315,7 @@ impl ScanManager {
     /// 重新加载插件
     pub async fn reload_plugins(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         info!("正在重新加载插件...");
-        let plugin_manager = self.plugin_manager.lock().await;
-        plugin_manager.load_plugins_from_db().await?;
+        self.plugin_manager.load_plugins().await?;
         info!("插件重新加载完成");
         Ok(())
     }
@@ -219,6 +328,15 @@ impl ScanManager {
             loop {
                 // 获取信号量许可
                 let _permit = manager.concurrency_limiter.acquire().await.unwrap();
+
+                // 主动扫描暂停期间不取出新任务，被动扫描不受影响
+                if manager.persistent && queue::is_paused() {
+                    if !manager.status.lock().await.is_running {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                    continue;
+                }
                 
                 // 获取任务
                 let task = {
@@ -228,16 +346,27 @@ impl ScanManager {
                 
                 match task {
                     Some(task) => {
-                        // 处理任务
-                        if let Err(e) = manager.process_task(task).await {
-                            error!("Task processing error: {}", e);
-                            let mut status = manager.status.lock().await;
-                            status.error_count += 1;
-                        }
+                        // 处理任务，已取消的任务直接丢弃
+                        let finished = if task.queued.is_cancelled() {
+                            debug!("跳过已取消的任务 {}", task.queued.id);
+                            true
+                        } else {
+                            match manager.process_task(task).await {
+                                Ok(finished) => finished,
+                                Err(e) => {
+                                    error!("Task processing error: {}", e);
+                                    let mut status = manager.status.lock().await;
+                                    status.error_count += 1;
+                                    true
+                                }
+                            }
+                        };
                         
                         // 更新完成计数
-                        let mut status = manager.status.lock().await;
-                        status.completed_count += 1;
+                        if finished {
+                            let mut status = manager.status.lock().await;
+                            status.completed_count += 1;
+                        }
                     }
                     None => {
                         // 队列为空，检查是否应该继续运行
@@ -254,31 +383,75 @@ impl ScanManager {
     }
     
     /// 处理单个扫描任务
-    async fn process_task(&self, task: ScanTask) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // info!("处理任务 {}: {} {}", task.id, task.request.method, task.request.url);
-        
-        // 运行所有扫描器
+    ///
+    /// 扫描器按顺序作为扫描阶段执行，从任务断点所在的阶段继续。
+    /// 返回任务是否执行完成，队列暂停时保存断点并将任务放回队列，返回false。
+    async fn process_task(&self, mut task: ScanTask) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        // info!("处理任务 {}: {} {}", task.queued.id, task.request.method, task.request.url);
+        let persisted = self.persistent && queue::is_available();
+        if persisted {
+            queue::set_status(&task.queued.id, QueueStatus::Running).await?;
+        }
+
+        let mut phases = Vec::new();
         for scanner in &self.scanners {
-            let results = scanner.scan(&task.request, &task.response).await;
+            phases.push(scanner.name().await);
+        }
+        phases.push(PLUGIN_PHASE.to_string());
+
+        let progress = if self.persistent {
+            TaskProgress::start(&task.queued)
+        } else {
+            TaskProgress::detached(&task.queued)
+        };
+        for (scanner, phase) in self.scanners.iter().zip(&phases) {
+            if progress.is_interrupted() {
+                break;
+            }
+            if progress.enter_phase(phase, &phases) {
+                continue;
+            }
+
+            // 运行扫描器
+            let results = progress.scope(scanner.scan(&task.request, &task.response)).await;
             for result in results {
                 if let Err(e) = self.result_tx.send(result).await {
                     error!("发送扫描结果失败: {}", e);
                 }
             }
+            progress.complete_phase(phase, &phases);
+            if persisted {
+                progress.save().await?;
+            }
         }
         
         // 运行插件扫描器
-        let plugin_manager = self.plugin_manager.lock().await;
-        let plugin_results = plugin_manager.scan(&task.request, &task.response).await;
-        
-        // 发送扫描结果
-        for scan_result in plugin_results {
-            if let Err(e) = self.result_tx.send(scan_result).await {
-                error!("发送扫描结果失败: {}", e);
+        if !progress.is_interrupted() && !progress.enter_phase(PLUGIN_PHASE, &phases) {
+            let plugin_results = progress.scope(self.plugin_manager.scan(&task.request, &task.response)).await;
+
+            // 发送扫描结果
+            for scan_result in plugin_results {
+                if let Err(e) = self.result_tx.send(scan_result).await {
+                    error!("发送扫描结果失败: {}", e);
+                }
             }
         }
-        
-        Ok(())
+
+        task.queued.checkpoint = progress.finish();
+        if progress.is_interrupted() && !task.queued.is_cancelled() {
+            debug!("任务 {} 已暂停于 {:?}", task.queued.id, task.queued.checkpoint.phase);
+            if persisted {
+                queue::save_checkpoint(&task.queued.id, &task.queued.checkpoint).await?;
+                queue::set_status(&task.queued.id, QueueStatus::Paused).await?;
+            }
+            self.task_queue.lock().await.push_front(task);
+            return Ok(false);
+        }
+
+        if persisted {
+            queue::complete(&task.queued.id).await?;
+        }
+        Ok(true)
     }
 }
 
@@ -293,6 +466,98 @@ impl Clone for ScanManager {
             status: self.status.clone(),
             result_tx: self.result_tx.clone(),
             stop_flag: self.stop_flag.clone(),
+            scan_id: self.scan_id.clone(),
+            persistent: self.persistent,
+            profile: self.profile.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::scan::profile::ScanProfile;
+    use crate::core::config::InsertionPointType;
+    use std::collections::HashMap;
+    use std::sync::atomic::AtomicUsize;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 本地HTTP服务，返回地址和收到的连接数
+    async fn counting_server() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let _ = stream.read(&mut buf).await;
+                    let _ = stream
+                        .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                        .await;
+                });
+            }
+        });
+        (format!("http://{}", addr), hits)
+    }
+
+    /// 只启用SQL注入检查的模板
+    fn sql_only_profile(insertion_points: Vec<InsertionPointType>) -> AppConfig {
+        let mut config = AppConfig::default();
+        let mut profile = ScanProfile::from_config("sql-only", &config);
+        let mut checks = serde_json::to_value(&profile.checks).unwrap();
+        for (name, check) in checks.as_object_mut().unwrap() {
+            check["enabled"] = serde_json::json!(name == "sql_injection");
         }
+        profile.checks = serde_json::from_value(checks).unwrap();
+        profile.checks.sql_injection.level = "low".to_string();
+        profile.insertion_points = insertion_points;
+        profile.apply(&mut config);
+        config
+    }
+
+    async fn run_task(config: AppConfig, url: &str) -> usize {
+        let (result_tx, _result_rx) = mpsc::channel(100);
+        let manager = ScanManager::new(Arc::new(config), result_tx).await;
+        let request = HttpRequest {
+            method: "GET".to_string(),
+            url: format!("{}/item?id=1", url),
+            headers: HashMap::new(),
+            body: Vec::new(),
+            params: vec![("id".to_string(), "1".to_string())],
+        };
+        let response = HttpResponse { status: 200, headers: HashMap::new(), body: b"ok".to_vec() };
+        let queued = QueuedTask::new(&manager.scan_id, QueueKind::Request, &request.url, String::new());
+        manager.process_task(ScanTask { request, response, queued }).await.unwrap();
+        manager.scanners.len()
+    }
+
+    #[tokio::test]
+    async fn test_profile_insertion_points_reach_scanners() {
+        let (url, hits) = counting_server().await;
+        assert_eq!(run_task(sql_only_profile(vec![InsertionPointType::Json]), &url).await, 1);
+        assert_eq!(hits.load(Ordering::SeqCst), 0, "禁用查询参数插入点时不应发送payload");
+
+        run_task(sql_only_profile(vec![InsertionPointType::Query]), &url).await;
+        assert!(hits.load(Ordering::SeqCst) > 0);
+    }
+
+    #[test]
+    fn test_task_data_profile() {
+        // 旧版本写入的任务带有完整配置，没有模板字段
+        let data: TaskData = serde_json::from_str(
+            r#"{"request":{"method":"GET","url":"http://a/","headers":{},"body":[],"params":[]},"response":{"status":200,"headers":{},"body":[]},"config":{}}"#,
+        )
+        .unwrap();
+        assert!(data.profile.is_none());
+
+        let data = TaskData { profile: Some("sql-only".to_string()), ..data };
+        let json = serde_json::to_string(&data).unwrap();
+        assert!(!json.contains("\"config\""), "队列中不保存扫描配置");
+        let restored: TaskData = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.profile.as_deref(), Some("sql-only"));
     }
 }
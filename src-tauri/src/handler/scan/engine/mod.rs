@@ -1,5 +1,6 @@
 pub mod result;
 pub mod manager;
+pub mod queue;
 
-pub use result::ScanResult;
+pub use result::{ByteRange, Confidence, ScanResult};
 pub use manager::ScanManager;
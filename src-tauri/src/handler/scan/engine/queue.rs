@@ -0,0 +1,576 @@
+//! 主动扫描队列的SQLite持久化
+//!
+//! 任务在执行前写入队列，执行过程中记录断点（扫描阶段、插入点、payload序号），
+//! 完成或取消后删除。应用重启后从断点继续未完成的任务。
+
+use crate::asm::asm_task::INNERASK_MODULE;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{query, Row};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const SELECT_COLUMNS: &str = "SELECT id, scan_id, kind, target, phase, insertion_point, payload_index, completed_points, status, data, created_at, updated_at FROM active_scan_queue";
+
+/// 队列任务类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueKind {
+    /// 对单个请求运行扫描器（ScanManager）
+    Request,
+    /// 对目标执行扫描任务（主动扫描协调器）
+    Target,
+}
+
+impl QueueKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueueKind::Request => "request",
+            QueueKind::Target => "target",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "target" => QueueKind::Target,
+            _ => QueueKind::Request,
+        }
+    }
+}
+
+/// 队列任务状态，完成和取消的任务直接从队列删除
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueStatus {
+    #[default]
+    Pending,
+    Running,
+    Paused,
+}
+
+impl QueueStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueueStatus::Pending => "pending",
+            QueueStatus::Running => "running",
+            QueueStatus::Paused => "paused",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "running" => QueueStatus::Running,
+            "paused" => QueueStatus::Paused,
+            _ => QueueStatus::Pending,
+        }
+    }
+}
+
+/// 扫描断点
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// 当前扫描阶段（扫描器名称或任务类型）
+    pub phase: Option<String>,
+    /// 当前插入点
+    pub insertion_point: Option<String>,
+    /// 当前插入点正在测试的payload序号
+    pub payload_index: usize,
+    /// 当前阶段已测试完的插入点
+    pub completed_points: Vec<String>,
+}
+
+impl Checkpoint {
+    /// 进入扫描阶段，阶段变化时清空插入点进度
+    pub fn enter_phase(&mut self, phase: &str) {
+        if self.phase.as_deref() != Some(phase) {
+            *self = Checkpoint {
+                phase: Some(phase.to_string()),
+                ..Default::default()
+            };
+        }
+    }
+
+    /// 记录即将测试的payload，返回false表示断点之前已测试过
+    fn advance(&mut self, point: &str, index: usize) -> bool {
+        if self.completed_points.iter().any(|p| p == point) {
+            return false;
+        }
+        if self.insertion_point.as_deref() == Some(point) {
+            if index < self.payload_index {
+                return false;
+            }
+        } else if let Some(previous) = self.insertion_point.replace(point.to_string()) {
+            self.completed_points.push(previous);
+        }
+        self.payload_index = index;
+        true
+    }
+}
+
+/// 队列任务
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedTask {
+    pub id: String,
+    /// 同一次扫描的任务共享scan_id
+    pub scan_id: String,
+    pub kind: QueueKind,
+    /// 扫描目标（URL或主机）
+    pub target: String,
+    pub status: QueueStatus,
+    pub checkpoint: Checkpoint,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// 恢复执行所需的数据（JSON），由任务类型自行解析
+    #[serde(skip)]
+    pub data: String,
+    /// 入队时的取消代数，用于识别取消全部之前的任务
+    #[serde(skip)]
+    pub epoch: u64,
+}
+
+impl QueuedTask {
+    pub fn new(scan_id: &str, kind: QueueKind, target: &str, data: String) -> Self {
+        let now = Utc::now().timestamp();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            scan_id: scan_id.to_string(),
+            kind,
+            target: target.to_string(),
+            status: if is_paused() { QueueStatus::Paused } else { QueueStatus::Pending },
+            checkpoint: Checkpoint::default(),
+            created_at: now,
+            updated_at: now,
+            data,
+            epoch: CONTROL.epoch.load(Ordering::SeqCst),
+        }
+    }
+
+    /// 任务是否已被取消
+    pub fn is_cancelled(&self) -> bool {
+        self.epoch < CONTROL.epoch.load(Ordering::SeqCst) || CONTROL.cancelled.lock().unwrap().contains(&self.id)
+    }
+}
+
+/// 执行中任务的断点，扫描器通过 [`should_test`] 更新
+pub struct TaskProgress {
+    id: String,
+    checkpoint: Mutex<Checkpoint>,
+    dirty: AtomicBool,
+    interrupted: AtomicBool,
+    /// 当前阶段是否因中断跳过了payload，跳过时阶段未完成
+    partial: AtomicBool,
+}
+
+tokio::task_local! {
+    static PROGRESS: Arc<TaskProgress>;
+}
+
+impl TaskProgress {
+    /// 创建任务进度并登记为执行中，暂停或取消时会被中断
+    pub fn start(task: &QueuedTask) -> Arc<Self> {
+        let progress = Self::new(task, is_paused() || task.is_cancelled());
+        CONTROL.running.lock().unwrap().insert(task.id.clone(), progress.clone());
+        progress
+    }
+
+    /// 创建不受队列暂停影响的任务进度，用于不进入持久化队列的被动扫描
+    pub fn detached(task: &QueuedTask) -> Arc<Self> {
+        Self::new(task, task.is_cancelled())
+    }
+
+    fn new(task: &QueuedTask, interrupted: bool) -> Arc<Self> {
+        Arc::new(Self {
+            id: task.id.clone(),
+            checkpoint: Mutex::new(task.checkpoint.clone()),
+            dirty: AtomicBool::new(false),
+            interrupted: AtomicBool::new(interrupted),
+            partial: AtomicBool::new(false),
+        })
+    }
+
+    /// 结束执行，返回最终断点
+    pub fn finish(&self) -> Checkpoint {
+        CONTROL.running.lock().unwrap().remove(&self.id);
+        self.checkpoint.lock().unwrap().clone()
+    }
+
+    /// 在任务上下文中执行扫描，期间定期保存断点
+    pub async fn scope<F: Future>(self: &Arc<Self>, future: F) -> F::Output {
+        let flusher = {
+            let progress = self.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(FLUSH_INTERVAL).await;
+                    if let Err(e) = progress.save().await {
+                        log::debug!("保存扫描断点失败: {}", e);
+                    }
+                }
+            })
+        };
+        let output = PROGRESS.scope(self.clone(), future).await;
+        flusher.abort();
+        output
+    }
+
+    /// 进入扫描阶段，返回该阶段是否应跳过（断点位于之后的阶段）
+    pub fn enter_phase(&self, phase: &str, phases: &[String]) -> bool {
+        let mut checkpoint = self.checkpoint.lock().unwrap();
+        let saved = checkpoint.phase.as_ref().and_then(|p| phases.iter().position(|name| name == p));
+        let current = phases.iter().position(|name| name == phase);
+        if let (Some(saved), Some(current)) = (saved, current) {
+            if current < saved {
+                return true;
+            }
+        }
+        checkpoint.enter_phase(phase);
+        self.dirty.store(true, Ordering::SeqCst);
+        false
+    }
+
+    /// 扫描阶段执行结束，未因中断跳过payload时断点移到下一阶段，恢复后不再重复执行该阶段
+    ///
+    /// 未通过 [`should_test`] 记录进度的扫描器只能以阶段为单位续扫。
+    pub fn complete_phase(&self, phase: &str, phases: &[String]) {
+        if self.partial.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        let next = phases.iter().position(|name| name == phase).and_then(|i| phases.get(i + 1));
+        if let Some(next) = next {
+            self.checkpoint.lock().unwrap().enter_phase(next);
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
+    }
+
+    fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::SeqCst);
+    }
+
+    /// 断点有变化时写入数据库
+    pub async fn save(&self) -> Result<()> {
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let checkpoint = self.checkpoint.lock().unwrap().clone();
+        save_checkpoint(&self.id, &checkpoint).await
+    }
+}
+
+/// 断点写入间隔
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 扫描器对插入点发送第index个payload前调用，返回false时跳过该payload
+///
+/// 断点之前已测试过的payload、以及任务被暂停或取消后都返回false。
+/// point在同一扫描阶段内需唯一（如“检测方式:参数名”），同一point的payload需连续测试。
+/// 不在队列任务中执行时（如单元测试）始终返回true。
+pub fn should_test(point: &str, index: usize) -> bool {
+    PROGRESS
+        .try_with(|progress| {
+            if progress.is_interrupted() {
+                progress.partial.store(true, Ordering::SeqCst);
+                return false;
+            }
+            let advanced = progress.checkpoint.lock().unwrap().advance(point, index);
+            if advanced {
+                progress.dirty.store(true, Ordering::SeqCst);
+            }
+            advanced
+        })
+        .unwrap_or(true)
+}
+
+/// 队列的暂停、取消状态
+#[derive(Default)]
+struct QueueControl {
+    paused: AtomicBool,
+    /// 取消全部时递增
+    epoch: AtomicU64,
+    cancelled: Mutex<HashSet<String>>,
+    running: Mutex<HashMap<String, Arc<TaskProgress>>>,
+}
+
+static CONTROL: Lazy<QueueControl> = Lazy::new(QueueControl::default);
+
+pub fn is_paused() -> bool {
+    CONTROL.paused.load(Ordering::SeqCst)
+}
+
+/// 暂停期间等待，任务被取消时立即返回
+pub async fn wait_while_paused(task: &QueuedTask) {
+    while is_paused() && !task.is_cancelled() {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// 暂停队列，执行中的任务在下一个断点处停止并保留进度
+pub async fn pause() -> Result<()> {
+    CONTROL.paused.store(true, Ordering::SeqCst);
+    for progress in CONTROL.running.lock().unwrap().values() {
+        progress.interrupt();
+    }
+
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+    query("UPDATE active_scan_queue SET status = 'paused', updated_at = ? WHERE status IN ('pending', 'running')")
+        .bind(Utc::now().timestamp())
+        .execute(&*pool)
+        .await?;
+    Ok(())
+}
+
+/// 继续执行暂停的队列
+pub async fn resume() -> Result<()> {
+    CONTROL.paused.store(false, Ordering::SeqCst);
+
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+    query("UPDATE active_scan_queue SET status = 'pending', updated_at = ? WHERE status = 'paused'")
+        .bind(Utc::now().timestamp())
+        .execute(&*pool)
+        .await?;
+    Ok(())
+}
+
+/// 取消指定任务，未指定时取消全部任务
+pub async fn cancel(id: Option<&str>) -> Result<()> {
+    match id {
+        Some(id) => {
+            CONTROL.cancelled.lock().unwrap().insert(id.to_string());
+            if let Some(progress) = CONTROL.running.lock().unwrap().get(id) {
+                progress.interrupt();
+            }
+        }
+        None => {
+            CONTROL.epoch.fetch_add(1, Ordering::SeqCst);
+            for progress in CONTROL.running.lock().unwrap().values() {
+                progress.interrupt();
+            }
+        }
+    }
+
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+    match id {
+        Some(id) => query("DELETE FROM active_scan_queue WHERE id = ?").bind(id).execute(&*pool).await?,
+        None => query("DELETE FROM active_scan_queue").execute(&*pool).await?,
+    };
+    Ok(())
+}
+
+fn from_row(row: &SqliteRow) -> QueuedTask {
+    let kind: String = row.get("kind");
+    let status: String = row.get("status");
+    let payload_index: i64 = row.get("payload_index");
+    let completed_points: Option<String> = row.get("completed_points");
+
+    QueuedTask {
+        id: row.get("id"),
+        scan_id: row.get("scan_id"),
+        kind: QueueKind::parse(&kind),
+        target: row.get("target"),
+        status: QueueStatus::parse(&status),
+        checkpoint: Checkpoint {
+            phase: row.get("phase"),
+            insertion_point: row.get("insertion_point"),
+            payload_index: payload_index as usize,
+            completed_points: completed_points
+                .and_then(|p| serde_json::from_str(&p).ok())
+                .unwrap_or_default(),
+        },
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        data: row.get("data"),
+        epoch: CONTROL.epoch.load(Ordering::SeqCst),
+    }
+}
+
+/// 任务写入队列
+pub async fn enqueue(task: &QueuedTask) -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    query(
+        r#"
+        INSERT INTO active_scan_queue (id, scan_id, kind, target, phase, insertion_point, payload_index,
+            completed_points, status, data, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&task.id)
+    .bind(&task.scan_id)
+    .bind(task.kind.as_str())
+    .bind(&task.target)
+    .bind(&task.checkpoint.phase)
+    .bind(&task.checkpoint.insertion_point)
+    .bind(task.checkpoint.payload_index as i64)
+    .bind(serde_json::to_string(&task.checkpoint.completed_points)?)
+    .bind(task.status.as_str())
+    .bind(&task.data)
+    .bind(task.created_at)
+    .bind(task.updated_at)
+    .execute(&*pool)
+    .await?;
+    Ok(())
+}
+
+/// 保存任务断点
+pub async fn save_checkpoint(id: &str, checkpoint: &Checkpoint) -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    query(
+        "UPDATE active_scan_queue SET phase = ?, insertion_point = ?, payload_index = ?, completed_points = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(&checkpoint.phase)
+    .bind(&checkpoint.insertion_point)
+    .bind(checkpoint.payload_index as i64)
+    .bind(serde_json::to_string(&checkpoint.completed_points)?)
+    .bind(Utc::now().timestamp())
+    .bind(id)
+    .execute(&*pool)
+    .await?;
+    Ok(())
+}
+
+/// 修改任务状态，暂停期间开始执行的任务保持暂停状态
+pub async fn set_status(id: &str, status: QueueStatus) -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    let status = if is_paused() { QueueStatus::Paused } else { status };
+    query("UPDATE active_scan_queue SET status = ?, updated_at = ? WHERE id = ?")
+        .bind(status.as_str())
+        .bind(Utc::now().timestamp())
+        .bind(id)
+        .execute(&*pool)
+        .await?;
+    Ok(())
+}
+
+/// 任务完成，从队列删除
+pub async fn complete(id: &str) -> Result<()> {
+    CONTROL.cancelled.lock().unwrap().remove(id);
+
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+    query("DELETE FROM active_scan_queue WHERE id = ?").bind(id).execute(&*pool).await?;
+    Ok(())
+}
+
+/// 查询队列中的任务，按入队顺序排列
+pub async fn list(kind: Option<QueueKind>) -> Result<Vec<QueuedTask>> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.read_conn);
+
+    let rows = match kind {
+        Some(kind) => {
+            query(&format!("{} WHERE kind = ? ORDER BY created_at, rowid", SELECT_COLUMNS))
+                .bind(kind.as_str())
+                .fetch_all(&*pool)
+                .await?
+        }
+        None => {
+            query(&format!("{} ORDER BY created_at, rowid", SELECT_COLUMNS))
+                .fetch_all(&*pool)
+                .await?
+        }
+    };
+    Ok(rows.iter().map(from_row).collect())
+}
+
+/// 加载上次未完成的任务用于恢复执行
+///
+/// 上次退出时执行中的任务重新置为待执行；存在暂停的任务时队列保持暂停。
+pub async fn restore(kind: QueueKind) -> Result<Vec<QueuedTask>> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+    query("UPDATE active_scan_queue SET status = 'pending' WHERE status = 'running' AND kind = ?")
+        .bind(kind.as_str())
+        .execute(&*pool)
+        .await?;
+
+    let tasks = list(Some(kind)).await?;
+    if tasks.iter().any(|t| t.status == QueueStatus::Paused) {
+        CONTROL.paused.store(true, Ordering::SeqCst);
+    }
+    Ok(tasks)
+}
+
+/// 队列是否可用（数据库已初始化）
+pub fn is_available() -> bool {
+    INNERASK_MODULE.get().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.enter_phase("SQL Injection Scanner");
+        assert!(checkpoint.advance("error:id", 0));
+        assert!(checkpoint.advance("error:id", 1));
+        assert!(checkpoint.advance("error:name", 0));
+        assert_eq!(checkpoint.completed_points, vec!["error:id".to_string()]);
+
+        // 从断点恢复：已完成的插入点和当前插入点之前的payload跳过
+        let mut resumed = checkpoint.clone();
+        resumed.enter_phase("SQL Injection Scanner");
+        assert!(!resumed.advance("error:id", 0));
+        assert!(!resumed.advance("error:id", 1));
+        assert!(resumed.advance("error:name", 0));
+        assert!(resumed.advance("error:name", 1));
+        assert!(resumed.advance("boolean:id", 0));
+
+        resumed.enter_phase("XSS Scanner");
+        assert_eq!(resumed.insertion_point, None);
+        assert!(resumed.completed_points.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_should_test_outside_task() {
+        assert!(should_test("error:id", 3));
+
+        let mut task = QueuedTask::new("scan", QueueKind::Request, "http://example.com/", String::new());
+        task.checkpoint.enter_phase("SQL Injection Scanner");
+        task.checkpoint.insertion_point = Some("error:id".to_string());
+        task.checkpoint.payload_index = 2;
+        let progress = TaskProgress::start(&task);
+        let tested: Vec<bool> = progress.scope(async { (0..4).map(|i| should_test("error:id", i)).collect() }).await;
+        assert_eq!(tested, vec![false, false, true, true]);
+        assert_eq!(progress.finish().payload_index, 3);
+    }
+
+    #[tokio::test]
+    async fn test_complete_phase() {
+        let phases: Vec<String> = ["SQL Injection Scanner", "XSS Scanner", "plugins"].iter().map(|s| s.to_string()).collect();
+        let task = QueuedTask::new("scan", QueueKind::Request, "http://example.com/", String::new());
+
+        // 执行完的阶段恢复时跳过
+        let progress = TaskProgress::detached(&task);
+        assert!(!progress.enter_phase("SQL Injection Scanner", &phases));
+        progress.complete_phase("SQL Injection Scanner", &phases);
+        let resumed = TaskProgress::detached(&QueuedTask { checkpoint: progress.finish(), ..task.clone() });
+        assert!(resumed.enter_phase("SQL Injection Scanner", &phases));
+        assert!(!resumed.enter_phase("XSS Scanner", &phases));
+
+        // 中断时跳过了payload的阶段保留断点
+        let progress = TaskProgress::detached(&task);
+        assert!(!progress.enter_phase("SQL Injection Scanner", &phases));
+        progress.interrupt();
+        assert!(!progress.scope(async { should_test("error:id", 0) }).await);
+        progress.complete_phase("SQL Injection Scanner", &phases);
+        assert_eq!(progress.finish().phase.as_deref(), Some("SQL Injection Scanner"));
+    }
+}
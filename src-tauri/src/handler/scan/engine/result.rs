@@ -1,6 +1,59 @@
 use chrono;
 use serde::{Deserialize, Serialize};
 
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+
+/// 原始报文中保留的最大消息体长度
+const MAX_RAW_BODY: usize = 256 * 1024;
+
+/// 漏洞置信度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// 已通过回连、回显或重放等方式确认
+    Certain,
+    /// 特征明确但未做二次确认
+    #[default]
+    Firm,
+    /// 仅有间接迹象，需要人工确认
+    Tentative,
+}
+
+impl Confidence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Confidence::Certain => "certain",
+            Confidence::Firm => "firm",
+            Confidence::Tentative => "tentative",
+        }
+    }
+
+    /// 置信度排序，数值越大越可信
+    pub fn rank(&self) -> u8 {
+        match self {
+            Confidence::Certain => 2,
+            Confidence::Firm => 1,
+            Confidence::Tentative => 0,
+        }
+    }
+
+    /// 解析数据库中的置信度，未知值按firm处理
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "certain" => Confidence::Certain,
+            "tentative" => Confidence::Tentative,
+            _ => Confidence::Firm,
+        }
+    }
+}
+
+/// 原始报文中的字节区间 `[start, end)`，用于前端高亮
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ByteRange {
+    pub start: usize,
+    pub end: usize,
+}
+
 /// 漏洞扫描结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanResult {
@@ -28,8 +81,182 @@ pub struct ScanResult {
     pub details: Option<String>,
     /// 时间戳
     pub timestamp: chrono::DateTime<chrono::Utc>,
-    /// 请求详情
+    /// 请求详情，通过 [`ScanResult::with_exchange`] 设置时为完整的原始请求
     pub request_details: Option<String>,
-    /// 响应详情
+    /// 响应详情，通过 [`ScanResult::with_exchange`] 设置时为完整的原始响应
     pub response_details: Option<String>,
+    /// 置信度
+    #[serde(default)]
+    pub confidence: Confidence,
+    /// payload在request_details中的字节区间
+    #[serde(default)]
+    pub payload_ranges: Vec<ByteRange>,
+    /// 证据在response_details中的字节区间
+    #[serde(default)]
+    pub evidence_ranges: Vec<ByteRange>,
+}
+
+impl ScanResult {
+    /// 记录证明漏洞的完整请求/响应，并标注payload和证据在报文中的位置
+    pub fn with_exchange(
+        self,
+        request: &HttpRequest,
+        response: Option<&HttpResponse>,
+        payload: Option<&str>,
+        evidence: Option<&str>,
+    ) -> Self {
+        self.with_raw_exchange(raw_request(request), response.map(raw_response), payload, evidence)
+    }
+
+    /// 与 [`ScanResult::with_exchange`] 相同，用于直接发送原始报文的扫描器
+    pub fn with_raw_exchange(
+        mut self,
+        raw_request: String,
+        raw_response: Option<String>,
+        payload: Option<&str>,
+        evidence: Option<&str>,
+    ) -> Self {
+        self.payload_ranges = payload.map(|p| find_payload_ranges(&raw_request, p)).unwrap_or_default();
+        self.request_details = Some(raw_request);
+
+        if let Some(raw_response) = raw_response {
+            self.evidence_ranges = evidence.map(|e| find_ranges(&raw_response, e)).unwrap_or_default();
+            self.response_details = Some(raw_response);
+        }
+        self
+    }
+}
+
+/// 消息体转为文本，超过 [`MAX_RAW_BODY`] 时截断
+fn raw_body(body: &[u8]) -> String {
+    let body = &body[..body.len().min(MAX_RAW_BODY)];
+    String::from_utf8_lossy(body).to_string()
+}
+
+/// 头部按名称排序后拼接，保证同一请求生成的报文一致
+fn raw_headers(headers: &std::collections::HashMap<String, String>, raw: &mut String) {
+    let mut headers: Vec<_> = headers.iter().collect();
+    headers.sort_by_key(|(name, _)| name.to_lowercase());
+    for (name, value) in headers {
+        raw.push_str(&format!("{}: {}\r\n", name, value));
+    }
+}
+
+/// 生成HTTP/1.1格式的原始请求
+///
+/// params非空时按params重建查询串，与实际发送测试请求的 [`build_request`] 一致，
+/// 注入到查询参数的payload只写入params。
+///
+/// [`build_request`]: crate::handler::scan::utils::http::build_request
+pub fn raw_request(request: &HttpRequest) -> String {
+    let (target, host) = match url::Url::parse(&request.url) {
+        Ok(mut url) => {
+            if !request.params.is_empty() {
+                url.set_query(None);
+                url.query_pairs_mut().extend_pairs(&request.params);
+            }
+            let mut target = url.path().to_string();
+            if let Some(query) = url.query() {
+                target.push('?');
+                target.push_str(query);
+            }
+            let host = match url.port() {
+                Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+                None => url.host_str().unwrap_or_default().to_string(),
+            };
+            (target, host)
+        }
+        Err(_) => (request.url.clone(), String::new()),
+    };
+
+    let mut raw = format!("{} {} HTTP/1.1\r\n", request.method, target);
+    if !host.is_empty() && !request.headers.keys().any(|k| k.eq_ignore_ascii_case("host")) {
+        raw.push_str(&format!("Host: {}\r\n", host));
+    }
+    raw_headers(&request.headers, &mut raw);
+    raw.push_str("\r\n");
+    raw.push_str(&raw_body(&request.body));
+    raw
+}
+
+/// 生成HTTP/1.1格式的原始响应
+pub fn raw_response(response: &HttpResponse) -> String {
+    let mut raw = format!("HTTP/1.1 {}\r\n", response.status);
+    raw_headers(&response.headers, &mut raw);
+    raw.push_str("\r\n");
+    raw.push_str(&raw_body(&response.body));
+    raw
+}
+
+/// 查找needle在报文中全部出现的位置
+pub fn find_ranges(haystack: &str, needle: &str) -> Vec<ByteRange> {
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    haystack
+        .match_indices(needle)
+        .map(|(start, m)| ByteRange { start, end: start + m.len() })
+        .collect()
+}
+
+/// 查找payload在请求中的位置，依次尝试原文、表单编码和百分号编码形式
+pub fn find_payload_ranges(raw_request: &str, payload: &str) -> Vec<ByteRange> {
+    let form_encoded: String = url::form_urlencoded::byte_serialize(payload.as_bytes()).collect();
+    let percent_encoded = urlencoding::encode(payload).to_string();
+    [payload.to_string(), form_encoded, percent_encoded]
+        .iter()
+        .map(|candidate| find_ranges(raw_request, candidate))
+        .find(|ranges| !ranges.is_empty())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::scan::utils::insertion;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_with_exchange_ranges() {
+        let request = HttpRequest::new(
+            "http://example.com:8080/search?q=test",
+            "GET",
+            HashMap::new(),
+            Vec::new(),
+            vec![("q".to_string(), "test".to_string())],
+        );
+        let request = insertion::with_param(&request, "q", "<script>alert(1)</script>");
+        let response = HttpResponse::new(200, HashMap::new(), b"<p><script>alert(1)</script></p>".to_vec());
+        let result = ScanResult {
+            vulnerability_type: "XSS".to_string(),
+            name: "XSS".to_string(),
+            description: String::new(),
+            risk_level: "High".to_string(),
+            url: request.url.clone(),
+            method: request.method.clone(),
+            parameter: Some("q".to_string()),
+            value: Some("<script>alert(1)</script>".to_string()),
+            evidence: None,
+            remediation: None,
+            details: None,
+            timestamp: chrono::Utc::now(),
+            request_details: None,
+            response_details: None,
+            confidence: Confidence::Certain,
+            payload_ranges: Vec::new(),
+            evidence_ranges: Vec::new(),
+        }
+        .with_exchange(&request, Some(&response), Some("<script>alert(1)</script>"), Some("<script>alert(1)</script>"));
+
+        let raw_request = result.request_details.as_deref().unwrap();
+        assert!(raw_request.starts_with("GET /search?q=%3Cscript%3E"));
+        assert!(raw_request.contains("Host: example.com:8080\r\n"));
+        let range = result.payload_ranges[0];
+        assert_eq!(&raw_request[range.start..range.end], "%3Cscript%3Ealert%281%29%3C%2Fscript%3E");
+
+        let raw_response = result.response_details.as_deref().unwrap();
+        let range = result.evidence_ranges[0];
+        assert_eq!(&raw_response[range.start..range.end], "<script>alert(1)</script>");
+        assert_eq!(serde_json::to_value(result.confidence).unwrap(), "certain");
+    }
 }
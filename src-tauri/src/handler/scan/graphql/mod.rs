@@ -0,0 +1,864 @@
+//! GraphQL端点识别与测试
+//!
+//! 按常见路径和响应结构识别端点，执行内省查询（被禁用时通过字段建议爆破还原根字段），
+//! 为每个根字段生成带变量的查询，变量作为插入点交给SQL注入、XSS等扫描器，
+//! 同时检查内省、字段建议、批量查询和查询深度限制。
+
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::utils::http;
+use anyhow::{anyhow, Result};
+use log::debug;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use url::Url;
+
+/// 常见GraphQL路径
+pub const GRAPHQL_PATHS: &[&str] = &[
+    "/graphql",
+    "/api/graphql",
+    "/graphql/v1",
+    "/v1/graphql",
+    "/api/v1/graphql",
+    "/graphql/api",
+    "/gql",
+    "/query",
+    "/graphiql",
+    "/playground",
+];
+
+/// 内省查询
+pub const INTROSPECTION_QUERY: &str = "query IntrospectionQuery { __schema { queryType { name } mutationType { name } types { kind name fields(includeDeprecated: true) { name args { name type { ...TypeRef } } type { ...TypeRef } } inputFields { name type { ...TypeRef } } enumValues(includeDeprecated: true) { name } } } } fragment TypeRef on __Type { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } } }";
+
+/// 字段建议爆破字典
+const FIELD_WORDLIST: &[&str] = &[
+    "user", "users", "me", "viewer", "account", "accounts", "admin", "profile", "node", "nodes", "search",
+    "order", "orders", "product", "products", "item", "items", "post", "posts", "comment", "comments",
+    "file", "files", "config", "settings", "login", "token", "session", "role", "roles", "group", "groups",
+    "customer", "customers", "payment", "payments", "invoice", "message", "messages", "project", "projects",
+];
+
+/// 批量查询的数量
+const BATCH_SIZE: usize = 10;
+
+/// 深度测试使用的嵌套层数
+const DEPTH_PROBE: usize = 12;
+
+/// 字段建议信息，例如 Did you mean "user" or "users"?
+static SUGGESTION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Did you mean (.+?)\?").unwrap());
+static QUOTED_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#""([_A-Za-z][_0-9A-Za-z]*)""#).unwrap());
+
+/// 类型引用
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypeRef {
+    pub kind: String,
+    pub name: Option<String>,
+    pub of_type: Option<Box<TypeRef>>,
+}
+
+impl TypeRef {
+    fn from_value(value: &Value) -> Self {
+        Self {
+            kind: value.get("kind").and_then(Value::as_str).unwrap_or_default().to_string(),
+            name: value.get("name").and_then(Value::as_str).map(|s| s.to_string()),
+            of_type: value
+                .get("ofType")
+                .filter(|v| !v.is_null())
+                .map(|v| Box::new(Self::from_value(v))),
+        }
+    }
+
+    /// 去掉LIST/NON_NULL包装后的类型名
+    pub fn named(&self) -> Option<&str> {
+        match &self.of_type {
+            Some(inner) => inner.named(),
+            None => self.name.as_deref(),
+        }
+    }
+
+    /// 是否必填
+    pub fn is_non_null(&self) -> bool {
+        self.kind == "NON_NULL"
+    }
+
+    /// 变量声明中的类型写法，例如 [String!]!
+    pub fn to_gql(&self) -> String {
+        match self.kind.as_str() {
+            "NON_NULL" => format!("{}!", self.of_type.as_ref().map(|t| t.to_gql()).unwrap_or_default()),
+            "LIST" => format!("[{}]", self.of_type.as_ref().map(|t| t.to_gql()).unwrap_or_default()),
+            _ => self.name.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// 参数或输入字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputValue {
+    pub name: String,
+    pub type_ref: TypeRef,
+}
+
+/// 字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Field {
+    pub name: String,
+    pub args: Vec<InputValue>,
+    /// 字段建议还原的字段没有类型信息
+    pub type_ref: Option<TypeRef>,
+}
+
+/// 类型定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeDef {
+    pub kind: String,
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub input_fields: Vec<InputValue>,
+    pub enum_values: Vec<String>,
+}
+
+/// GraphQL模式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphqlSchema {
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub types: Vec<TypeDef>,
+    /// 来源：introspection 或 suggestion
+    pub source: String,
+}
+
+/// 生成的操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphqlOperation {
+    /// query 或 mutation
+    pub operation_type: String,
+    pub field: String,
+    pub query: String,
+    pub variables: Value,
+}
+
+impl GraphqlOperation {
+    /// 构造POST JSON请求，变量以 variables.<参数名> 作为插入点
+    pub fn to_request(&self, endpoint: &str, headers: &HashMap<String, String>) -> HttpRequest {
+        json_request(endpoint, headers, &json!({"query": self.query, "variables": self.variables}))
+    }
+}
+
+/// 构造POST JSON请求，端点自带的查询参数保留在params中
+fn json_request(endpoint: &str, headers: &HashMap<String, String>, body: &Value) -> HttpRequest {
+    let mut headers = headers.clone();
+    headers.retain(|k, _| !k.eq_ignore_ascii_case("content-type") && !k.eq_ignore_ascii_case("content-length"));
+    headers.insert("Content-Type".to_string(), "application/json".to_string());
+    let params = Url::parse(endpoint)
+        .map(|url| url.query_pairs().map(|(k, v)| (k.to_string(), v.to_string())).collect())
+        .unwrap_or_default();
+
+    HttpRequest {
+        method: "POST".to_string(),
+        url: endpoint.to_string(),
+        headers,
+        body: serde_json::to_vec(body).unwrap_or_default(),
+        params,
+    }
+}
+
+impl GraphqlSchema {
+    /// 解析内省查询结果
+    pub fn from_introspection(data: &Value) -> Option<Self> {
+        let schema = data.get("__schema")?;
+        let root_name = |key: &str| {
+            schema
+                .get(key)
+                .and_then(|t| t.get("name"))
+                .and_then(Value::as_str)
+                .map(|s| s.to_string())
+        };
+        let input_values = |value: Option<&Value>| -> Vec<InputValue> {
+            value
+                .and_then(Value::as_array)
+                .map(|items| {
+                    items
+                        .iter()
+                        .map(|a| InputValue {
+                            name: a.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+                            type_ref: TypeRef::from_value(a.get("type").unwrap_or(&Value::Null)),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let types = schema
+            .get("types")?
+            .as_array()?
+            .iter()
+            .map(|t| TypeDef {
+                kind: t.get("kind").and_then(Value::as_str).unwrap_or_default().to_string(),
+                name: t.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+                fields: t
+                    .get("fields")
+                    .and_then(Value::as_array)
+                    .map(|fields| {
+                        fields
+                            .iter()
+                            .map(|f| Field {
+                                name: f.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+                                args: input_values(f.get("args")),
+                                type_ref: f.get("type").map(TypeRef::from_value),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                input_fields: input_values(t.get("inputFields")),
+                enum_values: t
+                    .get("enumValues")
+                    .and_then(Value::as_array)
+                    .map(|v| v.iter().filter_map(|e| e.get("name").and_then(Value::as_str)).map(|s| s.to_string()).collect())
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        Some(Self {
+            query_type: root_name("queryType"),
+            mutation_type: root_name("mutationType"),
+            types,
+            source: "introspection".to_string(),
+        })
+    }
+
+    /// 按名称查找类型
+    pub fn get_type(&self, name: &str) -> Option<&TypeDef> {
+        self.types.iter().find(|t| t.name == name)
+    }
+
+    /// 生成变量示例值
+    fn sample_value(&self, type_ref: &TypeRef, depth: usize) -> Value {
+        match type_ref.kind.as_str() {
+            "NON_NULL" => type_ref.of_type.as_ref().map(|t| self.sample_value(t, depth)).unwrap_or(Value::Null),
+            "LIST" => json!([type_ref.of_type.as_ref().map(|t| self.sample_value(t, depth)).unwrap_or(Value::Null)]),
+            _ => {
+                let name = type_ref.name.as_deref().unwrap_or_default();
+                match name {
+                    "Int" => json!(1),
+                    "Float" => json!(1.0),
+                    "Boolean" => json!(true),
+                    "ID" => json!("1"),
+                    "String" => json!("rshield"),
+                    _ => match self.get_type(name) {
+                        Some(t) if t.kind == "ENUM" => t.enum_values.first().map(|v| json!(v)).unwrap_or(Value::Null),
+                        Some(t) if t.kind == "INPUT_OBJECT" && depth < 3 => {
+                            let mut obj = Map::new();
+                            for f in &t.input_fields {
+                                obj.insert(f.name.clone(), self.sample_value(&f.type_ref, depth + 1));
+                            }
+                            Value::Object(obj)
+                        }
+                        Some(t) if t.kind == "INPUT_OBJECT" => Value::Null,
+                        // 自定义标量按字符串处理
+                        _ => json!("rshield"),
+                    },
+                }
+            }
+        }
+    }
+
+    /// 生成字段调用的变量声明、参数列表和示例变量，参数全部通过变量传入
+    fn field_call(&self, field: &Field) -> (String, String, Value) {
+        let mut variables = Map::new();
+        let mut declarations = Vec::new();
+        let mut arguments = Vec::new();
+        for arg in &field.args {
+            declarations.push(format!("${}: {}", arg.name, arg.type_ref.to_gql()));
+            arguments.push(format!("{}: ${}", arg.name, arg.name));
+            variables.insert(arg.name.clone(), self.sample_value(&arg.type_ref, 0));
+        }
+
+        let wrap = |items: Vec<String>| {
+            if items.is_empty() {
+                String::new()
+            } else {
+                format!("({})", items.join(", "))
+            }
+        };
+        (wrap(declarations), wrap(arguments), Value::Object(variables))
+    }
+
+    /// 生成返回类型的选择集
+    fn selection_set(&self, type_ref: Option<&TypeRef>) -> String {
+        let name = match type_ref.and_then(|t| t.named()) {
+            Some(name) => name,
+            // 类型未知时用__typename占位
+            None => return " { __typename }".to_string(),
+        };
+        let type_def = match self.get_type(name) {
+            Some(t) => t,
+            None => return String::new(),
+        };
+
+        match type_def.kind.as_str() {
+            "OBJECT" | "INTERFACE" => {
+                let scalars: Vec<&str> = type_def
+                    .fields
+                    .iter()
+                    .filter(|f| f.args.iter().all(|a| !a.type_ref.is_non_null()))
+                    .filter(|f| {
+                        f.type_ref
+                            .as_ref()
+                            .and_then(|t| t.named())
+                            .and_then(|n| self.get_type(n))
+                            .map(|t| t.kind == "SCALAR" || t.kind == "ENUM")
+                            .unwrap_or(false)
+                    })
+                    .map(|f| f.name.as_str())
+                    .take(10)
+                    .collect();
+                if scalars.is_empty() {
+                    " { __typename }".to_string()
+                } else {
+                    format!(" {{ {} }}", scalars.join(" "))
+                }
+            }
+            "UNION" => " { __typename }".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// 为根类型的每个字段生成操作
+    pub fn generate_operations(&self, include_mutations: bool) -> Vec<GraphqlOperation> {
+        let mut roots = vec![("query", self.query_type.clone())];
+        if include_mutations {
+            roots.push(("mutation", self.mutation_type.clone()));
+        }
+
+        let mut operations = Vec::new();
+        for (operation_type, root) in roots {
+            let root = match root.as_deref().and_then(|name| self.get_type(name)) {
+                Some(root) => root,
+                None => continue,
+            };
+            for field in &root.fields {
+                if field.name.starts_with("__") {
+                    continue;
+                }
+                let (declarations, arguments, variables) = self.field_call(field);
+                let query = format!(
+                    "{} RShield_{}{} {{ {}{}{} }}",
+                    operation_type,
+                    field.name,
+                    declarations,
+                    field.name,
+                    arguments,
+                    self.selection_set(field.type_ref.as_ref())
+                );
+
+                operations.push(GraphqlOperation {
+                    operation_type: operation_type.to_string(),
+                    field: field.name.clone(),
+                    query,
+                    variables,
+                });
+            }
+        }
+        operations
+    }
+
+    /// 查找对象类型之间的循环引用（A.x -> B, B.y -> A），用于构造深层嵌套查询
+    fn find_cycle(&self) -> Option<(Field, String, String)> {
+        let query_type = self.get_type(self.query_type.as_deref()?)?;
+        let object_type = |field: &Field| -> Option<String> {
+            let name = field.type_ref.as_ref()?.named()?;
+            (self.get_type(name)?.kind == "OBJECT").then(|| name.to_string())
+        };
+        // 嵌套字段只使用无必填参数的字段
+        let object_fields = |type_def: &TypeDef| -> Vec<(String, String)> {
+            type_def
+                .fields
+                .iter()
+                .filter(|f| f.args.iter().all(|a| !a.type_ref.is_non_null()))
+                .filter_map(|f| object_type(f).map(|t| (f.name.clone(), t)))
+                .collect()
+        };
+
+        for root_field in &query_type.fields {
+            let a = match object_type(root_field) {
+                Some(a) => a,
+                None => continue,
+            };
+            let a_def = match self.get_type(&a) {
+                Some(a_def) => a_def,
+                None => continue,
+            };
+            for (x, b) in object_fields(a_def) {
+                if let Some(b_def) = self.get_type(&b) {
+                    if let Some((y, _)) = object_fields(b_def).into_iter().find(|(_, t)| *t == a) {
+                        return Some((root_field.clone(), x, y));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// 构造深层嵌套查询
+    pub fn build_deep_query(&self, depth: usize) -> Option<GraphqlOperation> {
+        let (root_field, x, y) = self.find_cycle()?;
+        let mut selection = "__typename".to_string();
+        for i in (0..depth).rev() {
+            let field = if i % 2 == 0 { &x } else { &y };
+            selection = format!("{} {{ {} }}", field, selection);
+        }
+
+        let (declarations, arguments, variables) = self.field_call(&root_field);
+        Some(GraphqlOperation {
+            operation_type: "query".to_string(),
+            field: root_field.name.clone(),
+            query: format!(
+                "query RShield_depth{} {{ {}{} {{ {} }} }}",
+                declarations, root_field.name, arguments, selection
+            ),
+            variables,
+        })
+    }
+}
+
+/// 判断响应是否为GraphQL响应
+pub fn looks_like_graphql(body: &str) -> bool {
+    match serde_json::from_str::<Value>(body) {
+        Ok(Value::Object(obj)) => {
+            obj.get("data").map(|d| d.is_object() || d.is_null()).unwrap_or(false)
+                || obj
+                    .get("errors")
+                    .and_then(Value::as_array)
+                    .map(|errors| errors.iter().any(|e| e.get("message").is_some()))
+                    .unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+/// 拼接响应中的错误信息
+pub fn error_messages(body: &str) -> String {
+    serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|v| v.get("errors").and_then(Value::as_array).cloned())
+        .map(|errors| {
+            errors
+                .iter()
+                .filter_map(|e| e.get("message").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+/// 从错误信息中提取字段建议
+pub fn parse_suggestions(body: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for cap in SUGGESTION_RE.captures_iter(body) {
+        for name in QUOTED_RE.captures_iter(&cap[1]) {
+            let name = name[1].to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// GraphQL客户端
+pub struct GraphqlClient<'a> {
+    client: &'a reqwest::Client,
+    endpoint: String,
+    headers: HashMap<String, String>,
+}
+
+impl<'a> GraphqlClient<'a> {
+    pub fn new(client: &'a reqwest::Client, endpoint: &str, headers: HashMap<String, String>) -> Self {
+        Self {
+            client,
+            endpoint: endpoint.to_string(),
+            headers,
+        }
+    }
+
+    fn request(&self, body: &Value) -> HttpRequest {
+        json_request(&self.endpoint, &self.headers, body)
+    }
+
+    /// 发送JSON请求，返回请求和响应，用于记录发现的证据
+    async fn send(&self, body: &Value) -> Result<(HttpRequest, HttpResponse)> {
+        let request = self.request(body);
+        let response = http::send_request(self.client, &request).await?;
+        Ok((request, response))
+    }
+
+    /// 发送JSON请求，返回(状态码, 响应体)
+    async fn post(&self, body: &Value) -> Result<(u16, String)> {
+        let (_, response) = self.send(body).await?;
+        Ok((response.status, String::from_utf8_lossy(&response.body).to_string()))
+    }
+
+    /// 发送查询
+    pub async fn query(&self, query: &str) -> Result<(u16, String)> {
+        self.post(&json!({ "query": query })).await
+    }
+
+    /// 执行内省查询，同时返回内省请求和响应
+    pub async fn introspect(&self) -> Result<(GraphqlSchema, (HttpRequest, HttpResponse))> {
+        let (request, response) = self.send(&json!({ "query": INTROSPECTION_QUERY })).await?;
+        let json: Value = serde_json::from_slice(&response.body)?;
+        let schema = json
+            .get("data")
+            .and_then(GraphqlSchema::from_introspection)
+            .ok_or_else(|| anyhow!("内省查询被禁用"))?;
+        Ok((schema, (request, response)))
+    }
+
+    /// 通过字段建议还原根查询字段，同时返回第一个得到建议的请求和响应
+    pub async fn recover_by_suggestions(&self) -> Option<(GraphqlSchema, (HttpRequest, HttpResponse))> {
+        let mut fields: Vec<String> = Vec::new();
+        let mut exchange = None;
+        for word in FIELD_WORDLIST {
+            // 故意拼错一个字符以触发建议
+            let probe = format!("{}x", word);
+            let (request, response) = match self.send(&json!({ "query": format!("query {{ {} }}", probe) })).await {
+                Ok(result) => result,
+                Err(e) => {
+                    debug!("GraphQL字段建议请求失败: {}", e);
+                    continue;
+                }
+            };
+            let suggestions = parse_suggestions(&error_messages(&String::from_utf8_lossy(&response.body)));
+            if suggestions.is_empty() {
+                continue;
+            }
+            for name in suggestions {
+                if !fields.contains(&name) {
+                    fields.push(name);
+                }
+            }
+            exchange.get_or_insert((request, response));
+        }
+        let exchange = exchange?;
+
+        // 判断字段是否需要选择集：对象类型不带选择集时会报错
+        let mut query_fields = Vec::new();
+        for name in fields {
+            let needs_selection = match self.query(&format!("query {{ {} }}", name)).await {
+                Ok((_, body)) => body.contains("selection") || body.contains("subfields"),
+                Err(_) => true,
+            };
+            query_fields.push(Field {
+                name,
+                args: Vec::new(),
+                type_ref: if needs_selection {
+                    None
+                } else {
+                    Some(TypeRef {
+                        kind: "SCALAR".to_string(),
+                        name: Some("String".to_string()),
+                        of_type: None,
+                    })
+                },
+            });
+        }
+
+        let schema = GraphqlSchema {
+            query_type: Some("Query".to_string()),
+            mutation_type: None,
+            types: vec![
+                TypeDef {
+                    kind: "OBJECT".to_string(),
+                    name: "Query".to_string(),
+                    fields: query_fields,
+                    input_fields: Vec::new(),
+                    enum_values: Vec::new(),
+                },
+                TypeDef {
+                    kind: "SCALAR".to_string(),
+                    name: "String".to_string(),
+                    fields: Vec::new(),
+                    input_fields: Vec::new(),
+                    enum_values: Vec::new(),
+                },
+            ],
+            source: "suggestion".to_string(),
+        };
+        Some((schema, exchange))
+    }
+
+    /// 检查是否支持批量查询（JSON数组），支持时返回批量请求和响应
+    pub async fn supports_batching(&self) -> Option<(HttpRequest, HttpResponse)> {
+        let batch: Vec<Value> = (0..BATCH_SIZE).map(|_| json!({"query": "query { __typename }"})).collect();
+        let (request, response) = self.send(&Value::Array(batch)).await.ok()?;
+        serde_json::from_slice::<Value>(&response.body)
+            .ok()
+            .and_then(|v| v.as_array().map(|a| a.len() == BATCH_SIZE))
+            .unwrap_or(false)
+            .then_some((request, response))
+    }
+
+    /// 检查是否支持别名批量查询，支持时返回请求和响应
+    pub async fn supports_alias_batching(&self) -> Option<(HttpRequest, HttpResponse)> {
+        let aliases: Vec<String> = (0..BATCH_SIZE * 10).map(|i| format!("a{}: __typename", i)).collect();
+        let (request, response) = self.send(&json!({ "query": format!("query {{ {} }}", aliases.join(" ")) })).await.ok()?;
+        String::from_utf8_lossy(&response.body)
+            .contains(&format!("\"a{}\"", BATCH_SIZE * 10 - 1))
+            .then_some((request, response))
+    }
+
+    /// 检查深层嵌套查询是否被接受，被接受时返回请求和响应
+    pub async fn accepts_deep_query(&self, operation: &GraphqlOperation) -> Option<(HttpRequest, HttpResponse)> {
+        let (request, response) =
+            self.send(&json!({ "query": operation.query, "variables": operation.variables })).await.ok()?;
+        let body = String::from_utf8_lossy(&response.body);
+        let lower = body.to_lowercase();
+        let accepted = response.status < 400
+            && body.contains("\"data\"")
+            && !lower.contains("depth")
+            && !lower.contains("complexity")
+            && !lower.contains("too deep");
+        accepted.then_some((request, response))
+    }
+}
+
+/// 在站点上识别GraphQL端点，探测请求携带调用方的请求头，需要认证的端点也能识别
+pub async fn detect_endpoints(client: &reqwest::Client, base_url: &str, headers: &HashMap<String, String>) -> Vec<String> {
+    let base = match Url::parse(base_url) {
+        Ok(url) => url,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut candidates = Vec::new();
+    // 传入的地址本身可能就是端点
+    if base.path() != "/" {
+        candidates.push(base.as_str().to_string());
+    }
+    for path in GRAPHQL_PATHS {
+        if let Ok(url) = base.join(path) {
+            candidates.push(url.to_string());
+        }
+    }
+
+    let mut endpoints = Vec::new();
+    for candidate in candidates {
+        let gql = GraphqlClient::new(client, &candidate, headers.clone());
+        if let Ok((_, body)) = gql.query("query { __typename }").await {
+            if looks_like_graphql(&body) && !endpoints.contains(&candidate) {
+                endpoints.push(candidate);
+            }
+        }
+    }
+    endpoints
+}
+
+/// 端点分析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphqlReport {
+    pub endpoint: String,
+    pub schema: Option<GraphqlSchema>,
+    pub operations: Vec<GraphqlOperation>,
+    pub findings: Vec<ScanResult>,
+}
+
+/// 构造发现
+fn finding(endpoint: &str, name: &str, description: &str, risk_level: &str, evidence: String, remediation: &str) -> ScanResult {
+    ScanResult {
+        vulnerability_type: "GraphQL".to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        risk_level: risk_level.to_string(),
+        url: endpoint.to_string(),
+        method: "POST".to_string(),
+        parameter: None,
+        value: None,
+        evidence: Some(evidence),
+        remediation: Some(remediation.to_string()),
+        details: None,
+        timestamp: chrono::Utc::now(),
+        request_details: None,
+        response_details: None,
+        confidence: Confidence::Firm,
+        payload_ranges: Vec::new(),
+        evidence_ranges: Vec::new(),
+    }
+}
+
+/// 分析GraphQL端点：获取模式、生成操作并检查配置问题
+pub async fn analyze_endpoint(
+    client: &reqwest::Client,
+    endpoint: &str,
+    headers: HashMap<String, String>,
+    include_mutations: bool,
+) -> GraphqlReport {
+    let gql = GraphqlClient::new(client, endpoint, headers);
+    let mut findings = Vec::new();
+
+    let schema = match gql.introspect().await {
+        Ok((schema, (request, response))) => {
+            findings.push(
+                finding(
+                    endpoint,
+                    "GraphQL内省查询已开启",
+                    "生产环境开启内省查询会暴露完整的API模式，便于攻击者发现隐藏接口",
+                    "Low",
+                    format!("内省查询返回了 {} 个类型", schema.types.len()),
+                    "在生产环境关闭内省查询",
+                )
+                .with_exchange(&request, Some(&response), Some("__schema"), Some("__schema")),
+            );
+            Some(schema)
+        }
+        Err(e) => {
+            debug!("GraphQL内省失败 {}: {}", endpoint, e);
+            match gql.recover_by_suggestions().await {
+                Some((schema, (request, response))) => {
+                    let names: Vec<&str> = schema.types[0].fields.iter().map(|f| f.name.as_str()).collect();
+                    findings.push(
+                        finding(
+                            endpoint,
+                            "GraphQL字段建议已开启",
+                            "内省被禁用，但错误信息中的字段建议仍可用于还原API模式",
+                            "Info",
+                            format!("通过字段建议还原的根字段: {}", names.join(", ")),
+                            "关闭错误信息中的字段建议（Did you mean ...）",
+                        )
+                        .with_exchange(&request, Some(&response), None, Some("Did you mean")),
+                    );
+                    Some(schema)
+                }
+                None => None,
+            }
+        }
+    };
+
+    if let Some((request, response)) = gql.supports_batching().await {
+        findings.push(
+            finding(
+                endpoint,
+                "GraphQL支持批量查询",
+                "单个HTTP请求可以携带多个操作，攻击者可借此绕过基于请求次数的限速进行爆破",
+                "Low",
+                format!("包含 {} 个操作的JSON数组请求返回了 {} 个结果", BATCH_SIZE, BATCH_SIZE),
+                "关闭批量查询或按操作数量限速",
+            )
+            .with_exchange(&request, Some(&response), None, None),
+        );
+    }
+    if let Some((request, response)) = gql.supports_alias_batching().await {
+        let last_alias = format!("a{}", BATCH_SIZE * 10 - 1);
+        findings.push(
+            finding(
+                endpoint,
+                "GraphQL未限制别名数量",
+                "单个查询中可以使用大量别名重复调用同一字段，可用于爆破或放大资源消耗",
+                "Info",
+                format!("包含 {} 个别名的查询被正常执行", BATCH_SIZE * 10),
+                "限制单个查询中的别名数量和查询复杂度",
+            )
+            .with_exchange(&request, Some(&response), None, Some(&last_alias)),
+        );
+    }
+
+    if let Some(deep) = schema.as_ref().and_then(|s| s.build_deep_query(DEPTH_PROBE)) {
+        if let Some((request, response)) = gql.accepts_deep_query(&deep).await {
+            findings.push(
+                finding(
+                    endpoint,
+                    "GraphQL未限制查询深度",
+                    "服务端接受深层嵌套的循环查询，攻击者可以构造指数级开销的查询造成拒绝服务",
+                    "Medium",
+                    format!("嵌套 {} 层的查询被正常执行:\n{}", DEPTH_PROBE, deep.query),
+                    "限制查询深度和复杂度，并设置查询超时",
+                )
+                .with_exchange(&request, Some(&response), None, Some("\"data\"")),
+            );
+        }
+    }
+
+    let operations = schema
+        .as_ref()
+        .map(|s| s.generate_operations(include_mutations))
+        .unwrap_or_default();
+
+    GraphqlReport {
+        endpoint: endpoint.to_string(),
+        schema,
+        operations,
+        findings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_schema() -> GraphqlSchema {
+        let data = json!({
+            "__schema": {
+                "queryType": {"name": "Query"},
+                "mutationType": null,
+                "types": [
+                    {"kind": "OBJECT", "name": "Query", "fields": [
+                        {"name": "user", "args": [
+                            {"name": "id", "type": {"kind": "NON_NULL", "name": null, "ofType": {"kind": "SCALAR", "name": "ID", "ofType": null}}},
+                            {"name": "filter", "type": {"kind": "INPUT_OBJECT", "name": "UserFilter", "ofType": null}}
+                        ], "type": {"kind": "OBJECT", "name": "User", "ofType": null}}
+                    ]},
+                    {"kind": "OBJECT", "name": "User", "fields": [
+                        {"name": "id", "args": [], "type": {"kind": "SCALAR", "name": "ID", "ofType": null}},
+                        {"name": "name", "args": [], "type": {"kind": "SCALAR", "name": "String", "ofType": null}},
+                        {"name": "posts", "args": [], "type": {"kind": "LIST", "name": null, "ofType": {"kind": "OBJECT", "name": "Post", "ofType": null}}}
+                    ]},
+                    {"kind": "OBJECT", "name": "Post", "fields": [
+                        {"name": "author", "args": [], "type": {"kind": "OBJECT", "name": "User", "ofType": null}}
+                    ]},
+                    {"kind": "INPUT_OBJECT", "name": "UserFilter", "inputFields": [
+                        {"name": "role", "type": {"kind": "ENUM", "name": "Role", "ofType": null}}
+                    ]},
+                    {"kind": "ENUM", "name": "Role", "enumValues": [{"name": "ADMIN"}]},
+                    {"kind": "SCALAR", "name": "ID"},
+                    {"kind": "SCALAR", "name": "String"}
+                ]
+            }
+        });
+        GraphqlSchema::from_introspection(&data).unwrap()
+    }
+
+    #[test]
+    fn test_generate_operations() {
+        let operations = sample_schema().generate_operations(false);
+        assert_eq!(operations.len(), 1);
+        let op = &operations[0];
+        assert_eq!(
+            op.query,
+            "query RShield_user($id: ID!, $filter: UserFilter) { user(id: $id, filter: $filter) { id name } }"
+        );
+        assert_eq!(op.variables, json!({"id": "1", "filter": {"role": "ADMIN"}}));
+
+        let request = op.to_request("http://example.com/graphql?v=1", &HashMap::new());
+        let params = crate::handler::scan::utils::insertion::json_params(&request);
+        assert!(params.contains(&("variables.id".to_string(), "1".to_string())));
+        assert_eq!(request.params, vec![("v".to_string(), "1".to_string())]);
+    }
+
+    #[test]
+    fn test_build_deep_query() {
+        let operation = sample_schema().build_deep_query(3).unwrap();
+        assert_eq!(
+            operation.query,
+            "query RShield_depth($id: ID!, $filter: UserFilter) { user(id: $id, filter: $filter) { posts { author { posts { __typename } } } } }"
+        );
+    }
+
+    #[test]
+    fn test_parse_suggestions() {
+        let body = r#"{"errors":[{"message":"Cannot query field \"userx\" on type \"Query\". Did you mean \"user\" or \"users\"?"}]}"#;
+        assert_eq!(parse_suggestions(&error_messages(body)), vec!["user", "users"]);
+        assert!(looks_like_graphql(body));
+        assert!(!looks_like_graphql("<html></html>"));
+    }
+}
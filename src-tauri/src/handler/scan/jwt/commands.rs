@@ -0,0 +1,56 @@
+use super::{crack_secret, find_jwts, sign, JwtToken, DEFAULT_SECRETS};
+use serde_json::Value;
+
+/// 解码JWT
+#[tauri::command(rename_all = "snake_case")]
+pub async fn jwt_decode(token: String) -> Result<JwtToken, String> {
+    JwtToken::parse(&token).map_err(|e| e.to_string())
+}
+
+/// 从文本（例如重放器中的原始请求）提取JWT
+#[tauri::command(rename_all = "snake_case")]
+pub async fn jwt_find(text: String) -> Result<Vec<String>, String> {
+    Ok(find_jwts(&text))
+}
+
+/// 修改header/claims后重新签名，未传入的部分沿用原token
+///
+/// header中alg为none时生成无签名token；HS系列算法使用secret签名，
+/// 密钥混淆攻击时secret传入目标公钥的PEM文本即可
+#[tauri::command(rename_all = "snake_case")]
+pub async fn jwt_resign(
+    token: String,
+    header: Option<Value>,
+    claims: Option<Value>,
+    secret: Option<String>,
+) -> Result<String, String> {
+    let original = JwtToken::parse(&token).map_err(|e| e.to_string())?;
+    let header = header.unwrap_or(original.header);
+    let claims = claims.unwrap_or(original.claims);
+    sign(&header, &claims, secret.unwrap_or_default().as_bytes()).map_err(|e| e.to_string())
+}
+
+/// 爆破HMAC密钥，未指定字典时使用内置弱密钥
+#[tauri::command(rename_all = "snake_case")]
+pub async fn jwt_crack(token: String, wordlist_path: Option<String>) -> Result<Option<String>, String> {
+    let token = JwtToken::parse(&token).map_err(|e| e.to_string())?;
+    if !token.alg().to_uppercase().starts_with("HS") {
+        return Err(format!("算法 {} 不是HMAC，无法爆破", token.alg()));
+    }
+
+    let wordlist = match wordlist_path.filter(|p| !p.is_empty()) {
+        Some(path) => std::fs::read_to_string(&path).map_err(|e| format!("读取字典失败: {}", e))?,
+        None => String::new(),
+    };
+
+    // 字典较大时放到阻塞线程中执行
+    tokio::task::spawn_blocking(move || {
+        let candidates = DEFAULT_SECRETS
+            .iter()
+            .copied()
+            .chain(wordlist.lines().map(|l| l.trim_end_matches('\r')));
+        crack_secret(&token, candidates)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -1,10 +1,16 @@
 // pub mod Alltag; // Removed as it seems to be an unresolved module
 pub mod api; // This will be refactored/removed eventually
+pub mod api_import;
 pub mod ast;
 pub mod config;
+pub mod crawler;
 pub mod engine;
+pub mod graphql;
+pub mod jwt;
+pub mod oob;
 pub mod plugin;
 pub mod plugin_commands;
+pub mod profile;
 pub mod proxy;
 pub mod rules;
 pub mod scanners;
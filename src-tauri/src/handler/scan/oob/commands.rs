@@ -0,0 +1,51 @@
+use super::{store, OobInteraction, OobListener, OobToken};
+use crate::core::config::AppConfig;
+
+/// 获取全局监听器，未启动时按配置启动
+async fn listener() -> Result<std::sync::Arc<OobListener>, String> {
+    match OobListener::get() {
+        Some(listener) => Ok(listener),
+        None => OobListener::global(&AppConfig::default().oob)
+            .await
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// 申请OOB token
+#[tauri::command(rename_all = "snake_case")]
+pub async fn oob_generate_token(owner: Option<String>) -> Result<OobToken, String> {
+    let listener = listener().await?;
+    Ok(listener.new_token(owner.as_deref().unwrap_or("manual")))
+}
+
+/// 查询token的回连记录
+#[tauri::command(rename_all = "snake_case")]
+pub async fn oob_poll_interactions(token: String) -> Result<Vec<OobInteraction>, String> {
+    let listener = listener().await?;
+    let interactions = listener.poll(&token);
+    if !interactions.is_empty() {
+        return Ok(interactions);
+    }
+    // 内存中没有时查询历史记录（例如重启前的回连）
+    store::load_interactions(Some(&token), 100)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取最近的回连记录
+#[tauri::command(rename_all = "snake_case")]
+pub async fn oob_list_interactions(limit: Option<i64>) -> Result<Vec<OobInteraction>, String> {
+    store::load_interactions(None, limit.unwrap_or(200))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 清空回连记录
+#[tauri::command(rename_all = "snake_case")]
+pub async fn oob_clear_interactions() -> Result<String, String> {
+    store::clear_interactions().await.map_err(|e| e.to_string())?;
+    if let Some(listener) = OobListener::get() {
+        listener.interactions.lock().unwrap().clear();
+    }
+    Ok("success".to_string())
+}
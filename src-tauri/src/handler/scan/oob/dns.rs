@@ -0,0 +1,156 @@
+//! OOB DNS监听
+
+use super::{OobInteraction, OobListener};
+use chrono::Utc;
+use log::{debug, warn};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+
+/// DNS服务循环
+pub(super) async fn serve(listener: Arc<OobListener>, socket: UdpSocket) {
+    let mut buf = [0u8; 512];
+    loop {
+        let (n, addr) = match socket.recv_from(&mut buf).await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("OOB DNS接收失败: {}", e);
+                continue;
+            }
+        };
+
+        let query = match parse_dns_query(&buf[..n]) {
+            Some(q) => q,
+            None => continue,
+        };
+
+        if let Some(token) = listener.match_token(&query.name) {
+            listener.record(OobInteraction {
+                token,
+                protocol: "dns".to_string(),
+                remote_addr: addr.to_string(),
+                summary: format!("{} (type {})", query.name, query.qtype),
+                raw: query.name.clone(),
+                timestamp: Utc::now(),
+            });
+        }
+
+        let answer_ip = match listener.public_host().parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => ip,
+            _ => Ipv4Addr::LOCALHOST,
+        };
+        let response = build_dns_response(&buf[..n], &query, answer_ip);
+        if let Err(e) = socket.send_to(&response, addr).await {
+            debug!("OOB DNS响应发送失败: {}", e);
+        }
+    }
+}
+
+/// DNS查询
+#[derive(Debug, Clone)]
+struct DnsQuery {
+    /// 查询名（小写，不含末尾的点）
+    name: String,
+    /// 查询类型
+    qtype: u16,
+    /// 问题段结束位置
+    question_end: usize,
+}
+
+/// 解析DNS查询报文的第一个问题
+fn parse_dns_query(packet: &[u8]) -> Option<DnsQuery> {
+    if packet.len() < 12 {
+        return None;
+    }
+    let qdcount = u16::from_be_bytes([packet[4], packet[5]]);
+    if qdcount == 0 {
+        return None;
+    }
+
+    let mut pos = 12;
+    let mut labels = Vec::new();
+    loop {
+        let len = *packet.get(pos)? as usize;
+        pos += 1;
+        if len == 0 {
+            break;
+        }
+        // 问题段不应出现压缩指针
+        if len & 0xC0 != 0 {
+            return None;
+        }
+        let label = packet.get(pos..pos + len)?;
+        labels.push(String::from_utf8_lossy(label).to_lowercase());
+        pos += len;
+    }
+
+    let qtype = u16::from_be_bytes([*packet.get(pos)?, *packet.get(pos + 1)?]);
+    // 跳过qtype和qclass
+    let question_end = pos + 4;
+    if question_end > packet.len() {
+        return None;
+    }
+
+    Some(DnsQuery {
+        name: labels.join("."),
+        qtype,
+        question_end,
+    })
+}
+
+/// 构造DNS响应，A记录查询返回回连地址
+fn build_dns_response(packet: &[u8], query: &DnsQuery, ip: Ipv4Addr) -> Vec<u8> {
+    let answer_a = query.qtype == 1;
+    let mut response = Vec::with_capacity(query.question_end + 16);
+
+    // 头部：沿用ID，QR=1、AA=1，保留RD
+    response.extend_from_slice(&packet[0..2]);
+    response.push(0x84 | (packet[2] & 0x01));
+    response.push(0x00);
+    response.extend_from_slice(&[0x00, 0x01]);
+    response.extend_from_slice(&[0x00, if answer_a { 0x01 } else { 0x00 }]);
+    response.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+
+    // 问题段原样返回
+    response.extend_from_slice(&packet[12..query.question_end]);
+
+    if answer_a {
+        // 名称指针指向问题段，TTL为0避免缓存
+        response.extend_from_slice(&[0xC0, 0x0C, 0x00, 0x01, 0x00, 0x01]);
+        response.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        response.extend_from_slice(&[0x00, 0x04]);
+        response.extend_from_slice(&ip.octets());
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_query(name: &str, qtype: u16) -> Vec<u8> {
+        let mut packet = vec![0x12, 0x34, 0x01, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0];
+        for label in name.split('.') {
+            packet.push(label.len() as u8);
+            packet.extend_from_slice(label.as_bytes());
+        }
+        packet.push(0);
+        packet.extend_from_slice(&qtype.to_be_bytes());
+        packet.extend_from_slice(&[0x00, 0x01]);
+        packet
+    }
+
+    #[test]
+    fn test_parse_and_answer_dns_query() {
+        let packet = build_query("Rabc123.oob.rshield.local", 1);
+        let query = parse_dns_query(&packet).unwrap();
+        assert_eq!(query.name, "rabc123.oob.rshield.local");
+        assert_eq!(query.qtype, 1);
+
+        let response = build_dns_response(&packet, &query, Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(&response[0..2], &[0x12, 0x34]);
+        assert_eq!(response[7], 1);
+        assert_eq!(&response[response.len() - 4..], &[10, 0, 0, 1]);
+    }
+}
@@ -0,0 +1,168 @@
+//! OOB LDAP监听
+//!
+//! JNDI注入（Log4Shell等）会先发送bindRequest再发送searchRequest，
+//! 这里对bind返回成功、对search返回空结果，token位于search的baseObject中。
+
+use super::{OobInteraction, OobListener};
+use anyhow::Result;
+use chrono::Utc;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// LDAP协议操作类型
+const BIND_REQUEST: u8 = 0x60;
+const BIND_RESPONSE: u8 = 0x61;
+const SEARCH_REQUEST: u8 = 0x63;
+const SEARCH_RESULT_DONE: u8 = 0x65;
+
+/// 处理单个LDAP连接
+///
+/// 收到的数据中出现token时立即记录，之后的读写失败或超时不影响记录。
+pub(super) async fn handle(listener: &OobListener, mut stream: TcpStream, addr: SocketAddr) -> Result<()> {
+    let mut raw = Vec::new();
+    let mut buf = vec![0u8; 4096];
+    let mut recorded = false;
+
+    // bind + search，最多处理几轮消息
+    for _ in 0..4 {
+        let n = tokio::time::timeout(Duration::from_secs(10), stream.read(&mut buf)).await??;
+        if n == 0 {
+            break;
+        }
+        let packet = &buf[..n];
+        raw.extend_from_slice(packet);
+
+        if !recorded {
+            let text = printable(&raw);
+            if let Some(token) = listener.match_token(&text) {
+                recorded = true;
+                listener.record(OobInteraction {
+                    token,
+                    protocol: "ldap".to_string(),
+                    remote_addr: addr.to_string(),
+                    summary: "LDAP search".to_string(),
+                    raw: text,
+                    timestamp: Utc::now(),
+                });
+            }
+        }
+
+        let (message_id, op) = match parse_message_header(packet) {
+            Some(h) => h,
+            None => break,
+        };
+
+        match op {
+            BIND_REQUEST => stream.write_all(&build_result(message_id, BIND_RESPONSE)).await?,
+            SEARCH_REQUEST => {
+                stream.write_all(&build_result(message_id, SEARCH_RESULT_DONE)).await?;
+                break;
+            }
+            _ => break,
+        }
+    }
+
+    stream.shutdown().await.ok();
+    Ok(())
+}
+
+/// BER中的字符串是原样存放的，转为可打印文本后直接按文本匹配token
+fn printable(raw: &[u8]) -> String {
+    String::from_utf8_lossy(raw)
+        .chars()
+        .map(|c| if c.is_ascii_graphic() || c == ' ' { c } else { '.' })
+        .collect()
+}
+
+/// 读取BER长度字段，返回(长度, 长度字段占用的字节数)
+fn read_length(data: &[u8]) -> Option<(usize, usize)> {
+    let first = *data.first()?;
+    if first & 0x80 == 0 {
+        return Some((first as usize, 1));
+    }
+    let count = (first & 0x7F) as usize;
+    if count == 0 || count > 4 {
+        return None;
+    }
+    let mut len = 0usize;
+    for b in data.get(1..1 + count)? {
+        len = (len << 8) | *b as usize;
+    }
+    Some((len, 1 + count))
+}
+
+/// 解析LDAPMessage头部，返回(messageID, 操作类型)
+fn parse_message_header(packet: &[u8]) -> Option<(u32, u8)> {
+    if *packet.first()? != 0x30 {
+        return None;
+    }
+    let (_, len_size) = read_length(packet.get(1..)?)?;
+    let mut pos = 1 + len_size;
+
+    if *packet.get(pos)? != 0x02 {
+        return None;
+    }
+    let (id_len, id_len_size) = read_length(packet.get(pos + 1..)?)?;
+    pos += 1 + id_len_size;
+    if id_len == 0 || id_len > 4 {
+        return None;
+    }
+    let mut message_id = 0u32;
+    for b in packet.get(pos..pos + id_len)? {
+        message_id = (message_id << 8) | *b as u32;
+    }
+    pos += id_len;
+
+    Some((message_id, *packet.get(pos)?))
+}
+
+/// 构造resultCode为success的LDAPResult
+fn build_result(message_id: u32, op: u8) -> Vec<u8> {
+    let id_bytes: Vec<u8> = message_id
+        .to_be_bytes()
+        .iter()
+        .skip_while(|b| **b == 0)
+        .copied()
+        .collect();
+    let id_bytes = if id_bytes.is_empty() { vec![0] } else { id_bytes };
+
+    // resultCode(success) + matchedDN("") + diagnosticMessage("")
+    let result = [0x0A, 0x01, 0x00, 0x04, 0x00, 0x04, 0x00];
+
+    let mut body = vec![0x02, id_bytes.len() as u8];
+    body.extend_from_slice(&id_bytes);
+    body.push(op);
+    body.push(result.len() as u8);
+    body.extend_from_slice(&result);
+
+    let mut message = vec![0x30, body.len() as u8];
+    message.extend_from_slice(&body);
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bind_request_and_reply() {
+        // 匿名simple bind，messageID=1
+        let bind = [0x30, 0x0C, 0x02, 0x01, 0x01, 0x60, 0x07, 0x02, 0x01, 0x03, 0x04, 0x00, 0x80, 0x00];
+        assert_eq!(parse_message_header(&bind), Some((1, BIND_REQUEST)));
+
+        let reply = build_result(1, BIND_RESPONSE);
+        assert_eq!(
+            reply,
+            vec![0x30, 0x0C, 0x02, 0x01, 0x01, 0x61, 0x07, 0x0A, 0x01, 0x00, 0x04, 0x00, 0x04, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_long_form_length() {
+        assert_eq!(read_length(&[0x81, 0x90]), Some((0x90, 2)));
+        assert_eq!(read_length(&[0x82, 0x01, 0x00]), Some((0x100, 3)));
+        assert_eq!(read_length(&[0x85]), None);
+    }
+}
@@ -0,0 +1,440 @@
+//! 带外（OOB）回连服务
+//!
+//! 内置HTTP(S)、DNS、SMTP、LDAP监听，为盲打类检测分配唯一token，
+//! 目标回连时按token归档交互记录并写入SQLite，
+//! 扫描器、Rhai插件（`oob_token()`/`oob_poll(token)`）和前端通过token轮询结果。
+
+pub mod commands;
+mod dns;
+mod ldap;
+mod smtp;
+pub mod store;
+
+use crate::core::config::OobConfig;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::OnceCell;
+
+/// 全局OOB监听实例
+static OOB_LISTENER: OnceCell<Arc<OobListener>> = OnceCell::const_new();
+
+/// token有效期（秒）
+const TOKEN_TTL_SECS: i64 = 3600;
+
+/// 回连交互记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OobInteraction {
+    /// 命中的token
+    pub token: String,
+    /// 协议：http, https, dns, smtp, ldap
+    pub protocol: String,
+    /// 来源地址
+    pub remote_addr: String,
+    /// 交互摘要（HTTP请求行、DNS查询名等）
+    pub summary: String,
+    /// 原始数据
+    pub raw: String,
+    /// 时间
+    pub timestamp: DateTime<Utc>,
+}
+
+/// 已分配的token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OobToken {
+    /// token值（小写字母数字，可直接作为子域名）
+    pub token: String,
+    /// 申请方（扫描器名或插件名）
+    pub owner: String,
+    /// HTTP回连地址
+    pub http_url: String,
+    /// HTTPS回连地址
+    pub https_url: String,
+    /// DNS回连域名
+    pub dns_host: String,
+    /// LDAP回连地址（JNDI注入使用）
+    pub ldap_url: String,
+    /// SMTP回连邮箱
+    pub email: String,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+}
+
+/// OOB监听器
+pub struct OobListener {
+    config: OobConfig,
+    tokens: Mutex<HashMap<String, OobToken>>,
+    interactions: Mutex<HashMap<String, Vec<OobInteraction>>>,
+}
+
+impl OobListener {
+    /// 创建监听器（不启动监听）
+    pub fn new(config: OobConfig) -> Self {
+        Self {
+            config,
+            tokens: Mutex::new(HashMap::new()),
+            interactions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 获取全局监听器，首次调用时启动各协议监听
+    pub async fn global(config: &OobConfig) -> Result<Arc<OobListener>> {
+        if !config.enabled {
+            return Err(anyhow!("OOB监听未启用"));
+        }
+
+        OOB_LISTENER
+            .get_or_try_init(|| async {
+                let listener = Arc::new(OobListener::new(config.clone()));
+                listener.clone().start().await?;
+                Ok::<_, anyhow::Error>(listener)
+            })
+            .await
+            .cloned()
+    }
+
+    /// 获取供扫描器注入载荷使用的全局监听器
+    ///
+    /// 回连地址无法被目标访问时（未配置public_host且监听回环或任意地址）返回错误，
+    /// 扫描器据此跳过OOB载荷，避免注入必然收不到回连的地址。
+    pub async fn for_scan(config: &OobConfig) -> Result<Arc<OobListener>> {
+        let listener = Self::global(config).await?;
+        if !listener.is_routable() {
+            return Err(anyhow!(
+                "回连地址 {} 无法被目标访问，请配置oob.public_host",
+                listener.public_host()
+            ));
+        }
+        Ok(listener)
+    }
+
+    /// 获取已启动的全局监听器
+    pub fn get() -> Option<Arc<OobListener>> {
+        OOB_LISTENER.get().cloned()
+    }
+
+    /// 启动监听，HTTP为必需服务，其余协议绑定失败时仅告警（低端口通常需要root权限）
+    async fn start(self: Arc<Self>) -> Result<()> {
+        let http_listener = TcpListener::bind((self.config.bind_host.as_str(), self.config.http_port)).await?;
+        info!("OOB HTTP监听已启动: {}:{}", self.config.bind_host, self.config.http_port);
+        self.spawn_accept_loop("HTTP", http_listener, |listener, stream, addr| async move {
+            listener.handle_http(stream, addr, "http").await
+        });
+
+        if let Some(tcp) = self.bind_optional("HTTPS", self.config.https_port).await {
+            match self.build_tls_acceptor() {
+                Ok(acceptor) => {
+                    let acceptor = Arc::new(acceptor);
+                    self.spawn_accept_loop("HTTPS", tcp, move |listener, stream, addr| {
+                        let acceptor = acceptor.clone();
+                        async move {
+                            let tls_stream = acceptor.accept(stream).await?;
+                            listener.handle_http(tls_stream, addr, "https").await
+                        }
+                    });
+                }
+                Err(e) => warn!("OOB HTTPS证书生成失败: {}", e),
+            }
+        }
+
+        if let Some(tcp) = self.bind_optional("SMTP", self.config.smtp_port).await {
+            self.spawn_accept_loop("SMTP", tcp, |listener, stream, addr| async move {
+                smtp::handle(&listener, stream, addr).await
+            });
+        }
+
+        if let Some(tcp) = self.bind_optional("LDAP", self.config.ldap_port).await {
+            self.spawn_accept_loop("LDAP", tcp, |listener, stream, addr| async move {
+                ldap::handle(&listener, stream, addr).await
+            });
+        }
+
+        if self.config.dns_port != 0 {
+            match UdpSocket::bind((self.config.bind_host.as_str(), self.config.dns_port)).await {
+                Ok(socket) => {
+                    info!("OOB DNS监听已启动: {}:{}", self.config.bind_host, self.config.dns_port);
+                    tokio::spawn(dns::serve(self.clone(), socket));
+                }
+                Err(e) => warn!("OOB DNS监听启动失败 {}:{}: {}", self.config.bind_host, self.config.dns_port, e),
+            }
+        }
+
+        // 恢复未过期的token，重启后仍能归档之前注入的回连
+        match store::load_tokens_since(Utc::now() - ChronoDuration::seconds(TOKEN_TTL_SECS)).await {
+            Ok(saved) => {
+                let mut tokens = self.tokens.lock().unwrap();
+                for (token, owner, created_at) in saved {
+                    let oob_token = self.build_token(token, owner, created_at);
+                    tokens.insert(oob_token.token.clone(), oob_token);
+                }
+            }
+            Err(e) => debug!("加载OOB token失败: {}", e),
+        }
+
+        Ok(())
+    }
+
+    /// 绑定可选的TCP服务，端口为0表示不启用
+    async fn bind_optional(&self, name: &str, port: u16) -> Option<TcpListener> {
+        if port == 0 {
+            return None;
+        }
+        match TcpListener::bind((self.config.bind_host.as_str(), port)).await {
+            Ok(tcp) => {
+                info!("OOB {}监听已启动: {}:{}", name, self.config.bind_host, port);
+                Some(tcp)
+            }
+            Err(e) => {
+                warn!("OOB {}监听启动失败 {}:{}: {}", name, self.config.bind_host, port, e);
+                None
+            }
+        }
+    }
+
+    /// 启动TCP接收循环，每个连接单独处理
+    fn spawn_accept_loop<F, Fut>(self: &Arc<Self>, name: &'static str, tcp: TcpListener, handler: F)
+    where
+        F: Fn(Arc<OobListener>, TcpStream, SocketAddr) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let listener = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match tcp.accept().await {
+                    Ok((stream, addr)) => {
+                        let fut = handler(listener.clone(), stream, addr);
+                        tokio::spawn(async move {
+                            if let Err(e) = fut.await {
+                                debug!("处理OOB {}连接失败: {}", name, e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("OOB {}监听接受连接失败: {}", name, e),
+                }
+            }
+        });
+    }
+
+    /// 生成HTTPS使用的自签名证书
+    fn build_tls_acceptor(&self) -> Result<tokio_native_tls::TlsAcceptor> {
+        let names = vec![
+            self.config.domain.clone(),
+            format!("*.{}", self.config.domain),
+            self.public_host(),
+        ];
+        let cert = rcgen::generate_simple_self_signed(names)?;
+        let identity = native_tls::Identity::from_pkcs8(
+            cert.serialize_pem()?.as_bytes(),
+            cert.serialize_private_key_pem().as_bytes(),
+        )?;
+        Ok(tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity)?))
+    }
+
+    /// 载荷中使用的回连主机
+    fn public_host(&self) -> String {
+        self.config
+            .public_host
+            .clone()
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| self.config.bind_host.clone())
+    }
+
+    /// 回连地址是否可能被目标访问，回环地址、任意地址和localhost不可访问
+    pub fn is_routable(&self) -> bool {
+        let host = self.public_host();
+        match host.trim_matches(|c| c == '[' || c == ']').parse::<std::net::IpAddr>() {
+            Ok(ip) => !ip.is_loopback() && !ip.is_unspecified(),
+            Err(_) => !host.eq_ignore_ascii_case("localhost"),
+        }
+    }
+
+    /// 根据token生成各协议的回连地址
+    fn build_token(&self, token: String, owner: String, created_at: DateTime<Utc>) -> OobToken {
+        let host = self.public_host();
+        OobToken {
+            http_url: format!("http://{}:{}/{}", host, self.config.http_port, token),
+            https_url: format!("https://{}:{}/{}", host, self.config.https_port, token),
+            dns_host: format!("{}.{}", token, self.config.domain),
+            ldap_url: format!("ldap://{}:{}/{}", host, self.config.ldap_port, token),
+            email: format!("{}@{}", token, self.config.domain),
+            token,
+            owner,
+            created_at,
+        }
+    }
+
+    /// 申请新的token
+    pub fn new_token(&self, owner: &str) -> OobToken {
+        let token = format!("r{}", &uuid::Uuid::new_v4().simple().to_string()[..15]);
+        let oob_token = self.build_token(token.clone(), owner.to_string(), Utc::now());
+
+        {
+            let mut tokens = self.tokens.lock().unwrap();
+            // 顺便清理过期token及其回连记录
+            let now = Utc::now();
+            tokens.retain(|_, t| (now - t.created_at).num_seconds() < TOKEN_TTL_SECS);
+            self.interactions.lock().unwrap().retain(|token, _| tokens.contains_key(token));
+            tokens.insert(token, oob_token.clone());
+        }
+
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let saved = oob_token.clone();
+            handle.spawn(async move {
+                if let Err(e) = store::save_token(&saved).await {
+                    debug!("保存OOB token失败: {}", e);
+                }
+            });
+        }
+
+        oob_token
+    }
+
+    /// 查询token的回连记录
+    pub fn poll(&self, token: &str) -> Vec<OobInteraction> {
+        self.interactions
+            .lock()
+            .unwrap()
+            .get(token)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// 等待指定时间后查询回连记录，期间一旦命中立即返回
+    pub async fn wait_for(&self, token: &str, timeout: Duration) -> Vec<OobInteraction> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let interactions = self.poll(token);
+            if !interactions.is_empty() || tokio::time::Instant::now() >= deadline {
+                return interactions;
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    /// 配置的回连等待时间
+    pub fn wait_duration(&self) -> Duration {
+        Duration::from_millis(self.config.wait_ms)
+    }
+
+    /// 在数据中查找已分配的token
+    fn match_token(&self, haystack: &str) -> Option<String> {
+        let haystack = haystack.to_lowercase();
+        self.tokens
+            .lock()
+            .unwrap()
+            .keys()
+            .find(|token| haystack.contains(token.as_str()))
+            .cloned()
+    }
+
+    /// 记录交互并异步写入数据库
+    fn record(&self, interaction: OobInteraction) {
+        info!(
+            "收到OOB回连: {} {} 来自 {}",
+            interaction.protocol, interaction.summary, interaction.remote_addr
+        );
+        self.interactions
+            .lock()
+            .unwrap()
+            .entry(interaction.token.clone())
+            .or_default()
+            .push(interaction.clone());
+
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                if let Err(e) = store::save_interaction(&interaction).await {
+                    debug!("保存OOB交互记录失败: {}", e);
+                }
+            });
+        }
+    }
+
+    /// 处理HTTP(S)回连
+    async fn handle_http<S>(&self, mut stream: S, addr: SocketAddr, protocol: &str) -> Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut buf = vec![0u8; 8192];
+        let n = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf)).await??;
+        let raw = String::from_utf8_lossy(&buf[..n]).to_string();
+
+        let summary = raw.lines().next().unwrap_or("").to_string();
+        if let Some(token) = self.match_token(&raw) {
+            self.record(OobInteraction {
+                token,
+                protocol: protocol.to_string(),
+                remote_addr: addr.to_string(),
+                summary,
+                raw,
+                timestamp: Utc::now(),
+            });
+        }
+
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+            .await?;
+        stream.shutdown().await.ok();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_matching() {
+        let listener = OobListener::new(OobConfig::default());
+        let token = listener.new_token("test");
+        assert!(token.dns_host.starts_with(&token.token));
+        assert!(token.http_url.ends_with(&token.token));
+        assert!(token.ldap_url.starts_with("ldap://"));
+
+        let request = format!("GET /{} HTTP/1.1\r\nHost: x\r\n\r\n", token.token.to_uppercase());
+        assert_eq!(listener.match_token(&request), Some(token.token.clone()));
+        assert_eq!(listener.match_token("GET / HTTP/1.1"), None);
+    }
+
+    #[test]
+    fn test_record_and_poll() {
+        let listener = OobListener::new(OobConfig::default());
+        let token = listener.new_token("test");
+        listener.record(OobInteraction {
+            token: token.token.clone(),
+            protocol: "dns".to_string(),
+            remote_addr: "127.0.0.1:53".to_string(),
+            summary: token.dns_host.clone(),
+            raw: token.dns_host.clone(),
+            timestamp: Utc::now(),
+        });
+        assert_eq!(listener.poll(&token.token).len(), 1);
+        assert!(listener.poll("unknown").is_empty());
+
+        // token过期后，申请新token时清理其回连记录
+        listener.tokens.lock().unwrap().get_mut(&token.token).unwrap().created_at =
+            Utc::now() - ChronoDuration::seconds(TOKEN_TTL_SECS + 1);
+        listener.new_token("test");
+        assert!(listener.poll(&token.token).is_empty());
+    }
+
+    #[test]
+    fn test_routable_public_host() {
+        assert!(!OobListener::new(OobConfig::default()).is_routable());
+
+        let mut config = OobConfig::default();
+        config.bind_host = "0.0.0.0".to_string();
+        assert!(!OobListener::new(config.clone()).is_routable());
+        config.public_host = Some("oob.example.com".to_string());
+        assert!(OobListener::new(config.clone()).is_routable());
+        config.public_host = Some("203.0.113.10".to_string());
+        assert!(OobListener::new(config).is_routable());
+    }
+}
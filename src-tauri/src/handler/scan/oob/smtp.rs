@@ -0,0 +1,92 @@
+//! OOB SMTP监听
+//!
+//! 只实现收信所需的最小会话，用于确认邮件类注入（如邮件头注入、
+//! 以邮件地址形式触发的回连）。
+
+use super::{OobInteraction, OobListener};
+use anyhow::Result;
+use chrono::Utc;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// 单次会话最多记录的字节数
+const MAX_TRANSCRIPT: usize = 64 * 1024;
+
+/// 单行最大长度，超出部分按下一行处理
+const MAX_LINE: u64 = 4096;
+
+/// 单次会话最多读取的字节数
+const MAX_SESSION: usize = 1024 * 1024;
+
+/// 处理单个SMTP会话
+///
+/// 会话内容中出现token时立即记录，之后的读写失败或超时不影响记录。
+pub(super) async fn handle(listener: &OobListener, stream: TcpStream, addr: SocketAddr) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut transcript = String::new();
+    let mut received = 0;
+    let mut recorded = false;
+    let mut in_data = false;
+
+    writer.write_all(b"220 rshield ESMTP ready\r\n").await?;
+
+    loop {
+        let mut line = String::new();
+        let n = tokio::time::timeout(Duration::from_secs(30), (&mut reader).take(MAX_LINE).read_line(&mut line)).await??;
+        if n == 0 {
+            break;
+        }
+        received += n;
+        if transcript.len() < MAX_TRANSCRIPT {
+            transcript.push_str(&line);
+        }
+        if !recorded {
+            if let Some(token) = listener.match_token(&line) {
+                recorded = true;
+                let summary = transcript
+                    .lines()
+                    .find(|l| l.to_uppercase().starts_with("RCPT TO"))
+                    .unwrap_or(line.trim_end())
+                    .to_string();
+                listener.record(OobInteraction {
+                    token,
+                    protocol: "smtp".to_string(),
+                    remote_addr: addr.to_string(),
+                    summary,
+                    raw: transcript.clone(),
+                    timestamp: Utc::now(),
+                });
+            }
+        }
+        if received > MAX_SESSION {
+            break;
+        }
+
+        if in_data {
+            if line == ".\r\n" || line == ".\n" {
+                in_data = false;
+                writer.write_all(b"250 OK: queued\r\n").await?;
+            }
+            continue;
+        }
+
+        let command = line.trim_end().to_uppercase();
+        let reply: &[u8] = if command.starts_with("EHLO") || command.starts_with("HELO") {
+            b"250 rshield\r\n"
+        } else if command.starts_with("DATA") {
+            in_data = true;
+            b"354 End data with <CR><LF>.<CR><LF>\r\n"
+        } else if command.starts_with("QUIT") {
+            writer.write_all(b"221 Bye\r\n").await?;
+            break;
+        } else {
+            b"250 OK\r\n"
+        };
+        writer.write_all(reply).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! OOB token与交互记录的SQLite持久化
+
+use super::{OobInteraction, OobToken};
+use crate::asm::asm_task::INNERASK_MODULE;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use sqlx::{query, Row};
+use std::sync::Arc;
+
+/// 时间戳转换为DateTime，非法值按当前时间处理
+fn to_datetime(ts: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(ts, 0).single().unwrap_or_else(Utc::now)
+}
+
+/// 保存token，回连地址由监听配置生成，无需保存
+pub(super) async fn save_token(token: &OobToken) -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    query("INSERT INTO oob_token (token, owner, create_at) VALUES (?, ?, ?) ON CONFLICT DO NOTHING")
+        .bind(&token.token)
+        .bind(&token.owner)
+        .bind(token.created_at.timestamp())
+        .execute(&*pool)
+        .await?;
+
+    Ok(())
+}
+
+/// 加载指定时间之后创建的token，返回(token, 申请方, 创建时间)
+pub(super) async fn load_tokens_since(since: DateTime<Utc>) -> Result<Vec<(String, String, DateTime<Utc>)>> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.read_conn);
+
+    let rows = query("SELECT token, owner, create_at FROM oob_token WHERE create_at >= ?")
+        .bind(since.timestamp())
+        .fetch_all(&*pool)
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("token"), row.get("owner"), to_datetime(row.get("create_at"))))
+        .collect())
+}
+
+/// 保存交互记录
+pub(super) async fn save_interaction(interaction: &OobInteraction) -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    query(
+        "INSERT INTO oob_interaction (token, protocol, remote_addr, summary, raw, create_at) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&interaction.token)
+    .bind(&interaction.protocol)
+    .bind(&interaction.remote_addr)
+    .bind(&interaction.summary)
+    .bind(&interaction.raw)
+    .bind(interaction.timestamp.timestamp())
+    .execute(&*pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 查询交互记录，token为空时返回最近的记录
+pub async fn load_interactions(token: Option<&str>, limit: i64) -> Result<Vec<OobInteraction>> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.read_conn);
+
+    let rows = match token {
+        Some(token) => {
+            query("SELECT token, protocol, remote_addr, summary, raw, create_at FROM oob_interaction WHERE token = ? ORDER BY id DESC LIMIT ?")
+                .bind(token)
+                .bind(limit)
+                .fetch_all(&*pool)
+                .await?
+        }
+        None => {
+            query("SELECT token, protocol, remote_addr, summary, raw, create_at FROM oob_interaction ORDER BY id DESC LIMIT ?")
+                .bind(limit)
+                .fetch_all(&*pool)
+                .await?
+        }
+    };
+
+    Ok(rows
+        .iter()
+        .map(|row| OobInteraction {
+            token: row.get("token"),
+            protocol: row.get("protocol"),
+            remote_addr: row.get("remote_addr"),
+            summary: row.get("summary"),
+            raw: row.get("raw"),
+            timestamp: to_datetime(row.get("create_at")),
+        })
+        .collect())
+}
+
+/// 清空交互记录
+pub async fn clear_interactions() -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    query("DELETE FROM oob_interaction").execute(&*pool).await?;
+    Ok(())
+}
@@ -1,8 +1,10 @@
 // src-tauri/src/handler/scan/passive/handler.rs
 use tauri::{State, Emitter};
-use crate::handler::scan::common::types::{PassiveScanConfig, SuccessResponse, Vulnerability, VulnerabilityDetail};
+use crate::handler::scan::common::types::{PassiveScanConfig, SuccessResponse};
+use crate::handler::scan::profile;
+use crate::handler::scan::results::store;
 use crate::state::ScannerState;
-use crate::core::config::{AppConfig, ProxyConfig};
+use crate::core::config::ProxyConfig;
 use crate::internal::certificate::CertificateAuthority;
 use crate::scan::engine::manager::ScanManager;
 use crate::scan::proxy::Proxy;
@@ -37,7 +39,10 @@ pub async fn handle_start_passive_scan(
     let (result_tx, mut result_rx) = mpsc::channel(100); // mut result_rx will be used later
     let (request_count_tx, mut request_count_rx) = mpsc::channel(100); // mut request_count_rx will be used later
 
-    let app_config = Arc::new(AppConfig::default());
+    let (app_config, _) = profile::load_config(config.profile.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    let app_config = Arc::new(app_config);
 
     let cert_manager = if config.intercept_tls {
         let app_dir = std::env::current_dir()
@@ -112,49 +117,36 @@ pub async fn handle_start_passive_scan(
                 }
             });
 
-            let vulnerabilities_arc = state.vulnerabilities.clone();
             let status_arc_clone_res = state.status.clone();
             let running_arc_clone = state.running.clone();
             let window_clone = state.window.clone();
 
             tokio::spawn(async move {
                 while let Some(result) = result_rx.recv().await { // result_rx was defined earlier
-                    let new_vulnerability_id = vulnerabilities_arc.lock().await.len() as u32;
-                    let vulnerability = Vulnerability {
-                        id: new_vulnerability_id,
-                        vulnerability_type: result.vulnerability_type,
-                        name: result.name,
-                        url: result.url,
-                        parameter: result.parameter.clone(),
-                        value: result.value.clone(),
-                        evidence: result.evidence.clone(),
-                        risk_level: result.risk_level,
-                        timestamp: result.timestamp.to_utc().to_string(),
-                        description: result.description,
-                        solution: result.remediation.unwrap_or_else(|| "No solution provided".to_string()),
-                        details: result.details.map(|details_note| VulnerabilityDetail {
-                            note: details_note,
-                            request: result.request_details.unwrap_or_else(|| "No request captured".to_string()),
-                            response: result.response_details.unwrap_or_else(|| "No response captured".to_string()),
-                        }),
+                    // 重复发现的漏洞只累加次数，已修复的漏洞再次出现时重新通知前端
+                    let vulnerability = match store::save_vulnerability(result).await {
+                        Ok(saved) if saved.should_notify() => saved.vulnerability,
+                        Ok(_) => continue,
+                        Err(e) => {
+                            error!("保存漏洞失败: {}", e);
+                            continue;
+                        }
                     };
+                    let count = store::count_vulnerabilities().await.unwrap_or_default();
 
                     {
-                        let mut vulns_lock = vulnerabilities_arc.lock().await;
-                        vulns_lock.push(vulnerability.clone());
-
                         let mut status_lock = status_arc_clone_res.lock().await;
-                        status_lock.vulnerability_count = vulns_lock.len();
+                        status_lock.vulnerability_count = count;
                         status_lock.last_update = Some(Utc::now().to_rfc3339());
                         
-                        info!("发现漏洞: {} ({}) - 当前列表中共有 {} 个漏洞", 
+                        info!("发现漏洞: {} ({}) - 当前共有 {} 个漏洞", 
                               vulnerability.name, 
                               vulnerability.risk_level, 
-                              vulns_lock.len());
+                              count);
                     }
 
                     if let Err(emit_err) = window_clone.emit("vulnerability_found", serde_json::json!({
-                        "count": status_arc_clone_res.lock().await.vulnerability_count,
+                        "count": count,
                         "latest": vulnerability
                     })) {
                         error!("Failed to emit vulnerability_found event: {}", emit_err);
@@ -1,18 +1,37 @@
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use anyhow::Result;
 use tokio::sync::Mutex;
 use serde_json::Value;
-use rhai::{Engine, AST, Scope};
+use rhai::{Dynamic, Engine, AST, Scope};
 use log::{info, error};
 
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
 use crate::internal::plugin_export_func::set_plugin_export_func;
 
+/// 保存禁用插件列表的文件，位于插件目录下
+const DISABLED_FILE: &str = "disabled.json";
+
+thread_local! {
+    /// 当前线程上插件执行的截止时间，由引擎的进度回调检查
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// 当前线程上插件执行的截止时间，插件调用的阻塞式请求据此缩短等待时间
+pub(crate) fn deadline() -> Option<Instant> {
+    DEADLINE.with(|d| d.get())
+}
+
 /// 扫描插件管理器
 pub struct ScanPluginManager {
     plugin_dir: PathBuf,
     plugins: Arc<Mutex<std::collections::HashMap<String, ScanPlugin>>>,
     engine: Arc<Engine>,
+    /// 被禁用的插件ID
+    disabled: HashSet<String>,
 }
 
 /// 插件元数据
@@ -20,6 +39,7 @@ pub struct ScanPluginManager {
 pub struct PluginManifest {
     pub name: String,
     pub author: String,
+    #[serde(default, alias = "type", alias = "rtype")]
     pub type_: String,
     pub version: String,
     pub description: String,
@@ -54,6 +74,52 @@ pub struct PluginContext {
     pub target: String,
     #[serde(default)]
     pub params: serde_json::Map<String, Value>,
+    /// 扫描流程中被处理的请求，手动执行时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<PluginHttpRequest>,
+    /// 请求对应的响应
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<PluginHttpResponse>,
+}
+
+/// 传给插件的HTTP请求，请求体按UTF-8解码
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PluginHttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+    pub params: Vec<(String, String)>,
+}
+
+/// 传给插件的HTTP响应
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PluginHttpResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl From<&HttpRequest> for PluginHttpRequest {
+    fn from(request: &HttpRequest) -> Self {
+        Self {
+            method: request.method.clone(),
+            url: request.url.clone(),
+            headers: request.headers.clone(),
+            body: String::from_utf8_lossy(&request.body).to_string(),
+            params: request.params.clone(),
+        }
+    }
+}
+
+impl From<&HttpResponse> for PluginHttpResponse {
+    fn from(response: &HttpResponse) -> Self {
+        Self {
+            status: response.status,
+            headers: response.headers.clone(),
+            body: String::from_utf8_lossy(&response.body).to_string(),
+        }
+    }
 }
 
 /// 插件执行结果
@@ -78,7 +144,7 @@ impl ScanPlugin {
 
     /// 创建新插件并解析元数据
     pub fn new_with_manifest(script: String, ast: AST, path: PathBuf, engine: &Engine) -> Self {
-        let plugin = Self {
+        let mut plugin = Self {
             script,
             ast: Arc::new(ast),
             path,
@@ -86,7 +152,7 @@ impl ScanPlugin {
         };
         
         // 尝试解析元数据
-        plugin.parse_manifest(engine);
+        plugin.manifest = plugin.parse_manifest(engine);
         plugin
     }
 
@@ -96,17 +162,11 @@ impl ScanPlugin {
         let mut scope = Scope::new();
         
         // 尝试调用get_manifest函数
-        match engine.call_fn::<Value>(&mut scope, &self.ast, "get_manifest", ()) {
+        match engine.call_fn::<Dynamic>(&mut scope, &self.ast, "get_manifest", ()) {
             Ok(manifest_value) => {
-                // 尝试将返回值转换为PluginManifest
-                match serde_json::from_value::<PluginManifest>(manifest_value) {
-                    Ok(mut manifest) => {
-                        // 修复type字段 (JSON中无法使用type作为字段名，转换时使用type_)
-                        manifest.type_ = manifest.type_.clone();
-                        
-                        // 设置manifest并返回
-                        Some(manifest)
-                    },
+                // 尝试将返回值转换为PluginManifest，type字段由别名映射到type_
+                match rhai::serde::from_dynamic::<PluginManifest>(&manifest_value) {
+                    Ok(manifest) => Some(manifest),
                     Err(e) => {
                         error!("解析插件元数据失败: {}", e);
                         None
@@ -136,6 +196,43 @@ impl ScanPlugin {
     }
 }
 
+/// 执行插件的analyze函数，timeout为空时不限制执行时间
+pub async fn run_analyze(
+    engine: Arc<Engine>,
+    plugin: &ScanPlugin,
+    context: &PluginContext,
+    timeout: Option<Duration>,
+) -> Result<PluginResult> {
+    // 转换请求为JSON字符串
+    let request_json = serde_json::to_string(context)?;
+    let ast = plugin.ast.clone();
+    let deadline = timeout.map(|t| Instant::now() + t);
+
+    // 使用spawn_blocking执行Rhai引擎的阻塞操作
+    let task = tokio::task::spawn_blocking(move || {
+        DEADLINE.with(|d| d.set(deadline));
+        // 创建新的作用域
+        let mut scope = Scope::new();
+
+        // 执行插件的analyze函数
+        let result = engine.call_fn::<String>(&mut scope, &ast, "analyze", (request_json,));
+        DEADLINE.with(|d| d.set(None));
+        result
+    });
+
+    // 脚本阻塞在网络请求等外部调用时进度回调不会触发，这里同时限制等待时间
+    let result = match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, task)
+            .await
+            .map_err(|_| anyhow::anyhow!("插件执行超时"))???,
+        None => task.await??,
+    };
+
+    // 解析执行结果
+    let result: PluginResult = serde_json::from_str(&result)?;
+    Ok(result)
+}
+
 impl ScanPluginManager {
     /// 创建新的插件管理器
     pub fn new(plugin_dir: PathBuf) -> Result<Self> {
@@ -157,15 +254,60 @@ impl ScanPluginManager {
         engine.set_optimization_level(rhai::OptimizationLevel::Full);
         engine.set_strict_variables(false);  // Less strict variable access
 
-     
-        
+        // 超过截止时间时终止脚本，防止插件死循环拖住扫描
+        engine.on_progress(|_| {
+            DEADLINE.with(|deadline| match deadline.get() {
+                Some(deadline) if Instant::now() >= deadline => Some("timeout".into()),
+                _ => None,
+            })
+        });
+
+        let disabled = std::fs::read_to_string(plugin_dir.join(DISABLED_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
         Ok(Self {
             plugin_dir,
             plugins: Arc::new(Mutex::new(std::collections::HashMap::new())),
             engine: Arc::new(engine),
+            disabled,
         })
     }
 
+    /// 获取Rhai引擎
+    pub fn engine(&self) -> Arc<Engine> {
+        self.engine.clone()
+    }
+
+    /// 插件是否启用
+    pub fn is_enabled(&self, plugin_id: &str) -> bool {
+        !self.disabled.contains(plugin_id)
+    }
+
+    /// 启用或禁用插件，状态保存到插件目录
+    pub fn set_plugin_enabled(&mut self, plugin_id: &str, enabled: bool) -> Result<()> {
+        if enabled {
+            self.disabled.remove(plugin_id);
+        } else {
+            self.disabled.insert(plugin_id.to_string());
+        }
+
+        let mut disabled: Vec<&String> = self.disabled.iter().collect();
+        disabled.sort();
+        std::fs::write(self.plugin_dir.join(DISABLED_FILE), serde_json::to_string_pretty(&disabled)?)?;
+        Ok(())
+    }
+
+    /// 获取所有已启用的插件
+    pub async fn enabled_plugins(&self) -> Vec<(String, ScanPlugin)> {
+        let plugins = self.plugins.lock().await;
+        plugins.iter()
+            .filter(|(id, _)| self.is_enabled(id))
+            .map(|(id, plugin)| (id.clone(), plugin.clone()))
+            .collect()
+    }
+
     /// 获取插件目录
     pub fn plugin_dir(&self) -> &PathBuf {
         &self.plugin_dir
@@ -262,25 +404,7 @@ impl ScanPluginManager {
             anyhow::anyhow!("Plugin not found: {}", plugin_id)
         })?;
 
-        // 转换请求为JSON字符串
-        let request_json = serde_json::to_string(&context)?;
-
-        // Clone what we need for the blocking task
-        let engine = self.engine.clone();
-        let ast = plugin.ast.clone();
-
-        // 使用spawn_blocking执行Rhai引擎的阻塞操作
-        let result = tokio::task::spawn_blocking(move || {
-            // 创建新的作用域
-            let mut scope = Scope::new();
-            
-            // 执行插件的analyze函数
-            engine.call_fn::<String>(&mut scope, &ast, "analyze", (request_json,))
-        }).await??;
-
-        // 解析执行结果
-        let result: PluginResult = serde_json::from_str(&result)?;
-        Ok(result)
+        run_analyze(self.engine.clone(), plugin, &context, None).await
     }
 
     /// 添加插件
@@ -2,10 +2,18 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use anyhow::Result;
 use tauri::State;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, OnceCell};
 use serde_json::Value;
 use crate::handler::scan::plugin::{ScanPluginManager, PluginContext, PluginResult};
 
+/// 供扫描管理器使用的全局插件管理器，与前端命令共享同一实例
+static SCAN_PLUGIN_MANAGER: OnceCell<Arc<Mutex<ScanPluginManager>>> = OnceCell::const_new();
+
+/// 获取全局扫描插件管理器，未初始化时返回None
+pub fn global_scan_plugin_manager() -> Option<Arc<Mutex<ScanPluginManager>>> {
+    SCAN_PLUGIN_MANAGER.get().cloned()
+}
+
 /// 共享状态
 pub struct ScanPluginManagerState {
     pub inner: Arc<Mutex<ScanPluginManager>>,
@@ -23,7 +31,9 @@ impl ScanPluginManagerState {
 pub async fn init_scan_plugin_manager(plugin_dir: PathBuf) -> Result<ScanPluginManagerState> {
     let manager = ScanPluginManager::new(plugin_dir)?;
     manager.load_all_plugins().await?;
-    Ok(ScanPluginManagerState::new(manager))
+    let state = ScanPluginManagerState::new(manager);
+    let _ = SCAN_PLUGIN_MANAGER.set(state.inner.clone());
+    Ok(state)
 }
 
 /// 列出所有扫描插件
@@ -47,6 +57,7 @@ pub async fn list_scan_plugins(state: State<'_, ScanPluginManagerState>) -> Resu
                 "references": manifest.references,
                 "parameters": manifest.parameters,
                 "script": plugin.script(),
+                "enabled": manager.is_enabled(&id),
             })
         } else {
             // 否则使用基本信息
@@ -58,6 +69,7 @@ pub async fn list_scan_plugins(state: State<'_, ScanPluginManagerState>) -> Resu
                 "version": "1.0.0",
                 "description": "No description available",
                 "script": plugin.script(),
+                "enabled": manager.is_enabled(&id),
             })
         }
     }).collect();
@@ -285,7 +297,7 @@ pub async fn update_scan_plugin(
     script: String,
     state: State<'_, ScanPluginManagerState>
 ) -> Result<Value, String> {
-    let manager_state = state.inner.lock().await;
+    let mut manager_state = state.inner.lock().await;
     
     // 先验证脚本
     match manager_state.validate_plugin(&script).await {
@@ -319,6 +331,8 @@ pub async fn update_scan_plugin(
     // 重新加载插件
     let updated_plugin = manager_state.load_plugin(&plugin_path).await
         .map_err(|e| format!("Failed to reload plugin: {}", e))?;
+    manager_state.add_plugin(updated_plugin.clone()).await
+        .map_err(|e| format!("Failed to add plugin: {}", e))?;
     
     // 返回更新后的插件信息，包含元数据
     if let Some(manifest) = updated_plugin.manifest() {
@@ -360,8 +374,28 @@ pub async fn execute_scan_plugin(
     let context = PluginContext {
         target,
         params: params.unwrap_or_default(),
+        request: None,
+        response: None,
     };
     
     manager.execute_plugin(&plugin_id, context).await
         .map_err(|e| format!("Failed to execute plugin: {}", e))
-} 
\ No newline at end of file
+} 
+
+/// 启用或禁用扫描插件，禁用的插件不会在扫描流程中执行
+#[tauri::command]
+pub async fn set_scan_plugin_enabled(
+    plugin_id: String,
+    enabled: bool,
+    state: State<'_, ScanPluginManagerState>
+) -> Result<bool, String> {
+    let mut manager = state.inner.lock().await;
+
+    manager.get_plugin(&plugin_id).await
+        .ok_or_else(|| format!("Plugin not found: {}", plugin_id))?;
+
+    manager.set_plugin_enabled(&plugin_id, enabled)
+        .map_err(|e| format!("Failed to update plugin state: {}", e))?;
+
+    Ok(enabled)
+}
@@ -0,0 +1,63 @@
+// src-tauri/src/handler/scan/profile/handler.rs
+use crate::core::config::AppConfig;
+use crate::handler::scan::common::types::SuccessResponse;
+use log::info;
+use super::{store, ScanProfile};
+
+pub async fn handle_list_scan_profiles() -> Result<Vec<ScanProfile>, String> {
+    store::list().await.map_err(|e| format!("获取扫描模板失败: {}", e))
+}
+
+/// 以配置文件为基础的新模板
+pub async fn handle_get_default_scan_profile() -> Result<ScanProfile, String> {
+    Ok(ScanProfile::from_config("", &AppConfig::default()))
+}
+
+pub async fn handle_save_scan_profile(profile: ScanProfile) -> Result<SuccessResponse, String> {
+    store::save(&profile).await.map_err(|e| format!("保存扫描模板失败: {}", e))?;
+    info!("已保存扫描模板: {}", profile.name);
+    Ok(SuccessResponse {
+        success: true,
+        message: format!("扫描模板 {} 已保存", profile.name),
+    })
+}
+
+pub async fn handle_delete_scan_profile(name: String) -> Result<SuccessResponse, String> {
+    store::delete(&name).await.map_err(|e| format!("删除扫描模板失败: {}", e))?;
+    info!("已删除扫描模板: {}", name);
+    Ok(SuccessResponse {
+        success: true,
+        message: format!("扫描模板 {} 已删除", name),
+    })
+}
+
+/// 导出模板为YAML，指定路径时同时写入文件
+pub async fn handle_export_scan_profile(name: String, path: Option<String>) -> Result<String, String> {
+    let profile = store::get(&name)
+        .await
+        .map_err(|e| format!("获取扫描模板失败: {}", e))?
+        .ok_or_else(|| format!("扫描模板不存在: {}", name))?;
+    let content = profile.to_yaml().map_err(|e| format!("导出扫描模板失败: {}", e))?;
+
+    if let Some(path) = path.filter(|p| !p.is_empty()) {
+        std::fs::write(&path, &content).map_err(|e| format!("写入文件失败: {}", e))?;
+        info!("扫描模板 {} 已导出到 {}", name, path);
+    }
+    Ok(content)
+}
+
+/// 从YAML导入模板，同名模板被覆盖
+pub async fn handle_import_scan_profile(content: Option<String>, path: Option<String>) -> Result<ScanProfile, String> {
+    let content = match (content, path) {
+        (Some(content), _) if !content.trim().is_empty() => content,
+        (_, Some(path)) if !path.is_empty() => {
+            std::fs::read_to_string(&path).map_err(|e| format!("读取扫描模板失败: {}", e))?
+        }
+        _ => return Err("未指定扫描模板内容或文件".to_string()),
+    };
+
+    let profile = ScanProfile::from_yaml(&content).map_err(|e| e.to_string())?;
+    store::save(&profile).await.map_err(|e| format!("保存扫描模板失败: {}", e))?;
+    info!("已导入扫描模板: {}", profile.name);
+    Ok(profile)
+}
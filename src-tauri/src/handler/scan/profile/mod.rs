@@ -0,0 +1,172 @@
+//! 扫描模板
+//!
+//! 模板把启用的检查项、SQL注入/RCE检测级别、插入点类型、自定义payload、限速和超时
+//! 保存为一个命名配置，启动扫描时按名称选择，叠加在配置文件之上生效。
+//! 模板可以导出为YAML文件在团队内共享。
+
+pub mod handler;
+pub mod store;
+
+use crate::core::config::{AppConfig, InsertionPointType, RateLimitConfig, VulnerabilitiesConfig};
+use crate::core::governor::Governor;
+use crate::handler::scan::common::types::DetailedScanOptions;
+use anyhow::{anyhow, bail, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+const LEVELS: [&str; 3] = ["low", "medium", "high"];
+
+/// 扫描模板
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProfile {
+    /// 模板名称，唯一
+    pub name: String,
+    /// 说明
+    #[serde(default)]
+    pub description: String,
+    /// 启用的检查项，包含SQL注入/RCE的检测级别和自定义payload
+    pub checks: VulnerabilitiesConfig,
+    /// 测试的插入点类型
+    #[serde(default = "InsertionPointType::all")]
+    pub insertion_points: Vec<InsertionPointType>,
+    /// 出站请求限速配置
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    /// 单个请求的超时时间（毫秒）
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// 并发扫描任务数
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// 目标扫描的任务选项，未设置时按扫描类型选择
+    #[serde(default)]
+    pub scan_options: Option<DetailedScanOptions>,
+}
+
+fn default_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_concurrency() -> usize {
+    10
+}
+
+impl ScanProfile {
+    /// 以当前配置为基础创建模板
+    pub fn from_config(name: &str, config: &AppConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            description: String::new(),
+            checks: config.rules.vulnerabilities.clone(),
+            insertion_points: config.scanner.insertion_points.clone(),
+            rate_limit: config.rate_limit.clone(),
+            timeout_ms: config.scanner.timeout_ms,
+            concurrency: config.scanner.concurrency,
+            scan_options: None,
+        }
+    }
+
+    /// 将模板叠加到配置上
+    pub fn apply(&self, config: &mut AppConfig) {
+        config.rules.vulnerabilities = self.checks.clone();
+        config.scanner.insertion_points = self.insertion_points.clone();
+        config.scanner.timeout_ms = self.timeout_ms;
+        config.scanner.concurrency = self.concurrency;
+        config.rate_limit = self.rate_limit.clone();
+    }
+
+    /// 检查模板内容是否有效
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("模板名称不能为空");
+        }
+        for (check, level) in [
+            ("sql_injection", &self.checks.sql_injection.level),
+            ("rce", &self.checks.rce.level),
+        ] {
+            if !LEVELS.contains(&level.as_str()) {
+                bail!("{} 的检测级别无效: {}", check, level);
+            }
+        }
+        if self.insertion_points.is_empty() {
+            bail!("至少需要选择一种插入点类型");
+        }
+        if self.concurrency == 0 || self.timeout_ms == 0 {
+            bail!("并发数和超时时间必须大于0");
+        }
+        Ok(())
+    }
+
+    /// 导出为YAML
+    pub fn to_yaml(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// 从YAML导入
+    pub fn from_yaml(content: &str) -> Result<Self> {
+        let profile: ScanProfile = serde_yaml::from_str(content).map_err(|e| anyhow!("解析扫描模板失败: {}", e))?;
+        profile.validate()?;
+        Ok(profile)
+    }
+}
+
+/// 加载扫描使用的配置
+///
+/// 未指定模板时使用配置文件，指定模板时叠加模板内容。
+/// 出站请求调度器是全局的，限速配置随最近启动的扫描切换。
+pub async fn load_config(name: Option<&str>) -> Result<(AppConfig, Option<ScanProfile>)> {
+    let mut config = AppConfig::default();
+    let profile = match name.filter(|n| !n.is_empty()) {
+        Some(name) => {
+            let profile = store::get(name).await?.ok_or_else(|| anyhow!("扫描模板不存在: {}", name))?;
+            profile.apply(&mut config);
+            info!("使用扫描模板: {}", profile.name);
+            Some(profile)
+        }
+        None => None,
+    };
+
+    let governor = Governor::global();
+    if governor.config() != config.rate_limit {
+        governor.update_config(config.rate_limit.clone());
+    }
+    Ok((config, profile))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_roundtrip_and_apply() {
+        let mut profile = ScanProfile::from_config("api-quick", &AppConfig::default());
+        profile.checks.sql_injection.level = "low".to_string();
+        profile.checks.rce.payloads.insert("cmd".to_string(), vec![";id".to_string()]);
+        profile.insertion_points = vec![InsertionPointType::Json];
+        profile.rate_limit.requests_per_second = 2.0;
+        profile.timeout_ms = 3000;
+
+        let imported = ScanProfile::from_yaml(&profile.to_yaml().unwrap()).unwrap();
+        assert_eq!(imported.name, "api-quick");
+        assert_eq!(imported.checks.rce.payloads["cmd"], vec![";id".to_string()]);
+
+        let mut config = AppConfig::default();
+        imported.apply(&mut config);
+        assert_eq!(config.rules.vulnerabilities.sql_injection.level, "low");
+        assert!(config.scanner.allows(InsertionPointType::Json));
+        assert!(!config.scanner.allows(InsertionPointType::Query));
+        assert_eq!(config.scanner.timeout_ms, 3000);
+        assert_eq!(config.rate_limit.requests_per_second, 2.0);
+    }
+
+    #[test]
+    fn test_invalid_profile() {
+        let mut profile = ScanProfile::from_config("bad", &AppConfig::default());
+        profile.checks.sql_injection.level = "extreme".to_string();
+        assert!(ScanProfile::from_yaml(&profile.to_yaml().unwrap()).is_err());
+
+        profile.checks.sql_injection.level = "high".to_string();
+        profile.name = " ".to_string();
+        assert!(profile.validate().is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! 扫描模板的SQLite持久化，模板内容以JSON保存
+
+use super::ScanProfile;
+use crate::asm::asm_task::INNERASK_MODULE;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use sqlx::{query, Row};
+use std::sync::Arc;
+
+/// 查询全部模板，按名称排序
+pub async fn list() -> Result<Vec<ScanProfile>> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.read_conn);
+
+    let rows = query("SELECT content FROM scan_profile ORDER BY name")
+        .fetch_all(&*pool)
+        .await?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| serde_json::from_str(row.get::<&str, _>("content")).ok())
+        .collect())
+}
+
+/// 按名称查询模板
+pub async fn get(name: &str) -> Result<Option<ScanProfile>> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.read_conn);
+
+    let row = query("SELECT content FROM scan_profile WHERE name = ?")
+        .bind(name)
+        .fetch_optional(&*pool)
+        .await?;
+    match row {
+        Some(row) => Ok(Some(serde_json::from_str(row.get::<&str, _>("content"))?)),
+        None => Ok(None),
+    }
+}
+
+/// 保存模板，同名模板被覆盖
+pub async fn save(profile: &ScanProfile) -> Result<()> {
+    profile.validate()?;
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    let now = Utc::now().timestamp();
+    query(
+        r#"
+        INSERT INTO scan_profile (name, description, content, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(name) DO UPDATE SET
+            description = excluded.description,
+            content = excluded.content,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&profile.name)
+    .bind(&profile.description)
+    .bind(serde_json::to_string(profile)?)
+    .bind(now)
+    .bind(now)
+    .execute(&*pool)
+    .await?;
+    Ok(())
+}
+
+/// 删除模板
+pub async fn delete(name: &str) -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    query("DELETE FROM scan_profile WHERE name = ?").bind(name).execute(&*pool).await?;
+    Ok(())
+}
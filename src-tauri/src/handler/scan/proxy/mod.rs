@@ -195,7 +195,16 @@ impl HttpHandler for InterceptHandler {
                         None
                     }
                 })
-                .collect(),
+                .fold(HashMap::new(), |mut map: HashMap<String, String>, (name, value)| {
+                    // 同名头部（如多个Set-Cookie）按行合并，避免被覆盖
+                    map.entry(name)
+                        .and_modify(|v| {
+                            v.push('\n');
+                            v.push_str(&value);
+                        })
+                        .or_insert(value);
+                    map
+                }),
             body: bytes.to_vec(),
         };
         
@@ -707,7 +716,16 @@ impl HttpHandler for InterceptHandlerWithCounter {
                         None
                     }
                 })
-                .collect(),
+                .fold(HashMap::new(), |mut map: HashMap<String, String>, (name, value)| {
+                    // 同名头部（如多个Set-Cookie）按行合并，避免被覆盖
+                    map.entry(name)
+                        .and_modify(|v| {
+                            v.push('\n');
+                            v.push_str(&value);
+                        })
+                        .or_insert(value);
+                    map
+                }),
             body: bytes.to_vec(),
         };
         
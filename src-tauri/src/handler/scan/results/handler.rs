@@ -4,8 +4,10 @@ use std::io::Write;
 use tauri::State;
 use chrono::Local;
 use log::{info, error};
-use crate::handler::scan::common::types::{SuccessResponse, Vulnerability};
+use crate::handler::scan::common::types::{SuccessResponse, TriageStatus, Vulnerability};
 use crate::state::ScannerState;
+use super::report::{self, ReportBranding, ReportFormat, ReportOptions};
+use super::store;
 
 pub async fn handle_get_scan_vulnerabilities(
     state: State<'_, ScannerState>,
@@ -13,20 +15,32 @@ pub async fn handle_get_scan_vulnerabilities(
     println!("Results handler: handle_get_scan_vulnerabilities called");
     
     // 获取当前漏洞列表
-    let vulnerabilities = state.vulnerabilities.lock().await.clone();
+    let vulnerabilities = store::load_vulnerabilities().await.map_err(|e| e.to_string())?;
+    state.update_status(|status| {
+        status.vulnerability_count = vulnerabilities.len();
+    }).await;
     Ok(vulnerabilities)
 }
 
+pub async fn handle_update_scan_vulnerability_status(
+    ids: Vec<u32>,
+    status: TriageStatus,
+) -> Result<SuccessResponse, String> {
+    store::update_status(&ids, status).await.map_err(|e| e.to_string())?;
+
+    Ok(SuccessResponse {
+        success: true,
+        message: format!("已更新{}条漏洞的状态", ids.len()),
+    })
+}
+
 pub async fn handle_clear_scan_vulnerabilities(
     state: State<'_, ScannerState>,
 ) -> Result<SuccessResponse, String> {
     println!("Results handler: handle_clear_scan_vulnerabilities called");
     
     // 清空漏洞列表
-    {
-        let mut vulnerabilities = state.vulnerabilities.lock().await;
-        vulnerabilities.clear();
-    }
+    store::clear_vulnerabilities().await.map_err(|e| e.to_string())?;
     
     // 更新状态
     state.update_status(|status| {
@@ -40,12 +54,11 @@ pub async fn handle_clear_scan_vulnerabilities(
 }
 
 pub async fn handle_export_scan_vulnerabilities(
-    state: State<'_, ScannerState>,
     path: String,
 ) -> Result<SuccessResponse, String> {
     println!("Results handler: handle_export_scan_vulnerabilities called");
     
-    let vulnerabilities = state.vulnerabilities.lock().await.clone();
+    let vulnerabilities = store::load_vulnerabilities().await.map_err(|e| e.to_string())?;
     
     if vulnerabilities.is_empty() {
         return Ok(SuccessResponse {
@@ -54,11 +67,16 @@ pub async fn handle_export_scan_vulnerabilities(
         });
     }
     
-    // 导出为JSON文件
-    let json = match serde_json::to_string_pretty(&vulnerabilities) {
-        Ok(json) => json,
-        Err(e) => return Err(format!("序列化漏洞数据失败: {}", e)),
+    // 导出为JSON文件，与报告导出使用同一生成逻辑
+    let options = ReportOptions {
+        format: ReportFormat::Json,
+        path: path.clone(),
+        min_severity: None,
+        statuses: Vec::new(),
+        template: None,
+        branding: ReportBranding::default(),
     };
+    let json = report::generate(&vulnerabilities, &options).map_err(|e| format!("序列化漏洞数据失败: {}", e))?;
     
     // 创建并写入文件
     let file_path = if path.ends_with(".json") {
@@ -84,4 +102,37 @@ pub async fn handle_export_scan_vulnerabilities(
             Err(format!("创建导出文件失败: {}", e))
         }
     }
-} 
\ No newline at end of file
+}
+
+/// 按格式生成漏洞报告，支持按风险等级和研判状态筛选
+pub async fn handle_export_scan_report(options: ReportOptions) -> Result<SuccessResponse, String> {
+    if options.path.trim().is_empty() {
+        return Err("未指定导出路径".to_string());
+    }
+    let vulnerabilities = store::load_vulnerabilities().await.map_err(|e| e.to_string())?;
+    let vulnerabilities = report::filter(vulnerabilities, &options);
+    if vulnerabilities.is_empty() {
+        return Ok(SuccessResponse {
+            success: false,
+            message: "没有符合条件的漏洞".to_string(),
+        });
+    }
+
+    let content = report::generate(&vulnerabilities, &options).map_err(|e| format!("生成报告失败: {}", e))?;
+    let extension = options.format.extension();
+    let file_path = if std::path::Path::new(&options.path).is_dir() {
+        format!("{}/scan_report_{}.{}", options.path.trim_end_matches(['/', '\\']), Local::now().format("%Y%m%d_%H%M%S"), extension)
+    } else {
+        options.path.clone()
+    };
+    std::fs::write(&file_path, content).map_err(|e| {
+        error!("写入报告失败: {}", e);
+        format!("写入报告失败: {}", e)
+    })?;
+
+    info!("漏洞报告已导出至: {}", file_path);
+    Ok(SuccessResponse {
+        success: true,
+        message: format!("已导出{}条漏洞至{}", vulnerabilities.len(), file_path),
+    })
+}
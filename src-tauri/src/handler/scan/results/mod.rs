@@ -1,2 +1,4 @@
 pub mod handler;
+pub mod report;
+pub mod store;
 // pub mod processor; // Add if/when created 
\ No newline at end of file
@@ -0,0 +1,465 @@
+//! 漏洞报告生成
+//!
+//! 支持SARIF 2.1.0（供CI平台导入）、自包含的HTML报告和Markdown报告。
+//! HTML和Markdown报告通过minijinja模板渲染，可以指定自定义模板文件并设置标题、公司名称、
+//! logo等品牌信息，内置模板见`templates`目录。
+
+use crate::handler::scan::common::types::{TriageStatus, Vulnerability};
+use crate::handler::scan::engine::ByteRange;
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+const HTML_TEMPLATE: &str = include_str!("templates/report.html");
+const MARKDOWN_TEMPLATE: &str = include_str!("templates/report.md");
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+/// 报告中每个请求/响应保留的最大字节数
+const MAX_MESSAGE_BYTES: usize = 64 * 1024;
+
+/// 报告格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Json,
+    Sarif,
+    Html,
+    Markdown,
+}
+
+impl ReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Sarif => "sarif",
+            ReportFormat::Html => "html",
+            ReportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// 报告品牌信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportBranding {
+    /// 报告标题
+    pub title: String,
+    /// 客户或公司名称
+    pub company: String,
+    /// logo地址，HTML报告中可以使用data URI保持自包含
+    pub logo: String,
+    /// 主题色
+    pub primary_color: String,
+    /// 页脚文字
+    pub footer: String,
+}
+
+impl Default for ReportBranding {
+    fn default() -> Self {
+        Self {
+            title: "RShield 漏洞扫描报告".to_string(),
+            company: String::new(),
+            logo: String::new(),
+            primary_color: "#165dff".to_string(),
+            footer: "Generated by RShield".to_string(),
+        }
+    }
+}
+
+/// 报告导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportOptions {
+    pub format: ReportFormat,
+    /// 输出文件或目录，目录时自动生成文件名
+    pub path: String,
+    /// 最低风险等级，低于该等级的漏洞不写入报告
+    #[serde(default)]
+    pub min_severity: Option<String>,
+    /// 包含的研判状态，为空时包含除误报外的全部漏洞
+    #[serde(default)]
+    pub statuses: Vec<TriageStatus>,
+    /// 自定义模板文件，仅HTML和Markdown报告使用
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub branding: ReportBranding,
+}
+
+/// 风险等级排序，数值越大越严重
+pub fn severity_rank(level: &str) -> u8 {
+    match level.trim().to_lowercase().as_str() {
+        "critical" | "严重" => 4,
+        "high" | "高危" | "高" => 3,
+        "medium" | "中危" | "中" => 2,
+        "low" | "低危" | "低" => 1,
+        _ => 0,
+    }
+}
+
+fn severity_name(rank: u8) -> &'static str {
+    match rank {
+        4 => "critical",
+        3 => "high",
+        2 => "medium",
+        1 => "low",
+        _ => "info",
+    }
+}
+
+/// 按风险等级和研判状态筛选漏洞，按风险等级从高到低排序
+pub fn filter(vulnerabilities: Vec<Vulnerability>, options: &ReportOptions) -> Vec<Vulnerability> {
+    let min_rank = options.min_severity.as_deref().map(severity_rank).unwrap_or(0);
+    let mut selected: Vec<Vulnerability> = vulnerabilities
+        .into_iter()
+        .filter(|v| severity_rank(&v.risk_level) >= min_rank)
+        .filter(|v| {
+            if options.statuses.is_empty() {
+                v.status != TriageStatus::FalsePositive
+            } else {
+                options.statuses.contains(&v.status)
+            }
+        })
+        .collect();
+    selected.sort_by(|a, b| severity_rank(&b.risk_level).cmp(&severity_rank(&a.risk_level)).then(a.id.cmp(&b.id)));
+    selected
+}
+
+/// 报文片段，mark为true的片段需要高亮
+#[derive(Debug, Serialize)]
+struct Segment {
+    text: String,
+    mark: bool,
+}
+
+/// 按字节区间切分报文，超出长度的部分截断
+fn segments(message: &str, ranges: &[ByteRange]) -> Vec<Segment> {
+    let mut end_limit = message.len().min(MAX_MESSAGE_BYTES);
+    while !message.is_char_boundary(end_limit) {
+        end_limit -= 1;
+    }
+    let mut ranges: Vec<ByteRange> = ranges
+        .iter()
+        .filter(|r| r.start < r.end && r.end <= end_limit)
+        .filter(|r| message.is_char_boundary(r.start) && message.is_char_boundary(r.end))
+        .copied()
+        .collect();
+    ranges.sort_by_key(|r| r.start);
+
+    let mut out = Vec::new();
+    let mut pos = 0;
+    for range in ranges {
+        if range.start < pos {
+            continue;
+        }
+        if range.start > pos {
+            out.push(Segment { text: message[pos..range.start].to_string(), mark: false });
+        }
+        out.push(Segment { text: message[range.start..range.end].to_string(), mark: true });
+        pos = range.end;
+    }
+    if pos < end_limit {
+        out.push(Segment { text: message[pos..end_limit].to_string(), mark: false });
+    }
+    if end_limit < message.len() {
+        out.push(Segment { text: format!("\n... 已截断 {} 字节", message.len() - end_limit), mark: false });
+    }
+    out
+}
+
+/// Markdown代码块围栏，比内容中最长的连续反引号多一个，至少三个
+fn code_fence<'a>(texts: impl IntoIterator<Item = &'a str>) -> String {
+    let mut longest = 0;
+    for text in texts {
+        let mut run = 0;
+        for c in text.chars() {
+            run = if c == '`' { run + 1 } else { 0 };
+            longest = longest.max(run);
+        }
+    }
+    "`".repeat((longest + 1).max(3))
+}
+
+/// 模板中的漏洞
+#[derive(Debug, Serialize)]
+struct Finding<'a> {
+    #[serde(flatten)]
+    vulnerability: &'a Vulnerability,
+    severity: &'static str,
+    confidence_name: &'static str,
+    status_name: &'static str,
+    request: Option<Vec<Segment>>,
+    response: Option<Vec<Segment>>,
+    /// 证据、请求和响应代码块使用的围栏
+    fence: String,
+}
+
+/// 使用模板渲染HTML或Markdown报告
+pub fn render_template(vulnerabilities: &[Vulnerability], options: &ReportOptions) -> Result<String> {
+    let (name, builtin) = match options.format {
+        ReportFormat::Html => ("report.html", HTML_TEMPLATE),
+        ReportFormat::Markdown => ("report.md", MARKDOWN_TEMPLATE),
+        _ => return Err(anyhow!("{:?} 格式不使用模板", options.format)),
+    };
+    let source = match options.template.as_deref().filter(|t| !t.is_empty()) {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| anyhow!("读取报告模板失败 {}: {}", path, e))?,
+        None => builtin.to_string(),
+    };
+
+    let findings: Vec<Finding> = vulnerabilities
+        .iter()
+        .map(|v| {
+            let request = v.details.as_ref().map(|d| segments(&d.request, &d.payload_ranges));
+            let response = v.details.as_ref().map(|d| segments(&d.response, &d.evidence_ranges));
+            let fence = code_fence(
+                v.evidence
+                    .as_deref()
+                    .into_iter()
+                    .chain(request.iter().chain(response.iter()).flatten().map(|s| s.text.as_str())),
+            );
+            Finding {
+                vulnerability: v,
+                severity: severity_name(severity_rank(&v.risk_level)),
+                confidence_name: v.confidence.as_str(),
+                status_name: v.status.as_str(),
+                request,
+                response,
+                fence,
+            }
+        })
+        .collect();
+    let mut summary: BTreeMap<u8, usize> = BTreeMap::new();
+    for finding in &findings {
+        *summary.entry(severity_rank(&finding.vulnerability.risk_level)).or_default() += 1;
+    }
+    let summary: Vec<_> = (0..=4u8)
+        .rev()
+        .map(|rank| json!({ "severity": severity_name(rank), "count": summary.get(&rank).copied().unwrap_or(0) }))
+        .collect();
+
+    // 按模板名称的扩展名决定是否转义，report.html中的变量全部做HTML转义
+    let env = Environment::new();
+    let template = env
+        .template_from_named_str(name, &source)
+        .map_err(|e| anyhow!("解析报告模板失败: {}", e))?;
+    template
+        .render(json!({
+            "branding": options.branding,
+            "generated_at": Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            "total": findings.len(),
+            "summary": summary,
+            "findings": findings,
+        }))
+        .map_err(|e| anyhow!("渲染报告失败: {}", e))
+}
+
+fn sarif_level(rank: u8) -> &'static str {
+    match rank {
+        3 | 4 => "error",
+        2 => "warning",
+        _ => "note",
+    }
+}
+
+/// GitHub等平台按security-severity对安全类结果分级
+fn security_severity(rank: u8) -> &'static str {
+    match rank {
+        4 => "9.5",
+        3 => "8.0",
+        2 => "5.5",
+        1 => "3.0",
+        _ => "0.0",
+    }
+}
+
+/// SARIF规则标识，同一漏洞类型下可能有多个检查项，按类型和名称区分
+fn rule_id(v: &Vulnerability) -> String {
+    format!("{}/{}", v.vulnerability_type, v.name)
+}
+
+/// 生成SARIF 2.1.0报告，每个检查项（漏洞类型和名称）对应一条规则
+pub fn to_sarif(vulnerabilities: &[Vulnerability]) -> serde_json::Value {
+    let mut rule_index: BTreeMap<String, usize> = BTreeMap::new();
+    let mut rules = Vec::new();
+    for v in vulnerabilities {
+        let id = rule_id(v);
+        if rule_index.contains_key(&id) {
+            continue;
+        }
+        let rank = severity_rank(&v.risk_level);
+        rule_index.insert(id.clone(), rules.len());
+        rules.push(json!({
+            "id": id,
+            "name": v.name,
+            "shortDescription": { "text": v.name },
+            "fullDescription": { "text": v.description },
+            "help": { "text": v.solution },
+            "defaultConfiguration": { "level": sarif_level(rank) },
+            "properties": {
+                "tags": ["security"],
+                "security-severity": security_severity(rank),
+            },
+        }));
+    }
+
+    let results: Vec<_> = vulnerabilities
+        .iter()
+        .map(|v| {
+            let rank = severity_rank(&v.risk_level);
+            let mut message = v.name.clone();
+            if let Some(parameter) = v.parameter.as_deref().filter(|p| !p.is_empty()) {
+                message.push_str(&format!("（参数: {}）", parameter));
+            }
+            if let Some(evidence) = v.evidence.as_deref().filter(|e| !e.is_empty()) {
+                message.push_str(&format!("\n证据: {}", evidence));
+            }
+            let id = rule_id(v);
+            json!({
+                "ruleIndex": rule_index[&id],
+                "ruleId": id,
+                "level": sarif_level(rank),
+                "message": { "text": message },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": v.url } }
+                }],
+                "partialFingerprints": {
+                    "rshieldFinding/v1": format!("{}|{}|{}", v.vulnerability_type, v.url, v.parameter.as_deref().unwrap_or_default()),
+                },
+                "properties": {
+                    "severity": severity_name(rank),
+                    "confidence": v.confidence.as_str(),
+                    "status": v.status.as_str(),
+                    "occurrences": v.occurrences,
+                    "firstSeen": v.timestamp,
+                    "lastSeen": v.last_seen,
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "RShield",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/o0x1024/rshiled",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// 按格式生成报告内容
+pub fn generate(vulnerabilities: &[Vulnerability], options: &ReportOptions) -> Result<String> {
+    match options.format {
+        ReportFormat::Json => Ok(serde_json::to_string_pretty(vulnerabilities)?),
+        ReportFormat::Sarif => Ok(serde_json::to_string_pretty(&to_sarif(vulnerabilities))?),
+        ReportFormat::Html | ReportFormat::Markdown => render_template(vulnerabilities, options),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::scan::common::types::VulnerabilityDetail;
+    use crate::handler::scan::engine::Confidence;
+
+    fn vulnerability(id: u32, kind: &str, level: &str, status: TriageStatus) -> Vulnerability {
+        Vulnerability {
+            id,
+            vulnerability_type: kind.to_string(),
+            name: format!("{} finding", kind),
+            url: "http://example.com/search?q=1".to_string(),
+            risk_level: level.to_string(),
+            timestamp: "2024-01-01 00:00:00 UTC".to_string(),
+            description: "desc".to_string(),
+            solution: "fix".to_string(),
+            parameter: Some("q".to_string()),
+            value: None,
+            evidence: Some("<script>".to_string()),
+            details: Some(VulnerabilityDetail {
+                note: String::new(),
+                request: "GET /search?q=<script> HTTP/1.1".to_string(),
+                response: "HTTP/1.1 200 OK\r\n\r\n<b><script></b>".to_string(),
+                payload_ranges: vec![ByteRange { start: 14, end: 22 }],
+                evidence_ranges: vec![ByteRange { start: 22, end: 30 }],
+            }),
+            confidence: Confidence::Firm,
+            status,
+            occurrences: 1,
+            last_seen: String::new(),
+        }
+    }
+
+    fn options(format: ReportFormat) -> ReportOptions {
+        ReportOptions {
+            format,
+            path: String::new(),
+            min_severity: None,
+            statuses: Vec::new(),
+            template: None,
+            branding: ReportBranding::default(),
+        }
+    }
+
+    #[test]
+    fn test_filter_and_sarif() {
+        let all = vec![
+            vulnerability(1, "xss", "Medium", TriageStatus::New),
+            vulnerability(2, "sqli", "High", TriageStatus::Confirmed),
+            vulnerability(3, "xss", "Low", TriageStatus::New),
+            vulnerability(4, "cors", "High", TriageStatus::FalsePositive),
+        ];
+        let mut opts = options(ReportFormat::Sarif);
+        opts.min_severity = Some("medium".to_string());
+        let selected = filter(all, &opts);
+        assert_eq!(selected.iter().map(|v| v.id).collect::<Vec<_>>(), vec![2, 1]);
+
+        let sarif = to_sarif(&selected);
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 2);
+        assert_eq!(run["results"][0]["ruleId"], "sqli/sqli finding");
+        assert_eq!(run["results"][0]["level"], "error");
+        assert_eq!(run["results"][1]["ruleIndex"], 1);
+        assert_eq!(run["results"][1]["level"], "warning");
+
+        // 同一类型下名称不同的检查项使用各自的规则
+        let mut other = vulnerability(5, "xss", "Medium", TriageStatus::New);
+        other.name = "dom xss finding".to_string();
+        let sarif = to_sarif(&[selected[1].clone(), other]);
+        let run = &sarif["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(run["results"][1]["ruleIndex"], 1);
+        assert_eq!(rules[1]["shortDescription"]["text"], "dom xss finding");
+    }
+
+    #[test]
+    fn test_html_escapes_and_highlights() {
+        let findings = vec![vulnerability(1, "xss", "High", TriageStatus::New)];
+        let mut opts = options(ReportFormat::Html);
+        opts.branding.company = "ACME <Corp>".to_string();
+        let html = render_template(&findings, &opts).unwrap();
+        assert!(html.contains("ACME &lt;Corp&gt;"));
+        assert!(html.contains("<mark>&lt;script&gt;</mark>"));
+        assert!(!html.contains("<b><script></b>"));
+
+        let markdown = render_template(&findings, &options(ReportFormat::Markdown)).unwrap();
+        assert!(markdown.contains("xss finding"));
+        assert!(markdown.contains("GET /search?q=<script> HTTP/1.1"));
+
+        // 内容中的反引号不能提前结束代码块
+        let mut fenced = vulnerability(2, "xss", "High", TriageStatus::New);
+        fenced.evidence = Some("```\n# injected".to_string());
+        let markdown = render_template(&[fenced], &options(ReportFormat::Markdown)).unwrap();
+        assert!(markdown.contains("````\n```\n# injected\n````"));
+        assert!(markdown.contains("````http\nGET /search"));
+    }
+}
@@ -0,0 +1,267 @@
+//! 扫描漏洞的SQLite持久化
+//!
+//! 漏洞按(类型, 主机, 路径模板, 参数, 名称)去重，重复发现只累加次数并更新最后发现时间，
+//! 研判状态由前端修改。
+
+use crate::asm::asm_task::INNERASK_MODULE;
+use crate::handler::scan::common::types::{TriageStatus, Vulnerability, VulnerabilityDetail};
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::results::report::severity_rank;
+use crate::handler::scan::utils::path_template;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{query, Row};
+use std::sync::Arc;
+use url::Url;
+
+const SELECT_COLUMNS: &str = "SELECT id, vulnerability_type, name, url, parameter, value, risk_level, description, solution, evidence, details, confidence, status, occurrences, first_seen, last_seen FROM scan_vulnerability";
+
+/// 时间戳转换为DateTime，非法值按当前时间处理
+fn to_datetime(ts: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(ts, 0).single().unwrap_or_else(Utc::now)
+}
+
+/// 去重键中的主机和路径模板
+fn dedupe_location(url: &str) -> (String, String) {
+    match Url::parse(url) {
+        Ok(u) => {
+            let host = match u.port() {
+                Some(port) => format!("{}:{}", u.host_str().unwrap_or_default(), port),
+                None => u.host_str().unwrap_or_default().to_string(),
+            };
+            (host, path_template(u.path()))
+        }
+        Err(_) => (String::new(), path_template(url.split('?').next().unwrap_or_default())),
+    }
+}
+
+fn from_row(row: &SqliteRow) -> Vulnerability {
+    let parameter: String = row.get("parameter");
+    let details: Option<String> = row.get("details");
+    let status: String = row.get("status");
+    let confidence: String = row.get("confidence");
+    let occurrences: i64 = row.get("occurrences");
+    let id: i64 = row.get("id");
+
+    Vulnerability {
+        id: id as u32,
+        vulnerability_type: row.get("vulnerability_type"),
+        name: row.get::<Option<String>, _>("name").unwrap_or_default(),
+        url: row.get::<Option<String>, _>("url").unwrap_or_default(),
+        risk_level: row.get::<Option<String>, _>("risk_level").unwrap_or_default(),
+        timestamp: to_datetime(row.get("first_seen")).to_string(),
+        description: row.get::<Option<String>, _>("description").unwrap_or_default(),
+        solution: row.get::<Option<String>, _>("solution").unwrap_or_default(),
+        parameter: (!parameter.is_empty()).then_some(parameter),
+        value: row.get("value"),
+        evidence: row.get("evidence"),
+        details: details.and_then(|d| serde_json::from_str::<VulnerabilityDetail>(&d).ok()),
+        confidence: Confidence::parse(&confidence),
+        status: TriageStatus::parse(&status),
+        occurrences: occurrences as u32,
+        last_seen: to_datetime(row.get("last_seen")).to_string(),
+    }
+}
+
+/// 保存扫描结果后的漏洞记录
+#[derive(Debug, Clone)]
+pub struct SavedVulnerability {
+    pub vulnerability: Vulnerability,
+    /// 首次发现
+    pub is_new: bool,
+    /// 已标记为修复的漏洞再次出现，状态重新置为新发现
+    pub reopened: bool,
+}
+
+impl SavedVulnerability {
+    /// 首次发现或修复后再次出现时需要通知前端
+    pub fn should_notify(&self) -> bool {
+        self.is_new || self.reopened
+    }
+}
+
+/// 保存扫描结果，返回去重后的漏洞记录以及是否为首次发现、是否由已修复重新打开
+///
+/// 已标记为修复的漏洞再次出现时重新置为新发现；再次发现的风险等级或置信度更高时，
+/// 用新结果的风险等级、证据和报文替换原记录
+pub async fn save_vulnerability(result: ScanResult) -> Result<SavedVulnerability> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    let seen_at = result.timestamp.timestamp();
+    let vulnerability = Vulnerability::from_scan_result(0, result);
+    let (host, template) = dedupe_location(&vulnerability.url);
+    let parameter = vulnerability.parameter.clone().unwrap_or_default();
+    let details = vulnerability
+        .details
+        .as_ref()
+        .and_then(|d| serde_json::to_string(d).ok());
+
+    // 读取原记录和写入放在同一事务中，并发保存同一漏洞时只有一次会判定为重新打开
+    let mut tx = pool.begin().await?;
+    let previous: Option<(String, String, String)> = query(
+        "SELECT status, risk_level, confidence FROM scan_vulnerability \
+         WHERE vulnerability_type = ? AND host = ? AND path_template = ? AND parameter = ? AND name = ?",
+    )
+    .bind(&vulnerability.vulnerability_type)
+    .bind(&host)
+    .bind(&template)
+    .bind(&parameter)
+    .bind(&vulnerability.name)
+    .fetch_optional(&mut *tx)
+    .await?
+    .map(|row| {
+        (
+            row.get("status"),
+            row.get::<Option<String>, _>("risk_level").unwrap_or_default(),
+            row.get("confidence"),
+        )
+    });
+    let stronger = previous.as_ref().is_some_and(|(_, risk_level, confidence)| {
+        severity_rank(&vulnerability.risk_level) > severity_rank(risk_level)
+            || vulnerability.confidence.rank() > Confidence::parse(confidence).rank()
+    });
+
+    query(
+        r#"
+        INSERT INTO scan_vulnerability (vulnerability_type, name, url, host, path_template, parameter, value, risk_level,
+            description, solution, evidence, details, confidence, status, occurrences, first_seen, last_seen)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'new', 1, ?, ?)
+        ON CONFLICT (vulnerability_type, host, path_template, parameter, name) DO UPDATE SET
+            occurrences = occurrences + 1,
+            confidence = CASE WHEN excluded.confidence = 'certain' OR (excluded.confidence = 'firm' AND confidence = 'tentative')
+                THEN excluded.confidence ELSE confidence END,
+            risk_level = CASE WHEN ? THEN excluded.risk_level ELSE risk_level END,
+            evidence = CASE WHEN ? THEN excluded.evidence ELSE evidence END,
+            details = CASE WHEN ? THEN excluded.details ELSE details END,
+            value = CASE WHEN ? THEN excluded.value ELSE value END,
+            last_seen = excluded.last_seen,
+            status = CASE WHEN status = 'fixed' THEN 'new' ELSE status END
+        "#,
+    )
+    .bind(&vulnerability.vulnerability_type)
+    .bind(&vulnerability.name)
+    .bind(&vulnerability.url)
+    .bind(&host)
+    .bind(&template)
+    .bind(&parameter)
+    .bind(&vulnerability.value)
+    .bind(&vulnerability.risk_level)
+    .bind(&vulnerability.description)
+    .bind(&vulnerability.solution)
+    .bind(&vulnerability.evidence)
+    .bind(&details)
+    .bind(vulnerability.confidence.as_str())
+    .bind(seen_at)
+    .bind(seen_at)
+    .bind(stronger)
+    .bind(stronger)
+    .bind(stronger)
+    .bind(stronger)
+    .execute(&mut *tx)
+    .await?;
+
+    let row = query(&format!(
+        "{} WHERE vulnerability_type = ? AND host = ? AND path_template = ? AND parameter = ? AND name = ?",
+        SELECT_COLUMNS
+    ))
+    .bind(&vulnerability.vulnerability_type)
+    .bind(&host)
+    .bind(&template)
+    .bind(&parameter)
+    .bind(&vulnerability.name)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let saved = from_row(&row);
+    Ok(SavedVulnerability {
+        is_new: saved.occurrences == 1,
+        reopened: previous.is_some_and(|(status, _, _)| TriageStatus::parse(&status) == TriageStatus::Fixed),
+        vulnerability: saved,
+    })
+}
+
+/// 查询全部漏洞，按首次发现顺序排列
+pub async fn load_vulnerabilities() -> Result<Vec<Vulnerability>> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.read_conn);
+
+    let rows = query(&format!("{} ORDER BY id", SELECT_COLUMNS))
+        .fetch_all(&*pool)
+        .await?;
+    Ok(rows.iter().map(from_row).collect())
+}
+
+/// 去重后的漏洞数量
+pub async fn count_vulnerabilities() -> Result<usize> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.read_conn);
+
+    let count: i64 = query("SELECT COUNT(*) AS count FROM scan_vulnerability")
+        .fetch_one(&*pool)
+        .await?
+        .get("count");
+    Ok(count as usize)
+}
+
+/// 批量修改研判状态
+pub async fn update_status(ids: &[u32], status: TriageStatus) -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    for id in ids {
+        query("UPDATE scan_vulnerability SET status = ? WHERE id = ?")
+            .bind(status.as_str())
+            .bind(id)
+            .execute(&*pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// 清空漏洞记录
+pub async fn clear_vulnerabilities() -> Result<()> {
+    let task_module = INNERASK_MODULE.get().ok_or_else(|| anyhow!("数据库未初始化"))?;
+    let pool = Arc::clone(&task_module.write_conn);
+
+    query("DELETE FROM scan_vulnerability").execute(&*pool).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedupe_location() {
+        assert_eq!(
+            dedupe_location("https://example.com/api/users/42/orders?id=1"),
+            ("example.com".to_string(), "/api/users/{id}/orders".to_string())
+        );
+        assert_eq!(
+            dedupe_location("http://example.com:8080/item/3fa85f64-5717-4562-b3fc-2c963f66afa6"),
+            ("example.com:8080".to_string(), "/item/{id}".to_string())
+        );
+        assert_eq!(
+            dedupe_location("https://example.com/api/users/42").1,
+            dedupe_location("https://example.com/api/users/43").1
+        );
+    }
+
+    #[test]
+    fn test_triage_status_roundtrip() {
+        for status in [
+            TriageStatus::New,
+            TriageStatus::Confirmed,
+            TriageStatus::FalsePositive,
+            TriageStatus::Fixed,
+            TriageStatus::AcceptedRisk,
+        ] {
+            assert_eq!(TriageStatus::parse(status.as_str()), status);
+            assert_eq!(serde_json::to_value(status).unwrap(), status.as_str());
+        }
+        assert_eq!(TriageStatus::parse("unknown"), TriageStatus::New);
+    }
+}
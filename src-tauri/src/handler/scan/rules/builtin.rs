@@ -1,109 +1,110 @@
-/// XSS detection rule
-pub const XSS_RULE: &str = r#"
-/**
- * XSS detection rule
- * 
- * Detects Cross Site Scripting (XSS) vulnerabilities
- */
+//! 内置被动检测规则
+//!
+//! 规则格式与扩展目录中的YAML规则相同，匹配器和提取器复用指纹引擎的定义。
 
-// Check if response contains reflected parameters
-function detect(request, response) {
-    // Check if response contains reflected parameters
-    return false;
-}
-
-// Export detection function
-module.exports = {
-    name: "XSS Detector",
-    description: "Detects Cross Site Scripting (XSS) vulnerabilities",
-    detect: detect
-};
+/// SQL错误信息泄露
+pub const SQL_ERROR_RULE: &str = r#"
+id: builtin-sql-error
+name: SQL错误信息泄露
+description: 响应中包含数据库错误信息，可能存在SQL注入或调试信息泄露
+type: SQL Injection
+severity: medium
+remediation: 关闭数据库错误回显，对用户输入使用参数化查询
+matchers:
+  - type: regex
+    part: body
+    regex:
+      - "You have an error in your SQL syntax"
+      - "Warning: (?:mysql|mysqli|pg|sqlite|oci)_"
+      - "(?i)unclosed quotation mark after the character string"
+      - "ORA-\\d{5}: "
+      - "PG::SyntaxError|PSQLException|ERROR:\\s+syntax error at or near"
+      - "SQLITE_ERROR|sqlite3\\.OperationalError"
+extractors:
+  - type: regex
+    name: error
+    part: body
+    regex:
+      - "(?:You have an error in your SQL syntax[^<\\n]{0,80}|ORA-\\d{5}: [^<\\n]{0,80}|ERROR:\\s+syntax error at or near[^<\\n]{0,40})"
 "#;
 
-/// SQL Injection detection rule
-pub const SQL_INJECTION_RULE: &str = r#"
-/**
- * SQL Injection detection rule
- * 
- * Detects SQL Injection vulnerabilities
- */
-
-// Check if response contains SQL errors
-function detect(request, response) {
-    // Check if response contains SQL errors
-    return false;
-}
-
-// Export detection function
-module.exports = {
-    name: "SQL Injection Detector",
-    description: "Detects SQL Injection vulnerabilities",
-    detect: detect
-};
+/// 路径遍历导致的敏感文件内容
+pub const PATH_TRAVERSAL_RULE: &str = r#"
+id: builtin-sensitive-file
+name: 敏感文件内容泄露
+description: 响应中包含系统文件内容，可能存在路径遍历或任意文件读取
+type: Path Traversal
+severity: high
+remediation: 校验并规范化文件路径，限制可访问的目录
+matchers:
+  - type: regex
+    part: body
+    regex:
+      - "root:[x*]?:0:0:"
+      - "\\[boot loader\\]"
+      - "; for 16-bit app support"
 "#;
 
-/// RCE detection rule
+/// 命令执行回显
 pub const RCE_RULE: &str = r#"
-/**
- * RCE detection rule
- * 
- * Detects Remote Command Execution (RCE) vulnerabilities
- */
-
-// Check if response contains command execution errors
-function detect(request, response) {
-    // Check if response contains command execution errors
-    return false;
-}
-
-// Export detection function
-module.exports = {
-    name: "RCE Detector",
-    description: "Detects Remote Command Execution (RCE) vulnerabilities",
-    detect: detect
-};
+id: builtin-command-output
+name: 命令执行回显
+description: 响应中包含系统命令的输出，可能存在远程命令执行
+type: Remote Code Execution
+severity: high
+remediation: 避免将用户输入拼接到系统命令中，必要时使用白名单
+matchers:
+  - type: regex
+    part: body
+    regex:
+      - "uid=\\d+\\([\\w.-]+\\) gid=\\d+\\([\\w.-]+\\)"
+extractors:
+  - type: regex
+    name: output
+    part: body
+    regex:
+      - "uid=\\d+\\([\\w.-]+\\) gid=\\d+\\([\\w.-]+\\)"
 "#;
 
-/// Path Traversal detection rule
-pub const PATH_TRAVERSAL_RULE: &str = r#"
-/**
- * Path Traversal detection rule
- * 
- * Detects Path Traversal vulnerabilities
- */
-
-// Check if response contains sensitive file contents
-function detect(request, response) {
-    // Check if response contains sensitive file contents
-    return false;
-}
-
-// Export detection function
-module.exports = {
-    name: "Path Traversal Detector",
-    description: "Detects Path Traversal vulnerabilities",
-    detect: detect
-};
+/// 异常堆栈信息泄露
+pub const STACK_TRACE_RULE: &str = r#"
+id: builtin-stack-trace
+name: 异常堆栈信息泄露
+description: 响应中包含程序异常堆栈，泄露了代码结构和依赖信息
+type: Information Disclosure
+severity: low
+remediation: 生产环境关闭调试模式，使用统一的错误页面
+matchers:
+  - type: regex
+    part: body
+    regex:
+      - "\\bat (?:java|javax|org\\.springframework|org\\.apache)\\.[\\w.$]+\\([\\w]+\\.java:\\d+\\)"
+      - "Traceback \\(most recent call last\\):"
+      - "<b>(?:Fatal error|Warning)</b>:.+ in <b>[^<]+</b> on line <b>\\d+</b>"
+      - "System\\.[\\w.]+Exception: .+\\r?\\n\\s+at "
 "#;
 
-/// Open Redirect detection rule
+/// 开放重定向
 pub const OPEN_REDIRECT_RULE: &str = r#"
-/**
- * Open Redirect detection rule
- * 
- * Detects Open Redirect vulnerabilities
- */
-
-// Check if response is a redirect to an external domain
-function detect(request, response) {
-    // Check if response is a redirect to an external domain
-    return false;
-}
+id: builtin-open-redirect
+name: 开放重定向
+description: 请求参数中的外部地址被用作跳转目标，可能存在开放重定向
+type: Open Redirect
+severity: medium
+remediation: 跳转地址使用白名单或仅允许站内相对路径
+matchers-condition: and
+matchers:
+  - type: status
+    status: [301, 302, 303, 307, 308]
+  - type: regex
+    part: query
+    regex:
+      - "(?i)(?:redirect|url|next|return|returnurl|goto|target|dest)[\\w-]*=(?:https?(?::|%3a)|(?:/|%2f){2})"
+  - type: regex
+    part: location
+    regex:
+      - "^(?:https?:)?//"
+"#;
 
-// Export detection function
-module.exports = {
-    name: "Open Redirect Detector",
-    description: "Detects Open Redirect vulnerabilities",
-    detect: detect
-};
-"#; 
\ No newline at end of file
+/// 全部内置规则
+pub const BUILTIN_RULES: &[&str] = &[SQL_ERROR_RULE, PATH_TRAVERSAL_RULE, RCE_RULE, STACK_TRACE_RULE, OPEN_REDIRECT_RULE];
@@ -1,7 +1,69 @@
-use log::debug;
+use log::{debug, warn};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
+use super::{Rule, RuleType};
 use crate::scan::proxy::{HttpRequest, HttpResponse};
 
+/// 扩展规则目录快照（规则文件 -> 修改时间），用于判断是否需要重新加载
+pub type ExtensionSnapshot = BTreeMap<PathBuf, Option<SystemTime>>;
+
+/// 是否为YAML规则文件
+fn is_rule_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"))
+            .unwrap_or(false)
+}
+
+/// 获取扩展规则目录快照，目录不存在时返回空快照
+pub fn snapshot(dir: &Path) -> ExtensionSnapshot {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return ExtensionSnapshot::new(),
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_rule_file(path))
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+/// 加载扩展规则目录中的全部规则，解析失败的文件只记录日志
+pub fn load_rules(dir: &Path) -> HashMap<String, Rule> {
+    let mut rules = HashMap::new();
+    for path in snapshot(dir).into_keys() {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("读取扩展规则失败 {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        match Rule::parse(&content, RuleType::Extension) {
+            Ok(mut rule) => {
+                debug!("加载扩展规则 {} ({})", rule.id, path.display());
+                rule.path = Some(path);
+                if let Some(previous) = rules.insert(rule.id.clone(), rule) {
+                    warn!("扩展规则ID重复，已覆盖: {}", previous.id);
+                }
+            }
+            Err(e) => warn!("解析扩展规则失败 {}: {}", path.display(), e),
+        }
+    }
+    rules
+}
+
 /// Simple utility functions for rule processing
 pub struct RuleUtils;
 
@@ -1,64 +1,489 @@
+//! 声明式被动检测规则
+//!
+//! 规则使用YAML描述，匹配器和提取器复用指纹引擎的定义，匹配位置除了响应的
+//! `body`/`header`/`response` 外，还支持请求部分（见 [`Rule`]）。
+//! 内置规则随程序发布，扩展规则从 `rules.extensions_path` 目录加载并在文件变化时自动重新加载。
+
+pub mod builtin;
 pub mod extensions;
 
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::Arc;
-use std::marker::PhantomData;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
+use engine::extractors::ExtractorType;
+use engine::matchers::{Condition, MatcherType, Part};
+use engine::operators::Operators;
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 
 use crate::core::config::AppConfig;
+use crate::handler::scan::engine::result::{find_ranges, raw_request, raw_response};
+use crate::handler::scan::engine::{ByteRange, Confidence, ScanResult};
+use crate::handler::scan::utils::capitalize;
 use crate::scan::proxy::{HttpRequest, HttpResponse};
+use extensions::ExtensionSnapshot;
+
+/// 扩展规则目录的检查间隔
+const RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 全局共享的规则管理器，扫描器重复创建时沿用已加载的规则和目录快照
+static SHARED: Lazy<Mutex<Option<Arc<RuleManager>>>> = Lazy::new(|| Mutex::new(None));
 
+/// 证据中单个匹配内容的最大长度
+const MAX_EVIDENCE_LEN: usize = 200;
 
 /// Rule type
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum RuleType {
+    #[default]
     Builtin,
     Extension,
 }
 
+fn default_vulnerability_type() -> String {
+    "Rule".to_string()
+}
+
+fn default_severity() -> String {
+    "medium".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
 /// Rule definition
-#[derive(Debug, Clone)]
+///
+/// 匹配位置 `part` 可选值：
+/// - `body` / `header` / `response`：响应体、响应头、完整响应
+/// - `request` / `request_header` / `request_body`：完整请求、请求头、请求体
+/// - `url` / `path` / `query` / `method`：请求地址的各部分
+/// - 其他名称：同名的响应头（不区分大小写）
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Rule {
+    /// 规则唯一标识
+    pub id: String,
     pub name: String,
+    #[serde(default)]
     pub description: String,
+    /// 漏洞类型
+    #[serde(rename = "type", default = "default_vulnerability_type")]
+    pub vulnerability_type: String,
+    /// 风险等级：info/low/medium/high/critical
+    #[serde(default = "default_severity")]
+    pub severity: String,
+    #[serde(default)]
+    pub remediation: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 匹配器与提取器
+    #[serde(flatten)]
+    pub operators: Operators,
+    #[serde(skip)]
     pub rule_type: RuleType,
+    /// 扩展规则的文件路径
+    #[serde(skip)]
     pub path: Option<PathBuf>,
-    pub content: String,
+}
+
+/// 规则匹配结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuleMatch {
+    /// 匹配器命中的内容
+    pub matched: Vec<String>,
+    /// 提取器名称 -> 提取的内容
+    pub extracted: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Rule {
+    /// 解析并预编译规则
+    pub fn parse(content: &str, rule_type: RuleType) -> Result<Self, String> {
+        let mut rule: Rule = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+        if rule.id.trim().is_empty() {
+            return Err("规则缺少id".to_string());
+        }
+        if rule.operators.matchers.is_empty() {
+            return Err(format!("规则 {} 没有匹配器", rule.id));
+        }
+        rule.operators.compile().map_err(|e| format!("规则 {} 编译失败: {}", rule.id, e))?;
+        rule.rule_type = rule_type;
+        Ok(rule)
+    }
+
+    /// 获取匹配位置的内容，不存在时返回None
+    fn corpus(part: &Part, request: &HttpRequest, response: &HttpResponse) -> Option<String> {
+        let join_headers = |headers: &HashMap<String, String>| {
+            headers.iter().map(|(k, v)| format!("{}: {}\r\n", k, v)).collect::<String>()
+        };
+        let response_body = || String::from_utf8_lossy(&response.body).to_string();
+        let request_body = || String::from_utf8_lossy(&request.body).to_string();
+        let url = url::Url::parse(&request.url).ok();
+
+        let corpus = match part {
+            Part::Body => response_body(),
+            Part::Header => join_headers(&response.headers),
+            Part::Response => format!("{}\r\n{}", join_headers(&response.headers), response_body()),
+            Part::Name(name) => match name.to_ascii_lowercase().as_str() {
+                "request" => format!(
+                    "{} {}\r\n{}\r\n{}",
+                    request.method,
+                    request.url,
+                    join_headers(&request.headers),
+                    request_body()
+                ),
+                "request_header" => join_headers(&request.headers),
+                "request_body" => request_body(),
+                "url" => request.url.clone(),
+                "path" => url?.path().to_string(),
+                "query" => url?.query()?.to_string(),
+                "method" => request.method.clone(),
+                header => response
+                    .headers
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(header))
+                    .map(|(_, v)| v.clone())?,
+            },
+        };
+        Some(corpus)
+    }
+
+    /// 对请求/响应执行规则，匹配时返回命中内容和提取结果
+    pub fn evaluate(&self, request: &HttpRequest, response: &HttpResponse) -> Option<RuleMatch> {
+        let operators = &self.operators;
+        let mut result = RuleMatch::default();
+        let mut any_matched = false;
+
+        for matcher in operators.matchers.iter() {
+            let (is_match, words) = match &matcher.matcher_type {
+                MatcherType::Status(status) => (
+                    matcher.match_status_code(status, response.status),
+                    vec![response.status.to_string()],
+                ),
+                MatcherType::Word(word) => match Self::corpus(&matcher.part, request, response) {
+                    Some(corpus) => matcher.match_word(word, corpus),
+                    None => (false, Vec::new()),
+                },
+                MatcherType::Regex(re) => match Self::corpus(&matcher.part, request, response) {
+                    Some(corpus) => matcher.match_regex(re, corpus),
+                    None => (false, Vec::new()),
+                },
+                // favicon/dsl/binary/xpath 依赖主动请求或表达式引擎，规则中不支持
+                _ => (false, Vec::new()),
+            };
+
+            if matcher.negative(is_match) {
+                any_matched = true;
+                if !matcher.negative {
+                    result.matched.extend(words);
+                }
+                if matches!(operators.matchers_condition, Condition::Or) && operators.stop_at_first_match {
+                    break;
+                }
+            } else if matches!(operators.matchers_condition, Condition::And) {
+                return None;
+            }
+        }
+
+        if !any_matched {
+            return None;
+        }
+
+        for (index, extractor) in operators.extractors.iter().enumerate() {
+            let corpus = match Self::corpus(&extractor.part, request, response) {
+                Some(corpus) => corpus,
+                None => continue,
+            };
+            let (values, _) = match &extractor.extractor_type {
+                ExtractorType::Regex(re) => extractor.extract_regex(re, corpus, &None),
+                ExtractorType::JSON(json) => extractor.extrat_json(json, corpus),
+                _ => continue,
+            };
+            if !values.is_empty() {
+                let key = extractor.name.clone().unwrap_or_else(|| index.to_string());
+                result.extracted.entry(key).or_default().extend(values);
+            }
+        }
+
+        Some(result)
+    }
+
+    /// 将匹配结果转换为扫描结果，命中内容在原始报文中的位置作为高亮区间
+    fn to_scan_result(&self, matched: RuleMatch, request: &HttpRequest, response: &HttpResponse) -> ScanResult {
+        let truncate = |s: &str| s.chars().take(MAX_EVIDENCE_LEN).collect::<String>();
+        let mut evidence: Vec<String> = matched.matched.iter().map(|m| truncate(m)).collect();
+        for (name, values) in &matched.extracted {
+            evidence.push(format!(
+                "{}: {}",
+                name,
+                values.iter().map(|v| truncate(v)).collect::<Vec<_>>().join(", ")
+            ));
+        }
+
+        let source = match self.rule_type {
+            RuleType::Builtin => "内置规则",
+            RuleType::Extension => "扩展规则",
+        };
+
+        let raw_request = raw_request(request);
+        let raw_response = raw_response(response);
+        let hits: Vec<&String> = matched.matched.iter().chain(matched.extracted.values().flatten()).collect();
+        let ranges = |raw: &str| {
+            let mut ranges: Vec<ByteRange> = hits.iter().flat_map(|hit| find_ranges(raw, hit)).collect();
+            ranges.sort_by_key(|r| (r.start, r.end));
+            ranges.dedup();
+            ranges
+        };
+
+        ScanResult {
+            vulnerability_type: self.vulnerability_type.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            risk_level: capitalize(&self.severity),
+            url: request.url.clone(),
+            method: request.method.clone(),
+            parameter: None,
+            value: None,
+            evidence: (!evidence.is_empty()).then(|| evidence.join("\n")),
+            remediation: self.remediation.clone(),
+            details: Some(format!("{}: {}", source, self.id)),
+            timestamp: chrono::Utc::now(),
+            payload_ranges: ranges(&raw_request),
+            evidence_ranges: ranges(&raw_response),
+            request_details: Some(raw_request),
+            response_details: Some(raw_response),
+            confidence: Confidence::Firm,
+        }
+    }
+}
+
+/// 扩展规则及其目录快照
+struct ExtensionState {
+    rules: HashMap<String, Rule>,
+    snapshot: ExtensionSnapshot,
 }
 
 /// Rule manager
 pub struct RuleManager {
-    _config: Arc<AppConfig>,
-    _builtin_rules: HashMap<String, Rule>,
-    _extension_rules: HashMap<String, Rule>,
-    _not_send_sync: PhantomData<*const ()>,
+    config: Arc<AppConfig>,
+    builtin_rules: HashMap<String, Rule>,
+    extension_rules: RwLock<ExtensionState>,
+    /// 上次检查扩展目录的时间
+    last_check: Mutex<Instant>,
 }
 
 impl RuleManager {
     /// Create a new rule manager
-    pub fn new(_config: Arc<AppConfig>) -> Self {
-        Self {
-            _config,
-            _builtin_rules: HashMap::new(),
-            _extension_rules: HashMap::new(),
-            _not_send_sync: PhantomData,
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        let mut builtin_rules = HashMap::new();
+        if config.rules.enable_builtin {
+            for content in builtin::BUILTIN_RULES {
+                match Rule::parse(content, RuleType::Builtin) {
+                    Ok(rule) => {
+                        builtin_rules.insert(rule.id.clone(), rule);
+                    }
+                    Err(e) => warn!("解析内置规则失败: {}", e),
+                }
+            }
+        }
+
+        let manager = Self {
+            config,
+            builtin_rules,
+            extension_rules: RwLock::new(ExtensionState {
+                rules: HashMap::new(),
+                snapshot: ExtensionSnapshot::new(),
+            }),
+            last_check: Mutex::new(Instant::now()),
+        };
+        manager.reload_extensions();
+        manager
+    }
+
+    /// 获取共享的规则管理器，规则配置变化时重新创建
+    pub fn shared(config: Arc<AppConfig>) -> Arc<Self> {
+        let mut shared = SHARED.lock().unwrap();
+        if let Some(manager) = shared.as_ref().filter(|m| m.same_rules(&config)) {
+            return manager.clone();
         }
+        let manager = Arc::new(Self::new(config));
+        *shared = Some(manager.clone());
+        manager
     }
-    
+
+    /// 规则相关的配置是否一致
+    fn same_rules(&self, config: &AppConfig) -> bool {
+        let (a, b) = (&self.config.rules, &config.rules);
+        a.enable_builtin == b.enable_builtin
+            && a.enable_extensions == b.enable_extensions
+            && a.extensions_path == b.extensions_path
+    }
+
+    /// 扩展规则目录
+    fn extensions_dir(&self) -> &Path {
+        Path::new(&self.config.rules.extensions_path)
+    }
+
+    /// 重新加载扩展规则
+    pub fn reload_extensions(&self) {
+        if !self.config.rules.enable_extensions {
+            return;
+        }
+        let dir = self.extensions_dir();
+        let snapshot = extensions::snapshot(dir);
+        let rules = extensions::load_rules(dir);
+        info!("从 {} 加载了 {} 条扩展规则", dir.display(), rules.len());
+
+        let mut state = self.extension_rules.write().unwrap();
+        state.rules = rules;
+        state.snapshot = snapshot;
+    }
+
+    /// 扩展规则目录发生变化时重新加载，检查频率受 [`RELOAD_INTERVAL`] 限制
+    fn reload_if_changed(&self) {
+        if !self.config.rules.enable_extensions {
+            return;
+        }
+        {
+            let mut last_check = self.last_check.lock().unwrap();
+            if last_check.elapsed() < RELOAD_INTERVAL {
+                return;
+            }
+            *last_check = Instant::now();
+        }
+
+        let current = extensions::snapshot(self.extensions_dir());
+        let changed = self.extension_rules.read().unwrap().snapshot != current;
+        if changed {
+            info!("扩展规则目录发生变化，重新加载规则");
+            self.reload_extensions();
+        }
+    }
+
     /// Execute a rule
-    pub fn execute_rule(&self, _rule_name: &str, _request: &HttpRequest, _response: &HttpResponse) -> bool {
-        // Simplified implementation
-        false
+    pub fn execute_rule(&self, rule_name: &str, request: &HttpRequest, response: &HttpResponse) -> bool {
+        if let Some(rule) = self.builtin_rules.get(rule_name) {
+            return rule.evaluate(request, response).is_some();
+        }
+        self.extension_rules
+            .read()
+            .unwrap()
+            .rules
+            .get(rule_name)
+            .map(|rule| rule.evaluate(request, response).is_some())
+            .unwrap_or(false)
     }
-    
+
     /// Get all rules
-    pub fn get_all_rules(&self) -> HashMap<String, &Rule> {
-        HashMap::new()
+    pub fn get_all_rules(&self) -> HashMap<String, Rule> {
+        let mut rules = self.builtin_rules.clone();
+        rules.extend(
+            self.extension_rules
+                .read()
+                .unwrap()
+                .rules
+                .iter()
+                .map(|(id, rule)| (id.clone(), rule.clone())),
+        );
+        rules
     }
-    
+
     /// Scan request/response
-    pub fn scan(&self, _request: &HttpRequest, _response: &HttpResponse) -> Vec<String> {
-        Vec::new()
+    pub fn scan(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<ScanResult> {
+        self.reload_if_changed();
+
+        let extensions = self.extension_rules.read().unwrap();
+        self.builtin_rules
+            .values()
+            .chain(extensions.rules.values())
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| rule.evaluate(request, response).map(|m| rule.to_scan_result(m, request, response)))
+            .collect()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str) -> HttpRequest {
+        HttpRequest::new(url, "GET", HashMap::new(), Vec::new(), Vec::new())
+    }
+
+    fn response(status: u16, headers: Vec<(&str, &str)>, body: &str) -> HttpResponse {
+        HttpResponse::new(
+            status,
+            headers.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body.as_bytes().to_vec(),
+        )
+    }
+
+    #[test]
+    fn test_builtin_rules_parse() {
+        for content in builtin::BUILTIN_RULES {
+            assert!(Rule::parse(content, RuleType::Builtin).is_ok(), "{}", content);
+        }
+        assert!(Rule::parse("id: empty\nname: empty\n", RuleType::Extension).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_rule() {
+        let rule = Rule::parse(builtin::SQL_ERROR_RULE, RuleType::Builtin).unwrap();
+        let req = request("http://example.com/item?id=1'");
+        let resp = response(500, vec![], "You have an error in your SQL syntax; check the manual near ''1''' at line 1");
+        let matched = rule.evaluate(&req, &resp).unwrap();
+        assert!(matched.extracted["error"].iter().next().unwrap().starts_with("You have an error"));
+        let result = rule.to_scan_result(matched, &req, &resp);
+        let raw_response = result.response_details.as_deref().unwrap();
+        let range = result.evidence_ranges[0];
+        assert!(raw_response[range.start..range.end].starts_with("You have an error"));
+        assert!(rule.evaluate(&req, &response(200, vec![], "ok")).is_none());
+
+        let redirect = Rule::parse(builtin::OPEN_REDIRECT_RULE, RuleType::Builtin).unwrap();
+        let req = request("http://example.com/login?next=https%3A%2F%2Fevil.com");
+        assert!(redirect.evaluate(&req, &response(302, vec![("Location", "https://evil.com")], "")).is_some());
+        assert!(redirect.evaluate(&req, &response(302, vec![("Location", "/home")], "")).is_none());
+        assert!(redirect.evaluate(&request("http://example.com/login"), &response(302, vec![("Location", "https://evil.com")], "")).is_none());
+    }
+
+    #[test]
+    fn test_extension_hot_reload() {
+        let dir = std::env::temp_dir().join(format!("rshield-rules-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut config = AppConfig::default();
+        config.rules.enable_builtin = false;
+        config.rules.extensions_path = dir.to_string_lossy().to_string();
+        let manager = RuleManager::new(Arc::new(config));
+        assert!(manager.get_all_rules().is_empty());
+
+        std::fs::write(
+            dir.join("debug.yaml"),
+            "id: debug-header\nname: 调试头\nseverity: info\nmatchers:\n  - type: word\n    part: x-debug\n    words: [\"true\"]\n",
+        )
+        .unwrap();
+        *manager.last_check.lock().unwrap() = Instant::now() - RELOAD_INTERVAL;
+
+        let results = manager.scan(&request("http://example.com/"), &response(200, vec![("X-Debug", "true")], ""));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].risk_level, "Info");
+        assert!(manager.execute_rule("debug-header", &request("http://example.com/"), &response(200, vec![("x-debug", "true")], "")));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_shared_manager() {
+        let mut config = AppConfig::default();
+        config.rules.enable_extensions = false;
+        let first = RuleManager::shared(Arc::new(config.clone()));
+        assert!(Arc::ptr_eq(&first, &RuleManager::shared(Arc::new(config.clone()))));
+
+        config.rules.enable_builtin = false;
+        let changed = RuleManager::shared(Arc::new(config));
+        assert!(!Arc::ptr_eq(&first, &changed));
+        assert!(changed.get_all_rules().is_empty());
+    }
+}
@@ -0,0 +1,386 @@
+use crate::core::config::{AppConfig, AuthzConfig, AuthzRole};
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::scanners::xss::{compute_term_frequency, cosine_similarity};
+use crate::handler::scan::scanners::Scanner;
+use crate::handler::scan::utils::{http, path_template};
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{debug, info};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use url::Url;
+
+/// 携带身份信息的请求头，重放前全部移除
+const AUTH_HEADERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "x-auth-token",
+    "x-access-token",
+    "x-api-key",
+    "x-csrf-token",
+    "x-xsrf-token",
+    "proxy-authorization",
+];
+
+/// 静态资源后缀，不做越权测试
+const STATIC_EXTENSIONS: &[&str] = &[
+    ".js", ".css", ".png", ".jpg", ".jpeg", ".gif", ".svg", ".ico", ".woff", ".woff2", ".ttf", ".map", ".mp4", ".webp",
+];
+
+/// 可以安全重放的请求方法
+const SAFE_METHODS: &[&str] = &["GET", "HEAD", "OPTIONS"];
+
+/// 响应体过短时无法判断内容是否等价
+const MIN_BODY_LEN: usize = 32;
+
+/// 响应比较结果
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    status: u16,
+    length: usize,
+    similarity: f64,
+}
+
+/// 越权访问扫描器
+///
+/// 将代理到的高权限请求替换为低权限身份或去掉认证信息后重放，
+/// 低权限响应与原响应等价时认为接口缺少访问控制
+#[derive(Clone)]
+pub struct AuthzScanner {
+    /// 越权测试配置
+    config: AuthzConfig,
+    /// 白名单对应的正则
+    allow_list: Vec<Regex>,
+    /// HTTP客户端
+    http_client: reqwest::Client,
+    /// 本次扫描已测试的接口，键为方法+去掉ID后的路径
+    tested: Arc<Mutex<HashSet<String>>>,
+}
+
+impl AuthzScanner {
+    /// 创建新的越权访问扫描器
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        let authz = config.rules.vulnerabilities.authz.clone();
+        let allow_list = authz.allow_list.iter().filter_map(|p| Self::wildcard_regex(p)).collect();
+
+        // 不跟随跳转，未授权时常见的302到登录页需要原样比较
+        let http_client = http::client(Duration::from_millis(config.scanner.timeout_ms), false);
+
+        Self {
+            config: authz,
+            allow_list,
+            http_client,
+            tested: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// 通配符模式转换为正则，*匹配任意字符
+    fn wildcard_regex(pattern: &str) -> Option<Regex> {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return None;
+        }
+        let escaped = regex::escape(pattern).replace(r"\*", ".*");
+        Regex::new(&format!("(?i)^{}$", escaped)).ok()
+    }
+
+    /// 是否命中公开接口白名单，模式可以匹配完整URL或路径
+    fn is_allowed(&self, url: &str) -> bool {
+        let path = Url::parse(url).map(|u| u.path().to_string()).unwrap_or_default();
+        self.allow_list.iter().any(|re| re.is_match(url) || re.is_match(&path))
+    }
+
+    /// 请求是否携带身份信息
+    fn has_credentials(request: &HttpRequest) -> bool {
+        request
+            .headers
+            .keys()
+            .any(|k| AUTH_HEADERS.contains(&k.to_lowercase().as_str()))
+    }
+
+    /// 请求是否由配置的低权限身份发出（认证头或Cookie与某个身份一致），重放不会改变身份
+    fn is_role_request(&self, request: &HttpRequest) -> bool {
+        let header = |name: &str| {
+            request
+                .headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        };
+        self.config.roles.iter().any(|role| {
+            role.headers.iter().any(|(k, v)| header(k.as_str()) == Some(v.as_str()))
+                || role
+                    .cookie
+                    .as_deref()
+                    .filter(|c| !c.is_empty())
+                    .is_some_and(|c| header("cookie") == Some(c))
+        })
+    }
+
+    /// 接口去重键，路径中的数字和UUID片段替换为占位符
+    fn endpoint_key(request: &HttpRequest) -> String {
+        let path = Url::parse(&request.url)
+            .map(|u| format!("{}{}", u.host_str().unwrap_or_default(), u.path()))
+            .unwrap_or_else(|_| request.url.clone());
+        format!("{} {}", request.method.to_uppercase(), path_template(&path))
+    }
+
+    /// 是否需要测试该请求
+    fn should_test(&self, request: &HttpRequest, response: &HttpResponse) -> bool {
+        if !(200..300).contains(&response.status) || response.body.len() < MIN_BODY_LEN {
+            return false;
+        }
+        if !self.config.replay_unsafe_methods && !SAFE_METHODS.contains(&request.method.to_uppercase().as_str()) {
+            return false;
+        }
+        let path = Url::parse(&request.url).map(|u| u.path().to_lowercase()).unwrap_or_default();
+        if STATIC_EXTENSIONS.iter().any(|ext| path.ends_with(ext)) {
+            return false;
+        }
+        Self::has_credentials(request) && !self.is_allowed(&request.url) && !self.is_role_request(request)
+    }
+
+    /// 移除原请求的身份信息，role为None时即未认证请求
+    fn replay_request(request: &HttpRequest, role: Option<&AuthzRole>) -> HttpRequest {
+        let mut replay = request.clone();
+        replay.headers.retain(|k, _| !AUTH_HEADERS.contains(&k.to_lowercase().as_str()));
+
+        if let Some(role) = role {
+            for (k, v) in &role.headers {
+                replay.headers.retain(|existing, _| !existing.eq_ignore_ascii_case(k));
+                replay.headers.insert(k.clone(), v.clone());
+            }
+            if let Some(cookie) = role.cookie.as_ref().filter(|c| !c.is_empty()) {
+                replay.headers.insert("Cookie".to_string(), cookie.clone());
+            }
+        }
+        replay
+    }
+
+    /// 比较重放响应与原响应
+    fn compare(original: &HttpResponse, replayed: &HttpResponse) -> Comparison {
+        let similarity = if original.body == replayed.body {
+            1.0
+        } else {
+            let a = compute_term_frequency(&String::from_utf8_lossy(&original.body));
+            let b = compute_term_frequency(&String::from_utf8_lossy(&replayed.body));
+            cosine_similarity(&a, &b)
+        };
+        Comparison {
+            status: replayed.status,
+            length: replayed.body.len(),
+            similarity,
+        }
+    }
+
+    /// 状态码一致、长度差异在10%以内且内容相似度达到阈值时认为内容等价
+    fn is_equivalent(&self, original: &HttpResponse, comparison: &Comparison) -> bool {
+        if comparison.status != original.status {
+            return false;
+        }
+        let diff = original.body.len().abs_diff(comparison.length);
+        diff <= original.body.len() / 10 && comparison.similarity >= self.config.similarity_threshold
+    }
+
+    /// 发送重放请求
+    async fn send_request(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        http::send_request(&self.http_client, request).await
+    }
+
+    /// 构造扫描结果，replay为重放的请求和响应
+    fn build_result(
+        request: &HttpRequest,
+        response: &HttpResponse,
+        replay: (&HttpRequest, &HttpResponse),
+        identity: &str,
+        comparison: &Comparison,
+        unauthenticated: bool,
+    ) -> ScanResult {
+        let (vulnerability_type, name, description) = if unauthenticated {
+            (
+                "Unauthorized Access",
+                "未授权访问漏洞",
+                "去掉认证信息后重放请求，仍返回与登录用户相同的内容，接口缺少身份认证",
+            )
+        } else {
+            (
+                "Broken Access Control",
+                "越权访问漏洞",
+                "使用低权限身份重放高权限会话的请求，返回了等价的内容，接口缺少权限校验（水平或垂直越权）",
+            )
+        };
+
+        ScanResult {
+            vulnerability_type: vulnerability_type.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            risk_level: "High".to_string(),
+            url: request.url.to_string(),
+            method: request.method.to_string(),
+            parameter: Some(identity.to_string()),
+            value: None,
+            evidence: Some(format!(
+                "原响应: 状态 {}，{} 字节；{}: 状态 {}，{} 字节，内容相似度 {:.2}",
+                response.status,
+                response.body.len(),
+                identity,
+                comparison.status,
+                comparison.length,
+                comparison.similarity
+            )),
+            remediation: Some("在服务端对每个接口和资源校验当前用户的身份与权限，不依赖前端隐藏入口或不可猜测的ID".to_string()),
+            details: Some(format!("以 {} 身份重放请求获得与高权限会话等价的响应，若该接口本应公开，请加入越权测试白名单", identity)),
+            timestamp: chrono::Utc::now(),
+            request_details: None,
+            response_details: None,
+            confidence: Confidence::Firm,
+            payload_ranges: Vec::new(),
+            evidence_ranges: Vec::new(),
+        }
+        .with_exchange(replay.0, Some(replay.1), None, None)
+    }
+}
+
+#[async_trait]
+impl Scanner for AuthzScanner {
+    async fn name(&self) -> String {
+        "Authorization Scanner".to_string()
+    }
+
+    async fn scan(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+        if !self.should_test(request, response) {
+            return results;
+        }
+        if !self.tested.lock().unwrap().insert(Self::endpoint_key(request)) {
+            return results;
+        }
+
+        // 先测试未认证，未认证即可访问时无需再比较低权限身份
+        if self.config.test_unauthenticated {
+            let replay = Self::replay_request(request, None);
+            match self.send_request(&replay).await {
+                Ok(replayed) => {
+                    let comparison = Self::compare(response, &replayed);
+                    if self.is_equivalent(response, &comparison) {
+                        info!("发现未授权访问: {} {}", request.method, request.url);
+                        results.push(Self::build_result(request, response, (&replay, &replayed), "未认证", &comparison, true));
+                        return results;
+                    }
+                }
+                Err(e) => debug!("未认证重放失败: {}", e),
+            }
+        }
+
+        for role in &self.config.roles {
+            let replay = Self::replay_request(request, Some(role));
+            match self.send_request(&replay).await {
+                Ok(replayed) => {
+                    let comparison = Self::compare(response, &replayed);
+                    if self.is_equivalent(response, &comparison) {
+                        info!("发现越权访问: {} {} 身份 {}", request.method, request.url, role.name);
+                        results.push(Self::build_result(request, response, (&replay, &replayed), &role.name, &comparison, false));
+                    }
+                }
+                Err(e) => debug!("身份 {} 重放失败: {}", role.name, e),
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scanner(allow_list: &[&str]) -> AuthzScanner {
+        let mut config = AppConfig::default();
+        config.rules.vulnerabilities.authz.allow_list = allow_list.iter().map(|s| s.to_string()).collect();
+        AuthzScanner::new(Arc::new(config))
+    }
+
+    fn request(url: &str) -> HttpRequest {
+        let mut headers = HashMap::new();
+        headers.insert("Cookie".to_string(), "session=admin".to_string());
+        headers.insert("Authorization".to_string(), "Bearer admin".to_string());
+        headers.insert("Accept".to_string(), "application/json".to_string());
+        HttpRequest::new(url, "GET", headers, Vec::new(), Vec::new())
+    }
+
+    #[test]
+    fn test_replay_request() {
+        let original = request("http://example.com/api/users/1");
+        let unauthenticated = AuthzScanner::replay_request(&original, None);
+        assert_eq!(unauthenticated.headers.len(), 1);
+        assert!(unauthenticated.headers.contains_key("Accept"));
+
+        let role = AuthzRole {
+            name: "user".to_string(),
+            headers: HashMap::from([("authorization".to_string(), "Bearer user".to_string())]),
+            cookie: Some("session=user".to_string()),
+        };
+        let replay = AuthzScanner::replay_request(&original, Some(&role));
+        assert_eq!(replay.headers.get("authorization").unwrap(), "Bearer user");
+        assert_eq!(replay.headers.get("Cookie").unwrap(), "session=user");
+        assert!(!replay.headers.contains_key("Authorization"));
+    }
+
+    #[test]
+    fn test_should_test_and_allow_list() {
+        let scanner = scanner(&["*/public/*", "https://example.com/health"]);
+        let response = HttpResponse::new(200, HashMap::new(), vec![b'a'; 64]);
+
+        assert!(scanner.should_test(&request("http://example.com/api/orders/7"), &response));
+        assert!(!scanner.should_test(&request("http://example.com/public/news"), &response));
+        assert!(!scanner.should_test(&request("https://example.com/health"), &response));
+        assert!(!scanner.should_test(&request("http://example.com/app.js"), &response));
+
+        let anonymous = HttpRequest::new("http://example.com/api/orders/7", "GET", HashMap::new(), Vec::new(), Vec::new());
+        assert!(!scanner.should_test(&anonymous, &response));
+
+        let mut post = request("http://example.com/api/orders/7");
+        post.method = "DELETE".to_string();
+        assert!(!scanner.should_test(&post, &response));
+
+        // 低权限身份自己发出的请求不测试
+        let mut config = AppConfig::default();
+        config.rules.vulnerabilities.authz.roles = vec![AuthzRole {
+            name: "user".to_string(),
+            headers: HashMap::from([("authorization".to_string(), "Bearer user".to_string())]),
+            cookie: Some("session=user".to_string()),
+        }];
+        let scanner = AuthzScanner::new(Arc::new(config));
+        let mut own = request("http://example.com/api/orders/7");
+        own.headers.insert("Authorization".to_string(), "Bearer user".to_string());
+        assert!(!scanner.should_test(&own, &response));
+        let mut own_cookie = request("http://example.com/api/orders/7");
+        own_cookie.headers.insert("Cookie".to_string(), "session=user".to_string());
+        assert!(!scanner.should_test(&own_cookie, &response));
+        assert!(scanner.should_test(&request("http://example.com/api/orders/7"), &response));
+    }
+
+    #[test]
+    fn test_equivalence() {
+        let scanner = scanner(&[]);
+        let original = HttpResponse::new(200, HashMap::new(), b"order 7 total 100 address secret street".to_vec());
+        let same = HttpResponse::new(200, HashMap::new(), b"order 7 total 100 address secret street".to_vec());
+        let denied = HttpResponse::new(403, HashMap::new(), b"forbidden".to_vec());
+        let login = HttpResponse::new(200, HashMap::new(), b"please sign in to continue to your account".to_vec());
+
+        assert!(scanner.is_equivalent(&original, &AuthzScanner::compare(&original, &same)));
+        assert!(!scanner.is_equivalent(&original, &AuthzScanner::compare(&original, &denied)));
+        assert!(!scanner.is_equivalent(&original, &AuthzScanner::compare(&original, &login)));
+    }
+
+    #[test]
+    fn test_endpoint_key() {
+        let a = AuthzScanner::endpoint_key(&request("http://example.com/api/users/1?x=1"));
+        let b = AuthzScanner::endpoint_key(&request("http://example.com/api/users/2"));
+        assert_eq!(a, b);
+        assert_eq!(a, "GET example.com/api/users/{id}");
+    }
+}
@@ -0,0 +1,233 @@
+use crate::core::config::AppConfig;
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::scanners::Scanner;
+use crate::handler::scan::utils::http;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::debug;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// 攻击者控制的域名，使用保留顶级域名.example，不会解析到真实站点
+const ATTACKER_DOMAIN: &str = "rshield-attacker.example";
+
+/// 测试Origin
+#[derive(Debug, Clone, PartialEq)]
+struct OriginProbe {
+    /// 测试类型
+    kind: &'static str,
+    /// 发送的Origin
+    origin: String,
+    /// 允许携带凭证时的风险级别
+    risk_with_credentials: &'static str,
+}
+
+/// CORS配置错误扫描器
+#[derive(Clone)]
+pub struct CorsScanner {
+    /// HTTP客户端
+    http_client: reqwest::Client,
+}
+
+impl CorsScanner {
+    /// 创建新的CORS扫描器
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        // 只关心目标自身返回的CORS头，不跟随跳转
+        let http_client = http::client(Duration::from_millis(config.scanner.timeout_ms), false);
+
+        Self { http_client }
+    }
+
+    /// 根据目标地址生成测试Origin
+    fn generate_origins(url: &Url) -> Vec<OriginProbe> {
+        let scheme = url.scheme();
+        let host = url.host_str().unwrap_or_default();
+        // 去掉www前缀后的主域名，用于构造前缀/后缀绕过
+        let domain = host.strip_prefix("www.").unwrap_or(host);
+
+        let probe = |kind, origin: String, risk| OriginProbe {
+            kind,
+            origin,
+            risk_with_credentials: risk,
+        };
+
+        let mut origins = vec![
+            probe("任意来源反射", format!("{}://{}", scheme, ATTACKER_DOMAIN), "High"),
+            probe("null来源", "null".to_string(), "High"),
+            // 只校验前缀，例如 ^https://example.com
+            probe("后缀域名绕过", format!("{}://{}.{}", scheme, domain, ATTACKER_DOMAIN), "High"),
+            // 只校验后缀，例如 example.com$
+            probe("前缀域名绕过", format!("{}://{}{}", scheme, ATTACKER_DOMAIN.split('.').next().unwrap_or_default(), domain), "High"),
+            // 正则中未转义的点
+            probe("未转义点号绕过", format!("{}://{}", scheme, Self::unescaped_dot_variant(host)), "High"),
+            // 子域名可信，需配合子域名XSS或接管利用
+            probe("任意子域名", format!("{}://rshield.{}", scheme, domain), "Medium"),
+        ];
+
+        if scheme == "https" {
+            // 信任HTTP来源，中间人可注入脚本读取HTTPS响应
+            origins.push(probe("HTTP降级来源", format!("http://{}", host), "Medium"));
+        }
+
+        origins
+    }
+
+    /// 将域名中最后一个点之前的点替换为字母，例如 www.example.com -> wwwxexample.com
+    fn unescaped_dot_variant(domain: &str) -> String {
+        match domain.rfind('.') {
+            Some(last) => match domain[..last].rfind('.') {
+                Some(idx) => format!("{}x{}", &domain[..idx], &domain[idx + 1..]),
+                None => format!("rshieldx{}", domain),
+            },
+            None => format!("rshieldx{}", domain),
+        }
+    }
+
+    /// 大小写无关地获取头部
+    fn header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.trim())
+    }
+
+    /// 分析响应，返回(是否允许该来源, 是否允许携带凭证)
+    fn evaluate(origin: &str, response: &HttpResponse) -> (bool, bool) {
+        let allow_origin = Self::header(&response.headers, "access-control-allow-origin").unwrap_or("");
+        let allow_credentials = Self::header(&response.headers, "access-control-allow-credentials")
+            .map(|v| v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        (allow_origin == origin, allow_credentials)
+    }
+
+    /// 构造携带指定Origin的测试请求
+    fn origin_request(request: &HttpRequest, origin: &str) -> HttpRequest {
+        let mut test_request = request.clone();
+        test_request.headers.retain(|key, _| !key.eq_ignore_ascii_case("origin"));
+        test_request.headers.insert("Origin".to_string(), origin.to_string());
+        test_request
+    }
+
+    /// 发送测试请求
+    async fn send_request(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        http::send_request(&self.http_client, request).await
+    }
+}
+
+#[async_trait]
+impl Scanner for CorsScanner {
+    async fn name(&self) -> String {
+        "CORS Scanner".to_string()
+    }
+
+    async fn scan(&self, request: &HttpRequest, _response: &HttpResponse) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+        let url = match Url::parse(&request.url) {
+            Ok(url) => url,
+            Err(_) => return results,
+        };
+
+        for probe in Self::generate_origins(&url) {
+            let test_request = Self::origin_request(request, &probe.origin);
+            let response = match self.send_request(&test_request).await {
+                Ok(response) => response,
+                Err(e) => {
+                    debug!("CORS测试请求失败: {}", e);
+                    continue;
+                }
+            };
+
+            let (allowed, credentials) = Self::evaluate(&probe.origin, &response);
+            if !allowed {
+                continue;
+            }
+
+            // 不允许携带凭证时只能读取公开数据
+            let risk_level = if credentials { probe.risk_with_credentials } else { "Low" };
+            let evidence = format!(
+                "Origin: {}\nAccess-Control-Allow-Origin: {}{}",
+                probe.origin,
+                probe.origin,
+                if credentials { "\nAccess-Control-Allow-Credentials: true" } else { "" }
+            );
+
+            results.push(ScanResult {
+                vulnerability_type: "CORS".to_string(),
+                name: "CORS配置错误".to_string(),
+                description: format!(
+                    "服务端信任了不可信的Origin（{}），{}",
+                    probe.kind,
+                    if credentials {
+                        "且允许携带凭证，恶意站点可以以受害者身份读取响应内容"
+                    } else {
+                        "但未允许携带凭证，恶意站点只能读取无需认证的内容"
+                    }
+                ),
+                risk_level: risk_level.to_string(),
+                url: request.url.to_string(),
+                method: request.method.to_string(),
+                parameter: Some("Origin".to_string()),
+                value: Some(probe.origin.clone()),
+                evidence: Some(evidence),
+                remediation: Some("使用精确匹配的可信来源白名单，不要反射请求中的Origin，也不要信任null和HTTP来源".to_string()),
+                details: Some(format!("测试类型: {}\n允许携带凭证: {}", probe.kind, credentials)),
+                timestamp: chrono::Utc::now(),
+                request_details: None,
+                response_details: None,
+                confidence: Confidence::Certain,
+                payload_ranges: Vec::new(),
+                evidence_ranges: Vec::new(),
+            }
+            .with_exchange(&test_request, Some(&response), Some(probe.origin.as_str()), Some(probe.origin.as_str())));
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cors_scanner_name() {
+        let scanner = CorsScanner::new(Arc::new(AppConfig::default()));
+        assert_eq!(scanner.name().await, "CORS Scanner");
+    }
+
+    #[test]
+    fn test_generate_origins() {
+        let url = Url::parse("https://www.example.com/api").unwrap();
+        let origins: Vec<String> = CorsScanner::generate_origins(&url).into_iter().map(|o| o.origin).collect();
+        assert!(origins.contains(&"https://rshield-attacker.example".to_string()));
+        assert!(origins.contains(&"null".to_string()));
+        assert!(origins.contains(&"https://example.com.rshield-attacker.example".to_string()));
+        assert!(origins.contains(&"https://rshield-attackerexample.com".to_string()));
+        assert!(origins.contains(&"https://wwwxexample.com".to_string()));
+        assert!(origins.contains(&"https://rshield.example.com".to_string()));
+        assert!(origins.contains(&"http://www.example.com".to_string()));
+
+        let http_url = Url::parse("http://example.com/").unwrap();
+        assert!(!CorsScanner::generate_origins(&http_url).iter().any(|o| o.kind == "HTTP降级来源"));
+    }
+
+    #[test]
+    fn test_unescaped_dot_variant() {
+        assert_eq!(CorsScanner::unescaped_dot_variant("www.example.com"), "wwwxexample.com");
+        assert_eq!(CorsScanner::unescaped_dot_variant("example.com"), "rshieldxexample.com");
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let mut headers = HashMap::new();
+        headers.insert("Access-Control-Allow-Origin".to_string(), "null".to_string());
+        headers.insert("Access-Control-Allow-Credentials".to_string(), "true".to_string());
+        let response = HttpResponse { status: 200, headers, body: Vec::new() };
+
+        assert_eq!(CorsScanner::evaluate("null", &response), (true, true));
+        assert_eq!(CorsScanner::evaluate("https://rshield-attacker.example", &response), (false, true));
+    }
+}
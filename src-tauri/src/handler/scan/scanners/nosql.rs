@@ -0,0 +1,484 @@
+use crate::core::config::{AppConfig, InsertionPointType, ScannerConfig};
+use crate::handler::scan::engine::result::find_ranges;
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::scanners::xss::{compute_term_frequency, cosine_similarity};
+use crate::handler::scan::scanners::Scanner;
+use crate::handler::scan::utils::{generate_random_string, http, insertion};
+use anyhow::Result;
+use async_trait::async_trait;
+use log::debug;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// MongoDB及常见ODM的错误特征
+const ERROR_SIGNATURES: &[&str] = &[
+    "MongoError",
+    "MongoServerError",
+    "MongoDB.Driver",
+    "com.mongodb.",
+    "pymongo.errors",
+    "unknown operator: $",
+    "BadValue",
+    "$where is not allowed",
+    "CastError: Cast to",
+    "Cast to ObjectId failed",
+    "Mongoose",
+    "SyntaxError: unterminated string literal",
+    "JavaScript execution failed",
+];
+
+/// 恒真操作符：(操作符, 值)，值为空时使用随机串
+const TRUE_OPERATORS: &[(&str, &str)] = &[("$ne", ""), ("$gt", ""), ("$regex", ".*")];
+
+/// $where注入时的延迟（毫秒）
+const SLEEP_MS: u64 = 3000;
+
+/// 回溯爆炸的正则，服务端对长字段执行时会明显变慢
+const REDOS_PATTERN: &str = "^(.*.*)*!$";
+
+/// 判断两个响应相似的余弦相似度阈值
+const SIMILARITY_THRESHOLD: f64 = 0.95;
+
+/// 响应摘要
+struct Snapshot {
+    status: u16,
+    body: String,
+    terms: HashMap<String, f64>,
+    /// 原始响应，用于结果展示
+    response: HttpResponse,
+}
+
+/// 证明漏洞的载荷、证据以及对应的测试请求/响应
+struct Proof {
+    payload: String,
+    evidence: String,
+    request: HttpRequest,
+    response: Option<HttpResponse>,
+}
+
+impl Snapshot {
+    fn from_response(response: &HttpResponse) -> Self {
+        let body = String::from_utf8_lossy(&response.body).to_string();
+        Self {
+            status: response.status,
+            terms: compute_term_frequency(&body),
+            body,
+            response: response.clone(),
+        }
+    }
+
+    /// 状态码一致且词频向量的余弦相似度高于阈值
+    fn similar(&self, other: &Snapshot) -> bool {
+        if self.status != other.status {
+            return false;
+        }
+        if self.body == other.body {
+            return true;
+        }
+        cosine_similarity(&self.terms, &other.terms) >= SIMILARITY_THRESHOLD
+    }
+}
+
+/// NoSQL注入扫描器
+#[derive(Clone)]
+pub struct NoSqlInjectionScanner {
+    /// 配置
+    config: Arc<AppConfig>,
+    /// HTTP客户端
+    http_client: reqwest::Client,
+}
+
+impl NoSqlInjectionScanner {
+    /// 创建新的NoSQL注入扫描器
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        // 时间型检测需要等待延迟载荷返回
+        let timeout = Duration::from_millis(config.scanner.timeout_ms.max(SLEEP_MS * 3));
+        let http_client = http::client(timeout, true);
+        Self { config, http_client }
+    }
+
+    /// 收集插入点：查询参数、表单参数和JSON字段，已是操作符形式（`name[$op]`）的参数跳过
+    fn insertion_points(request: &HttpRequest, scanner: &ScannerConfig) -> Vec<(String, String)> {
+        insertion::params(request, scanner)
+            .into_iter()
+            .filter(|(name, _)| !name.contains('['))
+            .collect()
+    }
+
+    /// 构造操作符注入请求，查询或表单参数注入为 `user[$ne]=x`，JSON字段注入为 `{"user": {"$ne": "x"}}`
+    fn operator_request(request: &HttpRequest, name: &str, operator: &str, value: &str) -> Option<HttpRequest> {
+        insertion::with_param_renamed(request, name, &format!("{}[{}]", name, operator), value)
+            .or_else(|| insertion::with_json_value(request, name, json!({ operator: value })))
+    }
+
+    /// 在JSON请求体顶层加入$where条件
+    fn where_request(request: &HttpRequest, condition: &str) -> Option<HttpRequest> {
+        let mut json = serde_json::from_slice::<Value>(&request.body).ok()?;
+        json.as_object_mut()?.insert("$where".to_string(), Value::String(condition.to_string()));
+        let mut test_request = request.clone();
+        test_request.body = serde_json::to_vec(&json).ok()?;
+        Some(test_request)
+    }
+
+    /// $where字符串上下文中的布尔载荷：(真条件, 假条件, 对照假条件)
+    fn where_payloads(value: &str) -> Vec<(String, String, String)> {
+        ['\'', '"']
+            .iter()
+            .map(|q| {
+                (
+                    format!("{v}{q} || {q}1{q}=={q}1", v = value, q = q),
+                    format!("{v}{q} && {q}1{q}=={q}2", v = value, q = q),
+                    format!("{v}{q} && {q}2{q}=={q}3", v = value, q = q),
+                )
+            })
+            .collect()
+    }
+
+    /// 发送测试请求
+    async fn send_request(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        Ok(self.send_request_timed(request).await?.0)
+    }
+
+    /// 发送测试请求，同时返回不含限速排队时间的耗时
+    async fn send_request_timed(&self, request: &HttpRequest) -> Result<(HttpResponse, Duration)> {
+        http::send_timed(http::build_request(&self.http_client, request)?).await
+    }
+
+    async fn snapshot(&self, request: Option<HttpRequest>) -> Option<Snapshot> {
+        match self.send_request(&request?).await {
+            Ok(response) => Some(Snapshot::from_response(&response)),
+            Err(e) => {
+                debug!("NoSQL注入测试请求失败: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 查找响应中新出现的数据库错误特征
+    fn match_error(original_body: &str, body: &str) -> Option<&'static str> {
+        ERROR_SIGNATURES
+            .iter()
+            .find(|sig| body.contains(*sig) && !original_body.contains(*sig))
+            .copied()
+    }
+
+    /// 布尔差异判定：两次假条件响应一致（页面稳定），真条件与假条件不同
+    fn boolean_differs(true_snap: &Snapshot, false_snap: &Snapshot, control: &Snapshot) -> bool {
+        true_snap.status < 500 && false_snap.similar(control) && !true_snap.similar(false_snap)
+    }
+
+    /// 操作符注入布尔检测
+    async fn detect_operator_boolean(&self, request: &HttpRequest, name: &str) -> Option<Proof> {
+        let (r1, r2) = (generate_random_string(8), generate_random_string(8));
+        let false_snap = self.snapshot(Self::operator_request(request, name, "$eq", &r1)).await?;
+        let control = self.snapshot(Self::operator_request(request, name, "$eq", &r2)).await?;
+
+        for (operator, value) in TRUE_OPERATORS {
+            let value = if value.is_empty() && *operator == "$ne" { r1.as_str() } else { value };
+            let true_request = match Self::operator_request(request, name, operator, value) {
+                Some(test_request) => test_request,
+                None => continue,
+            };
+            let true_snap = match self.snapshot(Some(true_request.clone())).await {
+                Some(snap) => snap,
+                None => continue,
+            };
+            if Self::boolean_differs(&true_snap, &false_snap, &control) {
+                return Some(Proof {
+                    payload: format!("{}[{}]={}", name, operator, value),
+                    evidence: format!(
+                        "恒真条件 {} 返回状态 {}（{} 字节），恒假条件 $eq 返回状态 {}（{} 字节），两次恒假请求响应一致",
+                        operator,
+                        true_snap.status,
+                        true_snap.body.len(),
+                        false_snap.status,
+                        false_snap.body.len()
+                    ),
+                    request: true_request,
+                    response: Some(true_snap.response),
+                });
+            }
+        }
+        None
+    }
+
+    /// $where JavaScript注入布尔检测
+    async fn detect_where_boolean(&self, request: &HttpRequest, name: &str, value: &str) -> Option<Proof> {
+        for (t, f, c) in Self::where_payloads(value) {
+            let true_request = insertion::with_param(request, name, &t);
+            let true_snap = self.snapshot(Some(true_request.clone())).await?;
+            let false_snap = self.snapshot(Some(insertion::with_param(request, name, &f))).await?;
+            let control = self.snapshot(Some(insertion::with_param(request, name, &c))).await?;
+            if Self::boolean_differs(&true_snap, &false_snap, &control) {
+                return Some(Proof {
+                    evidence: format!("JavaScript条件 {} 与 {} 返回不同响应，两次假条件响应一致", t, f),
+                    payload: t,
+                    request: true_request,
+                    response: Some(true_snap.response),
+                });
+            }
+        }
+        None
+    }
+
+    /// 发送请求并返回耗时
+    async fn timed(&self, request: &HttpRequest) -> Option<Duration> {
+        self.send_request_timed(request).await.ok().map(|(_, elapsed)| elapsed)
+    }
+
+    /// 时间差异判定：载荷耗时显著高于基准，并重放一次确认
+    async fn confirm_delay(&self, test_request: &HttpRequest, baseline: Duration, min_delay: Duration) -> Option<Duration> {
+        let threshold = (baseline * 5).max(baseline + min_delay);
+        let first = self.timed(test_request).await?;
+        if first < threshold {
+            return None;
+        }
+        let second = self.timed(test_request).await?;
+        (second >= threshold).then_some(first.min(second))
+    }
+
+    /// 时间型检测（$where sleep和$regex回溯）
+    async fn detect_time(&self, request: &HttpRequest, name: &str, value: &str) -> Option<Proof> {
+        let baseline = self.timed(request).await?.max(self.timed(request).await?);
+        let sleep_delay = Duration::from_millis(SLEEP_MS * 4 / 5);
+
+        let mut candidates: Vec<(String, HttpRequest, Duration)> = ['\'', '"']
+            .iter()
+            .map(|q| {
+                let payload = format!("{v}{q};sleep({ms});{q}", v = value, q = q, ms = SLEEP_MS);
+                let test_request = insertion::with_param(request, name, &payload);
+                (payload, test_request, sleep_delay)
+            })
+            .collect();
+        if let Some(test_request) = Self::operator_request(request, name, "$regex", REDOS_PATTERN) {
+            candidates.push((format!("{}[$regex]={}", name, REDOS_PATTERN), test_request, Duration::from_secs(2)));
+        }
+
+        for (payload, test_request, min_delay) in candidates {
+            if let Some(elapsed) = self.confirm_delay(&test_request, baseline, min_delay).await {
+                return Some(Proof {
+                    payload,
+                    evidence: format!("基准响应时间: {:?}, 注入后响应时间: {:?}（重放确认）", baseline, elapsed),
+                    request: test_request,
+                    response: None,
+                });
+            }
+        }
+        None
+    }
+
+    /// 构造扫描结果
+    fn build_result(request: &HttpRequest, parameter: &str, name: &str, proof: Proof, details: String) -> ScanResult {
+        ScanResult {
+            vulnerability_type: "NoSQL Injection".to_string(),
+            name: name.to_string(),
+            description: "检测到NoSQL注入漏洞，用户输入被当作查询操作符或JavaScript条件传入MongoDB等文档数据库，可绕过认证或读取任意数据".to_string(),
+            risk_level: "High".to_string(),
+            url: request.url.to_string(),
+            method: request.method.to_string(),
+            parameter: Some(parameter.to_string()),
+            value: Some(proof.payload.clone()),
+            evidence: Some(proof.evidence),
+            remediation: Some("对查询参数做类型校验，拒绝对象和以$开头的键（如使用mongo-sanitize），禁用$where和服务端JavaScript".to_string()),
+            details: Some(details),
+            timestamp: chrono::Utc::now(),
+            request_details: None,
+            response_details: None,
+            confidence: Confidence::Firm,
+            payload_ranges: Vec::new(),
+            evidence_ranges: Vec::new(),
+        }
+        .with_exchange(&proof.request, proof.response.as_ref(), Some(proof.payload.as_str()), None)
+    }
+}
+
+#[async_trait]
+impl Scanner for NoSqlInjectionScanner {
+    async fn name(&self) -> String {
+        "NoSQL Injection Scanner".to_string()
+    }
+
+    async fn scan(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+        let original_body = String::from_utf8_lossy(&response.body).to_string();
+
+        let points = Self::insertion_points(request, &self.config.scanner);
+        for (name, value) in &points {
+            // 1. 操作符注入，对比恒真/恒假条件
+            if let Some(proof) = self.detect_operator_boolean(request, name).await {
+                results.push(Self::build_result(
+                    request,
+                    name,
+                    "NoSQL操作符注入漏洞",
+                    proof,
+                    format!("参数 {} 接受查询操作符对象，恒真条件改变了查询结果", name),
+                ));
+                continue;
+            }
+
+            // 2. 错误型，操作符或引号导致数据库报错
+            let quoted = format!("{}'\"\\", value);
+            let probes = [
+                (
+                    format!("{}[$rsinvalid]=1", name),
+                    Self::operator_request(request, name, "$rsinvalid", "1"),
+                ),
+                (quoted.clone(), Some(insertion::with_param(request, name, &quoted))),
+            ];
+            let mut found = false;
+            for (payload, test_request) in probes {
+                let Some(test_request) = test_request else {
+                    continue;
+                };
+                if let Some(snap) = self.snapshot(Some(test_request.clone())).await {
+                    if let Some(sig) = Self::match_error(&original_body, &snap.body) {
+                        let proof = Proof {
+                            payload,
+                            evidence: format!("响应中出现数据库错误特征: {}", sig),
+                            request: test_request,
+                            response: Some(snap.response),
+                        };
+                        let mut result = Self::build_result(
+                            request,
+                            name,
+                            "NoSQL注入漏洞（错误回显）",
+                            proof,
+                            format!("参数 {} 的畸形输入导致NoSQL数据库报错", name),
+                        );
+                        result.risk_level = "Medium".to_string();
+                        result.evidence_ranges = result
+                            .response_details
+                            .as_deref()
+                            .map(|raw| find_ranges(raw, sig))
+                            .unwrap_or_default();
+                        results.push(result);
+                        found = true;
+                        break;
+                    }
+                }
+            }
+            if found {
+                continue;
+            }
+
+            // 3. $where JavaScript注入，字符串上下文的布尔差异
+            if let Some(proof) = self.detect_where_boolean(request, name, value).await {
+                results.push(Self::build_result(
+                    request,
+                    name,
+                    "NoSQL $where JavaScript注入漏洞",
+                    proof,
+                    format!("参数 {} 被拼接进$where条件作为JavaScript执行", name),
+                ));
+                continue;
+            }
+
+            // 4. 时间型，$where sleep和$regex回溯
+            if let Some(proof) = self.detect_time(request, name, value).await {
+                results.push(Self::build_result(
+                    request,
+                    name,
+                    "时间型NoSQL注入漏洞",
+                    proof,
+                    format!("参数 {} 注入延迟载荷后响应明显变慢", name),
+                ));
+            }
+        }
+
+        // JSON请求体顶层的$where条件
+        if results.is_empty()
+            && self.config.scanner.allows(InsertionPointType::Json)
+            && !insertion::json_params(request).is_empty()
+        {
+            let false_snap = self.snapshot(Self::where_request(request, "1==2")).await;
+            let control = self.snapshot(Self::where_request(request, "2==3")).await;
+            let true_request = Self::where_request(request, "1==1");
+            let true_snap = self.snapshot(true_request.clone()).await;
+            if let (Some(t), Some(f), Some(c), Some(true_request)) = (true_snap, false_snap, control, true_request) {
+                if Self::boolean_differs(&t, &f, &c) {
+                    let proof = Proof {
+                        payload: "\"$where\":\"1==1\"".to_string(),
+                        evidence: "请求体顶层的$where条件1==1与1==2返回不同响应".to_string(),
+                        request: true_request,
+                        response: Some(t.response),
+                    };
+                    results.push(Self::build_result(
+                        request,
+                        "$where",
+                        "NoSQL $where JavaScript注入漏洞",
+                        proof,
+                        "服务端将JSON请求体直接作为查询条件，可注入$where执行JavaScript".to_string(),
+                    ));
+                }
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(status: u16, body: &str) -> Snapshot {
+        Snapshot::from_response(&HttpResponse::new(status, HashMap::new(), body.as_bytes().to_vec()))
+    }
+
+    #[tokio::test]
+    async fn test_nosql_scanner_name() {
+        let scanner = NoSqlInjectionScanner::new(Arc::new(AppConfig::default()));
+        assert_eq!(scanner.name().await, "NoSQL Injection Scanner");
+    }
+
+    #[test]
+    fn test_operator_request() {
+        let mut headers = HashMap::new();
+        headers.insert("Content-Type".to_string(), "application/json".to_string());
+        let request = HttpRequest {
+            method: "POST".to_string(),
+            url: "http://example.com/login?from=home".to_string(),
+            headers,
+            body: br#"{"user":"admin","pass":"x"}"#.to_vec(),
+            params: vec![("from".to_string(), "home".to_string())],
+        };
+
+        let points = NoSqlInjectionScanner::insertion_points(&request, &AppConfig::default().scanner);
+        assert_eq!(points.len(), 3);
+
+        let query = NoSqlInjectionScanner::operator_request(&request, "from", "$ne", "a").unwrap();
+        assert_eq!(query.params, vec![("from[$ne]".to_string(), "a".to_string())]);
+
+        let body = NoSqlInjectionScanner::operator_request(&request, "pass", "$gt", "").unwrap();
+        let json: Value = serde_json::from_slice(&body.body).unwrap();
+        assert_eq!(json["pass"], json!({"$gt": ""}));
+        assert_eq!(json["user"], "admin");
+
+        let where_request = NoSqlInjectionScanner::where_request(&request, "1==1").unwrap();
+        let json: Value = serde_json::from_slice(&where_request.body).unwrap();
+        assert_eq!(json["$where"], "1==1");
+    }
+
+    #[test]
+    fn test_boolean_differs() {
+        let login_ok = snapshot(200, "welcome back admin your dashboard");
+        let login_fail = snapshot(200, "invalid username or password");
+        assert!(NoSqlInjectionScanner::boolean_differs(&login_ok, &login_fail, &login_fail));
+        // 页面本身不稳定时不报告
+        let random = snapshot(200, "request id 83a1 completed");
+        assert!(!NoSqlInjectionScanner::boolean_differs(&login_ok, &login_fail, &random));
+        // 真条件报错不报告
+        let error = snapshot(500, "internal error");
+        assert!(!NoSqlInjectionScanner::boolean_differs(&error, &login_fail, &login_fail));
+    }
+
+    #[test]
+    fn test_match_error() {
+        let body = "MongoServerError: unknown operator: $rsinvalid";
+        assert_eq!(NoSqlInjectionScanner::match_error("", body), Some("MongoServerError"));
+        assert_eq!(NoSqlInjectionScanner::match_error(body, body), None);
+    }
+}
@@ -0,0 +1,311 @@
+use crate::core::config::AppConfig;
+use crate::handler::scan::ast::{self, html::parse_refresh_url};
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::scanners::Scanner;
+use crate::handler::scan::utils::{http, insertion};
+use anyhow::Result;
+use async_trait::async_trait;
+use log::debug;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// 注入的外部域名（保留域名，不会真实解析）
+const CANARY_HOST: &str = "rshield-canary.example.com";
+
+/// 开放重定向扫描器
+#[derive(Clone)]
+pub struct OpenRedirectScanner {
+    /// 配置
+    config: Arc<AppConfig>,
+    /// HTML AST分析器
+    html_analyzer: Arc<ast::HtmlAstAnalyzer>,
+    /// JavaScript AST分析器
+    js_analyzer: Arc<ast::JsAstAnalyzer>,
+    /// 不跟随跳转的HTTP客户端
+    http_client: reqwest::Client,
+    /// 跳转类参数名
+    redirect_params: Vec<String>,
+}
+
+impl OpenRedirectScanner {
+    /// 创建新的开放重定向扫描器
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        // 必须禁止自动跳转，否则拿不到Location头
+        let http_client = http::client(Duration::from_millis(config.scanner.timeout_ms), false);
+
+        let redirect_params = [
+            "url", "uri", "next", "return", "returnto", "return_to", "returnurl", "return_url",
+            "redirect", "redirect_to", "redirect_uri", "redirect_url", "redirecturl", "redir",
+            "goto", "target", "to", "dest", "destination", "continue", "callback", "forward",
+            "out", "view", "link", "jump", "jump_url", "back", "backurl", "service", "checkout_url",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        Self {
+            config,
+            html_analyzer: Arc::new(ast::HtmlAstAnalyzer::new()),
+            js_analyzer: Arc::new(ast::JsAstAnalyzer::new()),
+            http_client,
+            redirect_params,
+        }
+    }
+
+    /// 判断参数是否可能用于跳转
+    fn is_redirect_param(&self, name: &str, value: &str) -> bool {
+        let name = name.to_lowercase();
+        if self.redirect_params.contains(&name) {
+            return true;
+        }
+        if name.contains("redirect") || name.contains("return") || name.ends_with("url") {
+            return true;
+        }
+
+        // 参数值本身像URL或路径
+        let value = urlencoding::decode(value)
+            .map(|v| v.to_string())
+            .unwrap_or_else(|_| value.to_string())
+            .to_lowercase();
+        value.starts_with("http://") || value.starts_with("https://") || value.starts_with("//")
+    }
+
+    /// 生成重定向测试载荷，包含常见的白名单绕过变体
+    fn generate_payloads(&self, original_host: &str) -> Vec<String> {
+        let encoded_dots = CANARY_HOST.replace('.', "%2e");
+        vec![
+            format!("https://{}/", CANARY_HOST),
+            format!("//{}/", CANARY_HOST),
+            format!("\\/\\/{}/", CANARY_HOST),
+            format!("/\\{}/", CANARY_HOST),
+            format!("https://{}@{}/", original_host, CANARY_HOST),
+            format!("//{}@{}/", original_host, CANARY_HOST),
+            format!("//{}/", encoded_dots),
+            format!("https://{}%23.{}/", CANARY_HOST, original_host),
+        ]
+    }
+
+    /// 判断跳转目标解析后是否指向注入的外部域名
+    fn points_to_canary(base: &Url, target: &str) -> bool {
+        let target = target.trim();
+        if target.is_empty() {
+            return false;
+        }
+        match base.join(target) {
+            Ok(resolved) => resolved.host_str().map_or(false, |host| {
+                let host = host.trim_end_matches('.').to_lowercase();
+                host == CANARY_HOST || host.ends_with(&format!(".{}", CANARY_HOST))
+            }),
+            Err(_) => false,
+        }
+    }
+
+    /// 在响应中查找指向外部域名的跳转，返回(跳转方式, 跳转目标)
+    fn find_redirect_sink(&self, request_url: &str, response: &HttpResponse) -> Option<(String, String)> {
+        let base = Url::parse(request_url).ok()?;
+
+        for (name, value) in &response.headers {
+            // Location响应头
+            if name.eq_ignore_ascii_case("location")
+                && (300..400).contains(&response.status)
+                && Self::points_to_canary(&base, value)
+            {
+                return Some(("Location响应头".to_string(), value.clone()));
+            }
+
+            // Refresh响应头
+            if name.eq_ignore_ascii_case("refresh") {
+                if let Some(url) = parse_refresh_url(value) {
+                    if Self::points_to_canary(&base, &url) {
+                        return Some(("Refresh响应头".to_string(), url));
+                    }
+                }
+            }
+        }
+
+        let is_html = response.headers.iter().any(|(name, value)| {
+            name.eq_ignore_ascii_case("content-type") && value.to_lowercase().contains("html")
+        });
+        if !is_html || response.body.len() > 1024 * 1024 {
+            return None;
+        }
+
+        let body = String::from_utf8_lossy(&response.body);
+        if !body.contains(CANARY_HOST) && !body.contains(&CANARY_HOST.replace('.', "%2e")) {
+            return None;
+        }
+
+        // meta refresh
+        for url in self.html_analyzer.extract_meta_refresh_urls(&body) {
+            if Self::points_to_canary(&base, &url) {
+                return Some(("meta refresh".to_string(), url));
+            }
+        }
+
+        // JavaScript location sink
+        for script in self.html_analyzer.extract_inline_scripts(&body) {
+            for sink in self.js_analyzer.extract_location_sinks(&script) {
+                if Self::points_to_canary(&base, &sink.content) {
+                    let sink_name = sink.node_type.trim_start_matches("sink:").to_string();
+                    return Some((format!("JavaScript {}", sink_name), sink.content));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// 发送测试请求（不跟随跳转）
+    async fn send_request(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        http::send_request(&self.http_client, request).await
+    }
+
+    /// 收集可能用于跳转的参数，返回(参数名, 参数值)
+    fn collect_candidates(&self, request: &HttpRequest) -> Vec<(String, String)> {
+        insertion::params(request, &self.config.scanner)
+            .into_iter()
+            .filter(|(name, value)| self.is_redirect_param(name, value))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Scanner for OpenRedirectScanner {
+    async fn name(&self) -> String {
+        "Open Redirect Scanner".to_string()
+    }
+
+    async fn scan(&self, request: &HttpRequest, _response: &HttpResponse) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+
+        let original_host = match Url::parse(&request.url) {
+            Ok(url) => url.host_str().unwrap_or("localhost").to_string(),
+            Err(_) => return results,
+        };
+
+        for (param_name, param_value) in self.collect_candidates(request) {
+            for payload in self.generate_payloads(&original_host) {
+                let test_request = insertion::with_param(request, &param_name, &payload);
+                let test_response = match self.send_request(&test_request).await {
+                    Ok(resp) => resp,
+                    Err(e) => {
+                        debug!("开放重定向测试请求失败: {}", e);
+                        continue;
+                    }
+                };
+
+                if let Some((sink, target)) = self.find_redirect_sink(&request.url, &test_response) {
+                    results.push(ScanResult {
+                        vulnerability_type: "Open Redirect".to_string(),
+                        name: "开放重定向漏洞".to_string(),
+                        description: "检测到开放重定向漏洞，攻击者可以构造链接将用户跳转到任意外部站点，常用于钓鱼或窃取OAuth授权码".to_string(),
+                        risk_level: "Medium".to_string(),
+                        url: request.url.to_string(),
+                        method: request.method.to_string(),
+                        parameter: Some(param_name.clone()),
+                        value: Some(payload.clone()),
+                        evidence: Some(format!("{}: {}", sink, target)),
+                        remediation: Some("跳转目标使用白名单校验，仅允许站内相对路径，或使用映射ID代替完整URL".to_string()),
+                        details: Some(format!(
+                            "参数 {} (原值 {}) 注入 {} 后，响应通过{}跳转到外部域名 {}",
+                            param_name, param_value, payload, sink, CANARY_HOST
+                        )),
+                        timestamp: chrono::Utc::now(),
+                        request_details: None,
+                        response_details: None,
+                        confidence: Confidence::Firm,
+                        payload_ranges: Vec::new(),
+                        evidence_ranges: Vec::new(),
+                    }
+                    .with_exchange(&test_request, Some(&test_response), Some(payload.as_str()), Some(target.as_str())));
+                    // 同一参数找到一个可用载荷即可
+                    break;
+                }
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_response(status: u16, headers: Vec<(&str, &str)>, body: &str) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers: headers
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_redirect_scanner_name() {
+        let scanner = OpenRedirectScanner::new(Arc::new(AppConfig::default()));
+        assert_eq!(scanner.name().await, "Open Redirect Scanner");
+    }
+
+    #[test]
+    fn test_is_redirect_param() {
+        let scanner = OpenRedirectScanner::new(Arc::new(AppConfig::default()));
+        assert!(scanner.is_redirect_param("redirect_uri", "/home"));
+        assert!(scanner.is_redirect_param("returnUrl", "/home"));
+        assert!(scanner.is_redirect_param("q", "https%3A%2F%2Fexample.org%2F"));
+        assert!(!scanner.is_redirect_param("id", "42"));
+    }
+
+    #[test]
+    fn test_location_bypass_variants_resolve_to_canary() {
+        let base = Url::parse("https://target.test/login?next=/").unwrap();
+        let scanner = OpenRedirectScanner::new(Arc::new(AppConfig::default()));
+        for payload in scanner.generate_payloads("target.test") {
+            if payload.contains("%23") {
+                continue;
+            }
+            assert!(
+                OpenRedirectScanner::points_to_canary(&base, &payload),
+                "payload {} should resolve to canary",
+                payload
+            );
+        }
+        assert!(!OpenRedirectScanner::points_to_canary(&base, "/dashboard"));
+    }
+
+    #[test]
+    fn test_find_redirect_sink() {
+        let scanner = OpenRedirectScanner::new(Arc::new(AppConfig::default()));
+        let url = "https://target.test/login?next=x";
+
+        let location = create_test_response(302, vec![("Location", "//rshield-canary.example.com/")], "");
+        assert_eq!(
+            scanner.find_redirect_sink(url, &location).map(|(sink, _)| sink),
+            Some("Location响应头".to_string())
+        );
+
+        let meta = create_test_response(
+            200,
+            vec![("Content-Type", "text/html")],
+            r#"<html><head><meta http-equiv="refresh" content="0; url=https://rshield-canary.example.com/"></head></html>"#,
+        );
+        assert_eq!(
+            scanner.find_redirect_sink(url, &meta).map(|(sink, _)| sink),
+            Some("meta refresh".to_string())
+        );
+
+        let js = create_test_response(
+            200,
+            vec![("Content-Type", "text/html; charset=utf-8")],
+            r#"<html><script>window.location.href = "//rshield-canary.example.com/";</script></html>"#,
+        );
+        assert!(scanner.find_redirect_sink(url, &js).is_some());
+
+        let safe = create_test_response(302, vec![("Location", "/home")], "");
+        assert!(scanner.find_redirect_sink(url, &safe).is_none());
+    }
+}
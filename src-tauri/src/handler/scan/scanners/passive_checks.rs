@@ -0,0 +1,540 @@
+use crate::core::config::AppConfig;
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::rules::RuleManager;
+use crate::handler::scan::scanners::Scanner;
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// HTTPS页面中通过HTTP加载的主动/被动资源
+static MIXED_CONTENT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)<(script|iframe|img|link|audio|video|source|embed|object|form)\b[^>]*?\s(src|href|data|action)\s*=\s*["']?(http://[^"'\s>]+)"#).unwrap()
+});
+
+/// 带版本号的服务标识，例如 Apache/2.4.41
+static VERSION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[A-Za-z][\w.-]*/\d+(\.\d+)*").unwrap());
+
+/// HSTS最小有效期（180天）
+const HSTS_MIN_MAX_AGE: u64 = 15_552_000;
+
+/// 泄露技术栈信息的响应头
+const BANNER_HEADERS: &[&str] = &["x-powered-by", "x-aspnet-version", "x-aspnetmvc-version", "x-generator", "x-runtime"];
+
+/// 被动检查发现的问题
+#[derive(Debug, Clone, PartialEq)]
+struct Finding {
+    /// 去重标识
+    id: String,
+    name: String,
+    description: String,
+    risk_level: &'static str,
+    evidence: String,
+    remediation: String,
+}
+
+impl Finding {
+    fn new(id: impl Into<String>, name: &str, description: &str, risk_level: &'static str, evidence: String, remediation: &str) -> Self {
+        Self {
+            id: id.into(),
+            name: name.to_string(),
+            description: description.to_string(),
+            risk_level,
+            evidence,
+            remediation: remediation.to_string(),
+        }
+    }
+}
+
+/// 被动安全检查扫描器
+///
+/// 只分析代理记录的请求与响应，不发送任何额外流量。
+/// 除内置检查外，还会执行内置及扩展目录中的YAML规则。
+#[derive(Clone)]
+pub struct PassiveCheckScanner {
+    /// 声明式检测规则
+    rules: Arc<RuleManager>,
+    /// 本次扫描已上报的问题（主机|问题标识），同一主机的同类问题只上报一次
+    reported: Arc<Mutex<HashSet<String>>>,
+}
+
+impl PassiveCheckScanner {
+    /// 创建新的被动检查扫描器
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        Self {
+            rules: RuleManager::shared(config),
+            reported: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// 大小写无关地获取头部
+    fn header<'a>(headers: &'a std::collections::HashMap<String, String>, name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 是否为HTML响应
+    fn is_html(response: &HttpResponse) -> bool {
+        Self::header(&response.headers, "content-type")
+            .map(|ct| ct.to_lowercase().contains("text/html"))
+            .unwrap_or(false)
+    }
+
+    /// 检查CSP
+    fn check_csp(response: &HttpResponse) -> Vec<Finding> {
+        let remediation = "配置严格的Content-Security-Policy，避免使用 unsafe-inline、unsafe-eval 和通配符来源";
+        let csp = match Self::header(&response.headers, "content-security-policy") {
+            Some(csp) => csp.to_lowercase(),
+            None => {
+                if !Self::is_html(response) {
+                    return Vec::new();
+                }
+                return vec![Finding::new(
+                    "csp-missing",
+                    "缺少Content-Security-Policy",
+                    "HTML响应未设置CSP，无法在浏览器层面缓解XSS等注入攻击",
+                    "Low",
+                    "响应中不存在 Content-Security-Policy 头".to_string(),
+                    remediation,
+                )];
+            }
+        };
+
+        // 优先使用script-src，否则回退到default-src
+        let directive = |name: &str| {
+            csp.split(';')
+                .map(str::trim)
+                .find(|d| d.starts_with(name) && d[name.len()..].starts_with(' '))
+                .map(|d| d[name.len()..].trim().to_string())
+        };
+        let script_src = directive("script-src").or_else(|| directive("default-src"));
+
+        let mut weaknesses = Vec::new();
+        match &script_src {
+            Some(sources) => {
+                let tokens: Vec<&str> = sources.split_whitespace().collect();
+                // 存在nonce或hash时浏览器会忽略unsafe-inline
+                let has_nonce = tokens.iter().any(|t| t.starts_with("'nonce-") || t.starts_with("'sha"));
+                if tokens.contains(&"'unsafe-inline'") && !has_nonce {
+                    weaknesses.push("script-src 允许 'unsafe-inline'");
+                }
+                if tokens.contains(&"'unsafe-eval'") {
+                    weaknesses.push("script-src 允许 'unsafe-eval'");
+                }
+                if tokens.iter().any(|t| *t == "*" || *t == "http:" || *t == "https:") {
+                    weaknesses.push("script-src 允许任意来源");
+                }
+                if tokens.contains(&"data:") {
+                    weaknesses.push("script-src 允许 data: 来源");
+                }
+            }
+            None => weaknesses.push("未限制脚本来源（缺少 script-src/default-src）"),
+        }
+
+        if weaknesses.is_empty() {
+            return Vec::new();
+        }
+        vec![Finding::new(
+            "csp-weak",
+            "Content-Security-Policy配置薄弱",
+            "CSP策略存在可被绕过的配置，无法有效防御XSS",
+            "Low",
+            format!("Content-Security-Policy: {}\n问题: {}", csp, weaknesses.join("；")),
+            remediation,
+        )]
+    }
+
+    /// 检查HSTS
+    fn check_hsts(url: &Url, response: &HttpResponse) -> Vec<Finding> {
+        if url.scheme() != "https" {
+            return Vec::new();
+        }
+        let remediation = "设置 Strict-Transport-Security: max-age=31536000; includeSubDomains";
+
+        match Self::header(&response.headers, "strict-transport-security") {
+            None => vec![Finding::new(
+                "hsts-missing",
+                "缺少Strict-Transport-Security",
+                "HTTPS站点未启用HSTS，用户可能被降级到HTTP遭受中间人攻击",
+                "Low",
+                "响应中不存在 Strict-Transport-Security 头".to_string(),
+                remediation,
+            )],
+            Some(value) => {
+                let max_age = value
+                    .split(';')
+                    .map(str::trim)
+                    .find_map(|d| d.to_lowercase().strip_prefix("max-age=").map(|v| v.trim_matches('"').to_string()))
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .unwrap_or(0);
+                if max_age < HSTS_MIN_MAX_AGE {
+                    vec![Finding::new(
+                        "hsts-weak",
+                        "Strict-Transport-Security有效期过短",
+                        "HSTS的max-age过短，保护窗口不足",
+                        "Info",
+                        format!("Strict-Transport-Security: {}", value),
+                        remediation,
+                    )]
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+    }
+
+    /// 检查点击劫持防护
+    fn check_frame_options(response: &HttpResponse) -> Vec<Finding> {
+        if !Self::is_html(response) {
+            return Vec::new();
+        }
+        let has_frame_ancestors = Self::header(&response.headers, "content-security-policy")
+            .map(|csp| csp.to_lowercase().contains("frame-ancestors"))
+            .unwrap_or(false);
+        if has_frame_ancestors {
+            return Vec::new();
+        }
+
+        let remediation = "设置 X-Frame-Options: DENY/SAMEORIGIN，或使用CSP frame-ancestors 指令";
+        match Self::header(&response.headers, "x-frame-options") {
+            None => vec![Finding::new(
+                "xfo-missing",
+                "缺少X-Frame-Options",
+                "页面可被任意站点嵌入iframe，存在点击劫持风险",
+                "Low",
+                "响应中不存在 X-Frame-Options 头，CSP中也没有 frame-ancestors".to_string(),
+                remediation,
+            )],
+            Some(value) => {
+                let v = value.trim().to_uppercase();
+                if v == "DENY" || v == "SAMEORIGIN" {
+                    Vec::new()
+                } else {
+                    // ALLOW-FROM等取值已被现代浏览器忽略
+                    vec![Finding::new(
+                        "xfo-invalid",
+                        "X-Frame-Options取值无效",
+                        "X-Frame-Options取值不被现代浏览器支持，点击劫持防护失效",
+                        "Low",
+                        format!("X-Frame-Options: {}", value),
+                        remediation,
+                    )]
+                }
+            }
+        }
+    }
+
+    /// 检查Cookie属性
+    fn check_cookies(url: &Url, response: &HttpResponse) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let set_cookie = match Self::header(&response.headers, "set-cookie") {
+            Some(v) => v,
+            None => return findings,
+        };
+
+        for cookie in set_cookie.lines().map(str::trim).filter(|c| !c.is_empty()) {
+            let mut parts = cookie.split(';').map(str::trim);
+            let name = parts
+                .next()
+                .and_then(|kv| kv.split('=').next())
+                .unwrap_or("")
+                .to_string();
+            if name.is_empty() {
+                continue;
+            }
+            let attrs: Vec<String> = parts.map(|p| p.to_lowercase()).collect();
+            let has = |attr: &str| attrs.iter().any(|a| a == attr || a.starts_with(&format!("{}=", attr)));
+
+            let mut missing = Vec::new();
+            if url.scheme() == "https" && !has("secure") {
+                missing.push("Secure");
+            }
+            if !has("httponly") {
+                missing.push("HttpOnly");
+            }
+            if !has("samesite") {
+                missing.push("SameSite");
+            } else if attrs.iter().any(|a| a == "samesite=none") && !has("secure") {
+                missing.push("Secure（SameSite=None 要求）");
+            }
+
+            if missing.is_empty() {
+                continue;
+            }
+            findings.push(Finding::new(
+                format!("cookie-flags:{}", name),
+                "Cookie缺少安全属性",
+                "Cookie未设置必要的安全属性，可能被脚本读取、通过明文传输或在跨站请求中携带",
+                "Low",
+                format!("Set-Cookie: {}\n缺少属性: {}", cookie, missing.join(", ")),
+                "为Cookie设置 Secure、HttpOnly 和 SameSite=Lax/Strict 属性",
+            ));
+        }
+
+        findings
+    }
+
+    /// 检查CORS配置
+    fn check_cors(response: &HttpResponse) -> Vec<Finding> {
+        let origin = match Self::header(&response.headers, "access-control-allow-origin") {
+            Some(origin) => origin.trim(),
+            None => return Vec::new(),
+        };
+        let credentials = Self::header(&response.headers, "access-control-allow-credentials")
+            .map(|v| v.trim().eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
+        if !credentials || (origin != "*" && !origin.eq_ignore_ascii_case("null")) {
+            return Vec::new();
+        }
+        vec![Finding::new(
+            "cors-wildcard-credentials",
+            "CORS允许任意来源携带凭证",
+            "Access-Control-Allow-Origin为通配符或null且允许携带凭证，恶意站点可能读取用户的敏感数据",
+            "Medium",
+            format!("Access-Control-Allow-Origin: {}\nAccess-Control-Allow-Credentials: true", origin),
+            "使用受信任来源的白名单，不要对通配符或null来源开启凭证",
+        )]
+    }
+
+    /// 检查混合内容
+    fn check_mixed_content(url: &Url, response: &HttpResponse) -> Vec<Finding> {
+        if url.scheme() != "https" || !Self::is_html(response) {
+            return Vec::new();
+        }
+        let body = String::from_utf8_lossy(&response.body);
+        let resources: Vec<String> = MIXED_CONTENT_RE
+            .captures_iter(&body)
+            .map(|c| format!("<{}> {}", c[1].to_lowercase(), &c[3]))
+            .take(5)
+            .collect();
+
+        if resources.is_empty() {
+            return Vec::new();
+        }
+        vec![Finding::new(
+            "mixed-content",
+            "HTTPS页面包含混合内容",
+            "HTTPS页面通过HTTP加载资源，攻击者可在传输中篡改这些资源",
+            "Low",
+            format!("页面中的HTTP资源:\n{}", resources.join("\n")),
+            "所有资源均通过HTTPS加载，或设置CSP upgrade-insecure-requests",
+        )]
+    }
+
+    /// 检查版本信息泄露
+    fn check_banners(response: &HttpResponse) -> Vec<Finding> {
+        let mut leaked = Vec::new();
+        if let Some(server) = Self::header(&response.headers, "server") {
+            if VERSION_RE.is_match(server) {
+                leaked.push(format!("Server: {}", server));
+            }
+        }
+        for name in BANNER_HEADERS {
+            if let Some(value) = Self::header(&response.headers, name) {
+                leaked.push(format!("{}: {}", name, value));
+            }
+        }
+
+        if leaked.is_empty() {
+            return Vec::new();
+        }
+        vec![Finding::new(
+            "version-banner",
+            "服务版本信息泄露",
+            "响应头暴露了服务端软件及版本信息，便于攻击者查找对应的已知漏洞",
+            "Info",
+            leaked.join("\n"),
+            "移除或模糊化 Server、X-Powered-By 等响应头中的版本信息",
+        )]
+    }
+
+    /// 检查已认证页面的缓存策略
+    fn check_cache(request: &HttpRequest, response: &HttpResponse) -> Vec<Finding> {
+        let authenticated = Self::header(&request.headers, "authorization").is_some()
+            || Self::header(&request.headers, "cookie").is_some();
+        if !authenticated || response.status != 200 || (!Self::is_html(response) && !Self::is_json(response)) {
+            return Vec::new();
+        }
+
+        let cache_control = Self::header(&response.headers, "cache-control")
+            .unwrap_or("")
+            .to_lowercase();
+        let pragma_no_cache = Self::header(&response.headers, "pragma")
+            .map(|v| v.to_lowercase().contains("no-cache"))
+            .unwrap_or(false);
+        if cache_control.contains("no-store") || cache_control.contains("private") {
+            return Vec::new();
+        }
+        if cache_control.is_empty() && pragma_no_cache {
+            return Vec::new();
+        }
+
+        let evidence = if cache_control.is_empty() {
+            "已认证请求的响应未设置 Cache-Control".to_string()
+        } else {
+            format!("已认证请求的响应 Cache-Control: {}", cache_control)
+        };
+        vec![Finding::new(
+            "cacheable-authenticated",
+            "已认证页面可被缓存",
+            "携带凭证的请求返回的内容允许被共享缓存或浏览器缓存，敏感数据可能泄露给其他用户",
+            "Low",
+            evidence,
+            "对包含用户数据的响应设置 Cache-Control: no-store 或 private",
+        )]
+    }
+
+    /// 是否为JSON响应
+    fn is_json(response: &HttpResponse) -> bool {
+        Self::header(&response.headers, "content-type")
+            .map(|ct| ct.to_lowercase().contains("json"))
+            .unwrap_or(false)
+    }
+
+    /// 运行所有检查
+    fn run_checks(url: &Url, request: &HttpRequest, response: &HttpResponse) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        findings.extend(Self::check_csp(response));
+        findings.extend(Self::check_hsts(url, response));
+        findings.extend(Self::check_frame_options(response));
+        findings.extend(Self::check_cookies(url, response));
+        findings.extend(Self::check_cors(response));
+        findings.extend(Self::check_mixed_content(url, response));
+        findings.extend(Self::check_banners(response));
+        findings.extend(Self::check_cache(request, response));
+        findings
+    }
+}
+
+#[async_trait]
+impl Scanner for PassiveCheckScanner {
+    async fn name(&self) -> String {
+        "Passive Check Scanner".to_string()
+    }
+
+    async fn scan(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<ScanResult> {
+        let url = match Url::parse(&request.url) {
+            Ok(url) => url,
+            Err(_) => return Vec::new(),
+        };
+        let host = format!("{}://{}", url.scheme(), url.host_str().unwrap_or(""));
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host,
+        };
+
+        let findings = Self::run_checks(&url, request, response);
+        let mut reported = self.reported.lock().unwrap();
+
+        let mut results: Vec<ScanResult> = findings
+            .into_iter()
+            .filter(|f| reported.insert(format!("{}|{}", host, f.id)))
+            .map(|f| {
+                ScanResult {
+                    vulnerability_type: "Passive".to_string(),
+                    name: f.name,
+                    description: f.description,
+                    risk_level: f.risk_level.to_string(),
+                    url: request.url.to_string(),
+                    method: request.method.to_string(),
+                    parameter: None,
+                    value: None,
+                    evidence: Some(f.evidence.clone()),
+                    remediation: Some(f.remediation),
+                    details: Some(format!("主机: {}\n检查项: {}", host, f.id)),
+                    timestamp: chrono::Utc::now(),
+                    request_details: None,
+                    response_details: None,
+                    confidence: Confidence::Firm,
+                    payload_ranges: Vec::new(),
+                    evidence_ranges: Vec::new(),
+                }
+                .with_exchange(request, Some(response), None, Some(&f.evidence))
+            })
+            .collect();
+        drop(reported);
+
+        // 规则结果按路径模板在入库时去重，这里不再按主机过滤
+        results.extend(self.rules.scan(request, response));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn response(headers: Vec<(&str, &str)>, body: &str) -> HttpResponse {
+        HttpResponse {
+            status: 200,
+            headers: headers.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn ids(findings: Vec<Finding>) -> Vec<String> {
+        findings.into_iter().map(|f| f.id).collect()
+    }
+
+    #[test]
+    fn test_csp_checks() {
+        let html = response(vec![("Content-Type", "text/html")], "");
+        assert_eq!(ids(PassiveCheckScanner::check_csp(&html)), vec!["csp-missing"]);
+
+        let weak = response(vec![("Content-Security-Policy", "default-src 'self' 'unsafe-inline'")], "");
+        assert_eq!(ids(PassiveCheckScanner::check_csp(&weak)), vec!["csp-weak"]);
+
+        let nonce = response(vec![("Content-Security-Policy", "script-src 'nonce-abc' 'unsafe-inline'")], "");
+        assert!(PassiveCheckScanner::check_csp(&nonce).is_empty());
+    }
+
+    #[test]
+    fn test_cookie_flags() {
+        let url = Url::parse("https://example.com/").unwrap();
+        let resp = response(
+            vec![("set-cookie", "sid=1; Path=/; Secure; HttpOnly; SameSite=Lax\ntrack=2; Path=/")],
+            "",
+        );
+        let findings = PassiveCheckScanner::check_cookies(&url, &resp);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "cookie-flags:track");
+        assert!(findings[0].evidence.contains("Secure, HttpOnly, SameSite"));
+    }
+
+    #[test]
+    fn test_cors_and_mixed_content() {
+        let cors = response(
+            vec![("Access-Control-Allow-Origin", "*"), ("Access-Control-Allow-Credentials", "true")],
+            "",
+        );
+        assert_eq!(ids(PassiveCheckScanner::check_cors(&cors)), vec!["cors-wildcard-credentials"]);
+
+        let url = Url::parse("https://example.com/").unwrap();
+        let page = response(vec![("Content-Type", "text/html")], r#"<script src="http://cdn.example.com/a.js"></script>"#);
+        assert_eq!(ids(PassiveCheckScanner::check_mixed_content(&url, &page)), vec!["mixed-content"]);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_per_host() {
+        let scanner = PassiveCheckScanner::new(Arc::new(AppConfig::default()));
+        let mut request = HttpRequest {
+            method: "GET".to_string(),
+            url: "http://dedup.test/a".to_string(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+            params: Vec::new(),
+        };
+        let resp = response(vec![("Server", "Apache/2.4.41")], "");
+
+        assert_eq!(scanner.scan(&request, &resp).await.len(), 1);
+        request.url = "http://dedup.test/b".to_string();
+        assert!(scanner.scan(&request, &resp).await.is_empty());
+    }
+}
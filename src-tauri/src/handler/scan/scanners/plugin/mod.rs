@@ -1,34 +1,187 @@
 pub mod manager {
-    use crate::handler::scan::engine::ScanResult;
+    use crate::core::config::PluginConfig;
+    use crate::handler::scan::engine::{Confidence, ScanResult};
+    use crate::handler::scan::plugin::{run_analyze, PluginContext, PluginManifest, PluginResult, ScanPluginManager};
+    use crate::handler::scan::plugin_commands::global_scan_plugin_manager;
     use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
     use crate::handler::scan::scanners::Scanner;
+    use crate::handler::scan::utils::capitalize;
     use async_trait::async_trait;
-    use std::collections::HashMap;
-    
+    use log::{debug, warn};
+    use serde_json::Value;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::Mutex;
+
+    /// 插件结果中表示发现漏洞的状态
+    const VULNERABLE_STATUS: &str = "vulnerable";
+
+    /// 扫描流程中的插件执行器，对每个请求/响应执行已启用的Rhai扫描插件
     pub struct PluginManager {
-        _plugins: HashMap<String, String>,
+        /// 与前端命令共享的插件管理器，未初始化时不执行插件
+        plugins: Option<Arc<Mutex<ScanPluginManager>>>,
+        /// 是否执行插件
+        enabled: bool,
+        /// 单个插件的超时时间
+        timeout: Duration,
     }
-    
+
     impl PluginManager {
-        pub fn new() -> Self {
+        pub fn new(config: &PluginConfig) -> Self {
             PluginManager {
-                _plugins: HashMap::new(),
+                plugins: global_scan_plugin_manager(),
+                enabled: config.enabled,
+                timeout: Duration::from_millis(config.timeout_ms),
             }
         }
-        
-        pub async fn load_plugins_from_db(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+
+        /// 从插件目录重新加载插件
+        pub async fn load_plugins(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            if let Some(plugins) = &self.plugins {
+                plugins.lock().await.load_all_plugins().await?;
+            }
             Ok(())
         }
+
+        /// 将插件结果转换为扫描结果
+        ///
+        /// 只有status为vulnerable时才视为发现漏洞，data为对象或data.findings为数组时每项生成一条结果，
+        /// 缺失的字段使用插件元数据补全
+        pub(crate) fn to_scan_results(
+            plugin_id: &str,
+            manifest: Option<&PluginManifest>,
+            result: &PluginResult,
+            request: &HttpRequest,
+        ) -> Vec<ScanResult> {
+            if !result.status.eq_ignore_ascii_case(VULNERABLE_STATUS) {
+                return Vec::new();
+            }
+
+            let findings = match &result.data {
+                Some(Value::Object(data)) => match data.get("findings") {
+                    Some(Value::Array(items)) => items.clone(),
+                    _ => vec![Value::Object(data.clone())],
+                },
+                Some(Value::Array(items)) => items.clone(),
+                _ => vec![Value::Null],
+            };
+
+            let name = manifest.map(|m| m.name.clone()).unwrap_or_else(|| plugin_id.to_string());
+            findings
+                .iter()
+                .map(|finding| {
+                    let field = |key: &str| finding.get(key).and_then(Value::as_str).map(str::to_string);
+                    let risk_level = field("risk_level")
+                        .or_else(|| manifest.and_then(|m| m.severity.clone()))
+                        .unwrap_or_else(|| "Medium".to_string());
+
+                    ScanResult {
+                        vulnerability_type: field("type")
+                            .or_else(|| manifest.map(|m| m.type_.clone()).filter(|t| !t.is_empty()))
+                            .unwrap_or_else(|| "Plugin".to_string()),
+                        name: field("name").unwrap_or_else(|| name.clone()),
+                        description: field("description")
+                            .or_else(|| manifest.map(|m| m.description.clone()))
+                            .unwrap_or_default(),
+                        risk_level: capitalize(&risk_level),
+                        url: field("url").unwrap_or_else(|| request.url.clone()),
+                        method: request.method.clone(),
+                        parameter: field("parameter"),
+                        value: field("payload").or_else(|| field("value")),
+                        evidence: field("evidence").or_else(|| result.message.clone()),
+                        remediation: field("remediation"),
+                        details: Some(format!("由扫描插件 {} 发现", plugin_id)),
+                        timestamp: chrono::Utc::now(),
+                        request_details: field("request"),
+                        response_details: field("response").or_else(|| result.raw_output.clone()),
+                        // 插件未声明置信度时需要人工确认
+                        confidence: field("confidence")
+                            .map(|c| Confidence::parse(&c.to_lowercase()))
+                            .unwrap_or(Confidence::Tentative),
+                        payload_ranges: Vec::new(),
+                        evidence_ranges: Vec::new(),
+                    }
+                })
+                .collect()
+        }
     }
-    
+
     #[async_trait]
     impl Scanner for PluginManager {
         async fn name(&self) -> String {
             "Plugin Manager".to_string()
         }
-        
-        async fn scan(&self, _request: &HttpRequest, _response: &HttpResponse) -> Vec<ScanResult> {
-            Vec::new()
+
+        async fn scan(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<ScanResult> {
+            let plugins = match (&self.plugins, self.enabled) {
+                (Some(plugins), true) => plugins,
+                _ => return Vec::new(),
+            };
+
+            // 只在获取插件列表时持有锁，避免插件执行期间阻塞前端的插件管理操作
+            let (engine, enabled_plugins) = {
+                let manager = plugins.lock().await;
+                (manager.engine(), manager.enabled_plugins().await)
+            };
+
+            let context = PluginContext {
+                target: request.url.clone(),
+                params: serde_json::Map::new(),
+                request: Some(request.into()),
+                response: Some(response.into()),
+            };
+
+            let mut results = Vec::new();
+            for (plugin_id, plugin) in enabled_plugins {
+                match run_analyze(engine.clone(), &plugin, &context, Some(self.timeout)).await {
+                    Ok(result) => {
+                        debug!("扫描插件 {} 执行完成: {}", plugin_id, result.status);
+                        results.extend(Self::to_scan_results(&plugin_id, plugin.manifest(), &result, request));
+                    }
+                    Err(e) => warn!("扫描插件 {} 执行失败 {}: {}", plugin_id, request.url, e),
+                }
+            }
+            results
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::collections::HashMap;
+
+        fn plugin_result(status: &str, data: Value) -> PluginResult {
+            PluginResult {
+                status: status.to_string(),
+                message: Some("matched".to_string()),
+                raw_output: None,
+                data: Some(data),
+            }
+        }
+
+        #[test]
+        fn test_to_scan_results() {
+            let request = HttpRequest::new("http://example.com/a?id=1", "GET", HashMap::new(), Vec::new(), Vec::new());
+
+            let safe = plugin_result("safe", serde_json::json!({}));
+            assert!(PluginManager::to_scan_results("demo", None, &safe, &request).is_empty());
+
+            let single = plugin_result("vulnerable", serde_json::json!({ "risk_level": "high", "parameter": "id" }));
+            let results = PluginManager::to_scan_results("demo", None, &single, &request);
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].name, "demo");
+            assert_eq!(results[0].risk_level, "High");
+            assert_eq!(results[0].parameter.as_deref(), Some("id"));
+            assert_eq!(results[0].evidence.as_deref(), Some("matched"));
+
+            let multiple = plugin_result(
+                "Vulnerable",
+                serde_json::json!({ "findings": [{ "name": "a" }, { "name": "b", "url": "http://example.com/b" }] }),
+            );
+            let results = PluginManager::to_scan_results("demo", None, &multiple, &request);
+            assert_eq!(results.len(), 2);
+            assert_eq!(results[1].url, "http://example.com/b");
+            assert_eq!(results[0].risk_level, "Medium");
         }
     }
-} 
\ No newline at end of file
+}
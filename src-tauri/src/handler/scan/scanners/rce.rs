@@ -1,6 +1,6 @@
-use crate::core::config::AppConfig;
+use crate::core::config::{AppConfig, InsertionPointType};
 use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
-use crate::handler::scan::engine::ScanResult;
+use crate::handler::scan::engine::{Confidence, ScanResult};
 use crate::handler::scan::scanners::Scanner;
 use regex::Regex;
 use std::collections::HashMap;
@@ -123,6 +123,13 @@ impl RceScanner {
         payloads.insert("cmd".to_string(), cmd_payloads);
         payloads.insert("advanced_cmd".to_string(), advanced_cmd_payloads);
         payloads.insert("code".to_string(), code_payloads);
+
+        // 扫描配置中的自定义payload替换同名类别
+        for (kind, custom) in &config.rules.vulnerabilities.rce.payloads {
+            if !custom.is_empty() {
+                payloads.insert(kind.clone(), custom.clone());
+            }
+        }
         
         Self {
             config,
@@ -210,7 +217,12 @@ impl Scanner for RceScanner {
         // 检查响应中是否包含RCE错误
         if let Some(error) = self.check_rce_error(body) {
             // 检查所有请求参数（URL参数和POST参数）
-            for (param_name, param_value) in &request.params {
+            let params: &[(String, String)] = if self.config.scanner.allows(InsertionPointType::Query) {
+                &request.params
+            } else {
+                &[]
+            };
+            for (param_name, param_value) in params {
                 // 检查参数值是否可能导致RCE
                 let payloads = self.get_payloads_for_level(level);
                 
@@ -230,9 +242,13 @@ impl Scanner for RceScanner {
                             remediation: Some("避免使用危险函数执行系统命令，对用户输入进行严格过滤，实施输入验证和白名单机制".to_string()),
                             details: Some(format!("参数 {} 的值 {} 可能导致远程命令执行，响应中包含RCE错误: {}", param_name, param_value, error)),
                             timestamp: chrono::Utc::now(),
-                            request_details: Some(format!("{} {}\nHost: {}", request.method, request.url, request.url.split('/').nth(2).unwrap_or("unknown"))),
-                            response_details: Some(format!("响应包含RCE错误特征: ")),
-                        };
+                            request_details: None,
+                            response_details: None,
+                            confidence: Confidence::Firm,
+                            payload_ranges: Vec::new(),
+                            evidence_ranges: Vec::new(),
+                        }
+                        .with_exchange(request, Some(response), Some(payload.as_str()), Some(error.as_str()));
                         
                         results.push(result);
                         break;
@@ -296,6 +312,7 @@ mod tests {
                 timeout_ms: 5000,
                 save_results: false,
                 results_path: "./results.json".to_string(),
+                insertion_points: crate::core::config::InsertionPointType::all(),
             },
             rules: crate::core::config::RulesConfig {
                 enable_builtin: true,
@@ -307,14 +324,17 @@ mod tests {
                         use_ast: true,
                         max_params: 20,
                         max_depth: 3,
+                        dom_confirm: false,
                     },
                     sql_injection: crate::core::config::SqlInjectionConfig {
                         enabled: true,
                         level: "high".to_string(),
+                        payloads: Default::default(),
                     },
                     rce: crate::core::config::RceConfig {
                         enabled: true,
                         level: "high".to_string(),
+                        payloads: Default::default(),
                     },
                     path_traversal: crate::core::config::PathTraversalConfig {
                         enabled: true,
@@ -322,6 +342,15 @@ mod tests {
                     open_redirect: crate::core::config::OpenRedirectConfig {
                         enabled: true,
                     },
+                    ssrf: crate::core::config::SsrfConfig { enabled: true },
+                    xxe: crate::core::config::XxeConfig { enabled: true },
+                    ssti: crate::core::config::SstiConfig { enabled: true },
+                    passive_checks: crate::core::config::PassiveCheckConfig { enabled: true },
+                    cors: crate::core::config::CorsConfig { enabled: true },
+                    jwt: crate::core::config::JwtConfig { enabled: true },
+                    nosql_injection: crate::core::config::NoSqlInjectionConfig { enabled: true },
+                    java_deserialization: crate::core::config::JavaDeserializationConfig { enabled: true, exec_gadgets: false },
+                    authz: crate::core::config::AuthzConfig::default(),
                 },
             },
             logging: crate::core::config::LoggingConfig {
@@ -330,6 +359,9 @@ mod tests {
                 file_path: "logs/scan.log".to_string(),
                 colored_output: true,
             },
+            oob: crate::core::config::OobConfig::default(),
+            plugins: crate::core::config::PluginConfig::default(),
+            rate_limit: crate::core::config::RateLimitConfig::default(),
         })
     }
 
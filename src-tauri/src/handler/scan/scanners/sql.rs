@@ -1,8 +1,11 @@
 use crate::core::config::AppConfig;
+use crate::core::governor;
 use crate::global::config::CoreConfig;
 use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
-use crate::handler::scan::engine::ScanResult;
+use crate::handler::scan::engine::queue;
+use crate::handler::scan::engine::{Confidence, ScanResult};
 use crate::handler::scan::scanners::Scanner;
+use crate::handler::scan::utils::insertion;
 use anyhow::Result;
 use log::error;
 use regex::Regex;
@@ -10,7 +13,7 @@ use sqlparser::dialect::GenericDialect;
 use sqlparser::parser::Parser;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::Duration;
 use reqwest::Client;
 use url::Url;
 use async_trait::async_trait;
@@ -19,7 +22,7 @@ use async_trait::async_trait;
 #[derive(Clone)]
 pub struct SqlInjectionScanner {
     /// 配置
-    _config: Arc<AppConfig>,
+    config: Arc<AppConfig>,
     /// SQL错误模式
     error_patterns: Vec<Regex>,
     /// SQL注入测试载荷
@@ -30,7 +33,7 @@ pub struct SqlInjectionScanner {
 
 impl SqlInjectionScanner {
     /// 创建新的SQL注入扫描器
-    pub fn new(_config: Arc<AppConfig>) -> Self {
+    pub fn new(config: Arc<AppConfig>) -> Self {
         // 初始化SQL错误模式
         let mut error_patterns = Vec::new();
         
@@ -197,9 +200,16 @@ impl SqlInjectionScanner {
         payloads.insert("error".to_string(), error_payloads);
         payloads.insert("boolean".to_string(), boolean_payloads);
         payloads.insert("time".to_string(), time_payloads);
+
+        // 扫描配置中的自定义payload替换同名类别
+        for (kind, custom) in &config.rules.vulnerabilities.sql_injection.payloads {
+            if !custom.is_empty() {
+                payloads.insert(kind.clone(), custom.clone());
+            }
+        }
         
         Self {
-            _config,
+            config,
             error_patterns,
             payloads,
             _baseline_response_time: None,
@@ -280,6 +290,11 @@ impl SqlInjectionScanner {
     
     /// 发送测试请求并获取响应
     async fn send_request(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        Ok(self.send_request_timed(request).await?.0)
+    }
+
+    /// 发送测试请求，同时返回不含限速排队时间的耗时
+    async fn send_request_timed(&self, request: &HttpRequest) -> Result<(HttpResponse, Duration)> {
         //使用全局的reqwest客户端
         let client = match CoreConfig::global() {
             Ok(config) => config.http_client.clone().unwrap_or(Client::new()),
@@ -317,8 +332,11 @@ impl SqlInjectionScanner {
             _ => client.get(url), // 默认使用GET
         };
         
-        // 添加请求头
+        // 添加请求头，请求体可能被修改，长度交给reqwest重新计算
         for (key, value) in &request.headers {
+            if key.eq_ignore_ascii_case("content-length") {
+                continue;
+            }
             req_builder = req_builder.header(key, value);
         }
         
@@ -328,7 +346,7 @@ impl SqlInjectionScanner {
         }
         
         // 发送请求并获取响应
-        let resp = req_builder.send().await?;
+        let (resp, started) = governor::send_timed(req_builder).await?;
         let status = resp.status().as_u16();
         let headers = resp.headers()
             .iter()
@@ -336,11 +354,14 @@ impl SqlInjectionScanner {
             .collect();
         let body = resp.bytes().await?.to_vec();
         
-        Ok(HttpResponse {
-            status,
-            headers,
-            body,
-        })
+        Ok((
+            HttpResponse {
+                status,
+                headers,
+                body,
+            },
+            started.elapsed(),
+        ))
     }
     
     /// 检测错误型SQL注入
@@ -356,19 +377,14 @@ impl SqlInjectionScanner {
         };
         
         // 对每个错误注入payload进行测试
-        for payload in error_payloads {
+        for (index, payload) in error_payloads.iter().enumerate() {
+            // 从断点恢复时跳过已测试的payload
+            if !queue::should_test(&format!("error:{}", param_name), index) {
+                continue;
+            }
+
             // 构造测试请求，替换对应参数的值
-            let mut test_request = request.clone();
-            test_request.params = test_request.params
-                .into_iter()
-                .map(|(k, v)| {
-                    if k == param_name {
-                        (k, payload.clone())
-                    } else {
-                        (k, v)
-                    }
-                })
-                .collect();
+            let test_request = insertion::with_param(request, param_name, payload);
             
             // 发送测试请求并分析响应
             if let Ok(test_response) = self.send_request(&test_request).await {
@@ -397,12 +413,13 @@ impl SqlInjectionScanner {
                             details: Some(format!("参数 {} 注入payload {} 导致{}数据库错误: {}", 
                                 param_name, payload, db_type, error)),
                             timestamp: chrono::Utc::now(),
-                            request_details: Some(format!("{} {}\nHost: {}", request.method, request.url, request.url.split('/').nth(2).unwrap_or("unknown"))),
-                            response_details: Some(format!("响应体大小: {} 字节\n内容预览: {}", test_response.body.len(), match String::from_utf8(test_response.body[..std::cmp::min(200, test_response.body.len())].to_vec()) {
-                                Ok(s) => s,
-                                Err(_) => "[无法显示非UTF-8内容]".to_string(),
-                            }))
-                        });
+                            request_details: None,
+                            response_details: None,
+                            confidence: Confidence::Firm,
+                            payload_ranges: Vec::new(),
+                            evidence_ranges: Vec::new(),
+                        }
+                        .with_exchange(&test_request, Some(&test_response), Some(payload.as_str()), Some(error.as_str())));
                     }
                 }
             }
@@ -422,32 +439,17 @@ impl SqlInjectionScanner {
             Err(_) => return None, // 如果不是有效的UTF-8，跳过该检测
         };
         
-        for payload in payloads {
+        for (index, payload) in payloads.iter().enumerate() {
+            // 从断点恢复时跳过已测试的payload
+            if !queue::should_test(&format!("boolean:{}", param_name), index) {
+                continue;
+            }
+
             // 构造真条件测试请求，保留原始参数值并附加payload
-            let mut true_request = request.clone();
-            true_request.params = true_request.params
-                .into_iter()
-                .map(|(k, v)| {
-                    if k == param_name {
-                        (k, format!("{}{}", v, payload.replace("1=1", "1=1")))
-                    } else {
-                        (k, v)
-                    }
-                })
-                .collect();
+            let true_request = insertion::with_param(request, param_name, &format!("{}{}", param_value, payload.replace("1=1", "1=1")));
             
             // 构造假条件测试请求，保留原始参数值并附加payload
-            let mut false_request = request.clone();
-            false_request.params = false_request.params
-                .into_iter()
-                .map(|(k, v)| {
-                    if k == param_name {
-                        (k, format!("{}{}", v, payload.replace("1=1", "1=2")))
-                    } else {
-                        (k, v)
-                    }
-                })
-                .collect();
+            let false_request = insertion::with_param(request, param_name, &format!("{}{}", param_value, payload.replace("1=1", "1=2")));
             
             // 发送请求并获取响应
             if let (Ok(true_response), Ok(false_response)) = (
@@ -480,9 +482,13 @@ impl SqlInjectionScanner {
                         remediation: Some("使用参数化查询，避免直接拼接SQL语句，对用户输入进行严格过滤".to_string()),
                         details: Some(format!("参数 {} 注入payload {} 导致响应差异", param_name, format!("{}{}", param_value, payload))),
                         timestamp: chrono::Utc::now(),
-                        request_details: Some(format!("{} {}\nHost: {}", request.method, request.url, request.url.split('/').nth(2).unwrap_or("unknown"))),
-                        response_details: Some("存在布尔型SQL注入漏洞，真/假条件返回不同响应".to_string()),
-                    });
+                        request_details: None,
+                        response_details: None,
+                        confidence: Confidence::Firm,
+                        payload_ranges: Vec::new(),
+                        evidence_ranges: Vec::new(),
+                    }
+                    .with_exchange(&true_request, Some(&true_response), Some(payload.as_str()), None));
                 }
             }
         }
@@ -496,29 +502,19 @@ impl SqlInjectionScanner {
         let payloads = self.payloads.get("time").unwrap();
         
         // 首先发送正常请求获取基准响应时间
-        let start = Instant::now();
-        if let Ok(_) = self.send_request(request).await {
-            let baseline_duration = start.elapsed();
+        if let Ok((_, baseline_duration)) = self.send_request_timed(request).await {
             
-            for payload in payloads {
-                let start = Instant::now();
-                
+            for (index, payload) in payloads.iter().enumerate() {
+                // 从断点恢复时跳过已测试的payload
+                if !queue::should_test(&format!("time:{}", param_name), index) {
+                    continue;
+                }
+
                 // 构造测试请求，替换对应参数的值
-                let mut test_request = request.clone();
-                test_request.params = test_request.params
-                    .into_iter()
-                    .map(|(k, v)| {
-                        if k == param_name {
-                            (k, format!("{}{}", v, payload))
-                        } else {
-                            (k, v)
-                        }
-                    })
-                    .collect();
+                let test_request = insertion::with_param(request, param_name, &format!("{}{}", param_value, payload));
                 
                 // 发送测试请求并测量响应时间
-                if let Ok(_) = self.send_request(&test_request).await {
-                    let duration = start.elapsed();
+                if let Ok((test_response, duration)) = self.send_request_timed(&test_request).await {
                     
                     // 如果响应时间明显大于基准时间（5倍以上），可能存在时间型注入
                     if duration.as_secs_f64() > baseline_duration.as_secs_f64() * 5.0 {
@@ -535,9 +531,13 @@ impl SqlInjectionScanner {
                             remediation: Some("使用参数化查询，避免直接拼接SQL语句，对用户输入进行严格过滤".to_string()),
                             details: Some(format!("参数 {} 注入payload {} 导致响应延迟", param_name, format!("{}{}", param_value, payload))),
                             timestamp: chrono::Utc::now(),
-                            request_details: Some(format!("{} {}\nHost: {}", request.method, request.url, request.url.split('/').nth(2).unwrap_or("unknown"))),
-                            response_details: Some(format!("响应包含RCE错误特征: ")),
-                        });
+                            request_details: None,
+                            response_details: None,
+                            confidence: Confidence::Tentative,
+                            payload_ranges: Vec::new(),
+                            evidence_ranges: Vec::new(),
+                        }
+                        .with_exchange(&test_request, Some(&test_response), Some(payload.as_str()), None));
                     }
                 }
             }
@@ -548,7 +548,7 @@ impl SqlInjectionScanner {
     
     /// 检测联合查询注入
     #[allow(dead_code)]
-    async fn detect_union_injection(&self, request: &HttpRequest, response: &HttpResponse, param_name: &str, _param_value: &str) -> Option<ScanResult> {
+    async fn detect_union_injection(&self, request: &HttpRequest, response: &HttpResponse, param_name: &str, param_value: &str) -> Option<ScanResult> {
         let union_payloads = vec![
             "/*!50000UnIoN*//*!50000SeLeCt*/1,2,3,4,5--",
             "/*!12345UnIoN*//*!12345sElEcT*/1,2,3,4,5--",
@@ -562,19 +562,14 @@ impl SqlInjectionScanner {
             Err(_) => return None, // 如果不是有效的UTF-8，跳过该检测
         };
         
-        for payload in union_payloads {
+        for (index, payload) in union_payloads.into_iter().enumerate() {
+            // 从断点恢复时跳过已测试的payload
+            if !queue::should_test(&format!("union:{}", param_name), index) {
+                continue;
+            }
+
             // 构造测试请求
-            let mut test_request = request.clone();
-            test_request.params = test_request.params
-                .into_iter()
-                .map(|(k, v)| {
-                    if k == param_name {
-                        (k, format!("{}{}", v, payload))
-                    } else {
-                        (k, v)
-                    }
-                })
-                .collect();
+            let test_request = insertion::with_param(request, param_name, &format!("{}{}", param_value, payload));
             
             // 发送测试请求并分析响应
             if let Ok(test_response) = self.send_request(&test_request).await {
@@ -599,12 +594,13 @@ impl SqlInjectionScanner {
                         remediation: Some("使用参数化查询，避免直接拼接SQL语句，对用户输入进行严格过滤".to_string()),
                         details: Some(format!("参数 {} 注入payload {} 成功执行联合查询", param_name, payload)),
                         timestamp: chrono::Utc::now(),
-                        request_details: Some(format!("{} {}\nHost: {}", request.method, request.url, request.url.split('/').nth(2).unwrap_or("unknown"))),
-                        response_details: Some(format!("响应体大小: {} 字节\n内容预览: {}", test_response.body.len(), match String::from_utf8(test_response.body[..std::cmp::min(200, test_response.body.len())].to_vec()) {
-                            Ok(s) => s,
-                            Err(_) => "[无法显示非UTF-8内容]".to_string(),
-                        }))
-                    });
+                        request_details: None,
+                        response_details: None,
+                        confidence: Confidence::Tentative,
+                        payload_ranges: Vec::new(),
+                        evidence_ranges: Vec::new(),
+                    }
+                    .with_exchange(&test_request, Some(&test_response), Some(payload), None));
                 }
             }
         }
@@ -614,7 +610,7 @@ impl SqlInjectionScanner {
 
     /// 检测堆叠查询注入
     #[allow(dead_code)]
-    async fn detect_stacked_injection(&self, request: &HttpRequest, response: &HttpResponse, param_name: &str, _param_value: &str) -> Option<ScanResult> {
+    async fn detect_stacked_injection(&self, request: &HttpRequest, response: &HttpResponse, param_name: &str, param_value: &str) -> Option<ScanResult> {
         let stacked_payloads = vec![
             ";SELECT @@version--",
             ";SELECT SLEEP(0)--",
@@ -628,19 +624,14 @@ impl SqlInjectionScanner {
             Err(_) => return None, // 如果不是有效的UTF-8，跳过该检测
         };
         
-        for payload in stacked_payloads {
+        for (index, payload) in stacked_payloads.into_iter().enumerate() {
+            // 从断点恢复时跳过已测试的payload
+            if !queue::should_test(&format!("stacked:{}", param_name), index) {
+                continue;
+            }
+
             // 构造测试请求
-            let mut test_request = request.clone();
-            test_request.params = test_request.params
-                .into_iter()
-                .map(|(k, v)| {
-                    if k == param_name {
-                        (k, format!("{}{}", v, payload))
-                    } else {
-                        (k, v)
-                    }
-                })
-                .collect();
+            let test_request = insertion::with_param(request, param_name, &format!("{}{}", param_value, payload));
             
             // 发送测试请求并分析响应
             if let Ok(test_response) = self.send_request(&test_request).await {
@@ -668,9 +659,13 @@ impl SqlInjectionScanner {
                         remediation: Some("使用参数化查询，避免直接拼接SQL语句，对用户输入进行严格过滤".to_string()),
                         details: Some(format!("参数 {} 注入payload {} 成功执行堆叠查询", param_name, payload)),
                         timestamp: chrono::Utc::now(),
-                        request_details: Some(format!("{} {}\nHost: {}", request.method, request.url, request.url.split('/').nth(2).unwrap_or("unknown"))),
-                        response_details: Some(format!("响应中包含数据库信息，表明堆叠查询成功执行")),
-                    });
+                        request_details: None,
+                        response_details: None,
+                        confidence: Confidence::Firm,
+                        payload_ranges: Vec::new(),
+                        evidence_ranges: Vec::new(),
+                    }
+                    .with_exchange(&test_request, Some(&test_response), Some(payload), None));
                 }
             }
         }
@@ -702,70 +697,14 @@ impl Scanner for SqlInjectionScanner {
     async fn scan(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<ScanResult> {
         let mut results = Vec::new();
         
-        // 提取请求参数（URL和表单参数）
-        let mut parameters = Vec::new();
-        
-        // 处理URL参数
-        parameters.extend(request.params.clone());
-        
-        // 处理URL路径参数（REST风格API）
-        let url_path_parts: Vec<&str> = request.url.split('?').next().unwrap_or("").split('/').collect();
-        for (i, part) in url_path_parts.iter().enumerate() {
-            if !part.is_empty() && part.parse::<i64>().is_ok() {
-                // 可能是ID参数
-                parameters.push((format!("path_param_{}", i), part.to_string()));
-            }
-        }
-        
-        // 处理表单参数（如果是POST请求）
-        if request.method == "POST" && request.headers.get("Content-Type").map_or(false, |ct| ct.contains("application/x-www-form-urlencoded")) {
-            if let Ok(body_str) = String::from_utf8(request.body.clone()) {
-                for param_pair in body_str.split('&') {
-                    if let Some(idx) = param_pair.find('=') {
-                        let (name, value) = param_pair.split_at(idx);
-                        let value = &value[1..]; // 移除等号
-                        parameters.push((name.to_string(), value.to_string()));
-                    }
-                }
-            }
-        }
-        
-        // 处理JSON参数（如果是POST请求且是JSON格式）
-        if request.method == "POST" && request.headers.get("Content-Type").map_or(false, |ct| ct.contains("application/json")) {
-            if let Ok(body_str) = String::from_utf8(request.body.clone()) {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&body_str) {
-                    if let Some(obj) = json.as_object() {
-                        for (key, value) in obj {
-                            if let Some(str_value) = value.as_str() {
-                                parameters.push((key.clone(), str_value.to_string()));
-                            } else if let Some(num_value) = value.as_i64() {
-                                parameters.push((key.clone(), num_value.to_string()));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        // 处理Cookie参数
-        if let Some(cookie) = request.headers.get("Cookie") {
-            for cookie_pair in cookie.split(';') {
-                if let Some(idx) = cookie_pair.find('=') {
-                    let (name, value) = cookie_pair.split_at(idx);
-                    let value = &value[1..]; // 移除等号
-                    let name = name.trim();
-                    parameters.push((format!("cookie_{}", name), value.to_string()));
-                }
-            }
-        }
-        
-        // 处理HTTP头部（某些漏洞可能通过头部触发）
-        for (name, value) in &request.headers {
-            if ["User-Agent", "Referer", "X-Forwarded-For"].contains(&name.as_str()) {
-                parameters.push((format!("header_{}", name), value.clone()));
-            }
-        }
-        
+        // 按扫描配置的插入点类型提取参数：查询、表单、JSON字段（点号路径，如GraphQL的variables.id）、Cookie、请求头和路径中的ID
+        let parameters = insertion::params(request, &self.config.scanner);
+
+        // 检测级别：low只做错误型检测，medium增加布尔型和UNION型，high再增加时间型和堆叠查询
+        let level = self.config.rules.vulnerabilities.sql_injection.level.as_str();
+        let medium = level == "medium" || level == "high";
+        let high = level == "high";
+
         // 对每个参数进行检测
         for (param_name, param_value) in parameters {
             // 跳过空值
@@ -781,15 +720,20 @@ impl Scanner for SqlInjectionScanner {
             }
             
             // 2. 布尔型SQL注入检测
+            if !medium {
+                continue;
+            }
             if let Some(result) = self.detect_boolean_injection(request, response, &param_name, &param_value).await {
                 results.push(result);
                 continue;
             }
             
             // 3. 时间型SQL注入检测
-            if let Some(result) = self.detect_time_injection(request, &param_name, &param_value).await {
-                results.push(result);
-                continue;
+            if high {
+                if let Some(result) = self.detect_time_injection(request, &param_name, &param_value).await {
+                    results.push(result);
+                    continue;
+                }
             }
             
             // 4. UNION型SQL注入检测
@@ -799,6 +743,9 @@ impl Scanner for SqlInjectionScanner {
             }
             
             // 5. 堆叠查询SQL注入检测
+            if !high {
+                continue;
+            }
             if let Some(result) = self.detect_stacked_injection(request, response, &param_name, &param_value).await {
                 results.push(result);
                 continue;
@@ -864,6 +811,7 @@ mod tests {
                 timeout_ms: 5000,
                 save_results: false,
                 results_path: "./results.json".to_string(),
+                insertion_points: crate::core::config::InsertionPointType::all(),
             },
             rules: crate::core::config::RulesConfig {
                 enable_builtin: true,
@@ -875,14 +823,17 @@ mod tests {
                         use_ast: true,
                         max_params: 20,
                         max_depth: 3,
+                        dom_confirm: false,
                     },
                     sql_injection: crate::core::config::SqlInjectionConfig {
                         enabled: true,
                         level: "high".to_string(),
+                        payloads: Default::default(),
                     },
                     rce: crate::core::config::RceConfig {
                         enabled: true,
                         level: "high".to_string(),
+                        payloads: Default::default(),
                     },
                     path_traversal: crate::core::config::PathTraversalConfig {
                         enabled: true,
@@ -890,6 +841,15 @@ mod tests {
                     open_redirect: crate::core::config::OpenRedirectConfig {
                         enabled: true,
                     },
+                    ssrf: crate::core::config::SsrfConfig { enabled: true },
+                    xxe: crate::core::config::XxeConfig { enabled: true },
+                    ssti: crate::core::config::SstiConfig { enabled: true },
+                    passive_checks: crate::core::config::PassiveCheckConfig { enabled: true },
+                    cors: crate::core::config::CorsConfig { enabled: true },
+                    jwt: crate::core::config::JwtConfig { enabled: true },
+                    nosql_injection: crate::core::config::NoSqlInjectionConfig { enabled: true },
+                    java_deserialization: crate::core::config::JavaDeserializationConfig { enabled: true, exec_gadgets: false },
+                    authz: crate::core::config::AuthzConfig::default(),
                 },
             },
             logging: crate::core::config::LoggingConfig {
@@ -898,6 +858,9 @@ mod tests {
                 file_path: "logs/scan.log".to_string(),
                 colored_output: true,
             },
+            oob: crate::core::config::OobConfig::default(),
+            plugins: crate::core::config::PluginConfig::default(),
+            rate_limit: crate::core::config::RateLimitConfig::default(),
         })
     }
 
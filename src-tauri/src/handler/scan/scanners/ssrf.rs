@@ -0,0 +1,371 @@
+use crate::core::config::AppConfig;
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::oob::{OobListener, OobToken};
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::scanners::Scanner;
+use crate::handler::scan::utils::{http, insertion};
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{debug, warn};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 内网探测目标：(载荷, 特征列表, 说明)
+const INTERNAL_TARGETS: &[(&str, &[&str], &str)] = &[
+    (
+        "http://169.254.169.254/latest/meta-data/",
+        &["ami-id", "instance-id", "local-hostname", "security-credentials"],
+        "AWS元数据服务",
+    ),
+    (
+        "http://metadata.google.internal/computeMetadata/v1/?recursive=true",
+        &["\"projectId\"", "\"serviceAccounts\"", "computeMetadata"],
+        "GCP元数据服务",
+    ),
+    (
+        "http://100.100.100.200/latest/meta-data/",
+        &["instance-id", "region-id", "zone-id"],
+        "阿里云元数据服务",
+    ),
+    (
+        "http://169.254.169.254/metadata/instance?api-version=2021-02-01",
+        &["\"compute\"", "\"azEnvironment\"", "\"vmId\""],
+        "Azure元数据服务",
+    ),
+    (
+        "http://127.0.0.1/",
+        &[],
+        "本地回环地址",
+    ),
+    (
+        "http://[::1]/",
+        &[],
+        "IPv6本地回环地址",
+    ),
+    (
+        "http://2130706433/",
+        &[],
+        "十进制编码的回环地址",
+    ),
+];
+
+/// 不可达的对照地址，用于响应差异比较
+const CONTROL_TARGET: &str = "http://rshield-ssrf-control.invalid/";
+
+/// SSRF扫描器
+#[derive(Clone)]
+pub struct SsrfScanner {
+    /// 配置
+    config: Arc<AppConfig>,
+    /// HTTP客户端
+    http_client: reqwest::Client,
+    /// 可能携带URL的参数名
+    url_params: Vec<String>,
+}
+
+impl SsrfScanner {
+    /// 创建新的SSRF扫描器
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        let http_client = http::client(Duration::from_millis(config.scanner.timeout_ms), false);
+
+        let url_params = [
+            "url", "uri", "link", "src", "source", "href", "image", "img", "imageurl", "image_url",
+            "file", "path", "host", "domain", "site", "target", "dest", "fetch", "proxy", "feed",
+            "webhook", "callback", "endpoint", "api", "server", "resource", "resourceurl",
+            "sourceurl", "share", "load", "page", "preview", "download", "remote", "data",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        Self {
+            config,
+            http_client,
+            url_params,
+        }
+    }
+
+    /// 判断参数是否可能被服务端当作URL请求
+    fn is_url_param(&self, name: &str, value: &str) -> bool {
+        let name = name.to_lowercase();
+        if self.url_params.contains(&name) || name.ends_with("url") || name.ends_with("uri") {
+            return true;
+        }
+
+        let value = urlencoding::decode(value)
+            .map(|v| v.to_string())
+            .unwrap_or_else(|_| value.to_string())
+            .to_lowercase();
+        value.starts_with("http://")
+            || value.starts_with("https://")
+            || value.starts_with("//")
+            || value.starts_with("ftp://")
+    }
+
+    /// 收集候选参数名
+    fn collect_candidates(&self, request: &HttpRequest) -> Vec<String> {
+        insertion::params(request, &self.config.scanner)
+            .into_iter()
+            .filter(|(name, value)| self.is_url_param(name, value))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// 发送测试请求
+    async fn send_request(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        http::send_request(&self.http_client, request).await
+    }
+
+    /// 判断内网探测响应是否与对照响应存在明显差异
+    fn is_differential(control: &HttpResponse, probe: &HttpResponse) -> bool {
+        if probe.status != 200 || (control.status == probe.status && control.body.len() == probe.body.len()) {
+            return false;
+        }
+        if probe.body.is_empty() {
+            return false;
+        }
+        let (a, b) = (control.body.len() as f64, probe.body.len() as f64);
+        let ratio = (a - b).abs() / a.max(b);
+        control.status != probe.status || ratio > 0.3
+    }
+
+    /// 检查响应中是否包含内网服务特征
+    fn match_signature<'a>(response: &HttpResponse, signatures: &[&'a str]) -> Option<&'a str> {
+        let body = String::from_utf8_lossy(&response.body);
+        signatures.iter().find(|sig| body.contains(**sig)).copied()
+    }
+
+    /// 构造扫描结果，exchange为证明漏洞的(测试请求, 响应, 响应中的证据)
+    #[allow(clippy::too_many_arguments)]
+    fn build_result(
+        &self,
+        request: &HttpRequest,
+        param_name: &str,
+        payload: &str,
+        risk_level: &str,
+        confidence: Confidence,
+        exchange: (&HttpRequest, Option<&HttpResponse>, Option<&str>),
+        evidence: String,
+        details: String,
+    ) -> ScanResult {
+        ScanResult {
+            vulnerability_type: "SSRF".to_string(),
+            name: "服务端请求伪造漏洞".to_string(),
+            description: "检测到服务端请求伪造（SSRF）漏洞，攻击者可以让服务器向任意地址发起请求，访问内网服务或云元数据接口".to_string(),
+            risk_level: risk_level.to_string(),
+            url: request.url.to_string(),
+            method: request.method.to_string(),
+            parameter: Some(param_name.to_string()),
+            value: Some(payload.to_string()),
+            evidence: Some(evidence),
+            remediation: Some("对服务端请求的目标地址进行白名单校验，禁止访问内网与元数据地址，解析后再校验IP并禁用跳转".to_string()),
+            details: Some(details),
+            timestamp: chrono::Utc::now(),
+            request_details: None,
+            response_details: None,
+            confidence,
+            payload_ranges: Vec::new(),
+            evidence_ranges: Vec::new(),
+        }
+        .with_exchange(exchange.0, exchange.1, Some(payload), exchange.2)
+    }
+
+    /// 注入OOB地址，返回已注入的(参数名, 载荷, token)
+    async fn inject_oob(
+        &self,
+        listener: &OobListener,
+        request: &HttpRequest,
+        param_name: &str,
+    ) -> Vec<(String, OobToken)> {
+        let mut injected = Vec::new();
+
+        let http_token = listener.new_token("SSRF Scanner");
+        let dns_token = listener.new_token("SSRF Scanner");
+        let payloads = vec![
+            (http_token.http_url.clone(), http_token),
+            (format!("http://{}/", dns_token.dns_host), dns_token),
+        ];
+
+        for (payload, token) in payloads {
+            let test_request = insertion::with_param(request, param_name, &payload);
+            if let Err(e) = self.send_request(&test_request).await {
+                // 请求超时也可能是服务端正在回连，仍然等待回连结果
+                debug!("SSRF OOB测试请求失败: {}", e);
+            }
+            injected.push((payload, token));
+        }
+
+        injected
+    }
+
+    /// 内网地址探测，依据特征或与对照请求的差异判断
+    async fn probe_internal(&self, request: &HttpRequest, param_name: &str) -> Option<ScanResult> {
+        let control_request = insertion::with_param(request, param_name, CONTROL_TARGET);
+        let control = self.send_request(&control_request).await.ok()?;
+
+        for (payload, signatures, label) in INTERNAL_TARGETS {
+            let test_request = insertion::with_param(request, param_name, payload);
+            let probe = match self.send_request(&test_request).await {
+                Ok(resp) => resp,
+                Err(_) => continue,
+            };
+
+            if let Some(sig) = Self::match_signature(&probe, signatures) {
+                // 对照响应中也出现特征说明是页面本身的内容
+                if Self::match_signature(&control, &[sig]).is_none() {
+                    return Some(self.build_result(
+                        request,
+                        param_name,
+                        payload,
+                        "High",
+                        Confidence::Firm,
+                        (&test_request, Some(&probe), Some(sig)),
+                        format!("响应中包含{}特征: {}", label, sig),
+                        format!("参数 {} 注入 {} 后，响应返回了{}的内容", param_name, payload, label),
+                    ));
+                }
+            }
+
+            if signatures.is_empty() && Self::is_differential(&control, &probe) {
+                return Some(self.build_result(
+                    request,
+                    param_name,
+                    payload,
+                    "Medium",
+                    Confidence::Tentative,
+                    (&test_request, Some(&probe), None),
+                    format!(
+                        "对照请求: HTTP {} ({} 字节)，探测请求: HTTP {} ({} 字节)",
+                        control.status,
+                        control.body.len(),
+                        probe.status,
+                        probe.body.len()
+                    ),
+                    format!(
+                        "参数 {} 指向{}时的响应与指向不可达地址时明显不同，服务端可能请求了内网地址",
+                        param_name, label
+                    ),
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+#[async_trait]
+impl Scanner for SsrfScanner {
+    async fn name(&self) -> String {
+        "SSRF Scanner".to_string()
+    }
+
+    async fn scan(&self, request: &HttpRequest, _response: &HttpResponse) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+
+        let candidates = self.collect_candidates(request);
+        if candidates.is_empty() {
+            return results;
+        }
+
+        let listener = match OobListener::for_scan(&self.config.oob).await {
+            Ok(listener) => Some(listener),
+            Err(e) => {
+                warn!("OOB监听不可用，SSRF仅进行内网探测: {}", e);
+                None
+            }
+        };
+
+        // 先统一注入OOB载荷，再集中等待回连，减少等待时间
+        let mut pending = Vec::new();
+        if let Some(listener) = &listener {
+            for param_name in &candidates {
+                for (payload, token) in self.inject_oob(listener, request, param_name).await {
+                    pending.push((param_name.clone(), payload, token));
+                }
+            }
+        }
+
+        let mut confirmed_params = Vec::new();
+        if let Some(listener) = &listener {
+            let wait = listener.wait_duration();
+            let deadline = tokio::time::Instant::now() + wait;
+            for (param_name, payload, token) in &pending {
+                let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+                let interactions = listener.wait_for(&token.token, remaining).await;
+                if interactions.is_empty() || confirmed_params.contains(param_name) {
+                    continue;
+                }
+
+                let first = &interactions[0];
+                let test_request = insertion::with_param(request, param_name, payload);
+                results.push(self.build_result(
+                    request,
+                    param_name,
+                    payload,
+                    "High",
+                    Confidence::Certain,
+                    (&test_request, None, None),
+                    format!("收到来自 {} 的{}回连: {}", first.remote_addr, first.protocol.to_uppercase(), first.summary),
+                    format!(
+                        "参数 {} 注入带外地址后，服务端向回连服务器发起了{}次请求 (token: {})",
+                        param_name,
+                        interactions.len(),
+                        token.token
+                    ),
+                ));
+                confirmed_params.push(param_name.clone());
+            }
+        }
+
+        for param_name in &candidates {
+            if confirmed_params.contains(param_name) {
+                continue;
+            }
+            if let Some(result) = self.probe_internal(request, param_name).await {
+                results.push(result);
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn create_test_response(status: u16, body: &str) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers: HashMap::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ssrf_scanner_name() {
+        let scanner = SsrfScanner::new(Arc::new(AppConfig::default()));
+        assert_eq!(scanner.name().await, "SSRF Scanner");
+    }
+
+    #[test]
+    fn test_is_url_param() {
+        let scanner = SsrfScanner::new(Arc::new(AppConfig::default()));
+        assert!(scanner.is_url_param("imageUrl", "a.png"));
+        assert!(scanner.is_url_param("webhook", ""));
+        assert!(scanner.is_url_param("q", "https%3A%2F%2Fexample.org"));
+        assert!(!scanner.is_url_param("page_size", "20"));
+    }
+
+    #[test]
+    fn test_differential_and_signature() {
+        let control = create_test_response(500, "fetch failed");
+        let probe = create_test_response(200, "<html><title>Welcome to nginx!</title></html>");
+        assert!(SsrfScanner::is_differential(&control, &probe));
+        assert!(!SsrfScanner::is_differential(&control, &create_test_response(500, "fetch failed")));
+
+        let metadata = create_test_response(200, "ami-id\nhostname\ninstance-id\n");
+        assert_eq!(SsrfScanner::match_signature(&metadata, &["instance-id"]), Some("instance-id"));
+    }
+}
@@ -0,0 +1,379 @@
+use crate::core::config::AppConfig;
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::scanners::Scanner;
+use crate::handler::scan::utils::{http, insertion};
+use anyhow::Result;
+use async_trait::async_trait;
+use log::debug;
+use rand::Rng;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 用于触发模板解析错误的多语法探针
+const POLYGLOT: &str = "${{<%[%'\"}}%\\.";
+
+/// 模板引擎错误特征：(特征, 引擎)
+const ERROR_SIGNATURES: &[(&str, &str)] = &[
+    ("jinja2.exceptions", "Jinja2"),
+    ("TemplateSyntaxError", "Jinja2/Django"),
+    ("django.template", "Django"),
+    ("tornado.template", "Tornado"),
+    ("mako.exceptions", "Mako"),
+    ("Twig\\Error", "Twig"),
+    ("Twig_Error", "Twig"),
+    ("Smarty_Internal", "Smarty"),
+    ("SmartyCompilerException", "Smarty"),
+    ("freemarker.core", "Freemarker"),
+    ("FreeMarker template error", "Freemarker"),
+    ("org.apache.velocity", "Velocity"),
+    ("org.thymeleaf", "Thymeleaf"),
+    ("org.springframework.expression", "Spring EL"),
+    ("javax.el.ELException", "Java EL"),
+    ("ActionView::Template::Error", "ERB"),
+    ("(erb):", "ERB"),
+    ("ejs:", "EJS"),
+    ("nunjucks", "Nunjucks"),
+    ("Liquid error", "Liquid"),
+    ("Handlebars", "Handlebars"),
+    ("pug:", "Pug"),
+];
+
+/// 算术探针使用的表达式语法
+const SYNTAXES: &[&str] = &["{{}}", "${}", "<%= %>", "#{}", "*{}", "[[${}]]", "#set", "{}", "@()"];
+
+/// 判定步骤：(载荷, 期望输出)
+struct Step {
+    payload: String,
+    expected: String,
+}
+
+/// SSTI扫描器
+#[derive(Clone)]
+pub struct SstiScanner {
+    /// 配置
+    config: Arc<AppConfig>,
+    /// HTTP客户端
+    http_client: reqwest::Client,
+}
+
+impl SstiScanner {
+    /// 创建新的SSTI扫描器
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        let http_client = http::client(Duration::from_millis(config.scanner.timeout_ms), true);
+
+        Self {
+            config,
+            http_client,
+        }
+    }
+
+    /// 算术探针：按语法构造乘法表达式
+    fn arithmetic_probe(syntax: &str, a: u32, b: u32) -> String {
+        match syntax {
+            "{{}}" => format!("{{{{{}*{}}}}}", a, b),
+            "${}" => format!("${{{}*{}}}", a, b),
+            "<%= %>" => format!("<%= {}*{} %>", a, b),
+            "#{}" => format!("#{{{}*{}}}", a, b),
+            "*{}" => format!("*{{{}*{}}}", a, b),
+            "[[${}]]" => format!("[[${{{}*{}}}]]", a, b),
+            "#set" => format!("#set($rs={}*{})${{rs}}", a, b),
+            "{}" => format!("{{{}*{}}}", a, b),
+            "@()" => format!("@({}*{})", a, b),
+            _ => String::new(),
+        }
+    }
+
+    /// 随机乘数，每个探针单独生成，避免页面或缓存中恰好存在相同结果
+    fn random_operands() -> (u32, u32) {
+        let mut rng = rand::thread_rng();
+        (rng.gen_range(1000..9999u32), rng.gen_range(1000..9999u32))
+    }
+
+    /// 随机小写字母标记，用于判定步骤的字符串运算
+    fn random_marker() -> String {
+        let mut rng = rand::thread_rng();
+        (0..6).map(|_| rng.gen_range(b'a'..=b'z') as char).collect()
+    }
+
+    /// 根据已确认的语法生成用于区分引擎的判定步骤：(引擎, 步骤)
+    ///
+    /// 按顺序尝试，第一个全部命中的引擎即为识别结果。marker为小写字母，n为字符串重复次数。
+    fn decision_tree(syntax: &str, marker: &str, n: usize) -> Vec<(&'static str, Vec<Step>)> {
+        let step = |payload: String, expected: String| Step { payload, expected };
+        let m = marker;
+        let upper = || marker.to_uppercase();
+
+        match syntax {
+            "{{}}" => vec![
+                // Python系模板支持字符串乘法
+                (
+                    "Jinja2",
+                    vec![
+                        step(format!("{{{{{}*'{}'}}}}", n, m), m.repeat(n)),
+                        step(format!("{{{{'{}'|upper}}}}", m), upper()),
+                    ],
+                ),
+                ("Tornado", vec![step(format!("{{{{{}*'{}'}}}}", n, m), m.repeat(n))]),
+                (
+                    "Nunjucks",
+                    vec![
+                        step(format!("{{{{'{}'|upper}}}}", m), upper()),
+                        step(format!("{{{{'{}'.toUpperCase()}}}}", m), upper()),
+                    ],
+                ),
+                (
+                    "Twig",
+                    vec![
+                        step(format!("{{{{'{}'|upper}}}}", m), upper()),
+                        step(format!("{{{{'{}'~'{}'}}}}", m, m), m.repeat(2)),
+                    ],
+                ),
+            ],
+            "${}" => vec![
+                ("Freemarker", vec![step(format!("${{'{}'?upper_case}}", m), upper())]),
+                ("Mako", vec![step(format!("${{'{}'.join('{}')}}", m, m), m.chars().map(String::from).collect::<Vec<_>>().join(m))]),
+                ("Java EL / Spring EL", vec![step(format!("${{'{}'.toUpperCase()}}", m), upper())]),
+            ],
+            "<%= %>" => vec![
+                ("ERB", vec![step(format!("<%= '{}'.upcase %>", m), upper())]),
+                ("EJS / JSP", vec![step(format!("<%= '{}'.toUpperCase() %>", m), upper())]),
+            ],
+            "#{}" => vec![
+                ("Slim / Haml (Ruby)", vec![step(format!("#{{'{}'.upcase}}", m), upper())]),
+                ("Pug / JSF EL", vec![step(format!("#{{'{}'.toUpperCase()}}", m), upper())]),
+            ],
+            "*{}" | "[[${}]]" => vec![("Thymeleaf", vec![step(format!("[[${{'{}'.toUpperCase()}}]]", m), upper())])],
+            "#set" => vec![("Velocity", vec![step(format!("#set($rs='{}')$rs.toUpperCase()", m), upper())])],
+            "{}" => vec![("Smarty", vec![step(format!("{{'{}'|upper}}", m), upper())])],
+            "@()" => vec![("Razor", vec![step(format!("@(\"{}\".ToUpper())", m), upper())])],
+            _ => Vec::new(),
+        }
+    }
+
+    /// 发送测试请求
+    async fn send_request(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        http::send_request(&self.http_client, request).await
+    }
+
+    /// 发送载荷并判断响应中是否新出现期望输出，命中时返回测试请求和响应
+    async fn probe(
+        &self,
+        request: &HttpRequest,
+        original_body: &str,
+        name: &str,
+        value: &str,
+        expected: &str,
+    ) -> Option<(HttpRequest, HttpResponse)> {
+        if original_body.contains(expected) {
+            return None;
+        }
+        let test_request = insertion::with_param(request, name, value);
+        match self.send_request(&test_request).await {
+            Ok(response) => {
+                let body = String::from_utf8_lossy(&response.body);
+                // 载荷原样回显时期望值也可能出现在载荷中，需排除
+                (body.contains(expected) && !value.contains(expected)).then_some((test_request, response))
+            }
+            Err(e) => {
+                debug!("SSTI测试请求失败: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 查找响应中新出现的模板引擎错误特征
+    fn match_error(original_body: &str, body: &str) -> Option<(&'static str, &'static str)> {
+        ERROR_SIGNATURES
+            .iter()
+            .find(|(sig, _)| body.contains(sig) && !original_body.contains(sig))
+            .copied()
+    }
+
+    /// 沿判定树识别模板引擎，返回(引擎, 判定过程)
+    async fn identify_engine(
+        &self,
+        request: &HttpRequest,
+        original_body: &str,
+        name: &str,
+        prefix: &str,
+        syntax: &str,
+    ) -> Option<(String, Vec<String>)> {
+        let n = rand::thread_rng().gen_range(3..7usize);
+        for (engine, steps) in Self::decision_tree(syntax, &Self::random_marker(), n) {
+            let mut trace = Vec::new();
+            let mut matched = true;
+            for step in &steps {
+                let value = format!("{}{}", prefix, step.payload);
+                let ok = self.probe(request, original_body, name, &value, &step.expected).await.is_some();
+                trace.push(format!("{} => {}", step.payload, if ok { &step.expected } else { "未命中" }));
+                if !ok {
+                    matched = false;
+                    break;
+                }
+            }
+            if matched {
+                return Some((engine.to_string(), trace));
+            }
+        }
+        None
+    }
+
+    /// 构造扫描结果
+    #[allow(clippy::too_many_arguments)]
+    fn build_result(
+        &self,
+        request: &HttpRequest,
+        name: &str,
+        payload: &str,
+        risk_level: &str,
+        confidence: Confidence,
+        exchange: (&HttpRequest, &HttpResponse, &str),
+        evidence: String,
+        details: String,
+    ) -> ScanResult {
+        let (test_request, test_response, marker) = exchange;
+        ScanResult {
+            vulnerability_type: "SSTI".to_string(),
+            name: "服务端模板注入漏洞".to_string(),
+            description: "检测到服务端模板注入（SSTI）漏洞，用户输入被当作模板代码执行，通常可进一步导致远程代码执行".to_string(),
+            risk_level: risk_level.to_string(),
+            url: request.url.to_string(),
+            method: request.method.to_string(),
+            parameter: Some(name.to_string()),
+            value: Some(payload.to_string()),
+            evidence: Some(evidence),
+            remediation: Some("不要将用户输入拼接进模板源码，只作为模板变量传入；必要时使用沙箱模式的模板引擎".to_string()),
+            details: Some(details),
+            timestamp: chrono::Utc::now(),
+            request_details: None,
+            response_details: None,
+            confidence,
+            payload_ranges: Vec::new(),
+            evidence_ranges: Vec::new(),
+        }
+        .with_exchange(test_request, Some(test_response), Some(payload), Some(marker))
+    }
+}
+
+#[async_trait]
+impl Scanner for SstiScanner {
+    async fn name(&self) -> String {
+        "SSTI Scanner".to_string()
+    }
+
+    async fn scan(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+        let original_body = String::from_utf8_lossy(&response.body).to_string();
+
+        for (name, original_value) in insertion::params(request, &self.config.scanner) {
+            let mut confirmed = false;
+            for syntax in SYNTAXES {
+                let (a, b) = Self::random_operands();
+                let product = (a as u64 * b as u64).to_string();
+                let payload = Self::arithmetic_probe(syntax, a, b);
+                let value = format!("{}{}", original_value, payload);
+                let (test_request, test_response) = match self.probe(request, &original_body, &name, &value, &product).await {
+                    Some(exchange) => exchange,
+                    None => continue,
+                };
+
+                let (engine, trace) = self
+                    .identify_engine(request, &original_body, &name, &original_value, syntax)
+                    .await
+                    .unwrap_or_else(|| (format!("未知（{} 语法）", syntax), Vec::new()));
+
+                let mut details = format!("模板引擎: {}\n表达式语法: {}", engine, syntax);
+                if !trace.is_empty() {
+                    details.push_str(&format!("\n判定过程:\n{}", trace.join("\n")));
+                }
+
+                results.push(self.build_result(
+                    request,
+                    &name,
+                    &value,
+                    "High",
+                    Confidence::Certain,
+                    (&test_request, &test_response, &product),
+                    format!("表达式 {} 被计算，响应中出现结果 {}", payload, product),
+                    details,
+                ));
+                confirmed = true;
+                break;
+            }
+
+            if confirmed {
+                continue;
+            }
+
+            // 未能执行表达式时，检查是否触发模板解析错误
+            let value = format!("{}{}", original_value, POLYGLOT);
+            let test_request = insertion::with_param(request, &name, &value);
+            if let Ok(test_response) = self.send_request(&test_request).await {
+                let body = String::from_utf8_lossy(&test_response.body);
+                if let Some((sig, engine)) = Self::match_error(&original_body, &body) {
+                    results.push(self.build_result(
+                        request,
+                        &name,
+                        &value,
+                        "Medium",
+                        Confidence::Firm,
+                        (&test_request, &test_response, sig),
+                        format!("模板语法探针触发了模板引擎错误: {}", sig),
+                        format!("模板引擎: {}（基于错误信息推断）", engine),
+                    ));
+                }
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ssti_scanner_name() {
+        let scanner = SstiScanner::new(Arc::new(AppConfig::default()));
+        assert_eq!(scanner.name().await, "SSTI Scanner");
+    }
+
+    #[test]
+    fn test_arithmetic_probes() {
+        let get = |syntax: &str| SstiScanner::arithmetic_probe(syntax, 12, 34);
+        assert_eq!(get("{{}}"), "{{12*34}}");
+        assert_eq!(get("${}"), "${12*34}");
+        assert_eq!(get("<%= %>"), "<%= 12*34 %>");
+        assert_eq!(get("#set"), "#set($rs=12*34)${rs}");
+        assert_eq!(get("{}"), "{12*34}");
+
+        // 每种语法都应有对应的判定分支
+        for syntax in SYNTAXES {
+            assert!(!get(syntax).is_empty(), "{}", syntax);
+            assert!(!SstiScanner::decision_tree(syntax, "abc", 3).is_empty(), "{}", syntax);
+        }
+    }
+
+    #[test]
+    fn test_decision_tree_markers() {
+        let marker = SstiScanner::random_marker();
+        assert!(marker.len() == 6 && marker.chars().all(|c| c.is_ascii_lowercase()));
+
+        let tree = SstiScanner::decision_tree("{{}}", "abc", 3);
+        assert_eq!(tree[0].1[0].payload, "{{3*'abc'}}");
+        assert_eq!(tree[0].1[0].expected, "abcabcabc");
+        assert_eq!(tree[0].1[1].expected, "ABC");
+        let tree = SstiScanner::decision_tree("${}", "abc", 3);
+        assert_eq!(tree[1].1[0].payload, "${'abc'.join('abc')}");
+        assert_eq!(tree[1].1[0].expected, "aabcbabcc");
+    }
+
+    #[test]
+    fn test_match_error() {
+        let body = "Traceback ... jinja2.exceptions.TemplateSyntaxError: unexpected '<'";
+        assert_eq!(SstiScanner::match_error("", body), Some(("jinja2.exceptions", "Jinja2")));
+        assert_eq!(SstiScanner::match_error(body, body), None);
+    }
+}
@@ -1,16 +1,17 @@
-use crate::core::config::AppConfig;
+use crate::core::config::{AppConfig, InsertionPointType};
+use crate::core::governor;
 use crate::global::config::CoreConfig;
 use crate::handler::scan::ast::{self, AstAnalyzer, InjectionResult, RiskLevel};
-use crate::handler::scan::engine::ScanResult;
+use crate::handler::scan::engine::{Confidence, ScanResult};
 use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
-use crate::handler::scan::scanners::Scanner;
+use crate::handler::scan::scanners::{dom_xss, Scanner};
 use anyhow::Result;
 use async_trait::async_trait;
 use log::{debug, info, warn};
 use regex::Regex;
 use reqwest;
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::Mutex;
 use once_cell::sync::Lazy;
@@ -27,13 +28,15 @@ use tokio_native_tls::{native_tls, TlsConnector};
 #[derive(Clone)]
 pub struct XssScanner {
     /// 配置
-    _config: Arc<AppConfig>,
+    config: Arc<AppConfig>,
     /// HTML AST分析器
     html_analyzer: Arc<ast::HtmlAstAnalyzer>,
     /// JavaScript AST分析器
     js_analyzer: Arc<ast::JsAstAnalyzer>,
     /// HTTP客户端
     _http_client: reqwest::Client,
+    /// 本次扫描已做过DOM型XSS确认的页面
+    dom_tested: Arc<Mutex<HashSet<String>>>,
 }
 
 // 静态缓存，用于存储页面特征向量
@@ -43,7 +46,7 @@ static PAGE_CACHE: Lazy<Mutex<HashMap<String, Vec<(String, HashMap<String, f64>)
 
 impl XssScanner {
     /// 创建新的XSS扫描器
-    pub fn new(_config: Arc<AppConfig>) -> Self {
+    pub fn new(config: Arc<AppConfig>) -> Self {
         // 创建带代理的HTTP客户端
         // let proxy = reqwest::Proxy::http("http://127.0.0.1:9999").expect("无效的代理URL");
         // let http_client = reqwest::Client::builder()
@@ -57,10 +60,11 @@ impl XssScanner {
         //     });
 
         Self {
-            _config,
+            config,
             html_analyzer: Arc::new(ast::HtmlAstAnalyzer::new()),
             js_analyzer: Arc::new(ast::JsAstAnalyzer::new()),
             _http_client: reqwest::Client::new(),
+            dom_tested: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
@@ -428,6 +432,41 @@ impl XssScanner {
         findings
     }
 
+    /// 无头浏览器确认的DOM型XSS结果
+    fn dom_confirmed_result(confirmation: &dom_xss::DomXssConfirmation) -> ScanResult {
+        ScanResult {
+            vulnerability_type: "DOM-based XSS".to_string(),
+            name: "DOM型跨站脚本漏洞".to_string(),
+            description: "无头浏览器中注入的载荷经客户端脚本流入危险sink并执行，攻击者可以通过构造恶意链接或消息在客户端执行任意JavaScript代码".to_string(),
+            risk_level: "High".to_string(),
+            url: confirmation.url.clone(),
+            method: "GET".to_string(),
+            parameter: Some(confirmation.source.clone()),
+            value: Some(confirmation.payload.clone()),
+            evidence: Some(format!("来源 {} 的载荷流入 {} 后canary被执行", confirmation.source, confirmation.sink)),
+            remediation: Some("避免将location、postMessage等客户端可控数据写入innerHTML、document.write、eval等sink，使用textContent等安全API，postMessage需校验origin".to_string()),
+            details: Some(format!(
+                "来源: {}\nsink: {}\n载荷: {}\nsink收到的数据: {}",
+                confirmation.source,
+                confirmation.sink,
+                confirmation.payload,
+                confirmation.sink_value.clone().unwrap_or_default()
+            )),
+            timestamp: chrono::Utc::now(),
+            request_details: None,
+            response_details: None,
+            confidence: Confidence::Certain,
+            payload_ranges: Vec::new(),
+            evidence_ranges: Vec::new(),
+        }
+        .with_exchange(
+            &HttpRequest::new(&confirmation.url, "GET", HashMap::new(), Vec::new(), Vec::new()),
+            None,
+            Some(confirmation.payload.as_str()),
+            None,
+        )
+    }
+
     /// 检测DOM-based XSS
     async fn detect_dom_based_xss(
         &self,
@@ -511,14 +550,33 @@ impl XssScanner {
                     remediation: Some("对用户输入进行HTML转义，实施输入验证和白名单机制，使用安全的DOM API".to_string()),
                     details: Some(format!("参数 {} 的值 {} 在客户端JavaScript中使用，可能导致DOM型XSS", param_name, param_value)),
                     timestamp: chrono::Utc::now(),
-                    request_details: Some(format!("{} {}\nHost: {}", request.method, request.url, request.url.split('/').nth(2).unwrap_or("unknown"))),
-                    response_details: Some(format!("响应体大小: {} 字节\n内容预览: {}", response.body.len(), String::from_utf8_lossy(&response.body[..std::cmp::min(200, response.body.len())])))
-                };
+                    request_details: None,
+                    response_details: None,
+                    confidence: Confidence::Tentative,
+                    payload_ranges: Vec::new(),
+                    evidence_ranges: Vec::new(),
+                }
+                .with_exchange(request, Some(response), Some(param_value.as_str()), Some(param_value.as_str()));
 
                 results.push(result);
             }
         }
 
+        // 启用浏览器确认时，canary真正执行的来源提升为确认结果，浏览器不可用时保留静态检测结果
+        if self.config.rules.vulnerabilities.xss.dom_confirm && body_str.to_lowercase().contains("<script") {
+            for confirmation in dom_xss::confirm(&request.url, &self.dom_tested).await {
+                info!("确认DOM型XSS: {} 来源 {} sink {}", confirmation.url, confirmation.source, confirmation.sink);
+                let confirmed = Self::dom_confirmed_result(&confirmation);
+                let matched = results.iter().position(|r| {
+                    r.parameter.as_deref().is_some_and(|p| confirmation.source == format!("query:{}", p))
+                });
+                match matched {
+                    Some(index) => results[index] = confirmed,
+                    None => results.push(confirmed),
+                }
+            }
+        }
+
         Ok(results)
     }
 
@@ -706,6 +764,15 @@ impl XssScanner {
     }
 
     /// 添加一个新的工具函数，用于解析HTTP响应
+    /// 原始响应中的状态行和头部（包含结尾空行），正文解码后单独拼接
+    fn raw_response_head(response_bytes: &[u8]) -> String {
+        let text = String::from_utf8_lossy(response_bytes);
+        match text.find("\r\n\r\n") {
+            Some(end) => text[..end + 4].to_string(),
+            None => text.to_string(),
+        }
+    }
+
     async fn parse_http_response(
         &self,
         response_bytes: &[u8],
@@ -968,7 +1035,12 @@ impl XssScanner {
         let mut vulnerable_params = std::collections::HashSet::new();
 
         // 遍历所有参数，发送包含XSS payload的请求
-        for (param_name, param_value) in &request.params {
+        let params: &[(String, String)] = if self.config.scanner.allows(InsertionPointType::Query) {
+            &request.params
+        } else {
+            &[]
+        };
+        for (param_name, param_value) in params {
             // 如果这个参数已经发现漏洞，跳过后续测试
             if vulnerable_params.contains(param_name) {
                 debug!("参数 {} 已发现XSS漏洞，跳过后续测试", param_name);
@@ -1166,6 +1238,8 @@ impl XssScanner {
                 };
 
                 let mut new_response = String::new();
+                // 完整的原始响应，用于结果展示
+                let mut new_response_raw = String::new();
 
                 if scheme == "http" {
                     // 创建TCP连接
@@ -1180,6 +1254,7 @@ impl XssScanner {
                                             // 将响应体转换为字符串进行分析
                                             let response_text =
                                                 String::from_utf8_lossy(&body).to_string();
+                                            new_response_raw = format!("{}{}", Self::raw_response_head(&response_bytes), response_text);
                                             new_response = response_text;
                                         }
                                         Err(e) => {
@@ -1188,6 +1263,7 @@ impl XssScanner {
                                             let response_text =
                                                 String::from_utf8_lossy(&response_bytes)
                                                     .to_string();
+                                            new_response_raw = response_text.clone();
                                             new_response = response_text;
                                         }
                                     }
@@ -1239,6 +1315,7 @@ impl XssScanner {
                                                     // 将响应体转换为字符串进行分析
                                                     let response_text =
                                                         String::from_utf8_lossy(&body).to_string();
+                                                    new_response_raw = format!("{}{}", Self::raw_response_head(&response_bytes), response_text);
                                                     new_response = response_text;
                                                 }
                                                 Err(e) => {
@@ -1247,6 +1324,7 @@ impl XssScanner {
                                                     let response_text =
                                                         String::from_utf8_lossy(&response_bytes)
                                                             .to_string();
+                                                    new_response_raw = response_text.clone();
                                                     new_response = response_text;
                                                 }
                                             }
@@ -1297,100 +1375,27 @@ impl XssScanner {
                                 method: request.method.to_string(),
                                 parameter: Some(param_name.clone()),
                                 value: Some(payload.clone()),
-                                evidence: injection_result.injection_content,
+                                evidence: injection_result.injection_content.clone(),
                                 remediation: Some("对用户输入进行严格过滤，使用HTML编码输出用户数据，实施内容安全策略(CSP)".to_string()),
                                 details: injection_result.details,
                                 timestamp: chrono::Utc::now(),
-                                request_details: Some(format!("{} {}\nHost: {}", request.method, request.url, request.url.split('/').nth(2).unwrap_or("unknown"))),
-                                response_details: Some(format!("响应体大小: {} 字节\n内容预览: {}", response.body.len(), String::from_utf8_lossy(&response.body[..std::cmp::min(200, response.body.len())])))
-                            };
-
-                            // 保存构造的请求URL和检测到漏洞的代码片段作为证据
-                            let mut evidence_parts = Vec::new();
-
-                            // 添加构造的请求URL
-                            evidence_parts.push(format!("测试URL: {}", &new_path));
-
-                            // 提取检测到漏洞的代码片段
-                            if let Some(start_index) = new_response.find(&payload) {
-                                let _start = if start_index > 50 {
-                                    start_index - 50
-                                } else {
-                                    0
-                                };
+                                request_details: None,
+                                response_details: None,
+                                confidence: Confidence::Firm,
+                                payload_ranges: Vec::new(),
+                                evidence_ranges: Vec::new(),
                             }
-
-                            // 构造完整的请求详情
-                            let request_detail = match request.method.as_str() {
-                                "GET" => {
-                                    format!(
-                                        "{} {} HTTP/1.1\nHost: {}\n{}\n\n",
-                                        request.method,
-                                        &new_path,
-                                        if let Ok(url) = url::Url::parse(&new_path) {
-                                            url.host_str().unwrap_or("unknown").to_string()
-                                        } else {
-                                            "unknown".to_string()
-                                        },
-                                        {
-                                            let mut headers_str = String::new();
-                                            for (name, value) in &request.headers {
-                                                if !name.eq_ignore_ascii_case("host") {
-                                                    headers_str.push_str(&format!(
-                                                        "{}: {}\r\n",
-                                                        name, value
-                                                    ));
-                                                }
-                                            }
-                                            headers_str
-                                        }
-                                    )
-                                }
-                                "POST" => {
-                                    let mut headers_str = String::new();
-                                    for (name, value) in &request.headers {
-                                        headers_str.push_str(&format!("{}: {}\n", name, value));
-                                    }
-
-                                    let body_str = String::from_utf8_lossy(&request.body);
-                                    format!(
-                                        "{} {} HTTP/1.1\n{}\n{}",
-                                        request.method, &new_path, headers_str, body_str
-                                    )
-                                }
-                                _ => format!("{} {}", request.method, &new_path),
-                            };
-
-                            // 构造响应详情
-                            let response_detail = format!(
-                                "HTTP/1.1 200 OK\nContent-Type: text/html\n\n{}",
-                                if new_response.len() > 1000 {
-                                    // 如果响应体太长，只显示相关部分
-                                    if let Some(start_index) = new_response.find(&payload) {
-                                        let start = if start_index > 200 {
-                                            start_index - 200
-                                        } else {
-                                            0
-                                        };
-                                        let end = std::cmp::min(
-                                            start_index + payload.len() + 200,
-                                            new_response.len(),
-                                        );
-                                        format!("... {} ...", &new_response[start..end])
-                                    } else {
-                                        format!("{}...(响应体过长，已截断)", &new_response[..1000])
-                                    }
-                                } else {
-                                    new_response.to_string()
-                                }
+                            .with_raw_exchange(
+                                http_request.clone(),
+                                Some(new_response_raw.clone()),
+                                Some(payload.as_str()),
+                                injection_result.injection_content.as_deref(),
                             );
 
-                            // 更新ScanResult中的evidence字段
-                            let evidence_text = evidence_parts.join("\n\n");
+                            // 保存构造的请求URL作为证据
+                            let evidence_text = format!("测试URL: {}", &new_path);
                             let result_with_details = ScanResult {
                                 evidence: Some(evidence_text),
-                                request_details: Some(request_detail),
-                                response_details: Some(response_detail),
                                 ..result
                             };
 
@@ -1421,6 +1426,7 @@ impl XssScanner {
     fn check_params_reflection(
         &self,
         request: &HttpRequest,
+        response: &HttpResponse,
         body_text: &str,
         results: &Vec<ScanResult>,
     ) -> Vec<ScanResult> {
@@ -1464,14 +1470,13 @@ impl XssScanner {
                         param_name, param_value
                     )),
                     timestamp: chrono::Utc::now(),
-                    request_details: Some(format!(
-                        "{} {}\nHost: {}",
-                        request.method,
-                        request.url,
-                        request.url.split('/').nth(2).unwrap_or("unknown")
-                    )),
-                    response_details: Some("反射检测成功，详情见证据部分".to_string()),
-                };
+                    request_details: None,
+                    response_details: None,
+                    confidence: Confidence::Tentative,
+                    payload_ranges: Vec::new(),
+                    evidence_ranges: Vec::new(),
+                }
+                .with_exchange(request, Some(response), Some(param_value.as_str()), Some(param_value.as_str()));
 
                 new_results.push(result);
             }
@@ -1520,7 +1525,7 @@ impl XssScanner {
             //获取全局的http_client
             let http_client  = CoreConfig::global().unwrap().http_client.clone().unwrap();
             //先请求URL，获取响应
-            let response = match http_client.get(url).send().await {
+            let response = match governor::send(http_client.get(url)).await {
                 Ok(response) => response,
                 Err(e) => {
                     // error!("请求URL失败: {}", e);
@@ -1725,7 +1730,7 @@ impl XssScanner {
                 }
 
                 // 对于经过lossy转换的内容，进行反射检测
-                results = self.check_params_reflection(request, &lossy_string, &results);
+                results = self.check_params_reflection(request, response, &lossy_string, &results);
 
                 // 因为文本内容质量较差，仅检测反射，不进行后续扫描
                 return Ok(results);
@@ -1770,20 +1775,13 @@ impl XssScanner {
                         param_name, param_value
                     )),
                     timestamp: chrono::Utc::now(),
-                    request_details: Some(format!(
-                        "{} {}\nHost: {}",
-                        request.method,
-                        request.url,
-                        request.url.split('/').nth(2).unwrap_or("unknown")
-                    )),
-                    response_details: Some(format!(
-                        "响应体大小: {} 字节\n内容预览: {}",
-                        response.body.len(),
-                        String::from_utf8_lossy(
-                            &response.body[..std::cmp::min(200, response.body.len())]
-                        )
-                    )),
-                };
+                    request_details: None,
+                    response_details: None,
+                    confidence: Confidence::Tentative,
+                    payload_ranges: Vec::new(),
+                    evidence_ranges: Vec::new(),
+                }
+                .with_exchange(request, Some(response), Some(param_value.as_str()), Some(param_value.as_str()));
 
                 results.push(result);
             }
@@ -1833,7 +1831,7 @@ async fn decode_gzip(compressed_data: &[u8]) -> Result<Vec<u8>> {
 }
 
 // 计算词频向量
-fn compute_term_frequency(text: &str) -> HashMap<String, f64> {
+pub(crate) fn compute_term_frequency(text: &str) -> HashMap<String, f64> {
     let mut term_freq = HashMap::new();
     let words: Vec<&str> = text.split_whitespace().collect();
     
@@ -1857,7 +1855,7 @@ fn compute_term_frequency(text: &str) -> HashMap<String, f64> {
 }
 
 // 计算余弦相似度
-fn cosine_similarity(vec1: &HashMap<String, f64>, vec2: &HashMap<String, f64>) -> f64 {
+pub(crate) fn cosine_similarity(vec1: &HashMap<String, f64>, vec2: &HashMap<String, f64>) -> f64 {
     let mut dot_product = 0.0;
     
     // 计算点积
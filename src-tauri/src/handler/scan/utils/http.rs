@@ -0,0 +1,116 @@
+//! 扫描器发送测试请求
+//!
+//! 客户端沿用全局代理设置，请求统一经过出站请求调度器（[`governor`]）发送，
+//! 按目标主机限速并根据响应调整发送速率。
+
+use crate::core::governor;
+use crate::global::config::CoreConfig;
+use crate::handler::scan::proxy::{HttpRequest, HttpResponse};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Duration;
+use url::Url;
+
+/// 创建扫描器使用的HTTP客户端，忽略证书错误并沿用全局代理
+///
+/// 检查跳转本身（Location头、未授权时的302）的扫描器需要关闭follow_redirects。
+pub fn client(timeout: Duration, follow_redirects: bool) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .timeout(timeout);
+    if !follow_redirects {
+        builder = builder.redirect(reqwest::redirect::Policy::none());
+    }
+
+    if let Ok(core_config) = CoreConfig::global() {
+        if let Some(proxy) = core_config.proxy.filter(|p| !p.is_empty()) {
+            if let Ok(p) = reqwest::Proxy::all(&proxy) {
+                builder = builder.proxy(p);
+            }
+        }
+    }
+
+    builder.build().unwrap_or_else(|_| reqwest::Client::new())
+}
+
+/// 按测试请求构造reqwest请求
+///
+/// 查询参数按params重建并保持顺序；请求体可能被修改，Content-Length和Host交给reqwest重新计算。
+pub fn build_request(client: &reqwest::Client, request: &HttpRequest) -> Result<reqwest::RequestBuilder> {
+    let mut url = Url::parse(&request.url)?;
+    url.set_query(None);
+    if !request.params.is_empty() {
+        let mut query_pairs = url.query_pairs_mut();
+        for (key, value) in &request.params {
+            query_pairs.append_pair(key, value);
+        }
+    }
+
+    let method = reqwest::Method::from_bytes(request.method.as_bytes()).unwrap_or(reqwest::Method::GET);
+    let mut req_builder = client.request(method, url);
+
+    for (key, value) in &request.headers {
+        if key.eq_ignore_ascii_case("content-length") || key.eq_ignore_ascii_case("host") {
+            continue;
+        }
+        req_builder = req_builder.header(key, value);
+    }
+
+    if !request.body.is_empty() {
+        req_builder = req_builder.body(request.body.clone());
+    }
+
+    Ok(req_builder)
+}
+
+/// 经调度器发送请求并读取完整响应
+pub async fn send(builder: reqwest::RequestBuilder) -> Result<HttpResponse> {
+    Ok(send_timed(builder).await?.0)
+}
+
+/// 经调度器发送请求，同时返回不含限速排队时间的耗时
+pub async fn send_timed(builder: reqwest::RequestBuilder) -> Result<(HttpResponse, Duration)> {
+    let (resp, started) = governor::send_timed(builder).await?;
+    let status = resp.status().as_u16();
+    let headers: HashMap<String, String> = resp
+        .headers()
+        .iter()
+        .map(|(k, v)| (k.as_str().to_string(), v.to_str().unwrap_or("").to_string()))
+        .collect();
+    let body = resp.bytes().await?.to_vec();
+
+    Ok((HttpResponse { status, headers, body }, started.elapsed()))
+}
+
+/// 构造并发送测试请求
+pub async fn send_request(client: &reqwest::Client, request: &HttpRequest) -> Result<HttpResponse> {
+    send(build_request(client, request)?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_request() {
+        let mut headers = HashMap::new();
+        headers.insert("Host".to_string(), "example.com".to_string());
+        headers.insert("Content-Length".to_string(), "1".to_string());
+        headers.insert("X-Test".to_string(), "1".to_string());
+        let request = HttpRequest {
+            method: "POST".to_string(),
+            url: "http://example.com/a?old=1".to_string(),
+            headers,
+            body: b"a=1&b=2".to_vec(),
+            params: vec![("q".to_string(), "a b".to_string()), ("id".to_string(), "1".to_string())],
+        };
+
+        let built = build_request(&reqwest::Client::new(), &request).unwrap().build().unwrap();
+        assert_eq!(built.method(), reqwest::Method::POST);
+        assert_eq!(built.url().as_str(), "http://example.com/a?q=a+b&id=1");
+        assert_eq!(built.headers().get("x-test").unwrap(), "1");
+        assert!(built.headers().get("host").is_none());
+        assert!(built.headers().get("content-length").is_none());
+        assert_eq!(built.body().and_then(|b| b.as_bytes()), Some(&b"a=1&b=2"[..]));
+    }
+}
@@ -0,0 +1,383 @@
+//! 请求插入点
+//!
+//! 统一处理查询参数、表单参数、JSON请求体中的字段、Cookie、请求头和路径段，
+//! JSON字段使用点号路径命名（如 `variables.user.id`、`items.0.name`），
+//! Cookie、请求头和路径段分别命名为 `cookie:<名称>`、`header:<名称>`、`path:<段序号>`。
+
+use crate::core::config::{InsertionPointType, ScannerConfig};
+use crate::handler::scan::proxy::HttpRequest;
+use serde_json::Value;
+use url::Url;
+
+/// 作为插入点测试的请求头
+const INJECTABLE_HEADERS: &[&str] = &["User-Agent", "Referer", "X-Forwarded-For"];
+
+/// 获取Content-Type（小写）
+fn content_type(request: &HttpRequest) -> String {
+    request
+        .headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.to_lowercase())
+        .unwrap_or_default()
+}
+
+/// 递归展开JSON中的标量字段
+fn flatten_json(value: &Value, prefix: &str, out: &mut Vec<(String, String)>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                flatten_json(v, &join(k), out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                flatten_json(v, &join(&i.to_string()), out);
+            }
+        }
+        Value::String(s) if !prefix.is_empty() => out.push((prefix.to_string(), s.clone())),
+        Value::Number(n) if !prefix.is_empty() => out.push((prefix.to_string(), n.to_string())),
+        Value::Bool(b) if !prefix.is_empty() => out.push((prefix.to_string(), b.to_string())),
+        _ => {}
+    }
+}
+
+/// 提取JSON请求体中的字段，返回(路径, 值)
+pub fn json_params(request: &HttpRequest) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    if !content_type(request).contains("json") {
+        return params;
+    }
+    if let Ok(json) = serde_json::from_slice::<Value>(&request.body) {
+        flatten_json(&json, "", &mut params);
+    }
+    params
+}
+
+/// 提取表单请求体中的字段
+pub fn form_params(request: &HttpRequest) -> Vec<(String, String)> {
+    if !content_type(request).contains("application/x-www-form-urlencoded") {
+        return Vec::new();
+    }
+    url::form_urlencoded::parse(&request.body)
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// 提取Cookie中的字段
+pub fn cookie_params(request: &HttpRequest) -> Vec<(String, String)> {
+    request
+        .headers
+        .iter()
+        .filter(|(k, _)| k.eq_ignore_ascii_case("cookie"))
+        .flat_map(|(_, v)| v.split(';'))
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (format!("cookie:{}", k.trim()), v.trim().to_string()))
+        .collect()
+}
+
+/// 提取可注入的请求头
+pub fn header_params(request: &HttpRequest) -> Vec<(String, String)> {
+    INJECTABLE_HEADERS
+        .iter()
+        .filter_map(|name| {
+            request
+                .headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| (format!("header:{}", name), v.clone()))
+        })
+        .collect()
+}
+
+/// 提取像ID的路径段（纯数字），例如 `/users/42` 中的42
+pub fn path_params(request: &HttpRequest) -> Vec<(String, String)> {
+    let url = match Url::parse(&request.url) {
+        Ok(url) => url,
+        Err(_) => return Vec::new(),
+    };
+    url.path_segments()
+        .map(|segments| {
+            segments
+                .enumerate()
+                .filter(|(_, segment)| !segment.is_empty() && segment.parse::<i64>().is_ok())
+                .map(|(i, segment)| (format!("path:{}", i), segment.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 按扫描配置允许的插入点类型提取查询参数、表单字段、JSON字段、Cookie、请求头和路径段
+pub fn params(request: &HttpRequest, scanner: &ScannerConfig) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    if scanner.allows(InsertionPointType::Query) {
+        params.extend(request.params.iter().cloned());
+    }
+    if scanner.allows(InsertionPointType::Form) {
+        params.extend(form_params(request));
+    }
+    if scanner.allows(InsertionPointType::Json) {
+        params.extend(json_params(request));
+    }
+    if scanner.allows(InsertionPointType::Cookie) {
+        params.extend(cookie_params(request));
+    }
+    if scanner.allows(InsertionPointType::Header) {
+        params.extend(header_params(request));
+    }
+    if scanner.allows(InsertionPointType::Path) {
+        params.extend(path_params(request));
+    }
+    params
+}
+
+/// 按路径设置JSON字段
+fn set_json_path(json: &mut Value, path: &str, value: Value) -> bool {
+    let mut target = json;
+    for key in path.split('.') {
+        target = match target {
+            Value::Object(map) => match map.get_mut(key) {
+                Some(v) => v,
+                None => return false,
+            },
+            Value::Array(items) => match key.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                Some(v) => v,
+                None => return false,
+            },
+            _ => return false,
+        };
+    }
+    *target = value;
+    true
+}
+
+/// 替换Cookie字段，分号会截断Cookie，写入前编码
+fn set_cookie(request: &mut HttpRequest, name: &str, value: &str) -> bool {
+    let key = match request.headers.keys().find(|k| k.eq_ignore_ascii_case("cookie")) {
+        Some(key) => key.clone(),
+        None => return false,
+    };
+    let mut found = false;
+    let pairs: Vec<String> = request.headers[&key]
+        .split(';')
+        .map(|pair| match pair.split_once('=') {
+            Some((k, _)) if k.trim() == name => {
+                found = true;
+                format!("{}={}", k.trim(), value.replace(';', "%3B"))
+            }
+            _ => pair.trim().to_string(),
+        })
+        .collect();
+    if found {
+        request.headers.insert(key, pairs.join("; "));
+    }
+    found
+}
+
+/// 替换路径段
+fn set_path_segment(request: &mut HttpRequest, index: usize, value: &str) -> bool {
+    let mut url = match Url::parse(&request.url) {
+        Ok(url) => url,
+        Err(_) => return false,
+    };
+    let mut segments: Vec<String> = match url.path_segments() {
+        Some(segments) => segments.map(str::to_string).collect(),
+        None => return false,
+    };
+    match segments.get_mut(index) {
+        Some(segment) => *segment = value.to_string(),
+        None => return false,
+    }
+    match url.path_segments_mut() {
+        Ok(mut path) => {
+            path.clear().extend(&segments);
+        }
+        Err(_) => return false,
+    }
+    request.url = url.to_string();
+    true
+}
+
+/// 将指定插入点的值替换为value
+///
+/// 依次尝试查询参数、表单参数和JSON字段，`cookie:`、`header:`、`path:` 前缀的插入点替换
+/// 对应的Cookie、请求头和路径段，找不到插入点时返回原请求的副本
+pub fn with_param(request: &HttpRequest, name: &str, value: &str) -> HttpRequest {
+    let mut test_request = request.clone();
+
+    if request.params.iter().any(|(k, _)| k == name) {
+        test_request.params = request
+            .params
+            .iter()
+            .map(|(k, v)| if k == name { (k.clone(), value.to_string()) } else { (k.clone(), v.clone()) })
+            .collect();
+        return test_request;
+    }
+
+    let form = form_params(request);
+    if form.iter().any(|(k, _)| k == name) {
+        let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+        for (k, v) in &form {
+            serializer.append_pair(k, if k == name { value } else { v.as_str() });
+        }
+        test_request.body = serializer.finish().into_bytes();
+        return test_request;
+    }
+
+    if content_type(request).contains("json") {
+        if let Ok(mut json) = serde_json::from_slice::<Value>(&request.body) {
+            if set_json_path(&mut json, name, Value::String(value.to_string())) {
+                test_request.body = serde_json::to_vec(&json).unwrap_or_else(|_| request.body.clone());
+                return test_request;
+            }
+        }
+    }
+
+    if let Some(cookie) = name.strip_prefix("cookie:") {
+        set_cookie(&mut test_request, cookie, value);
+    } else if let Some(header) = name.strip_prefix("header:") {
+        if let Some(key) = request.headers.keys().find(|k| k.eq_ignore_ascii_case(header)) {
+            test_request.headers.insert(key.clone(), value.to_string());
+        }
+    } else if let Some(index) = name.strip_prefix("path:").and_then(|i| i.parse::<usize>().ok()) {
+        set_path_segment(&mut test_request, index, value);
+    }
+
+    test_request
+}
+
+/// 将JSON字段替换为任意JSON值（如NoSQL操作符对象），字段不存在时返回None
+pub fn with_json_value(request: &HttpRequest, path: &str, value: Value) -> Option<HttpRequest> {
+    if !content_type(request).contains("json") {
+        return None;
+    }
+    let mut json = serde_json::from_slice::<Value>(&request.body).ok()?;
+    if !set_json_path(&mut json, path, value) {
+        return None;
+    }
+    let mut test_request = request.clone();
+    test_request.body = serde_json::to_vec(&json).ok()?;
+    Some(test_request)
+}
+
+/// 将查询或表单参数改名后写入新值，例如把 `user=admin` 改写为 `user[$ne]=x`
+///
+/// 找不到参数时返回None
+pub fn with_param_renamed(request: &HttpRequest, name: &str, new_name: &str, value: &str) -> Option<HttpRequest> {
+    let mut test_request = request.clone();
+
+    if request.params.iter().any(|(k, _)| k == name) {
+        test_request.params = request
+            .params
+            .iter()
+            .map(|(k, v)| if k == name { (new_name.to_string(), value.to_string()) } else { (k.clone(), v.clone()) })
+            .collect();
+        return Some(test_request);
+    }
+
+    let form = form_params(request);
+    if form.iter().any(|(k, _)| k == name) {
+        let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+        for (k, v) in &form {
+            if k == name {
+                serializer.append_pair(new_name, value);
+            } else {
+                serializer.append_pair(k, v);
+            }
+        }
+        test_request.body = serializer.finish().into_bytes();
+        return Some(test_request);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn json_request(body: &str) -> HttpRequest {
+        let mut headers = HashMap::new();
+        headers.insert("Content-Type".to_string(), "application/json".to_string());
+        HttpRequest {
+            method: "POST".to_string(),
+            url: "http://example.com/graphql".to_string(),
+            headers,
+            body: body.as_bytes().to_vec(),
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_json_params_and_with_param() {
+        let request = json_request(r#"{"query":"q","variables":{"id":1,"tags":["a"]}}"#);
+        let params = json_params(&request);
+        assert!(params.contains(&("variables.id".to_string(), "1".to_string())));
+        assert!(params.contains(&("variables.tags.0".to_string(), "a".to_string())));
+
+        let modified = with_param(&request, "variables.id", "1'");
+        let json: Value = serde_json::from_slice(&modified.body).unwrap();
+        assert_eq!(json["variables"]["id"], "1'");
+        assert_eq!(json["query"], "q");
+    }
+
+    #[test]
+    fn test_with_param_query() {
+        let mut request = json_request("{}");
+        request.params = vec![("a".to_string(), "1".to_string()), ("b".to_string(), "2".to_string())];
+        let modified = with_param(&request, "b", "x");
+        assert_eq!(modified.params[1], ("b".to_string(), "x".to_string()));
+        assert_eq!(modified.params[0], ("a".to_string(), "1".to_string()));
+
+        let renamed = with_param_renamed(&request, "a", "a[$ne]", "x").unwrap();
+        assert_eq!(renamed.params[0], ("a[$ne]".to_string(), "x".to_string()));
+        assert!(with_param_renamed(&request, "c", "c[$ne]", "x").is_none());
+    }
+
+    #[test]
+    fn test_with_json_value() {
+        let request = json_request(r#"{"user":{"name":"bob"},"pass":"x"}"#);
+        let modified = with_json_value(&request, "user.name", serde_json::json!({"$ne": ""})).unwrap();
+        let json: Value = serde_json::from_slice(&modified.body).unwrap();
+        assert_eq!(json["user"]["name"]["$ne"], "");
+        assert_eq!(json["pass"], "x");
+        assert!(with_json_value(&request, "missing", Value::Null).is_none());
+    }
+
+    #[test]
+    fn test_cookie_header_path_points() {
+        let mut request = json_request("{}");
+        request.url = "http://example.com/users/42/posts?x=1".to_string();
+        request.headers.insert("Cookie".to_string(), "sid=abc; lang=en".to_string());
+        request.headers.insert("user-agent".to_string(), "Mozilla".to_string());
+        let mut scanner = crate::core::config::AppConfig::default().scanner;
+        scanner.insertion_points = vec![InsertionPointType::Cookie, InsertionPointType::Header, InsertionPointType::Path];
+
+        let points = params(&request, &scanner);
+        assert_eq!(
+            points,
+            vec![
+                ("cookie:sid".to_string(), "abc".to_string()),
+                ("cookie:lang".to_string(), "en".to_string()),
+                ("header:User-Agent".to_string(), "Mozilla".to_string()),
+                ("path:1".to_string(), "42".to_string()),
+            ]
+        );
+
+        let modified = with_param(&request, "cookie:lang", "en';--");
+        assert_eq!(modified.headers["Cookie"], "sid=abc; lang=en'%3B--");
+        let modified = with_param(&request, "header:User-Agent", "x'");
+        assert_eq!(modified.headers["user-agent"], "x'");
+        let modified = with_param(&request, "path:1", "42'");
+        assert_eq!(modified.url, "http://example.com/users/42'/posts?x=1");
+    }
+}
@@ -1,3 +1,6 @@
+pub mod http;
+pub mod insertion;
+
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use rand::{distributions::Alphanumeric, Rng};
@@ -118,6 +121,29 @@ pub fn get_mime_type<P: AsRef<Path>>(path: P) -> String {
     .to_string()
 }
 
+/// 路径模板，数字和UUID等标识片段替换为占位符，用于按接口去重
+pub fn path_template(path: &str) -> String {
+    path.split('/')
+        .map(|seg| {
+            let is_id = !seg.is_empty()
+                && (seg.chars().all(|c| c.is_ascii_digit())
+                    || (seg.len() >= 16 && seg.chars().all(|c| c.is_ascii_hexdigit() || c == '-')));
+            if is_id { "{id}" } else { seg }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 首字母大写、其余小写，用于把规则和插件的风险等级统一为内置扫描器的写法
+pub fn capitalize(level: &str) -> String {
+    let lower = level.to_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 /// 检查字符串是否为URL
 pub fn is_url(s: &str) -> bool {
     s.starts_with("http://") || s.starts_with("https://")
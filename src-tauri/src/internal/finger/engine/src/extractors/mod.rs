@@ -36,7 +36,7 @@ impl PartialEq for Extractor {
 }
 
 impl Extractor {
-  pub(crate) fn compile(&mut self) -> Result<()> {
+  pub fn compile(&mut self) -> Result<()> {
     if let ExtractorType::Regex(regexps) = &self.extractor_type {
       for re in regexps.regex.iter() {
         let rec = fancy_regex::Regex::new(re).map_err(new_regex_error)?;
@@ -67,7 +67,7 @@ impl Extractor {
     }
     (extract_result, BTreeMap::new())
   }
-  pub(crate) fn extract_regex(
+  pub fn extract_regex(
     &self,
     regexps: &ERegex,
     corpus: String,
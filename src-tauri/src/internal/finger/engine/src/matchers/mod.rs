@@ -39,7 +39,7 @@ impl PartialEq for Matcher {
 }
 
 impl Matcher {
-  pub(crate) fn compile(&mut self) -> Result<()> {
+  pub fn compile(&mut self) -> Result<()> {
     if let MatcherType::Regex(regexps) = &self.matcher_type {
       for re in regexps.regex.iter() {
         let rec = fancy_regex::Regex::new(re).map_err(new_regex_error)?;
@@ -70,7 +70,7 @@ impl Matcher {
     }
     (false, matched_words)
   }
-  pub(crate) fn match_word(&self, word: &Word, corpus: String) -> (bool, Vec<String>) {
+  pub fn match_word(&self, word: &Word, corpus: String) -> (bool, Vec<String>) {
     let words = if self.case_insensitive {
       corpus.to_ascii_lowercase()
     } else {
@@ -103,7 +103,7 @@ impl Matcher {
     }
     (false, matched_words)
   }
-  pub(crate) fn match_regex(&self, regexs: &MRegex, corpus: String) -> (bool, Vec<String>) {
+  pub fn match_regex(&self, regexs: &MRegex, corpus: String) -> (bool, Vec<String>) {
     let mut matched_regexes = Vec::new();
     for re in self.regex.iter() {
       let matcher = if let Ok(matcher) = re.captures(&corpus) {
@@ -135,7 +135,7 @@ impl Matcher {
     }
     (false, matched_regexes)
   }
-  pub(crate) fn match_status_code(&self, status: &Status, status_code: u16) -> bool {
+  pub fn match_status_code(&self, status: &Status, status_code: u16) -> bool {
     for code in status.status.iter() {
       if code != &status_code {
         continue;
@@ -145,7 +145,7 @@ impl Matcher {
     false
   }
 
-  pub(crate) fn negative(&self, is_match: bool) -> bool {
+  pub fn negative(&self, is_match: bool) -> bool {
     if self.negative {
       !is_match
     } else {
@@ -21,7 +21,7 @@ pub struct Operators {
 }
 
 impl Operators {
-  pub(crate) fn compile(&mut self) -> Result<()> {
+  pub fn compile(&mut self) -> Result<()> {
     for matcher in self.matchers.iter_mut() {
       matcher.compile().map_err(new_regex_error)?;
     }
@@ -4,7 +4,9 @@ use regex::Regex;
 use rhai::{Dynamic, Engine};
 use serde::{Deserialize, Serialize};
 
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, time::{Duration, Instant}};
+use crate::core::governor;
+use crate::handler::scan::plugin;
 
 pub fn set_plugin_export_func(engine: &mut Engine) {
     // 注册内置函数
@@ -45,6 +47,10 @@ pub fn set_plugin_export_func(engine: &mut Engine) {
         rng.gen::<f64>() // 生成0到1之间的随机浮点数
     });
 
+    // 注册OOB回连函数
+    engine.register_fn("oob_token", oob_token);
+    engine.register_fn("oob_poll", oob_poll);
+
     // 注册正则提取函数
     engine.register_fn("regex_matches", |text: &str, pattern: &str| -> Dynamic {
         let re = Regex::new(pattern).unwrap();
@@ -56,6 +62,39 @@ pub fn set_plugin_export_func(engine: &mut Engine) {
     });
 }
 
+/// 申请OOB token，返回包含各协议回连地址的map
+fn oob_token() -> Dynamic {
+    match crate::handler::scan::oob::OobListener::get() {
+        Some(listener) if !listener.is_routable() => {
+            warn!("OOB回连地址无法被目标访问，请配置oob.public_host");
+            Dynamic::UNIT
+        }
+        Some(listener) => {
+            let token = listener.new_token("rhai");
+            match serde_json::to_value(&token) {
+                Ok(value) => value_to_dynamic(value),
+                Err(_) => Dynamic::UNIT,
+            }
+        }
+        None => {
+            warn!("OOB回连服务未启动");
+            Dynamic::UNIT
+        }
+    }
+}
+
+/// 查询token的回连记录，返回交互记录数组
+fn oob_poll(token: &str) -> Dynamic {
+    let interactions = match crate::handler::scan::oob::OobListener::get() {
+        Some(listener) => listener.poll(token),
+        None => Vec::new(),
+    };
+    match serde_json::to_value(&interactions) {
+        Ok(value) => value_to_dynamic(value),
+        Err(_) => Dynamic::from(rhai::Array::new()),
+    }
+}
+
 /// Base64编码
 fn base64_encode(text: &str) -> String {
     base64::Engine::encode(&base64::engine::general_purpose::STANDARD, text)
@@ -236,9 +275,16 @@ fn http_request(json_params: &str) -> String {
         }
     };
     // println!("params: {:?}", params);
+    // 插件设置了执行超时时，请求超时不超过剩余的执行时间
+    let deadline = plugin::deadline();
+    let mut timeout = Duration::from_secs(params.timeout.unwrap_or(30) as u64);
+    if let Some(deadline) = deadline {
+        timeout = timeout.min(deadline.saturating_duration_since(Instant::now()));
+    }
+
     // 创建HTTP客户端
     let client_builder = reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(params.timeout.unwrap_or(30) as u64))
+        .timeout(timeout)
         .danger_accept_invalid_certs(true);
 
     // 设置代理
@@ -280,11 +326,16 @@ fn http_request(json_params: &str) -> String {
     };
 
     // 执行请求
-    process_request(client, params)
+    process_request(client, params, timeout, deadline)
 }
 
 /// 处理HTTP请求
-fn process_request(client: reqwest::blocking::Client, params: HttpRequestParams) -> String {
+fn process_request(
+    client: reqwest::blocking::Client,
+    params: HttpRequestParams,
+    timeout: Duration,
+    deadline: Option<Instant>,
+) -> String {
     let method = match params.method.to_uppercase().as_str() {
         "GET" => reqwest::Method::GET,
         "POST" => reqwest::Method::POST,
@@ -297,7 +348,7 @@ fn process_request(client: reqwest::blocking::Client, params: HttpRequestParams)
     };
 
     // 创建请求
-    let mut request_builder = client.request(method, &params.url);
+    let mut request_builder = client.request(method, &params.url).timeout(timeout);
 
     // 添加URL参数
     if let Some(query_params) = params.params {
@@ -317,7 +368,7 @@ fn process_request(client: reqwest::blocking::Client, params: HttpRequestParams)
     }
 
     // 执行请求
-    match request_builder.send() {
+    match governor::send_blocking(request_builder, deadline) {
         Ok(response) => {
             let status = response.status().as_u16();
 
@@ -113,6 +113,14 @@ pub async fn run() {
     // 初始化暴力破解模块
     let brute_force_state = BruteForceState::new();
 
+    // 启动OOB回连服务
+    let oob_config = crate::core::config::AppConfig::default().oob;
+    if oob_config.enabled {
+        if let Err(e) = scan::oob::OobListener::global(&oob_config).await {
+            error!("启动OOB回连服务失败: {}", e);
+        }
+    }
+
     // 初始化Repeater插件
     // 不再使用插件方式
     // let repeater_plugin = handler::repeater::init();
@@ -161,11 +169,25 @@ pub async fn run() {
             run_scan_by_type,
             scan::api_commands::start_passive_scan,
             scan::api_commands::start_active_scan,
+            scan::api_commands::import_api_spec,
+            scan::api_commands::start_crawl,
+            scan::api_commands::pause_active_scan,
+            scan::api_commands::resume_active_scan,
+            scan::api_commands::cancel_active_scan,
+            scan::api_commands::get_active_scan_queue,
+            scan::api_commands::list_scan_profiles,
+            scan::api_commands::get_default_scan_profile,
+            scan::api_commands::save_scan_profile,
+            scan::api_commands::delete_scan_profile,
+            scan::api_commands::export_scan_profile,
+            scan::api_commands::import_scan_profile,
             scan::api_commands::stop_passive_scan,
             scan::api_commands::get_scan_status,
             scan::api_commands::get_scan_vulnerabilities,
             scan::api_commands::clear_scan_vulnerabilities,
             scan::api_commands::export_scan_vulnerabilities,
+            scan::api_commands::export_scan_report,
+            scan::api_commands::update_scan_vulnerability_status,
             get_risks,
             // asm::port_scan,
             asm::get_asset_statistics,
@@ -173,6 +195,13 @@ pub async fn run() {
             asm::visualization::get_risk_heatmap_data,
             asm::visualization::generate_compliance_report,
             asm::visualization::open_file,
+            asm::graphql::get_graphql_schemas,
+            asm::graphql::graphql_analyze,
+            asm::vulndb::import_vuln_feed,
+            asm::vulndb::list_vuln_feeds,
+            asm::vulndb::remove_vuln_feed,
+            asm::vulndb::lookup_cves,
+            asm::vulndb::match_task_cves,
             get_asm_config,
             update_asm_config,
             list_rhai_plugins,
@@ -205,6 +234,16 @@ pub async fn run() {
             scan::plugin_commands::delete_scan_plugin,
             scan::plugin_commands::update_scan_plugin,
             scan::plugin_commands::execute_scan_plugin,
+            scan::plugin_commands::set_scan_plugin_enabled,
+            // OOB回连相关命令
+            scan::oob::commands::oob_generate_token,
+            scan::oob::commands::oob_poll_interactions,
+            scan::oob::commands::oob_list_interactions,
+            scan::oob::commands::oob_clear_interactions,
+            scan::jwt::commands::jwt_decode,
+            scan::jwt::commands::jwt_find,
+            scan::jwt::commands::jwt_resign,
+            scan::jwt::commands::jwt_crack,
             open_url,
             // Repeater命令
             handler::repeater::repeater_send_request,
@@ -262,6 +301,13 @@ pub async fn run() {
                 .expect("Failed to get main window");
             app.manage(ScannerState::new(main_window));
 
+            // 恢复上次退出时未完成的主动扫描队列
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<ScannerState>();
+                scan::active::handler::resume_active_scan_queue(&state).await;
+            });
+
             // 初始化应用全局状态
             app.manage(AppState::new());
 
@@ -8,7 +8,7 @@ use std::env;
 use std::fs::OpenOptions;
 
 use rshield_lib::{
-    database::init_db, global::config::CoreConfig, handler::asm::asm_task::asm_init,
+    database::{init_db, migrate_db}, global::config::CoreConfig, handler::asm::asm_task::asm_init,
 };
 
 use pistol::vs::vs_scan;
@@ -30,6 +30,8 @@ async fn main() {
         //初始化数据库
         init_db().await;
     }
+    // 已有数据库补齐新增的表和列
+    migrate_db().await;
 
     // Initialize ASM in background
     let handle = tokio::runtime::Handle::current();
@@ -8,7 +8,9 @@ use crate::scan::proxy::Proxy;
 use anyhow::{Result, anyhow};
 
 // Import types from the new common location
-use crate::handler::scan::common::types::{ScannerStatus, Vulnerability, VulnerabilityDetail};
+use crate::handler::scan::common::types::ScannerStatus;
+use crate::handler::scan::engine::ScanResult;
+use crate::handler::scan::results::store;
 
 /// 应用全局状态
 pub struct AppState {
@@ -27,7 +29,6 @@ impl AppState {
 pub struct ScannerState {
     pub running: Arc<Mutex<bool>>,
     pub status: Arc<Mutex<ScannerStatus>>,
-    pub vulnerabilities: Arc<Mutex<Vec<Vulnerability>>>,
     pub window: Arc<WebviewWindow>,
     pub proxy: Arc<Mutex<Option<Arc<Proxy>>>>,
 }
@@ -46,7 +47,6 @@ impl ScannerState {
                 last_stop_time: None,
                 message: None,
             })),
-            vulnerabilities: Arc::new(Mutex::new(Vec::new())),
             window: Arc::new(window),
             proxy: Arc::new(Mutex::new(None)),
         }
@@ -68,12 +68,19 @@ impl ScannerState {
         }
     }
 
-    pub async fn add_vulnerability(&self, vulnerability: Vulnerability) {
-        let mut vulnerabilities = self.vulnerabilities.lock().await;
-        vulnerabilities.push(vulnerability.clone());
-        
+    pub async fn add_vulnerability(&self, result: ScanResult) {
+        let vulnerability = match store::save_vulnerability(result).await {
+            Ok(saved) if saved.should_notify() => saved.vulnerability,
+            Ok(_) => return,
+            Err(e) => {
+                log::error!("保存漏洞失败: {}", e);
+                return;
+            }
+        };
+
+        let count = store::count_vulnerabilities().await.unwrap_or_default();
         self.update_status(|status| {
-            status.vulnerability_count = vulnerabilities.len();
+            status.vulnerability_count = count;
         }).await;
         
         if let Err(e) = self.window.as_ref().emit(
@@ -60,6 +60,7 @@ pub fn create_test_config() -> Arc<AppConfig> {
             timeout_ms: 5000,
             save_results: false,
             results_path: "./results.json".to_string(),
+            insertion_points: rshield_lib::core::config::InsertionPointType::all(),
         },
         rules: rshield_lib::core::config::RulesConfig {
             enable_builtin: true,
@@ -71,17 +72,29 @@ pub fn create_test_config() -> Arc<AppConfig> {
                     use_ast: true,
                     max_params: 20,
                     max_depth: 3,
+                    dom_confirm: false,
                 },
                 sql_injection: rshield_lib::core::config::SqlInjectionConfig {
                     enabled: true,
                     level: "high".to_string(),
+                    payloads: HashMap::new(),
                 },
                 rce: rshield_lib::core::config::RceConfig {
                     enabled: true,
                     level: "high".to_string(),
+                    payloads: HashMap::new(),
                 },
                 path_traversal: rshield_lib::core::config::PathTraversalConfig { enabled: true },
                 open_redirect: rshield_lib::core::config::OpenRedirectConfig { enabled: true },
+                ssrf: rshield_lib::core::config::SsrfConfig { enabled: true },
+                xxe: rshield_lib::core::config::XxeConfig { enabled: true },
+                ssti: rshield_lib::core::config::SstiConfig { enabled: true },
+                passive_checks: rshield_lib::core::config::PassiveCheckConfig { enabled: true },
+                cors: rshield_lib::core::config::CorsConfig { enabled: true },
+                jwt: rshield_lib::core::config::JwtConfig { enabled: true },
+                nosql_injection: rshield_lib::core::config::NoSqlInjectionConfig { enabled: true },
+                java_deserialization: rshield_lib::core::config::JavaDeserializationConfig { enabled: true, exec_gadgets: false },
+                authz: rshield_lib::core::config::AuthzConfig::default(),
             },
         },
         logging: rshield_lib::core::config::LoggingConfig {
@@ -90,5 +103,8 @@ pub fn create_test_config() -> Arc<AppConfig> {
             file_path: "logs/scan.log".to_string(),
             colored_output: true,
         },
+        oob: rshield_lib::core::config::OobConfig::default(),
+        plugins: rshield_lib::core::config::PluginConfig::default(),
+        rate_limit: rshield_lib::core::config::RateLimitConfig::default(),
     })
 }
@@ -58,6 +58,11 @@ export async function executeScanPlugin(params: {
   });
 }
 
+// 启用或禁用扫描插件，禁用的插件不会在被动/主动扫描中执行
+export async function setScanPluginEnabled(pluginId: string, enabled: boolean) {
+  return invoke('set_scan_plugin_enabled', { pluginId, enabled });
+}
+
 // ... rest of the file remains unchanged ...
 
  
\ No newline at end of file
@@ -13,12 +13,26 @@ export interface ScannerStatus {
 }
 
 // 定义漏洞详情接口
+// 原始报文中的字节区间 [start, end)
+export interface ByteRange {
+  start: number;
+  end: number;
+}
+
 export interface VulnerabilityDetail {
   note:string
   request: string;
   response: string;
+  payload_ranges?: ByteRange[];   // payload在请求中的字节区间
+  evidence_ranges?: ByteRange[];  // 证据在响应中的字节区间
 }
 
+// 漏洞置信度
+export type Confidence = 'certain' | 'firm' | 'tentative';
+
+// 漏洞研判状态
+export type TriageStatus = 'new' | 'confirmed' | 'false_positive' | 'fixed' | 'accepted_risk';
+
 // 定义漏洞接口
 export interface Vulnerability {
   id: number;
@@ -35,6 +49,10 @@ export interface Vulnerability {
   request_details?: string;   // 构造的请求详情
   response_details?: string;  // 响应详情
   details?: VulnerabilityDetail;
+  confidence: Confidence;     // 置信度
+  status: TriageStatus;       // 研判状态
+  occurrences: number;        // 重复发现次数
+  last_seen: string;          // 最后发现时间
 }
 
 // 原始扫描器配置，用于被动扫描表单
@@ -55,6 +73,7 @@ export interface ScanConfig {
   save_results: boolean;   // 是否保存结果
   results_path: string;    // 结果保存路径
   use_plugins: boolean;  // 是否使用插件
+  profile?: string;      // 扫描模板名称
 }
 
 export interface ActiveScanConfig {
@@ -88,6 +107,7 @@ export interface ActiveScanConfig {
       options?: string[]; // 例如 ["ssh_pubkey", "cron_job", "remote_command", "ms17_010"]
     };
   };
+  profile?: string; // 扫描模板名称
 }
 
 export interface SuccessResponse {
@@ -95,6 +115,88 @@ export interface SuccessResponse {
   message: string;
 }
 
+export type QueueStatus = 'pending' | 'running' | 'paused';
+
+export interface ScanCheckpoint {
+  phase?: string;
+  insertion_point?: string;
+  payload_index: number;
+  completed_points: string[];
+}
+
+// 持久化的主动扫描队列任务
+export interface QueuedScanTask {
+  id: string;
+  scan_id: string;
+  kind: 'request' | 'target';
+  target: string;
+  status: QueueStatus;
+  checkpoint: ScanCheckpoint;
+  created_at: number;
+  updated_at: number;
+}
+
+export type InsertionPointType = 'query' | 'form' | 'json' | 'cookie' | 'header' | 'path' | 'body';
+
+// 爬虫配置，发现的请求加入主动扫描
+export interface CrawlConfig {
+  targets: string[];
+  max_depth?: number;
+  max_pages?: number;
+  submit_forms?: boolean;
+  render_js?: boolean;
+  use_sitemap?: boolean;
+  include_subdomains?: boolean;
+  exclude?: string[];
+  headers?: Record<string, string>;
+  scan?: boolean;
+  profile?: string | null;
+}
+
+export interface CrawlSummary {
+  pages: number;
+  requests: number;
+  forms: number;
+}
+
+// 报告导出选项
+export type ReportFormat = 'json' | 'sarif' | 'html' | 'markdown';
+
+export interface ReportOptions {
+  format: ReportFormat;
+  path: string;                   // 输出文件或目录
+  min_severity?: string | null;   // 最低风险等级
+  statuses?: TriageStatus[];      // 为空时排除误报
+  template?: string | null;       // 自定义HTML/Markdown模板文件
+  branding?: {
+    title?: string;
+    company?: string;
+    logo?: string;
+    primary_color?: string;
+    footer?: string;
+  };
+}
+
+// 扫描模板：检查项、检测级别、插入点、自定义payload、限速和超时
+export interface ScanProfile {
+  name: string;
+  description: string;
+  checks: Record<string, any>; // 与config.yaml中rules.vulnerabilities结构相同
+  insertion_points: InsertionPointType[];
+  rate_limit: {
+    enabled: boolean;
+    requests_per_second: number;
+    max_in_flight: number;
+    backoff_base_ms: number;
+    backoff_max_ms: number;
+    adaptive: boolean;
+    max_slowdown: number;
+  };
+  timeout_ms: number;
+  concurrency: number;
+  scan_options?: ActiveScanConfig['detailed_scan_options'] | null;
+}
+
 export interface AssetStatistics {
   total_domains: number;
   total_ips: number;
@@ -124,7 +226,8 @@ const scannerService = {
         intercept_tls: config.intercept_tls || false,
         save_results: config.save_results || false,
         results_path: config.results_path || '',
-        use_plugins: config.use_plugins || false
+        use_plugins: config.use_plugins || false,
+        profile: config.profile || null
       };
       
       await invoke('start_passive_scan', { config: scanConfig });
@@ -146,6 +249,95 @@ const scannerService = {
     }
   },
 
+  // Crawl targets and queue discovered requests for active scanning
+  async startCrawl(config: CrawlConfig): Promise<boolean> {
+    try {
+      const response = await invoke<SuccessResponse>('start_crawl', { config });
+      return response.success;
+    } catch (error) {
+      console.error('Failed to start crawler:', error);
+      return false;
+    }
+  },
+
+  // Pause the active scan queue
+  async pauseActiveScan(): Promise<boolean> {
+    try {
+      const response = await invoke<SuccessResponse>('pause_active_scan');
+      return response.success;
+    } catch (error) {
+      console.error('Failed to pause active scan:', error);
+      return false;
+    }
+  },
+
+  // Resume the paused active scan queue
+  async resumeActiveScan(): Promise<boolean> {
+    try {
+      const response = await invoke<SuccessResponse>('resume_active_scan');
+      return response.success;
+    } catch (error) {
+      console.error('Failed to resume active scan:', error);
+      return false;
+    }
+  },
+
+  // Cancel a queued task, or the whole queue when no id is given
+  async cancelActiveScan(taskId?: string): Promise<boolean> {
+    try {
+      const response = await invoke<SuccessResponse>('cancel_active_scan', { taskId: taskId ?? null });
+      return response.success;
+    } catch (error) {
+      console.error('Failed to cancel active scan:', error);
+      return false;
+    }
+  },
+
+  // Get unfinished tasks in the active scan queue
+  async getActiveScanQueue(): Promise<QueuedScanTask[]> {
+    try {
+      return await invoke<QueuedScanTask[]>('get_active_scan_queue');
+    } catch (error) {
+      console.error('Failed to get active scan queue:', error);
+      return [];
+    }
+  },
+
+  // List saved scan profiles
+  async listScanProfiles(): Promise<ScanProfile[]> {
+    try {
+      return await invoke<ScanProfile[]>('list_scan_profiles');
+    } catch (error) {
+      console.error('Failed to list scan profiles:', error);
+      return [];
+    }
+  },
+
+  // A new profile based on config.yaml
+  async getDefaultScanProfile(): Promise<ScanProfile> {
+    return await invoke<ScanProfile>('get_default_scan_profile');
+  },
+
+  async saveScanProfile(profile: ScanProfile): Promise<boolean> {
+    const response = await invoke<SuccessResponse>('save_scan_profile', { profile });
+    return response.success;
+  },
+
+  async deleteScanProfile(name: string): Promise<boolean> {
+    const response = await invoke<SuccessResponse>('delete_scan_profile', { name });
+    return response.success;
+  },
+
+  // Export a profile as YAML, optionally writing it to path
+  async exportScanProfile(name: string, path?: string): Promise<string> {
+    return await invoke<string>('export_scan_profile', { name, path: path ?? null });
+  },
+
+  // Import a profile from YAML content or a file path
+  async importScanProfile(content?: string, path?: string): Promise<ScanProfile> {
+    return await invoke<ScanProfile>('import_scan_profile', { content: content ?? null, path: path ?? null });
+  },
+
   // Stop the scanner
   async stopScanner(): Promise<boolean> {
     try {
@@ -196,6 +388,17 @@ const scannerService = {
     }
   },
 
+  // Update triage status of vulnerabilities
+  async updateVulnerabilityStatus(ids: number[], status: TriageStatus): Promise<boolean> {
+    try {
+      const response = await invoke<SuccessResponse>('update_scan_vulnerability_status', { ids, status });
+      return response.success;
+    } catch (e) {
+      console.error('更新漏洞状态失败:', e);
+      throw e;
+    }
+  },
+
   // Clear all vulnerabilities
   async clearVulnerabilities(): Promise<boolean> {
     try {
@@ -226,6 +429,11 @@ const scannerService = {
     }
   },
 
+  // 导出SARIF/HTML/Markdown报告
+  async exportReport(options: ReportOptions): Promise<SuccessResponse> {
+    return await invoke<SuccessResponse>('export_scan_report', { options });
+  },
+
   // 获取资产统计数据
   async getAssetStatistics(taskId: number): Promise<AssetStatistics> {
     try {
@@ -36,6 +36,15 @@ export default {
   'asm.risk.count':'Count',
   'asm.risk.risk_type':'Risk Type',
   'asm.risk.export': 'Export Risks',
+  'asm.risk.vulndb': 'Vuln DB',
+  'asm.risk.vulndb_import': 'Import NVD/OSV Feed',
+  'asm.risk.vulndb_match': 'Match Current Task',
+  'asm.risk.vulndb_tip': 'Imported feed files are re-imported when they change, and component and service versions are matched to CVEs again',
+  'asm.risk.vulndb_path': 'File',
+  'asm.risk.vulndb_format': 'Format',
+  'asm.risk.vulndb_imported': 'Imported, entries',
+  'asm.risk.vulndb_matched': 'New CVE risks',
+  'asm.risk.vulndb_task': 'Filter by task ID first',
   'asm.risk.columns': 'Columns',
   'asm.risk.handle': 'Handle',
   'asm.plugin': 'Plugins',
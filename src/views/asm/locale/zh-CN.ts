@@ -134,6 +134,15 @@ export default {
   'asm.risk.columns': '列',
   'asm.risk.handle': '处理',
   'asm.risk.export': '导出风险',
+  'asm.risk.vulndb': '漏洞库',
+  'asm.risk.vulndb_import': '导入NVD/OSV数据源',
+  'asm.risk.vulndb_match': '匹配当前任务',
+  'asm.risk.vulndb_tip': '导入的数据源文件更新后会自动重新导入，并将组件和端口服务版本重新匹配到CVE',
+  'asm.risk.vulndb_path': '文件',
+  'asm.risk.vulndb_format': '格式',
+  'asm.risk.vulndb_imported': '导入完成，漏洞条目',
+  'asm.risk.vulndb_matched': '新增CVE风险',
+  'asm.risk.vulndb_task': '请先按任务ID筛选',
   'asm.website.status_code': '状态码',
   'asm.website.render_title': '网站标题',
   'asm.website.finger': 'WEB指纹',
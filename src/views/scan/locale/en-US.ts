@@ -101,8 +101,68 @@ export default {
   'scan.prev_page': 'Previous',
   'scan.next_page': 'Next',
   
+  // Scan queue
+  'scan.scan_queue': 'Scan Queue',
+  'scan.pause_scan': 'Pause',
+  'scan.resume_scan': 'Resume',
+  'scan.cancel': 'Cancel',
+  'scan.cancel_all': 'Cancel All',
+  'scan.cancel_queue_confirm': 'Cancel all tasks in the scan queue?',
+  'scan.queue_paused': 'Paused',
+  'scan.queue_resumed': 'Scan resumed',
+  'scan.queue_cancelled': 'Scan task cancelled',
+  'scan.operation_failed': 'Operation failed',
+  'scan.queue_status.pending': 'Pending',
+  'scan.queue_status.running': 'Running',
+  'scan.queue_status.paused': 'Paused',
+
+  // Scan profiles
+  'scan.scan_profile': 'Scan Profile',
+  'scan.scan_profile_placeholder': 'Use config file',
+  'scan.import_profile': 'Import',
+  'scan.export_profile': 'Export',
+  'scan.profile_imported': 'Imported scan profile {name}',
+
+  // Reports
+  'scan.export_report': 'Export Report',
+  'scan.report_format': 'Format',
+  'scan.report_path_placeholder': 'Output file or directory',
+  'scan.report_path_required': 'Please enter an output path',
+  'scan.report_min_severity': 'Minimum Severity',
+  'scan.report_status_placeholder': 'False positives excluded by default',
+  'scan.report_title': 'Report Title',
+  'scan.report_company': 'Customer',
+  'scan.report_logo': 'Logo',
+  'scan.report_template': 'Custom Template',
+  'scan.report_template_placeholder': 'Template file path, empty for built-in',
+
+  // Crawler
+  'scan.crawler': 'Crawler',
+  'scan.crawl_max_depth': 'Max Depth',
+  'scan.crawl_max_pages': 'Max Pages',
+  'scan.crawl_submit_forms': 'Submit Forms',
+  'scan.crawl_render_js': 'Render JS',
+  'scan.crawl_use_sitemap': 'robots/sitemap',
+  'scan.crawl_include_subdomains': 'Include Subdomains',
+  'scan.crawl_and_scan': 'Crawl & Scan',
+  'scan.crawl_started': 'Crawler started',
+  'scan.crawl_start_failed': 'Failed to start crawler',
+  'scan.crawl_completed': 'Crawl finished: {pages} pages fetched, {requests} requests found',
+  
   // Vulnerability fields
   'scan.parameter': 'Parameter',
+  'scan.occurrences': 'Hits',
+  'scan.triage_status': 'Status',
+  'scan.update_status_failed': 'Failed to update status',
+  'scan.triage.new': 'New',
+  'scan.triage.confirmed': 'Confirmed',
+  'scan.triage.false_positive': 'False Positive',
+  'scan.triage.fixed': 'Fixed',
+  'scan.triage.accepted_risk': 'Accepted Risk',
+  'scan.confidence': 'Confidence',
+  'scan.confidence_level.certain': 'Certain',
+  'scan.confidence_level.firm': 'Firm',
+  'scan.confidence_level.tentative': 'Tentative',
   'scan.value': 'Value',
   
   // HTTP details
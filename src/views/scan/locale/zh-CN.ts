@@ -111,8 +111,68 @@ export default {
   'scan.prev_page': '上一页',
   'scan.next_page': '下一页',
   
+  // 扫描队列
+  'scan.scan_queue': '扫描队列',
+  'scan.pause_scan': '暂停',
+  'scan.resume_scan': '继续',
+  'scan.cancel': '取消',
+  'scan.cancel_all': '全部取消',
+  'scan.cancel_queue_confirm': '确定取消队列中的全部扫描任务？',
+  'scan.queue_paused': '已暂停',
+  'scan.queue_resumed': '扫描已继续',
+  'scan.queue_cancelled': '扫描任务已取消',
+  'scan.operation_failed': '操作失败',
+  'scan.queue_status.pending': '等待中',
+  'scan.queue_status.running': '扫描中',
+  'scan.queue_status.paused': '已暂停',
+
+  // 扫描模板
+  'scan.scan_profile': '扫描模板',
+  'scan.scan_profile_placeholder': '使用配置文件',
+  'scan.import_profile': '导入模板',
+  'scan.export_profile': '导出模板',
+  'scan.profile_imported': '已导入扫描模板 {name}',
+
+  // 报告
+  'scan.export_report': '导出报告',
+  'scan.report_format': '报告格式',
+  'scan.report_path_placeholder': '输出文件或目录',
+  'scan.report_path_required': '请输入导出路径',
+  'scan.report_min_severity': '最低风险等级',
+  'scan.report_status_placeholder': '默认排除误报',
+  'scan.report_title': '报告标题',
+  'scan.report_company': '客户名称',
+  'scan.report_logo': 'Logo',
+  'scan.report_template': '自定义模板',
+  'scan.report_template_placeholder': '模板文件路径，留空使用内置模板',
+
+  // 爬虫
+  'scan.crawler': '爬虫',
+  'scan.crawl_max_depth': '最大深度',
+  'scan.crawl_max_pages': '最大页面数',
+  'scan.crawl_submit_forms': '提交表单',
+  'scan.crawl_render_js': '渲染JS',
+  'scan.crawl_use_sitemap': 'robots/sitemap',
+  'scan.crawl_include_subdomains': '包含子域名',
+  'scan.crawl_and_scan': '爬取并扫描',
+  'scan.crawl_started': '爬虫已启动',
+  'scan.crawl_start_failed': '启动爬虫失败',
+  'scan.crawl_completed': '爬取完成，请求 {pages} 个页面，发现 {requests} 个请求',
+  
   // 漏洞字段
   'scan.parameter': '参数名称',
+  'scan.occurrences': '次数',
+  'scan.triage_status': '状态',
+  'scan.update_status_failed': '更新状态失败',
+  'scan.triage.new': '新发现',
+  'scan.triage.confirmed': '已确认',
+  'scan.triage.false_positive': '误报',
+  'scan.triage.fixed': '已修复',
+  'scan.triage.accepted_risk': '接受风险',
+  'scan.confidence': '置信度',
+  'scan.confidence_level.certain': '确定',
+  'scan.confidence_level.firm': '可信',
+  'scan.confidence_level.tentative': '待确认',
   'scan.value': '参数值',
   
   // HTTP详情